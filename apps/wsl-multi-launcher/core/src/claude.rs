@@ -0,0 +1,453 @@
+//! Claude Code sessions (`claude:` windows)
+//!
+//! A `claude:` block describes "a Claude Code session on project X"; it is
+//! expanded into the `claude` command line and the project directory when
+//! the config is loaded, so the rest of the launcher sees a plain WSL window.
+//!
+//! What a session is doing is guessed from two sources: whether its
+//! processes use CPU, and the last turn of its newest transcript under
+//! `~/.claude/projects/<project path with non-alphanumerics as '-'>/`.
+
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::process::Command;
+use std::time::Duration;
+
+use crate::config::{ClaudeConfig, ClaudeResume, ClaudeSettings, Config, WindowConfig};
+use crate::error::{LauncherError, Result};
+use crate::shell;
+use crate::wsl::WslLauncher;
+
+/// How long CPU use is sampled for
+const CPU_SAMPLE: Duration = Duration::from_secs(1);
+
+/// Transcript lines read from the end of the file
+const TAIL_LINES: usize = 20;
+
+/// Separator line of per-window script output, followed by the window name
+pub const SECTION: &str = "==wml== ";
+
+/// Shell snippet setting `$t` to the directory claude keeps transcripts in
+pub const PROJECTS_DIR: &str = "t=\"${CLAUDE_CONFIG_DIR:-$HOME/.claude}/projects\"";
+
+/// Roles available without a `roles:` entry, as (name, system prompt)
+pub const BUILTIN_ROLES: &[(&str, &str)] = &[
+    (
+        "architect",
+        "You are the architect in a team of coding agents working in parallel. \
+         Plan and design: break the work into well-scoped tasks, define the interfaces \
+         between them and write down the decisions. Leave large implementations to the implementers.",
+    ),
+    (
+        "implementer",
+        "You are an implementer in a team of coding agents working in parallel. \
+         Implement the task you are given with focused, tested changes that follow the agreed design, \
+         and stay within its scope.",
+    ),
+    (
+        "reviewer",
+        "You are the reviewer in a team of coding agents working in parallel. \
+         Review changes for correctness, missing tests and consistency with the design, \
+         and report concrete issues instead of rewriting the code yourself.",
+    ),
+];
+
+/// System prompt of a role; `roles` from the config win over built-in ones
+pub fn role_prompt<'a>(role: &str, roles: &'a BTreeMap<String, String>) -> Option<&'a str> {
+    roles
+        .get(role)
+        .map(String::as_str)
+        .or_else(|| BUILTIN_ROLES.iter().find(|(name, _)| *name == role).map(|(_, prompt)| *prompt))
+}
+
+/// Quote a shell word that may span lines as `$'...'`, so the command
+/// stays on one line on its way through wt.exe
+fn quote_text(s: &str) -> String {
+    if !s.contains('\n') {
+        return shell::quote(s);
+    }
+    format!("$'{}'", s.replace('\\', "\\\\").replace('\'', "\\'").replace('\n', "\\n"))
+}
+
+/// `claude` command line of a preset
+pub fn command(preset: &ClaudeConfig, roles: &BTreeMap<String, String>) -> String {
+    let mut args = vec!["claude".to_string()];
+    let system_prompt: Vec<&str> = preset
+        .role
+        .as_deref()
+        .and_then(|role| role_prompt(role, roles))
+        .into_iter()
+        .chain(preset.append_system_prompt.as_deref())
+        .map(str::trim)
+        .collect();
+    if !system_prompt.is_empty() {
+        args.extend(["--append-system-prompt".to_string(), quote_text(&system_prompt.join("\n\n"))]);
+    }
+    if let Some(ref model) = preset.model {
+        args.extend(["--model".to_string(), shell::quote(model)]);
+    }
+    if let Some(ref mode) = preset.permission_mode {
+        args.extend(["--permission-mode".to_string(), shell::quote(mode)]);
+    }
+    match preset.settings {
+        Some(ClaudeSettings::File(ref path)) => args.extend(["--settings".to_string(), shell::quote_path(path)]),
+        Some(ClaudeSettings::Inline(ref settings)) => {
+            let json = serde_json::Value::Object(settings.clone()).to_string();
+            args.extend(["--settings".to_string(), shell::quote(&json)]);
+        }
+        None => {}
+    }
+    if preset.continue_last {
+        args.push("--continue".to_string());
+    }
+    match preset.resume {
+        Some(ClaudeResume::Pick(true)) => args.push("--resume".to_string()),
+        Some(ClaudeResume::Session(ref id)) => args.extend(["--resume".to_string(), shell::quote(id)]),
+        // Recorded conversations are picked at launch (`conversation_command`)
+        Some(ClaudeResume::Pick(false)) | Some(ClaudeResume::Policy(_)) | None => {}
+    }
+    args.extend(preset.args.iter().map(|arg| shell::quote(arg)));
+    if let Some(ref prompt) = preset.prompt {
+        args.push(quote_text(prompt.trim()));
+    }
+    args.join(" ")
+}
+
+/// Conversation a launch of a `resume: always|ask` window runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Conversation<'a> {
+    /// Continue this recorded conversation
+    Continue(&'a str),
+    /// Start a new conversation under this ID
+    Start(&'a str),
+}
+
+/// `claude` command line of a preset running `conversation`
+///
+/// Claude refuses to resume a conversation whose transcript is gone, so a
+/// recorded one is started afresh under the same ID in that case.
+pub fn conversation_command(preset: &ClaudeConfig, roles: &BTreeMap<String, String>, conversation: Conversation) -> String {
+    let claude = command(preset, roles);
+    match conversation {
+        Conversation::Continue(id) => format!(
+            "{projects}; if {dir} && [ -f \"$p\"/{file} ]; then {claude} --resume {id}; else {claude} --session-id {id}; fi",
+            projects = PROJECTS_DIR,
+            dir = transcript_dir_script(&preset.project),
+            file = shell::quote(&format!("{}.jsonl", id)),
+            id = shell::quote(id),
+        ),
+        Conversation::Start(id) => format!("{} --session-id {}", claude, shell::quote(id)),
+    }
+}
+
+/// A new conversation ID (a random UUID, which claude requires)
+pub fn new_session_id() -> Result<String> {
+    let uuid = "/proc/sys/kernel/random/uuid";
+    let id = std::fs::read_to_string(uuid).map_err(LauncherError::io("read", uuid))?;
+    Ok(id.trim().to_string())
+}
+
+/// What a claude session is doing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Activity {
+    /// Generating a reply or running a tool
+    Busy,
+    /// Waiting for a permission answer before running a tool
+    Waiting,
+    /// Done with its turn; free for the next prompt
+    Idle,
+}
+
+impl fmt::Display for Activity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Activity::Busy => "busy",
+            Activity::Waiting => "waiting",
+            Activity::Idle => "idle",
+        })
+    }
+}
+
+impl Activity {
+    /// Marker and word shown in a status title
+    fn label(self) -> &'static str {
+        match self {
+            Activity::Busy => "●working",
+            Activity::Waiting => "◐waiting",
+            Activity::Idle => "○idle",
+        }
+    }
+}
+
+/// Title of a claude window showing its model, worktree branch and
+/// activity, e.g. `impl-auth [sonnet] feature/auth ●working`
+///
+/// It starts with the window name and a space, which is all focus and
+/// toggle need to find the window by title.
+pub fn status_title(window: &WindowConfig, activity: Option<Activity>) -> String {
+    let mut title = window.name.clone();
+    if let Some(model) = window.claude.as_ref().and_then(|c| c.model.as_deref()) {
+        title.push_str(&format!(" [{}]", model));
+    }
+    if let Some(ref worktree) = window.worktree {
+        title.push_str(&format!(" {}", worktree.branch));
+    }
+    if let Some(activity) = activity {
+        title.push_str(&format!(" {}", activity.label()));
+    }
+    title
+}
+
+/// Last turn recorded in a transcript
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Turn {
+    /// Prompt or tool result; the model is answering it
+    User,
+    /// Reply asking to run a tool
+    ToolUse,
+    /// Reply without a tool call
+    Reply,
+}
+
+/// User entries that aren't sent to the model: slash command echoes,
+/// interruptions and metadata
+fn is_local_entry(entry: &serde_json::Value) -> bool {
+    if entry["isMeta"].as_bool() == Some(true) {
+        return true;
+    }
+    let content = &entry["message"]["content"];
+    let text = content
+        .as_str()
+        .or_else(|| content.as_array()?.first()?["text"].as_str())
+        .unwrap_or_default();
+    text.starts_with("<command-") || text.starts_with("<local-command") || text.starts_with("[Request interrupted")
+}
+
+/// Last user or assistant turn of transcript lines
+fn last_turn(lines: &[String]) -> Option<Turn> {
+    lines.iter().rev().find_map(|line| {
+        let entry: serde_json::Value = serde_json::from_str(line).ok()?;
+        match entry["type"].as_str()? {
+            "user" if !is_local_entry(&entry) => Some(Turn::User),
+            "assistant" => {
+                let content = entry["message"]["content"].as_array();
+                let tool_use = content.is_some_and(|c| c.iter().any(|part| part["type"] == "tool_use"));
+                Some(if tool_use { Turn::ToolUse } else { Turn::Reply })
+            }
+            _ => None,
+        }
+    })
+}
+
+/// Combine CPU use and the last transcript turn into an activity
+fn classify(turn: Option<Turn>, cpu_busy: bool) -> Activity {
+    match turn {
+        _ if cpu_busy => Activity::Busy,
+        // Waiting on the API uses no CPU
+        Some(Turn::User) => Activity::Busy,
+        Some(Turn::ToolUse) => Activity::Waiting,
+        Some(Turn::Reply) | None => Activity::Idle,
+    }
+}
+
+/// Shell snippet setting `$p` to the transcript directory of `project`
+/// under `$t` (see [`PROJECTS_DIR`]); fails if the project doesn't exist
+pub fn transcript_dir_script(project: &str) -> String {
+    format!(
+        "d=$(cd {} 2>/dev/null && pwd -P) && p=\"$t/$(printf '%s' \"$d\" | sed 's/[^a-zA-Z0-9]/-/g')\"",
+        shell::quote_path(project)
+    )
+}
+
+/// Shell snippet printing the `SECTION` line of a window
+pub fn section_script(window: &str) -> String {
+    format!("printf '%s\\n' {}", shell::quote(&format!("{}{}", SECTION, window)))
+}
+
+/// Script printing the tail of the newest transcript of each window's
+/// project, each after a `SECTION` line with the window name
+fn transcript_script(windows: &[&WindowConfig]) -> String {
+    let mut script = String::from(PROJECTS_DIR);
+    for window in windows {
+        let Some(ref preset) = window.claude else { continue };
+        script.push_str(&format!(
+            "; {}; {} && f=$(ls -t \"$p\"/*.jsonl 2>/dev/null | head -n 1) && [ -n \"$f\" ] && tail -n {} \"$f\"",
+            section_script(&window.name),
+            transcript_dir_script(&preset.project),
+            TAIL_LINES
+        ));
+    }
+    script
+}
+
+/// Split per-window script output into lines per window name
+pub fn parse_sections(output: &str) -> HashMap<String, Vec<String>> {
+    let mut tails: HashMap<String, Vec<String>> = HashMap::new();
+    let mut current = None;
+    for line in output.lines() {
+        if let Some(name) = line.strip_prefix(SECTION) {
+            current = Some(name.to_string());
+            tails.entry(name.to_string()).or_default();
+        } else if let Some(ref name) = current {
+            tails.entry(name.clone()).or_default().push(line.to_string());
+        }
+    }
+    tails
+}
+
+/// Activity of each running claude window of `session`, by window name
+///
+/// Takes about a second, for sampling CPU use.
+pub fn activity(config: &Config, session: &str) -> Result<HashMap<String, Activity>> {
+    let windows: Vec<&WindowConfig> = config.windows.iter().filter(|w| w.claude.is_some()).collect();
+    if windows.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let busy = WslLauncher::new(&config.wsl_distribution).busy_windows(session, CPU_SAMPLE)?;
+    let output = Command::new("wsl.exe")
+        .args(["-d", &config.wsl_distribution, "--exec", "bash", "-c", &transcript_script(&windows)])
+        .output()
+        .map_err(LauncherError::WslRun)?;
+    let tails = parse_sections(&String::from_utf8_lossy(&output.stdout));
+
+    Ok(windows
+        .iter()
+        .map(|w| {
+            let turn = tails.get(&w.name).and_then(|lines| last_turn(lines));
+            (w.name.clone(), classify(turn, busy.contains(&w.name)))
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command() {
+        let preset = ClaudeConfig { project: "~/src/api".to_string(), ..Default::default() };
+        assert_eq!(command(&preset, &BTreeMap::new()), "claude");
+
+        let preset = ClaudeConfig {
+            model: Some("opus".to_string()),
+            permission_mode: Some("acceptEdits".to_string()),
+            resume: Some(ClaudeResume::Session("abc-123".to_string())),
+            args: vec!["--add-dir".to_string(), "../shared lib".to_string()],
+            ..preset
+        };
+        assert_eq!(
+            command(&preset, &BTreeMap::new()),
+            "claude --model opus --permission-mode acceptEdits --resume abc-123 --add-dir '../shared lib'"
+        );
+
+        let preset = ClaudeConfig { resume: Some(ClaudeResume::Pick(true)), ..Default::default() };
+        assert_eq!(command(&preset, &BTreeMap::new()), "claude --resume");
+        let preset = ClaudeConfig { continue_last: true, ..Default::default() };
+        assert_eq!(command(&preset, &BTreeMap::new()), "claude --continue");
+        let preset = ClaudeConfig { prompt: Some("Read plan.md\nthen start\n".to_string()), ..Default::default() };
+        assert_eq!(command(&preset, &BTreeMap::new()), "claude $'Read plan.md\\nthen start'");
+
+        let preset = ClaudeConfig { settings: Some(ClaudeSettings::File("~/.claude/reviewer.json".to_string())), ..Default::default() };
+        assert_eq!(command(&preset, &BTreeMap::new()), "claude --settings \"$HOME\"/.claude/reviewer.json");
+        let settings: ClaudeSettings = serde_yaml::from_str("permissions:\n  deny: [\"Edit\", \"Write\"]\n").unwrap();
+        let preset = ClaudeConfig { model: Some("haiku".to_string()), settings: Some(settings), ..Default::default() };
+        assert_eq!(
+            command(&preset, &BTreeMap::new()),
+            "claude --model haiku --settings '{\"permissions\":{\"deny\":[\"Edit\",\"Write\"]}}'"
+        );
+    }
+
+    #[test]
+    fn test_conversation_command() {
+        let preset = ClaudeConfig {
+            project: "~/src/api".to_string(),
+            model: Some("sonnet".to_string()),
+            resume: Some(ClaudeResume::Policy(crate::config::ResumePolicy::Always)),
+            ..Default::default()
+        };
+        let id = "0f8fad5b-d9cb-469f-a165-70867728950e";
+        assert_eq!(
+            conversation_command(&preset, &BTreeMap::new(), Conversation::Start(id)),
+            format!("claude --model sonnet --session-id {}", id)
+        );
+        let command = conversation_command(&preset, &BTreeMap::new(), Conversation::Continue(id));
+        assert!(command.starts_with(PROJECTS_DIR));
+        assert!(command.contains(&format!("&& [ -f \"$p\"/{}.jsonl ]; then claude --model sonnet --resume {};", id, id)));
+        assert!(command.ends_with(&format!("else claude --model sonnet --session-id {}; fi", id)));
+    }
+
+    #[test]
+    fn test_new_session_id() {
+        let id = new_session_id().unwrap();
+        assert_eq!(id.len(), 36);
+        assert_ne!(id, new_session_id().unwrap());
+    }
+
+    #[test]
+    fn test_quote_text() {
+        assert_eq!(quote_text("Be brief."), "'Be brief.'");
+        assert_eq!(quote_text("It's\nC:\\dir"), "$'It\\'s\\nC:\\\\dir'");
+    }
+
+    #[test]
+    fn test_role_prompt() {
+        let roles = BTreeMap::from([("reviewer".to_string(), "Only review.".to_string())]);
+        assert_eq!(role_prompt("reviewer", &roles), Some("Only review."));
+        assert!(role_prompt("architect", &roles).unwrap().starts_with("You are the architect"));
+        assert_eq!(role_prompt("tester", &roles), None);
+    }
+
+    #[test]
+    fn test_last_turn() {
+        let lines = |entries: &[&str]| entries.iter().map(|e| e.to_string()).collect::<Vec<_>>();
+        let prompt = r#"{"type":"user","message":{"role":"user","content":"fix the tests"}}"#;
+        let tool_use = r#"{"type":"assistant","message":{"content":[{"type":"text","text":"Running"},{"type":"tool_use","name":"Bash"}]}}"#;
+        let reply = r#"{"type":"assistant","message":{"content":[{"type":"text","text":"Done"}]}}"#;
+        let command = r#"{"type":"user","message":{"role":"user","content":"<command-name>/clear</command-name>"}}"#;
+        let summary = r#"{"type":"summary","summary":"Fix tests"}"#;
+
+        assert_eq!(last_turn(&lines(&[prompt])), Some(Turn::User));
+        assert_eq!(last_turn(&lines(&[prompt, tool_use])), Some(Turn::ToolUse));
+        assert_eq!(last_turn(&lines(&[prompt, reply, summary])), Some(Turn::Reply));
+        assert_eq!(last_turn(&lines(&[reply, command, "not json"])), Some(Turn::Reply));
+        assert_eq!(last_turn(&lines(&[])), None);
+    }
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(classify(Some(Turn::Reply), true), Activity::Busy);
+        assert_eq!(classify(Some(Turn::User), false), Activity::Busy);
+        assert_eq!(classify(Some(Turn::ToolUse), false), Activity::Waiting);
+        assert_eq!(classify(Some(Turn::Reply), false), Activity::Idle);
+        assert_eq!(classify(None, false), Activity::Idle);
+    }
+
+    #[test]
+    fn test_status_title() {
+        let mut window = WindowConfig {
+            name: "impl-auth".to_string(),
+            claude: Some(ClaudeConfig { model: Some("sonnet".to_string()), ..Default::default() }),
+            ..Default::default()
+        };
+        assert_eq!(status_title(&window, Some(Activity::Busy)), "impl-auth [sonnet] ●working");
+        window.worktree = Some(crate::config::Worktree {
+            repo: "~/app".to_string(),
+            branch: "feature/auth".to_string(),
+            base: None,
+            path: "~/app-worktrees/impl-auth".to_string(),
+        });
+        assert_eq!(status_title(&window, Some(Activity::Idle)), "impl-auth [sonnet] feature/auth ○idle");
+        window.claude = Some(ClaudeConfig::default());
+        assert_eq!(status_title(&window, None), "impl-auth feature/auth");
+    }
+
+    #[test]
+    fn test_parse_sections() {
+        let output = "==wml== api\n{\"a\":1}\n{\"b\":2}\n==wml== web\n";
+        let tails = parse_sections(output);
+        assert_eq!(tails["api"], vec![r#"{"a":1}"#, r#"{"b":2}"#]);
+        assert!(tails["web"].is_empty());
+    }
+}
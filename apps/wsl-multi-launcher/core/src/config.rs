@@ -0,0 +1,2778 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::claude;
+use crate::error::{LauncherError, Result};
+use crate::hotkey;
+use crate::queue;
+use crate::worktree;
+
+/// Main configuration structure
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Config {
+    /// WSL distribution name (e.g., "Ubuntu-24.04")
+    pub wsl_distribution: String,
+
+    /// Target display index (0 = primary, 1 = secondary, etc.)
+    #[serde(default)]
+    pub target_display: u32,
+
+    /// Layout configuration
+    pub layout: LayoutConfig,
+
+    /// Window configurations
+    #[serde(default)]
+    pub windows: Vec<WindowConfig>,
+
+    /// Pass working directories via `wsl.exe --cd` (disable for older WSL versions)
+    #[serde(default = "default_true")]
+    pub use_wsl_cd: bool,
+
+    /// Terminal emulator used to host the sessions
+    #[serde(default)]
+    pub terminal: TerminalKind,
+
+    /// Restart policy for `watch`
+    #[serde(default)]
+    pub watch: WatchConfig,
+
+    /// How `stop` and `restart` shut windows down
+    #[serde(default)]
+    pub stop: StopConfig,
+
+    /// Output logging of WSL windows
+    #[serde(default)]
+    pub logs: LogConfig,
+
+    /// Commands run around launching, arranging and stopping the workspace
+    #[serde(default)]
+    pub hooks: HooksConfig,
+
+    /// Global hotkeys registered while `watch` runs
+    #[serde(default)]
+    pub hotkeys: HotkeysConfig,
+
+    /// Projects filled into `project_template`, one window each
+    #[serde(default)]
+    pub projects: Vec<ProjectEntry>,
+
+    /// Window of every `projects` entry; `{repo}`, `{name}`, `{branch}` and
+    /// `{task}` in its strings stand for the entry's values
+    #[serde(default)]
+    pub project_template: Option<serde_yaml::Value>,
+
+    /// One claude window per branch, each in its own git worktree
+    #[serde(default)]
+    pub worktrees: Option<WorktreesConfig>,
+
+    /// System prompts by role name for `claude.role`, adding to or
+    /// overriding the built-in roles
+    #[serde(default)]
+    pub roles: BTreeMap<String, String>,
+
+    /// Worker windows running claude on task files from a shared directory
+    #[serde(default)]
+    pub queue: Option<QueueConfig>,
+
+    /// Request budget the claude windows share
+    #[serde(default)]
+    pub rate_limit: Option<RateLimitConfig>,
+
+    /// Short commands expanding to a command line, e.g. `down: stop --all`
+    #[serde(default)]
+    pub aliases: BTreeMap<String, String>,
+}
+
+/// Work-stealing pool of claude windows (`queue:`)
+///
+/// Each worker window takes the next file from `<dir>/pending`, runs
+/// `claude -p` on it and moves it to `done` or `failed` with its output.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueueConfig {
+    /// Queue directory inside the WSL distribution
+    pub dir: String,
+
+    /// Number of worker windows
+    #[serde(default = "default_queue_workers")]
+    pub workers: u32,
+
+    /// Seconds a worker waits before looking for tasks again
+    #[serde(default = "default_queue_poll_secs")]
+    pub poll_secs: u64,
+
+    /// Claude options of the workers; `project` is where tasks run
+    pub claude: ClaudeConfig,
+}
+
+fn default_queue_workers() -> u32 {
+    2
+}
+
+fn default_queue_poll_secs() -> u64 {
+    5
+}
+
+/// Queue a window works for (set for windows from `queue:`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueueWorker {
+    pub dir: String,
+    pub poll_secs: u64,
+}
+
+/// Request budget shared by the claude windows (`rate_limit:`)
+///
+/// Near a limit, `dispatch` and `broadcast` hold back the next prompt and
+/// `watch` pauses the queue workers, so parallel agents don't all run
+/// into the API's rate limits at once.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateLimitConfig {
+    /// API requests per minute across all windows
+    #[serde(default)]
+    pub requests_per_minute: Option<u64>,
+
+    /// Input and output tokens per minute across all windows
+    #[serde(default)]
+    pub tokens_per_minute: Option<u64>,
+
+    /// Share of a limit (0-1] from which new work is held back
+    #[serde(default = "default_rate_limit_threshold")]
+    pub threshold: f64,
+
+    /// Seconds between prompts sent to different windows
+    #[serde(default = "default_rate_limit_stagger")]
+    pub stagger_secs: u64,
+}
+
+fn default_rate_limit_threshold() -> f64 {
+    0.8
+}
+
+fn default_rate_limit_stagger() -> u64 {
+    5
+}
+
+/// Entry of `projects`: a repo path, or a repo with a window name, branch
+/// and task
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ProjectEntry {
+    Repo(String),
+    Detailed {
+        repo: String,
+        #[serde(default)]
+        name: Option<String>,
+        #[serde(default)]
+        branch: Option<String>,
+        #[serde(default)]
+        task: Option<String>,
+    },
+}
+
+/// Variables of `project_template`
+const PROJECT_VARIABLES: &[&str] = &["repo", "name", "branch", "task"];
+
+impl ProjectEntry {
+    pub fn repo(&self) -> &str {
+        match self {
+            ProjectEntry::Repo(repo) | ProjectEntry::Detailed { repo, .. } => repo,
+        }
+    }
+
+    /// Window name; defaults to the repo's directory name
+    pub fn name(&self) -> String {
+        match self {
+            ProjectEntry::Detailed { name: Some(name), .. } => name.clone(),
+            _ => self.repo().trim_end_matches('/').rsplit('/').next().unwrap_or_default().to_string(),
+        }
+    }
+
+    /// Value of a template variable; None if the entry doesn't set it
+    fn variable(&self, variable: &str) -> Option<String> {
+        match (variable, self) {
+            ("repo", _) => Some(self.repo().to_string()),
+            ("name", _) => Some(self.name()),
+            ("branch", ProjectEntry::Detailed { branch, .. }) => branch.clone(),
+            ("task", ProjectEntry::Detailed { task, .. }) => task.clone(),
+            _ => None,
+        }
+    }
+
+    /// Replace the variables in `text` with the entry's values; other
+    /// braces (e.g. `${HOME}`) are left alone
+    fn fill(&self, text: &str) -> Result<String> {
+        let mut out = String::new();
+        let mut rest = text;
+        while let Some(start) = rest.find('{') {
+            out.push_str(&rest[..start]);
+            let after = &rest[start + 1..];
+            match after.find('}').map(|end| &after[..end]) {
+                Some(variable) if PROJECT_VARIABLES.contains(&variable) => {
+                    let value = self.variable(variable).ok_or_else(|| {
+                        LauncherError::InvalidConfig(format!("{{{}}} is used but not set for this project", variable))
+                    })?;
+                    out.push_str(&value);
+                    rest = &after[variable.len() + 1..];
+                }
+                _ => {
+                    out.push('{');
+                    rest = after;
+                }
+            }
+        }
+        out.push_str(rest);
+        Ok(out)
+    }
+
+    /// Fill the variables in every string of a template
+    fn fill_value(&self, value: &mut serde_yaml::Value) -> Result<()> {
+        match value {
+            serde_yaml::Value::String(text) => *text = self.fill(text)?,
+            serde_yaml::Value::Sequence(items) => {
+                for item in items {
+                    self.fill_value(item)?;
+                }
+            }
+            serde_yaml::Value::Mapping(map) => {
+                for (_, item) in map.iter_mut() {
+                    self.fill_value(item)?;
+                }
+            }
+            serde_yaml::Value::Tagged(tagged) => self.fill_value(&mut tagged.value)?,
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// Worktree-per-window workspace (`worktrees:`)
+///
+/// Each branch becomes a `claude:` window working in
+/// `<dir>/<window name>`, a git worktree of `repo` created on launch.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorktreesConfig {
+    /// Repository the worktrees are added to
+    pub repo: String,
+
+    /// Directory holding the worktrees (default: `<repo>-worktrees`)
+    #[serde(default)]
+    pub dir: Option<String>,
+
+    /// Commit new branches start from (default: the repo's HEAD)
+    #[serde(default)]
+    pub base: Option<String>,
+
+    /// Claude options of every worktree window (`project` is set per window)
+    #[serde(default)]
+    pub claude: ClaudeConfig,
+
+    /// Branches, one window each
+    pub branches: Vec<WorktreeBranch>,
+
+    /// Extra window summarizing the changes of every worktree
+    #[serde(default)]
+    pub summary: Option<WorktreeSummary>,
+
+    /// Files put into every worktree before its window launches
+    #[serde(default)]
+    pub provision: Vec<ProvisionFile>,
+}
+
+impl WorktreesConfig {
+    /// Directory holding the worktrees
+    pub fn dir(&self) -> String {
+        self.dir
+            .clone()
+            .unwrap_or_else(|| format!("{}-worktrees", self.repo.trim_end_matches('/')))
+    }
+}
+
+/// Window of `worktrees.summary`, re-running a command in every worktree
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorktreeSummary {
+    /// Window name
+    #[serde(default = "default_summary_name")]
+    pub name: String,
+
+    /// Command run in each worktree; `$WT_NAME`, `$WT_BRANCH` and `$WT_BASE`
+    /// are the window name, branch and base
+    #[serde(default = "default_summary_command")]
+    pub command: String,
+
+    /// Seconds between refreshes
+    #[serde(default = "default_summary_interval")]
+    pub interval_secs: u64,
+}
+
+fn default_summary_name() -> String {
+    "changes".to_string()
+}
+
+fn default_summary_command() -> String {
+    "git diff --stat \"$(git merge-base \"$WT_BASE\" HEAD)\"".to_string()
+}
+
+fn default_summary_interval() -> u64 {
+    10
+}
+
+impl Default for WorktreeSummary {
+    // Keep in sync with the serde defaults
+    fn default() -> Self {
+        Self {
+            name: default_summary_name(),
+            command: default_summary_command(),
+            interval_secs: default_summary_interval(),
+        }
+    }
+}
+
+/// Entry of `worktrees.branches`: a branch name, or a branch with a window
+/// name and optionally the prompt its session starts with
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum WorktreeBranch {
+    Branch(String),
+    Named {
+        branch: String,
+        name: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        prompt: Option<String>,
+    },
+}
+
+impl WorktreeBranch {
+    pub fn branch(&self) -> &str {
+        match self {
+            WorktreeBranch::Branch(branch) | WorktreeBranch::Named { branch, .. } => branch,
+        }
+    }
+
+    /// Window (and worktree directory) name; defaults to the branch with
+    /// '/' replaced by '-'
+    pub fn name(&self) -> String {
+        match self {
+            WorktreeBranch::Branch(branch) => branch.replace('/', "-"),
+            WorktreeBranch::Named { name, .. } => name.clone(),
+        }
+    }
+
+    /// Prompt of this branch's session, replacing `worktrees.claude.prompt`
+    pub fn prompt(&self) -> Option<&str> {
+        match self {
+            WorktreeBranch::Named { prompt, .. } => prompt.as_deref(),
+            WorktreeBranch::Branch(_) => None,
+        }
+    }
+}
+
+/// Entry of a window's `provision:`: a file (or glob) put into the working
+/// directory, or one with the directory to put it in and whether to link it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ProvisionFile {
+    Path(String),
+    Entry {
+        from: String,
+        /// Directory relative to the working directory (default: the
+        /// working directory itself)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        to: Option<String>,
+        /// Symlink instead of copying, so edits to the source show up
+        #[serde(default)]
+        link: bool,
+    },
+}
+
+impl ProvisionFile {
+    pub fn from(&self) -> &str {
+        match self {
+            ProvisionFile::Path(from) | ProvisionFile::Entry { from, .. } => from,
+        }
+    }
+
+    pub fn to(&self) -> &str {
+        match self {
+            ProvisionFile::Entry { to: Some(to), .. } => to,
+            _ => ".",
+        }
+    }
+
+    pub fn link(&self) -> bool {
+        matches!(self, ProvisionFile::Entry { link: true, .. })
+    }
+}
+
+/// Git worktree a window works in (set for windows from `worktrees:`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Worktree {
+    pub repo: String,
+    pub branch: String,
+    pub base: Option<String>,
+    pub path: String,
+}
+
+/// Global hotkeys handled by `watch` (keys like "Win+Alt+R")
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HotkeysConfig {
+    /// Modifiers that, with digit N, focus the Nth window (e.g. "Win+Alt"
+    /// makes Win+Alt+1..9 focus windows 1..9)
+    #[serde(default)]
+    pub focus: Option<String>,
+
+    /// Move every window back into its cell
+    #[serde(default)]
+    pub arrange: Option<String>,
+}
+
+/// Commands run at points of the launcher's lifecycle
+///
+/// Each hook takes one command or a list, run in order. A failing
+/// `pre_launch` hook stops the launch; failures of the others are only
+/// reported.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HooksConfig {
+    /// Before windows are launched
+    #[serde(default, deserialize_with = "one_or_many")]
+    pub pre_launch: Vec<HookCommand>,
+
+    /// After windows are launched
+    #[serde(default, deserialize_with = "one_or_many")]
+    pub post_launch: Vec<HookCommand>,
+
+    /// After windows are moved into their cells
+    #[serde(default, deserialize_with = "one_or_many")]
+    pub post_arrange: Vec<HookCommand>,
+
+    /// Before windows are closed by `stop` / `restart`
+    #[serde(default, deserialize_with = "one_or_many")]
+    pub pre_stop: Vec<HookCommand>,
+}
+
+/// A hook command: a plain string runs with bash in the WSL distribution,
+/// `{ windows: "..." }` runs with PowerShell on Windows
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum HookCommand {
+    Wsl(String),
+    Windows { windows: String },
+}
+
+impl HookCommand {
+    pub fn command(&self) -> &str {
+        match self {
+            HookCommand::Wsl(command) | HookCommand::Windows { windows: command } => command,
+        }
+    }
+}
+
+/// Accept a single hook command as well as a list
+fn one_or_many<'de, D>(deserializer: D) -> std::result::Result<Vec<HookCommand>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(HookCommand),
+        Many(Vec<HookCommand>),
+    }
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(command) => vec![command],
+        OneOrMany::Many(commands) => commands,
+    })
+}
+
+/// Per-window output logs (see `logs` subcommand)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogConfig {
+    /// Record each WSL window's output to a log file
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// A log bigger than this is rotated when its window starts
+    #[serde(default = "default_log_max_size")]
+    pub max_size_mb: u64,
+
+    /// Rotated logs to keep (`<window>.log.1` ... `.N`)
+    #[serde(default = "default_log_keep")]
+    pub keep: u32,
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        // Keep in sync with the serde defaults
+        Self {
+            enabled: false,
+            max_size_mb: default_log_max_size(),
+            keep: default_log_keep(),
+        }
+    }
+}
+
+fn default_log_max_size() -> u64 {
+    10
+}
+
+fn default_log_keep() -> u32 {
+    3
+}
+
+/// Shutdown behaviour of `stop` / `restart`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StopConfig {
+    /// Seconds to wait after SIGTERM before closing the window (0 = close right away)
+    #[serde(default = "default_stop_grace")]
+    pub grace_secs: u64,
+
+    /// Archive the transcripts of stopped `claude:` windows
+    #[serde(default)]
+    pub archive_transcripts: bool,
+}
+
+impl Default for StopConfig {
+    fn default() -> Self {
+        // Keep in sync with the serde defaults
+        Self { grace_secs: default_stop_grace(), archive_transcripts: false }
+    }
+}
+
+fn default_stop_grace() -> u64 {
+    5
+}
+
+/// Restart policy used by the `watch` supervisor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchConfig {
+    /// Seconds between checks
+    #[serde(default = "default_watch_interval")]
+    pub interval_secs: u64,
+
+    /// Restarts per window before giving up on it
+    #[serde(default = "default_max_restarts")]
+    pub max_restarts: u32,
+
+    /// Delay before the first restart; doubles with every further restart
+    #[serde(default = "default_backoff")]
+    pub backoff_secs: u64,
+
+    /// A window whose processes used no CPU for this long is idle (e.g. "30m")
+    #[serde(default)]
+    pub idle_after: Option<String>,
+
+    /// What to do with idle windows
+    #[serde(default)]
+    pub idle_action: IdleAction,
+
+    /// Serve Prometheus metrics on this address (e.g. "127.0.0.1:9464")
+    #[serde(default)]
+    pub metrics_addr: Option<String>,
+
+    /// Raise a Windows toast when a window's command exits or a claude
+    /// session waits for a permission answer
+    #[serde(default)]
+    pub notify: bool,
+
+    /// Keep the titles of claude windows showing their model, branch and
+    /// activity (e.g. `api [sonnet] ●working`)
+    #[serde(default)]
+    pub status_titles: bool,
+}
+
+impl WatchConfig {
+    /// Idle threshold, if idle detection is enabled
+    pub fn idle_after(&self) -> Result<Option<Duration>> {
+        self.idle_after.as_deref().map(parse_duration).transpose()
+    }
+}
+
+/// What `watch` does with a window that has been idle for `idle_after`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum IdleAction {
+    /// Mark it idle in `ps`
+    #[default]
+    Flag,
+    /// Close it (and don't relaunch it)
+    Close,
+}
+
+impl Default for WatchConfig {
+    fn default() -> Self {
+        // Keep in sync with the serde defaults
+        Self {
+            interval_secs: default_watch_interval(),
+            max_restarts: default_max_restarts(),
+            backoff_secs: default_backoff(),
+            idle_after: None,
+            idle_action: IdleAction::Flag,
+            metrics_addr: None,
+            notify: false,
+            status_titles: false,
+        }
+    }
+}
+
+fn default_watch_interval() -> u64 {
+    5
+}
+
+fn default_max_restarts() -> u32 {
+    5
+}
+
+fn default_backoff() -> u64 {
+    2
+}
+
+/// Supported terminal emulators
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TerminalKind {
+    #[default]
+    #[serde(alias = "wt")]
+    WindowsTerminal,
+    Alacritty,
+    Wezterm,
+    /// Plain console windows (no Windows Terminal required)
+    Conhost,
+}
+
+/// What a window runs: a WSL shell or a native Windows program
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WindowKind {
+    #[default]
+    Wsl,
+    /// Windows PowerShell (`powershell.exe`)
+    Powershell,
+    /// Command Prompt (`cmd.exe`)
+    Cmd,
+    /// Any Windows executable given by `command`, with `args`
+    WindowsExe,
+}
+
+/// Layout configuration
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LayoutConfig {
+    /// Grid format (e.g., "2x4" for 2 columns, 4 rows)
+    pub grid: String,
+
+    /// How sessions are laid out on screen
+    #[serde(default)]
+    pub mode: LaunchMode,
+}
+
+/// How configured windows are presented
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum LaunchMode {
+    /// One Windows Terminal window per session, arranged in the grid
+    #[default]
+    Windows,
+    /// One Windows Terminal window with a tab per session
+    Tabs,
+    /// One Windows Terminal window split into a pane per grid cell
+    Panes,
+}
+
+impl Config {
+    /// Grid cell of each window, or None for dropdown windows
+    ///
+    /// Dropdown windows don't take a cell, so the remaining windows fill the
+    /// grid in order without gaps.
+    pub fn grid_slots(&self) -> Vec<Option<usize>> {
+        let mut next = 0;
+        self.windows
+            .iter()
+            .map(|w| {
+                (!w.dropdown).then(|| {
+                    next += 1;
+                    next - 1
+                })
+            })
+            .collect()
+    }
+
+    /// Lay the windows matched by `filter` out in `grid` instead, filling
+    /// its cells in order; the other windows are dropped
+    pub fn regrid(&mut self, grid: &str, filter: &WindowFilter) -> Result<()> {
+        self.windows.retain(|w| filter.matches(w));
+        self.layout.grid = grid.to_string();
+        let (cols, rows) = self.layout.parse_grid()?;
+        let windows = self.windows.iter().filter(|w| !w.dropdown).count();
+        if windows > (cols * rows) as usize {
+            return Err(LauncherError::TooManyWindows { windows, grid: grid.to_string(), cells: cols * rows });
+        }
+        Ok(())
+    }
+}
+
+/// Windows a command acts on, picked by name and tag
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "clap", derive(clap::Args))]
+pub struct WindowFilter {
+    /// Only these windows (comma-separated names), in their cells, leaving the others as they are
+    #[cfg_attr(feature = "clap", arg(long, value_delimiter = ',', value_name = "NAMES"))]
+    pub only: Vec<String>,
+
+    /// Skip these windows (comma-separated names)
+    #[cfg_attr(feature = "clap", arg(long, value_delimiter = ',', value_name = "NAMES"))]
+    pub exclude: Vec<String>,
+
+    /// Skip windows with any of these tags (comma-separated)
+    #[cfg_attr(feature = "clap", arg(long, value_delimiter = ',', value_name = "TAGS"))]
+    pub exclude_tags: Vec<String>,
+}
+
+impl WindowFilter {
+    pub fn is_empty(&self) -> bool {
+        self.only.is_empty() && self.exclude.is_empty() && self.exclude_tags.is_empty()
+    }
+
+    pub fn matches(&self, window: &WindowConfig) -> bool {
+        (self.only.is_empty() || self.only.contains(&window.name))
+            && !self.exclude.contains(&window.name)
+            && !window.tags.iter().any(|t| self.exclude_tags.contains(t))
+    }
+
+    /// Fail on names that aren't in `config` (a typo would otherwise pick
+    /// nothing, or everything) and when no window is left
+    pub fn check(&self, config: &Config) -> Result<()> {
+        for name in self.only.iter().chain(&self.exclude) {
+            if !config.windows.iter().any(|w| &w.name == name) {
+                return Err(LauncherError::UnknownWindow(name.clone()));
+            }
+        }
+        if !self.is_empty() && !config.windows.iter().any(|w| self.matches(w)) {
+            return Err(LauncherError::NoWindowsLeft);
+        }
+        Ok(())
+    }
+}
+
+impl LayoutConfig {
+    /// Parse grid string into (columns, rows)
+    pub fn parse_grid(&self) -> Result<(u32, u32)> {
+        let invalid = || LauncherError::InvalidGrid { grid: self.grid.clone() };
+        let (cols, rows) = self.grid.split_once('x').ok_or_else(invalid)?;
+        Ok((cols.parse().map_err(|_| invalid())?, rows.parse().map_err(|_| invalid())?))
+    }
+}
+
+/// Individual window configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WindowConfig {
+    /// Window name/identifier
+    pub name: String,
+
+    /// Labels for selecting groups of windows (e.g. `broadcast --tags`)
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    /// What the window runs (defaults to a WSL shell)
+    #[serde(rename = "type", default)]
+    pub kind: WindowKind,
+
+    /// Arguments for a `windows-exe` window's executable
+    #[serde(default)]
+    pub args: Vec<String>,
+
+    /// Quake-style scratch window: launched hidden over the top half of the
+    /// display instead of taking a grid cell, shown with `toggle <name>`
+    #[serde(default)]
+    pub dropdown: bool,
+
+    /// Command to execute in the window (defaults to "bash")
+    #[serde(default)]
+    pub command: Option<String>,
+
+    /// Sequence of commands to execute in order (alternative to `command`)
+    #[serde(default)]
+    pub commands: Vec<String>,
+
+    /// Keep running the remaining `commands` when one fails (`;` instead of `&&`)
+    #[serde(default)]
+    pub continue_on_error: bool,
+
+    /// Working directory (supports ~ for home)
+    #[serde(default)]
+    pub working_dir: Option<String>,
+
+    /// Drop to an interactive shell after the command exits
+    #[serde(default)]
+    pub keep_open: bool,
+
+    /// Always close the window after the command exits, even on failure
+    #[serde(default)]
+    pub close_on_exit: bool,
+
+    /// Run the command in a login shell (`bash -lc`) so profile files are sourced
+    #[serde(default)]
+    pub login_shell: bool,
+
+    /// Windows Terminal profile to launch with (`wt -p`), for its fonts/colors
+    #[serde(default)]
+    pub wt_profile: Option<String>,
+
+    /// Windows Terminal color scheme name (`--colorScheme`)
+    #[serde(default)]
+    pub color_scheme: Option<String>,
+
+    /// Tab color as "#RGB" or "#RRGGBB" (`--tabColor`)
+    #[serde(default)]
+    pub tab_color: Option<String>,
+
+    /// Keep the window title fixed to `name` even if the shell rewrites it
+    /// (`--suppressApplicationTitle`), so title matching stays deterministic
+    #[serde(default = "default_true")]
+    pub suppress_app_title: bool,
+
+    /// Launch in Windows Terminal focus mode (`--focus`)
+    #[serde(default)]
+    pub wt_focus: bool,
+
+    /// Launch maximized (`-M`); the window is not moved into its grid cell
+    #[serde(default)]
+    pub wt_maximized: bool,
+
+    /// Launch fullscreen (`-F`); the window is not moved into its grid cell
+    #[serde(default)]
+    pub wt_fullscreen: bool,
+
+    /// Font override, applied through the generated Windows Terminal profile
+    #[serde(default)]
+    pub font: Option<FontConfig>,
+
+    /// Launch the terminal elevated (administrator); shows a UAC prompt
+    #[serde(default)]
+    pub elevated: bool,
+
+    /// Run the command inside this tmux session, reattaching if it survives
+    #[serde(default)]
+    pub tmux_session: Option<String>,
+
+    /// Run the command inside this Zellij session, reattaching if it survives
+    #[serde(default)]
+    pub zellij_session: Option<String>,
+
+    /// Zellij layout file used when the session is first created
+    #[serde(default)]
+    pub zellij_layout: Option<String>,
+
+    /// Probe run by `watch` to check the window's program is healthy
+    #[serde(default)]
+    pub healthcheck: Option<HealthCheckConfig>,
+
+    /// Commands run around launching, arranging and stopping this window
+    #[serde(default)]
+    pub hooks: HooksConfig,
+
+    /// Global hotkey that focuses this window while `watch` runs
+    #[serde(default)]
+    pub hotkey: Option<String>,
+
+    /// Files copied or linked into the working directory before launch
+    /// (slash commands, CLAUDE.md, ...)
+    #[serde(default)]
+    pub provision: Vec<ProvisionFile>,
+
+    /// Claude Code session on a project; expands into `command` and `working_dir`
+    #[serde(default)]
+    pub claude: Option<ClaudeConfig>,
+
+    /// Worktree of a window generated from `worktrees:`, created on launch
+    #[serde(skip)]
+    pub worktree: Option<Worktree>,
+
+    /// Queue of a worker window generated from `queue:`
+    #[serde(skip)]
+    pub queue: Option<QueueWorker>,
+}
+
+/// Claude Code session preset of a window (`claude:`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ClaudeConfig {
+    /// Project directory the session works on; becomes the working directory
+    #[serde(default)]
+    pub project: String,
+
+    /// Model (`--model`), e.g. "opus" or "sonnet"
+    #[serde(default)]
+    pub model: Option<String>,
+
+    /// Permission mode (`--permission-mode`), e.g. "plan" or "acceptEdits"
+    #[serde(default)]
+    pub permission_mode: Option<String>,
+
+    /// Claude settings of this window (`--settings`): a settings file, or
+    /// the settings themselves (permissions, env, ...)
+    #[serde(default)]
+    pub settings: Option<ClaudeSettings>,
+
+    /// Continue the most recent conversation in the project (`--continue`)
+    #[serde(default, rename = "continue")]
+    pub continue_last: bool,
+
+    /// `true` to pick a conversation to resume, a session ID (`--resume`),
+    /// or whether to continue the conversation recorded for the window
+    /// (`always`, `ask`, `never`)
+    #[serde(default)]
+    pub resume: Option<ClaudeResume>,
+
+    /// Role whose system prompt is appended (`roles:` or a built-in role
+    /// such as "architect", "implementer" or "reviewer")
+    #[serde(default)]
+    pub role: Option<String>,
+
+    /// Text appended to claude's system prompt (`--append-system-prompt`),
+    /// after the role's prompt
+    #[serde(default)]
+    pub append_system_prompt: Option<String>,
+
+    /// Extra arguments passed to `claude` as-is
+    #[serde(default)]
+    pub args: Vec<String>,
+
+    /// Prompt the session starts working on (claude's initial prompt); sent
+    /// again whenever the window is relaunched
+    #[serde(default)]
+    pub prompt: Option<String>,
+
+    /// Tokens / cost the window may use per period before `watch` warns
+    #[serde(default)]
+    pub budget: Option<BudgetConfig>,
+}
+
+/// Usage budget of one claude window (`claude.budget`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BudgetConfig {
+    /// Input, cache write and output tokens (cache reads don't count)
+    #[serde(default)]
+    pub tokens: Option<u64>,
+
+    /// Estimated cost in USD
+    #[serde(default)]
+    pub cost_usd: Option<f64>,
+
+    /// Period the usage is counted over, ending now (e.g. "24h", "168h")
+    #[serde(default = "default_budget_period")]
+    pub period: String,
+
+    /// Stop `dispatch` from sending tasks to the window while it is over
+    #[serde(default)]
+    pub pause_dispatch: bool,
+}
+
+fn default_budget_period() -> String {
+    "24h".to_string()
+}
+
+impl BudgetConfig {
+    pub fn period(&self) -> Result<Duration> {
+        parse_duration(&self.period)
+    }
+}
+
+/// Value of `claude.settings`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ClaudeSettings {
+    /// Path of a settings JSON file
+    File(String),
+    /// Settings written out in the config, passed to claude as JSON
+    Inline(serde_json::Map<String, serde_json::Value>),
+}
+
+/// Value of `claude.resume`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ClaudeResume {
+    /// `true` opens claude's picker of past conversations
+    Pick(bool),
+    /// Continue the window's recorded conversation on relaunch
+    Policy(ResumePolicy),
+    /// Resume this session ID
+    Session(String),
+}
+
+/// Whether a window continues the claude conversation recorded for it in
+/// the state file when it is launched again
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResumePolicy {
+    /// Always continue it
+    Always,
+    /// Ask on the terminal first (continue without asking if there is none)
+    Ask,
+    /// Start a new conversation every time
+    Never,
+}
+
+impl ClaudeResume {
+    /// Policy for the recorded conversation, if it is one other than `never`
+    pub fn recorded_policy(&self) -> Option<ResumePolicy> {
+        match self {
+            ClaudeResume::Policy(policy) if *policy != ResumePolicy::Never => Some(*policy),
+            _ => None,
+        }
+    }
+}
+
+/// Health probe for a window, run inside the WSL distribution
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthCheckConfig {
+    /// Shell command; exit status 0 means healthy (e.g. "curl -sf localhost:3000")
+    pub command: String,
+
+    /// Time between probes (e.g. "30s", "5m")
+    #[serde(default = "default_health_interval")]
+    pub interval: String,
+
+    /// A probe running longer than this counts as failed
+    #[serde(default = "default_health_timeout")]
+    pub timeout: String,
+
+    /// Consecutive failures before the window is marked unhealthy
+    #[serde(default = "default_health_retries")]
+    pub retries: u32,
+
+    /// Restart the window once it is unhealthy instead of only flagging it
+    #[serde(default)]
+    pub restart: bool,
+}
+
+impl HealthCheckConfig {
+    pub fn interval(&self) -> Result<Duration> {
+        parse_duration(&self.interval)
+    }
+
+    pub fn timeout(&self) -> Result<Duration> {
+        parse_duration(&self.timeout)
+    }
+}
+
+fn default_health_interval() -> String {
+    "30s".to_string()
+}
+
+fn default_health_timeout() -> String {
+    "10s".to_string()
+}
+
+fn default_health_retries() -> u32 {
+    3
+}
+
+/// Parse a duration such as "30s", "5m", "1h" or a bare number of seconds
+pub fn parse_duration(s: &str) -> Result<Duration> {
+    let s = s.trim();
+    let (number, unit) = match s.find(|c: char| !c.is_ascii_digit()) {
+        Some(pos) => s.split_at(pos),
+        None => (s, "s"),
+    };
+    let value: u64 = number.parse().map_err(|_| LauncherError::InvalidDuration {
+        value: s.to_string(),
+        reason: "expected e.g. \"30s\", \"5m\"",
+    })?;
+    let secs = match unit.trim() {
+        "s" => value,
+        "m" => value * 60,
+        "h" => value * 3600,
+        _ => {
+            return Err(LauncherError::InvalidDuration { value: s.to_string(), reason: "unit must be s, m or h" });
+        }
+    };
+    Ok(Duration::from_secs(secs))
+}
+
+/// Font settings for a window
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FontConfig {
+    /// Font face (e.g., "Cascadia Mono")
+    #[serde(default)]
+    pub face: Option<String>,
+
+    /// Font size in points
+    #[serde(default)]
+    pub size: Option<f32>,
+}
+
+impl Default for WindowConfig {
+    fn default() -> Self {
+        // Keep in sync with the serde defaults
+        Self {
+            name: String::new(),
+            tags: Vec::new(),
+            command: None,
+            commands: Vec::new(),
+            continue_on_error: false,
+            working_dir: None,
+            keep_open: false,
+            close_on_exit: false,
+            login_shell: false,
+            wt_profile: None,
+            color_scheme: None,
+            tab_color: None,
+            suppress_app_title: true,
+            wt_focus: false,
+            wt_maximized: false,
+            wt_fullscreen: false,
+            font: None,
+            elevated: false,
+            tmux_session: None,
+            zellij_session: None,
+            zellij_layout: None,
+            healthcheck: None,
+            hooks: HooksConfig::default(),
+            hotkey: None,
+            provision: Vec::new(),
+            claude: None,
+            worktree: None,
+            queue: None,
+            kind: WindowKind::Wsl,
+            args: Vec::new(),
+            dropdown: false,
+        }
+    }
+}
+
+impl WindowConfig {
+    /// Commands to run in the window, in order
+    pub fn steps(&self) -> Vec<String> {
+        if !self.commands.is_empty() {
+            self.commands.clone()
+        } else {
+            vec![self.command.clone().unwrap_or_else(default_command)]
+        }
+    }
+
+    /// Separator used to join `steps()` into a single shell command
+    pub fn step_separator(&self) -> &'static str {
+        if self.continue_on_error { "; " } else { " && " }
+    }
+
+    /// Whether the window has any of `tags` (always true if `tags` is empty)
+    pub fn has_any_tag(&self, tags: &[String]) -> bool {
+        tags.is_empty() || tags.iter().any(|t| self.tags.contains(t))
+    }
+
+    /// Whether the window manages its own size and should not be arranged
+    pub fn skips_arrange(&self) -> bool {
+        self.wt_maximized || self.wt_fullscreen
+    }
+
+    /// Human readable summary of the command(s)
+    pub fn command_summary(&self) -> String {
+        match self.kind {
+            WindowKind::Wsl => self.steps().join(self.step_separator()),
+            WindowKind::WindowsExe => std::iter::once(self.command.clone().unwrap_or_default())
+                .chain(self.args.iter().cloned())
+                .collect::<Vec<_>>()
+                .join(" "),
+            WindowKind::Powershell | WindowKind::Cmd => {
+                let shell = if self.kind == WindowKind::Cmd { "cmd" } else { "powershell" };
+                match self.native_command() {
+                    Some(command) => format!("[{}] {}", shell, command),
+                    None => format!("[{}]", shell),
+                }
+            }
+        }
+    }
+
+    /// Command for a PowerShell/cmd window, or None for an interactive shell
+    pub fn native_command(&self) -> Option<String> {
+        if !self.commands.is_empty() {
+            let separator = match (self.kind, self.continue_on_error) {
+                (WindowKind::Cmd, false) => " && ",
+                (WindowKind::Cmd, true) => " & ",
+                _ => "; ",
+            };
+            Some(self.commands.join(separator))
+        } else {
+            self.command.clone()
+        }
+    }
+
+    /// Windows working directory for a native window (WSL windows cd inside WSL)
+    pub fn native_working_dir(&self) -> Option<&str> {
+        match self.kind {
+            WindowKind::Wsl => None,
+            _ => self.working_dir.as_deref(),
+        }
+    }
+}
+
+fn default_command() -> String {
+    "bash".to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Load configuration from a YAML file
+pub fn load<P: AsRef<Path>>(path: P) -> Result<Config> {
+    load_with(path, |_| Ok(()))
+}
+
+/// Load a config, letting `edit` change it after parsing, before its
+/// sections are expanded into windows and validated
+///
+/// `edit` may fail with an error of its own, which is returned as is.
+pub fn load_with<P, F, E>(path: P, edit: F) -> Result<Config, E>
+where
+    P: AsRef<Path>,
+    F: FnOnce(&mut Config) -> Result<(), E>,
+    E: From<LauncherError>,
+{
+    let path = path.as_ref();
+    let content = fs::read_to_string(path)
+        .map_err(|source| LauncherError::ConfigRead { path: path.to_path_buf(), source })?;
+    parse(&content, path, edit)
+}
+
+/// Load a config from `content`, as if read from `path`
+pub fn load_str(content: &str, path: &Path) -> Result<Config> {
+    parse(content, path, |_| Ok(()))
+}
+
+fn parse<E: From<LauncherError>>(
+    content: &str,
+    path: &Path,
+    edit: impl FnOnce(&mut Config) -> Result<(), E>,
+) -> Result<Config, E> {
+    let mut config: Config = serde_yaml::from_str(content)
+        .map_err(|source| LauncherError::ConfigParse { path: path.to_path_buf(), source })?;
+    edit(&mut config)?;
+
+    // Validate configuration
+    expand_projects(&mut config)?;
+    expand_worktrees(&mut config)?;
+    expand_queue(&mut config)?;
+    validate(&config)?;
+    expand_presets(&mut config);
+
+    Ok(config)
+}
+
+/// Append a window per `projects` entry, filled into `project_template`
+fn expand_projects(config: &mut Config) -> Result<()> {
+    if config.projects.is_empty() {
+        if config.project_template.is_some() {
+            return Err(LauncherError::InvalidConfig(
+                "project_template is set but projects is empty".to_string(),
+            ));
+        }
+        return Ok(());
+    }
+    let mut template = match config.project_template {
+        Some(serde_yaml::Value::Mapping(ref template)) => template.clone(),
+        Some(_) => return Err(LauncherError::InvalidConfig(
+            "project_template must be a window (a mapping)".to_string(),
+        )),
+        None => serde_yaml::Mapping::new(),
+    };
+    // Named after the project and working in its repo unless the template says otherwise
+    template.entry("name".into()).or_insert("{name}".into());
+    match template.get_mut("claude") {
+        Some(serde_yaml::Value::Mapping(claude)) => {
+            claude.entry("project".into()).or_insert("{repo}".into());
+        }
+        _ => {
+            template.entry("working_dir".into()).or_insert("{repo}".into());
+        }
+    }
+
+    for entry in &config.projects {
+        if entry.repo().trim().is_empty() {
+            return Err(LauncherError::InvalidConfig(format!("projects: invalid entry {:?}", entry)));
+        }
+        let mut value = serde_yaml::Value::Mapping(template.clone());
+        let setting = || format!("project_template for '{}'", entry.repo());
+        entry.fill_value(&mut value).map_err(|e| LauncherError::setting(setting(), e))?;
+        let mut window: WindowConfig =
+            serde_yaml::from_value(value).map_err(|e| LauncherError::setting(setting(), e))?;
+        if !window.tags.iter().any(|t| t == "project") {
+            window.tags.push("project".to_string());
+        }
+        config.windows.push(window);
+    }
+    Ok(())
+}
+
+/// Append a `claude:` window per `worktrees.branches` entry
+fn expand_worktrees(config: &mut Config) -> Result<()> {
+    let Some(ref worktrees) = config.worktrees else { return Ok(()) };
+    if worktrees.repo.trim().is_empty() {
+        return Err(LauncherError::InvalidConfig("worktrees.repo must not be empty".to_string()));
+    }
+    if worktrees.branches.is_empty() {
+        return Err(LauncherError::InvalidConfig(
+            "worktrees.branches must list at least one branch".to_string(),
+        ));
+    }
+    if !worktrees.claude.project.is_empty() {
+        return Err(LauncherError::InvalidConfig(
+            "worktrees.claude.project is set per window; remove it".to_string(),
+        ));
+    }
+
+    let dir = worktrees.dir();
+    let first = config.windows.len();
+    for entry in &worktrees.branches {
+        let name = entry.name();
+        if entry.branch().trim().is_empty() || name.trim().is_empty() || name.contains('/') {
+            return Err(LauncherError::InvalidConfig(format!(
+                "worktrees.branches: invalid entry {:?}",
+                entry
+            )));
+        }
+        let path = format!("{}/{}", dir.trim_end_matches('/'), name);
+        config.windows.push(WindowConfig {
+            name: name.clone(),
+            tags: vec!["worktree".to_string()],
+            claude: Some(ClaudeConfig {
+                project: path.clone(),
+                prompt: entry.prompt().map(str::to_string).or_else(|| worktrees.claude.prompt.clone()),
+                ..worktrees.claude.clone()
+            }),
+            worktree: Some(Worktree {
+                repo: worktrees.repo.clone(),
+                branch: entry.branch().to_string(),
+                base: worktrees.base.clone(),
+                path,
+            }),
+            provision: worktrees.provision.clone(),
+            ..Default::default()
+        });
+    }
+
+    if let Some(ref summary) = worktrees.summary {
+        if summary.name.trim().is_empty() || summary.command.trim().is_empty() {
+            return Err(LauncherError::InvalidConfig(
+                "worktrees.summary: name and command must not be empty".to_string(),
+            ));
+        }
+        if summary.interval_secs == 0 {
+            return Err(LauncherError::InvalidConfig(
+                "worktrees.summary.interval_secs must be at least 1".to_string(),
+            ));
+        }
+        let entries: Vec<(&str, &Worktree)> = config.windows[first..]
+            .iter()
+            .filter_map(|w| w.worktree.as_ref().map(|wt| (w.name.as_str(), wt)))
+            .collect();
+        let command = worktree::summary_command(&worktrees.repo, worktrees.base.as_deref(), summary, &entries);
+        config.windows.push(WindowConfig {
+            name: summary.name.clone(),
+            tags: vec!["summary".to_string()],
+            command: Some(command),
+            working_dir: Some(worktrees.repo.clone()),
+            ..Default::default()
+        });
+    }
+    Ok(())
+}
+
+/// Append the worker windows of `queue:`
+fn expand_queue(config: &mut Config) -> Result<()> {
+    let Some(ref queue) = config.queue else { return Ok(()) };
+    if queue.dir.trim().is_empty() {
+        return Err(LauncherError::InvalidConfig("queue.dir must not be empty".to_string()));
+    }
+    if queue.workers == 0 {
+        return Err(LauncherError::InvalidConfig("queue.workers must be at least 1".to_string()));
+    }
+    if queue.poll_secs == 0 {
+        return Err(LauncherError::InvalidConfig("queue.poll_secs must be at least 1".to_string()));
+    }
+    if queue.claude.continue_last || queue.claude.resume.is_some() {
+        return Err(LauncherError::InvalidConfig(
+            "queue.claude: every task starts a new conversation; remove 'continue' and 'resume'".to_string(),
+        ));
+    }
+    if queue.claude.prompt.is_some() {
+        return Err(LauncherError::InvalidConfig(
+            "queue.claude: the prompt of every task comes from its file; remove 'prompt'".to_string(),
+        ));
+    }
+
+    for i in 1..=queue.workers {
+        config.windows.push(WindowConfig {
+            name: format!("queue-{}", i),
+            tags: vec!["queue".to_string()],
+            claude: Some(queue.claude.clone()),
+            queue: Some(QueueWorker { dir: queue.dir.clone(), poll_secs: queue.poll_secs }),
+            ..Default::default()
+        });
+    }
+    Ok(())
+}
+
+/// Turn `claude:` presets into the command and working directory they stand for
+fn expand_presets(config: &mut Config) {
+    for window in &mut config.windows {
+        if let Some(ref preset) = window.claude {
+            let command = claude::command(preset, &config.roles);
+            window.command = Some(match window.queue {
+                Some(ref worker) => queue::worker_command(worker, &window.name, &command),
+                None => command,
+            });
+            window.working_dir = Some(preset.project.clone());
+        }
+    }
+}
+
+/// Check that a window only uses options that apply to its type
+fn validate_kind(window: &WindowConfig) -> Result<()> {
+    if window.kind == WindowKind::Wsl {
+        if !window.args.is_empty() {
+            return Err(LauncherError::window(&window.name, "'args' requires type 'windows-exe'"));
+        }
+        return Ok(());
+    }
+
+    let wsl_only = [
+        ("login_shell", window.login_shell),
+        ("tmux_session", window.tmux_session.is_some()),
+        ("zellij_session", window.zellij_session.is_some()),
+        ("provision", !window.provision.is_empty()),
+    ];
+    if let Some((option, _)) = wsl_only.iter().find(|(_, set)| *set) {
+        return Err(LauncherError::window(
+            &window.name,
+            format!("'{}' is only supported for WSL windows", option),
+        ));
+    }
+
+    match window.kind {
+        WindowKind::WindowsExe => {
+            if window.command.is_none() {
+                return Err(LauncherError::window(
+                    &window.name,
+                    "type 'windows-exe' requires 'command' (the executable)",
+                ));
+            }
+            if !window.commands.is_empty() || window.keep_open {
+                return Err(LauncherError::window(
+                    &window.name,
+                    "'commands' and 'keep_open' are not supported for type 'windows-exe'",
+                ));
+            }
+        }
+        _ => {
+            if !window.args.is_empty() {
+                return Err(LauncherError::window(&window.name, "'args' requires type 'windows-exe'"));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Validate configuration
+fn validate_healthcheck(name: &str, check: &HealthCheckConfig) -> Result<()> {
+    if check.command.trim().is_empty() {
+        return Err(LauncherError::window(name, "healthcheck.command must not be empty"));
+    }
+    let interval = check
+        .interval()
+        .map_err(|e| LauncherError::setting(format!("Window '{}': healthcheck.interval", name), e))?;
+    let timeout = check
+        .timeout()
+        .map_err(|e| LauncherError::setting(format!("Window '{}': healthcheck.timeout", name), e))?;
+    if interval.is_zero() || timeout.is_zero() {
+        return Err(LauncherError::window(name, "healthcheck interval and timeout must be greater than 0"));
+    }
+    if check.retries == 0 {
+        return Err(LauncherError::window(name, "healthcheck.retries must be at least 1"));
+    }
+    Ok(())
+}
+
+fn validate_hooks(scope: &str, hooks: &HooksConfig) -> Result<()> {
+    let all = [&hooks.pre_launch, &hooks.post_launch, &hooks.post_arrange, &hooks.pre_stop];
+    if all.iter().flat_map(|h| h.iter()).any(|h| h.command().trim().is_empty()) {
+        return Err(LauncherError::InvalidConfig(format!("{}: hook commands must not be empty", scope)));
+    }
+    Ok(())
+}
+
+fn validate(config: &Config) -> Result<()> {
+    // Check grid format
+    let (cols, rows) = config.layout.parse_grid()?;
+    let max_windows = cols * rows;
+
+    if config.windows.is_empty() {
+        return Err(LauncherError::InvalidConfig("At least one window must be configured".to_string()));
+    }
+
+    if config.watch.interval_secs == 0 {
+        return Err(LauncherError::InvalidConfig("watch.interval_secs must be greater than 0".to_string()));
+    }
+    if config.logs.max_size_mb == 0 || config.logs.keep == 0 {
+        return Err(LauncherError::InvalidConfig(
+            "logs.max_size_mb and logs.keep must be at least 1".to_string(),
+        ));
+    }
+
+    validate_hooks("hooks", &config.hooks)?;
+    hotkey::bindings(config)?;
+    for (name, command) in &config.aliases {
+        if name.is_empty() || name.starts_with('-') || name.contains(char::is_whitespace) {
+            return Err(LauncherError::InvalidConfig(format!(
+                "Alias '{}' must be one word not starting with '-'",
+                name
+            )));
+        }
+        if command.trim().is_empty() {
+            return Err(LauncherError::InvalidConfig(format!("Alias '{}' has an empty command", name)));
+        }
+    }
+
+    if let Some(ref limits) = config.rate_limit {
+        if limits.requests_per_minute.is_none() && limits.tokens_per_minute.is_none() {
+            return Err(LauncherError::InvalidConfig(
+                "rate_limit needs requests_per_minute or tokens_per_minute".to_string(),
+            ));
+        }
+        if limits.requests_per_minute == Some(0) || limits.tokens_per_minute == Some(0) {
+            return Err(LauncherError::InvalidConfig("rate_limit limits must be greater than 0".to_string()));
+        }
+        if !(limits.threshold > 0.0 && limits.threshold <= 1.0) {
+            return Err(LauncherError::InvalidConfig(
+                "rate_limit.threshold must be greater than 0 and at most 1".to_string(),
+            ));
+        }
+    }
+
+    let idle_after = config.watch.idle_after().map_err(|e| LauncherError::setting("watch.idle_after", e))?;
+    if idle_after.is_some_and(|d| d.is_zero()) {
+        return Err(LauncherError::InvalidConfig("watch.idle_after must be greater than 0".to_string()));
+    }
+
+    // Tabs share one window, so only the other modes are limited by the grid
+    let grid_windows = config.windows.iter().filter(|w| !w.dropdown).count();
+    if config.layout.mode != LaunchMode::Tabs && grid_windows > max_windows as usize {
+        return Err(LauncherError::TooManyWindows {
+            windows: grid_windows,
+            grid: config.layout.grid.clone(),
+            cells: max_windows,
+        });
+    }
+
+    // Tabs and panes are Windows Terminal features
+    if config.layout.mode != LaunchMode::Windows && config.terminal != TerminalKind::WindowsTerminal {
+        return Err(LauncherError::InvalidConfig(format!(
+            "layout.mode '{:?}' requires terminal: windows-terminal",
+            config.layout.mode
+        )));
+    }
+
+    if config.layout.mode != LaunchMode::Windows && config.windows.iter().any(|w| w.dropdown) {
+        return Err(LauncherError::InvalidConfig(format!(
+            "'dropdown' windows are only supported in layout.mode 'windows' (got '{:?}')",
+            config.layout.mode
+        )));
+    }
+
+    // An elevated tab/pane can't live in a non-elevated window
+    if config.layout.mode != LaunchMode::Windows && config.windows.iter().any(|w| w.elevated) {
+        return Err(LauncherError::InvalidConfig(format!(
+            "'elevated' windows are only supported in layout.mode 'windows' (got '{:?}')",
+            config.layout.mode
+        )));
+    }
+
+    // Check for duplicate names
+    let mut names = std::collections::HashSet::new();
+    for window in &config.windows {
+        if !names.insert(&window.name) {
+            return Err(LauncherError::InvalidConfig(format!("Duplicate window name: {}", window.name)));
+        }
+    }
+
+    // Two windows attached to one multiplexer session would mirror each other
+    let mut tmux_sessions = std::collections::HashSet::new();
+    let mut zellij_sessions = std::collections::HashSet::new();
+    for window in &config.windows {
+        if window.tmux_session.is_some() && window.zellij_session.is_some() {
+            return Err(LauncherError::window(
+                &window.name,
+                "'tmux_session' and 'zellij_session' cannot be used together",
+            ));
+        }
+        if window.zellij_layout.is_some() && window.zellij_session.is_none() {
+            return Err(LauncherError::window(&window.name, "'zellij_layout' requires 'zellij_session'"));
+        }
+        if let Some(ref session) = window.tmux_session {
+            if session.is_empty() || session.contains(['.', ':']) {
+                return Err(LauncherError::window(
+                    &window.name,
+                    format!("invalid tmux_session '{}' (must be non-empty, without '.' or ':')", session),
+                ));
+            }
+            if !tmux_sessions.insert(session) {
+                return Err(LauncherError::InvalidConfig(format!("Duplicate tmux_session: {}", session)));
+            }
+        }
+        if let Some(ref session) = window.zellij_session {
+            if session.is_empty() || session.contains(['/', ' ']) {
+                return Err(LauncherError::window(
+                    &window.name,
+                    format!(
+                        "invalid zellij_session '{}' (must be non-empty, without '/' or spaces)",
+                        session
+                    ),
+                ));
+            }
+            if !zellij_sessions.insert(session) {
+                return Err(LauncherError::InvalidConfig(format!("Duplicate zellij_session: {}", session)));
+            }
+        }
+    }
+
+    for window in &config.windows {
+        validate_kind(window)?;
+        if window.command.is_some() && !window.commands.is_empty() {
+            return Err(LauncherError::window(
+                &window.name,
+                "'command' and 'commands' cannot be used together",
+            ));
+        }
+        if window.tags.iter().any(|t| t.trim().is_empty()) {
+            return Err(LauncherError::window(&window.name, "'tags' must not contain empty entries"));
+        }
+        if window.commands.iter().any(|c| c.trim().is_empty()) {
+            return Err(LauncherError::window(&window.name, "'commands' must not contain empty entries"));
+        }
+        if let Some(ref color) = window.tab_color
+            && !is_hex_color(color)
+        {
+            return Err(LauncherError::window(
+                &window.name,
+                format!("invalid tab_color '{}'. Expected '#RGB' or '#RRGGBB'", color),
+            ));
+        }
+        if window.wt_maximized && window.wt_fullscreen {
+            return Err(LauncherError::window(
+                &window.name,
+                "'wt_maximized' and 'wt_fullscreen' cannot be used together",
+            ));
+        }
+        if let Some(size) = window.font.as_ref().and_then(|f| f.size)
+            && size <= 0.0
+        {
+            return Err(LauncherError::window(&window.name, "font size must be positive"));
+        }
+        if window.keep_open && window.close_on_exit {
+            return Err(LauncherError::window(
+                &window.name,
+                "'keep_open' and 'close_on_exit' cannot be used together",
+            ));
+        }
+        if let Some(ref check) = window.healthcheck {
+            validate_healthcheck(&window.name, check)?;
+        }
+        validate_hooks(&format!("Window '{}': hooks", window.name), &window.hooks)?;
+        validate_provision(window)?;
+        if let Some(ref preset) = window.claude {
+            validate_claude(window, preset, &config.roles)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_provision(window: &WindowConfig) -> Result<()> {
+    if window.provision.is_empty() {
+        return Ok(());
+    }
+    // A claude window works in its project
+    if window.working_dir.is_none() && window.claude.is_none() {
+        return Err(LauncherError::window(&window.name, "'provision' requires 'working_dir'"));
+    }
+    for file in &window.provision {
+        let from = file.from();
+        if !(from.starts_with('/') || from == "~" || from.starts_with("~/")) {
+            return Err(LauncherError::window(
+                &window.name,
+                format!("provision source '{}' must be an absolute path or start with ~/", from),
+            ));
+        }
+        let to = file.to();
+        if to.is_empty() || to.starts_with(['/', '~']) || to.split('/').any(|part| part == "..") {
+            return Err(LauncherError::window(
+                &window.name,
+                format!("provision target '{}' must be a directory inside the working directory", to),
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn validate_claude(window: &WindowConfig, preset: &ClaudeConfig, roles: &BTreeMap<String, String>) -> Result<()> {
+    if window.kind != WindowKind::Wsl {
+        return Err(LauncherError::window(&window.name, "'claude' is only supported for WSL windows"));
+    }
+    if window.command.is_some() || !window.commands.is_empty() || window.working_dir.is_some() {
+        return Err(LauncherError::window(&window.name, "'claude' sets the command and working directory; \
+            remove 'command', 'commands' and 'working_dir'"));
+    }
+    if preset.project.trim().is_empty() {
+        return Err(LauncherError::window(&window.name, "claude.project must not be empty"));
+    }
+    let resumes = preset.resume.as_ref().is_some_and(|r| {
+        !matches!(r, ClaudeResume::Pick(false) | ClaudeResume::Policy(ResumePolicy::Never))
+    });
+    if preset.continue_last && resumes {
+        return Err(LauncherError::window(
+            &window.name,
+            "claude.continue and claude.resume cannot be used together",
+        ));
+    }
+    if matches!(preset.resume, Some(ClaudeResume::Session(ref id)) if id.trim().is_empty()) {
+        return Err(LauncherError::window(
+            &window.name,
+            "claude.resume must be true, always, ask, never or a session ID",
+        ));
+    }
+    if let Some(ref role) = preset.role
+        && claude::role_prompt(role, roles).is_none()
+    {
+        let mut known: Vec<&str> = roles.keys().map(String::as_str).collect();
+        known.extend(claude::BUILTIN_ROLES.iter().map(|(name, _)| *name).filter(|name| !roles.contains_key(*name)));
+        return Err(LauncherError::window(
+            &window.name,
+            format!("unknown claude.role '{}' (known: {})", role, known.join(", ")),
+        ));
+    }
+    if preset.append_system_prompt.as_ref().is_some_and(|p| p.trim().is_empty()) {
+        return Err(LauncherError::window(&window.name, "claude.append_system_prompt must not be empty"));
+    }
+    if preset.prompt.as_ref().is_some_and(|p| p.trim().is_empty()) {
+        return Err(LauncherError::window(&window.name, "claude.prompt must not be empty"));
+    }
+    if preset.model.as_ref().is_some_and(|m| m.trim().is_empty()) {
+        return Err(LauncherError::window(&window.name, "claude.model must not be empty"));
+    }
+    if matches!(preset.settings, Some(ClaudeSettings::File(ref path)) if path.trim().is_empty()) {
+        return Err(LauncherError::window(
+            &window.name,
+            "claude.settings must be a file path or a mapping of settings",
+        ));
+    }
+    if let Some(ref budget) = preset.budget {
+        if budget.tokens.is_none() && budget.cost_usd.is_none() {
+            return Err(LauncherError::window(&window.name, "claude.budget needs tokens or cost_usd"));
+        }
+        if budget.tokens == Some(0) || budget.cost_usd.is_some_and(|cost| cost <= 0.0) {
+            return Err(LauncherError::window(&window.name, "claude.budget limits must be greater than 0"));
+        }
+        let period = budget
+            .period()
+            .map_err(|e| LauncherError::setting(format!("Window '{}': claude.budget.period", window.name), e))?;
+        if period.is_zero() {
+            return Err(LauncherError::window(&window.name, "claude.budget.period must be greater than 0"));
+        }
+    }
+    Ok(())
+}
+
+/// Check for a "#RGB" or "#RRGGBB" color
+fn is_hex_color(s: &str) -> bool {
+    match s.strip_prefix('#') {
+        Some(hex) => (hex.len() == 3 || hex.len() == 6) && hex.chars().all(|c| c.is_ascii_hexdigit()),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_grid() {
+        let layout = LayoutConfig { grid: "2x4".to_string(), ..Default::default() };
+        assert_eq!(layout.parse_grid().unwrap(), (2, 4));
+
+        let layout = LayoutConfig { grid: "3x3".to_string(), ..Default::default() };
+        assert_eq!(layout.parse_grid().unwrap(), (3, 3));
+
+        let layout = LayoutConfig { grid: "1x1".to_string(), ..Default::default() };
+        assert_eq!(layout.parse_grid().unwrap(), (1, 1));
+    }
+
+    #[test]
+    fn test_parse_grid_invalid() {
+        let layout = LayoutConfig { grid: "invalid".to_string(), ..Default::default() };
+        assert!(layout.parse_grid().is_err());
+
+        let layout = LayoutConfig { grid: "2".to_string(), ..Default::default() };
+        assert!(layout.parse_grid().is_err());
+
+        let layout = LayoutConfig { grid: "axb".to_string(), ..Default::default() };
+        assert!(layout.parse_grid().is_err());
+    }
+
+    #[test]
+    fn test_parse_yaml_config() {
+        let yaml = r#"
+wsl_distribution: Ubuntu-24.04
+target_display: 1
+layout:
+  grid: "2x2"
+windows:
+  - name: "test-1"
+    command: "bash"
+    working_dir: "~"
+  - name: "test-2"
+    command: "htop"
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(config.wsl_distribution, "Ubuntu-24.04");
+        assert_eq!(config.target_display, 1);
+        assert_eq!(config.layout.grid, "2x2");
+        assert_eq!(config.windows.len(), 2);
+        assert_eq!(config.windows[0].name, "test-1");
+        assert_eq!(config.windows[0].command.as_deref(), Some("bash"));
+        assert_eq!(config.windows[0].working_dir, Some("~".to_string()));
+        assert_eq!(config.windows[1].name, "test-2");
+        assert_eq!(config.windows[1].command.as_deref(), Some("htop"));
+        assert_eq!(config.windows[1].working_dir, None);
+    }
+
+    #[test]
+    fn test_default_command() {
+        let yaml = r#"
+wsl_distribution: Ubuntu
+layout:
+  grid: "1x1"
+windows:
+  - name: "test"
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(config.windows[0].steps(), vec!["bash".to_string()]);
+    }
+
+    #[test]
+    fn test_window_filter() {
+        let yaml = r#"
+wsl_distribution: Ubuntu
+layout:
+  grid: 2x2
+windows:
+  - name: api
+    tags: [backend]
+  - name: docker
+    tags: [heavy, backend]
+  - name: web
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        let picked = |filter: &WindowFilter| -> Vec<&str> {
+            config.windows.iter().filter(|w| filter.matches(w)).map(|w| w.name.as_str()).collect()
+        };
+        let names = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        assert!(WindowFilter::default().is_empty());
+        assert_eq!(picked(&WindowFilter::default()), ["api", "docker", "web"]);
+        let filter = WindowFilter { exclude_tags: names(&["heavy"]), ..Default::default() };
+        assert_eq!(picked(&filter), ["api", "web"]);
+        let filter = WindowFilter { only: names(&["api", "docker"]), exclude: names(&["docker"]), ..Default::default() };
+        assert_eq!(picked(&filter), ["api"]);
+        assert!(filter.check(&config).is_ok());
+
+        let typo = WindowFilter { exclude: names(&["dokcer"]), ..Default::default() };
+        assert_eq!(typo.check(&config).unwrap_err().to_string(), "No window named 'dokcer'");
+        let nothing = WindowFilter { exclude_tags: names(&["backend"]), exclude: names(&["web"]), ..Default::default() };
+        assert!(nothing.check(&config).is_err());
+    }
+
+    #[test]
+    fn test_regrid() {
+        let yaml = r#"
+wsl_distribution: Ubuntu
+layout:
+  grid: 2x2
+windows:
+  - name: api
+  - name: docker
+    tags: [heavy]
+  - name: drop
+    dropdown: true
+  - name: web
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        let filter = WindowFilter { exclude_tags: vec!["heavy".to_string()], ..Default::default() };
+
+        let mut regridded = config.clone();
+        regridded.regrid("3x1", &filter).unwrap();
+        assert_eq!(regridded.layout.parse_grid().unwrap(), (3, 1));
+        let names: Vec<&str> = regridded.windows.iter().map(|w| w.name.as_str()).collect();
+        assert_eq!(names, ["api", "drop", "web"]);
+        // web moves up into the cell docker had
+        assert_eq!(regridded.grid_slots(), [Some(0), None, Some(1)]);
+
+        let err = config.clone().regrid("1x2", &WindowFilter::default()).unwrap_err();
+        assert_eq!(err.to_string(), "3 windows don't fit in a 1x2 grid (2 cells)");
+        assert!(config.clone().regrid("3", &filter).is_err());
+    }
+
+    #[test]
+    fn test_default_target_display() {
+        let yaml = r#"
+wsl_distribution: Ubuntu
+layout:
+  grid: "1x1"
+windows:
+  - name: "test"
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(config.target_display, 0);
+    }
+
+    #[test]
+    fn test_validate_empty_windows() {
+        let config = Config {
+            wsl_distribution: "Ubuntu".to_string(),
+            target_display: 0,
+            layout: LayoutConfig { grid: "2x2".to_string(), ..Default::default() },
+            windows: vec![],
+            ..Default::default()
+        };
+        assert!(validate(&config).is_err());
+    }
+
+    #[test]
+    fn test_validate_too_many_windows() {
+        let config = Config {
+            wsl_distribution: "Ubuntu".to_string(),
+            target_display: 0,
+            layout: LayoutConfig { grid: "1x1".to_string(), ..Default::default() },
+            windows: vec![
+                WindowConfig { name: "a".to_string(), command: Some("bash".to_string()), ..Default::default() },
+                WindowConfig { name: "b".to_string(), command: Some("bash".to_string()), ..Default::default() },
+            ],
+            ..Default::default()
+        };
+        assert!(validate(&config).is_err());
+    }
+
+    #[test]
+    fn test_validate_duplicate_names() {
+        let config = Config {
+            wsl_distribution: "Ubuntu".to_string(),
+            target_display: 0,
+            layout: LayoutConfig { grid: "2x2".to_string(), ..Default::default() },
+            windows: vec![
+                WindowConfig { name: "same".to_string(), command: Some("bash".to_string()), ..Default::default() },
+                WindowConfig { name: "same".to_string(), command: Some("bash".to_string()), ..Default::default() },
+            ],
+            ..Default::default()
+        };
+        assert!(validate(&config).is_err());
+    }
+
+    #[test]
+    fn test_validate_success() {
+        let config = Config {
+            wsl_distribution: "Ubuntu".to_string(),
+            target_display: 0,
+            layout: LayoutConfig { grid: "2x2".to_string(), ..Default::default() },
+            windows: vec![
+                WindowConfig { name: "a".to_string(), command: Some("bash".to_string()), ..Default::default() },
+                WindowConfig { name: "b".to_string(), command: Some("bash".to_string()), ..Default::default() },
+            ],
+            ..Default::default()
+        };
+        assert!(validate(&config).is_ok());
+    }
+
+    #[test]
+    fn test_parse_commands_list() {
+        let yaml = r#"
+wsl_distribution: Ubuntu
+layout:
+  grid: "1x1"
+windows:
+  - name: "dev"
+    commands: ["nvm use 20", "npm run dev"]
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(config.windows[0].steps(), vec!["nvm use 20", "npm run dev"]);
+        assert_eq!(config.windows[0].command_summary(), "nvm use 20 && npm run dev");
+        assert!(validate(&config).is_ok());
+    }
+
+    #[test]
+    fn test_continue_on_error_separator() {
+        let window = WindowConfig {
+            name: "a".to_string(),
+            commands: vec!["make test".to_string(), "bash".to_string()],
+            continue_on_error: true,
+            ..Default::default()
+        };
+        assert_eq!(window.command_summary(), "make test; bash");
+    }
+
+    #[test]
+    fn test_validate_command_and_commands_conflict() {
+        let config = Config {
+            wsl_distribution: "Ubuntu".to_string(),
+            target_display: 0,
+            layout: LayoutConfig { grid: "1x1".to_string(), ..Default::default() },
+            windows: vec![WindowConfig {
+                name: "a".to_string(),
+                command: Some("bash".to_string()),
+                commands: vec!["htop".to_string()],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        assert!(validate(&config).is_err());
+    }
+
+    #[test]
+    fn test_validate_keep_open_and_close_on_exit_conflict() {
+        let config = Config {
+            wsl_distribution: "Ubuntu".to_string(),
+            target_display: 0,
+            layout: LayoutConfig { grid: "1x1".to_string(), ..Default::default() },
+            windows: vec![WindowConfig {
+                name: "a".to_string(),
+                keep_open: true,
+                close_on_exit: true,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        assert!(validate(&config).is_err());
+    }
+
+    #[test]
+    fn test_validate_elevated_requires_windows_mode() {
+        let mut config = Config {
+            wsl_distribution: "Ubuntu".to_string(),
+            target_display: 0,
+            layout: LayoutConfig { grid: "1x1".to_string(), mode: LaunchMode::Tabs },
+            windows: vec![WindowConfig {
+                name: "admin".to_string(),
+                elevated: true,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        assert!(validate(&config).is_err());
+
+        config.layout.mode = LaunchMode::Windows;
+        assert!(validate(&config).is_ok());
+    }
+
+    #[test]
+    fn test_validate_tmux_session() {
+        let window = |name: &str, session: &str| WindowConfig {
+            name: name.to_string(),
+            tmux_session: Some(session.to_string()),
+            ..Default::default()
+        };
+        let mut config = Config {
+            wsl_distribution: "Ubuntu".to_string(),
+            target_display: 0,
+            layout: LayoutConfig { grid: "2x1".to_string(), ..Default::default() },
+            windows: vec![window("a", "work-a"), window("b", "work-b")],
+            ..Default::default()
+        };
+        assert!(validate(&config).is_ok());
+
+        config.windows[1].tmux_session = Some("work-a".to_string());
+        assert!(validate(&config).is_err());
+
+        config.windows[1].tmux_session = Some("bad:name".to_string());
+        assert!(validate(&config).is_err());
+    }
+
+    #[test]
+    fn test_validate_zellij_session() {
+        let mut config = Config {
+            wsl_distribution: "Ubuntu".to_string(),
+            target_display: 0,
+            layout: LayoutConfig { grid: "1x1".to_string(), ..Default::default() },
+            windows: vec![WindowConfig {
+                name: "a".to_string(),
+                zellij_session: Some("work".to_string()),
+                zellij_layout: Some("~/layouts/dev.kdl".to_string()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        assert!(validate(&config).is_ok());
+
+        config.windows[0].tmux_session = Some("work".to_string());
+        assert!(validate(&config).is_err());
+
+        config.windows[0].tmux_session = None;
+        config.windows[0].zellij_session = None;
+        assert!(validate(&config).is_err());
+    }
+
+    #[test]
+    fn test_parse_window_kind() {
+        let yaml = r#"
+wsl_distribution: Ubuntu
+layout:
+  grid: "2x1"
+windows:
+  - name: "claude"
+  - name: "watch"
+    type: windows-exe
+    command: "C:\\tools\\watcher.exe"
+    args: ["--dir", "C:\\src"]
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert!(validate(&config).is_ok());
+        assert_eq!(config.windows[0].kind, WindowKind::Wsl);
+        assert_eq!(config.windows[1].kind, WindowKind::WindowsExe);
+        assert_eq!(config.windows[1].command_summary(), r"C:\tools\watcher.exe --dir C:\src");
+    }
+
+    #[test]
+    fn test_validate_window_kind_options() {
+        let mut config = Config {
+            wsl_distribution: "Ubuntu".to_string(),
+            target_display: 0,
+            layout: LayoutConfig { grid: "1x1".to_string(), ..Default::default() },
+            windows: vec![WindowConfig {
+                name: "ps".to_string(),
+                kind: WindowKind::Powershell,
+                tmux_session: Some("ps".to_string()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        assert!(validate(&config).is_err());
+
+        // windows-exe needs the executable in `command`
+        config.windows[0] = WindowConfig {
+            name: "exe".to_string(),
+            kind: WindowKind::WindowsExe,
+            ..Default::default()
+        };
+        assert!(validate(&config).is_err());
+
+        // args only make sense for windows-exe
+        config.windows[0] = WindowConfig {
+            name: "wsl".to_string(),
+            args: vec!["-x".to_string()],
+            ..Default::default()
+        };
+        assert!(validate(&config).is_err());
+    }
+
+    #[test]
+    fn test_native_command() {
+        let window = WindowConfig {
+            name: "build".to_string(),
+            kind: WindowKind::Cmd,
+            commands: vec!["npm ci".to_string(), "npm run watch".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(window.native_command().as_deref(), Some("npm ci && npm run watch"));
+        assert_eq!(window.command_summary(), "[cmd] npm ci && npm run watch");
+    }
+
+    #[test]
+    fn test_dropdown_takes_no_grid_cell() {
+        let window = |name: &str, dropdown: bool| WindowConfig {
+            name: name.to_string(),
+            dropdown,
+            ..Default::default()
+        };
+        let config = Config {
+            wsl_distribution: "Ubuntu".to_string(),
+            target_display: 0,
+            layout: LayoutConfig { grid: "2x1".to_string(), ..Default::default() },
+            windows: vec![window("a", false), window("scratch", true), window("b", false)],
+            ..Default::default()
+        };
+        // Three windows fit a 2-cell grid because the dropdown has no cell
+        assert!(validate(&config).is_ok());
+        assert_eq!(config.grid_slots(), vec![Some(0), None, Some(1)]);
+    }
+
+    #[test]
+    fn test_default_use_wsl_cd() {
+        let yaml = r#"
+wsl_distribution: Ubuntu
+layout:
+  grid: "1x1"
+windows:
+  - name: "test"
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert!(config.use_wsl_cd);
+    }
+
+    #[test]
+    fn test_parse_layout_mode() {
+        let yaml = r#"
+wsl_distribution: Ubuntu
+layout:
+  grid: "1x1"
+  mode: tabs
+windows:
+  - name: "a"
+  - name: "b"
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(config.layout.mode, LaunchMode::Tabs);
+        // Tabs are not limited by the grid size
+        assert!(validate(&config).is_ok());
+    }
+
+    #[test]
+    fn test_is_hex_color() {
+        assert!(is_hex_color("#ff8800"));
+        assert!(is_hex_color("#F80"));
+        assert!(!is_hex_color("ff8800"));
+        assert!(!is_hex_color("#ff88"));
+        assert!(!is_hex_color("#gg8800"));
+    }
+
+    #[test]
+    fn test_window_default_matches_serde() {
+        let yaml = r#"name: "test""#;
+        let window: WindowConfig = serde_yaml::from_str(yaml).unwrap();
+        let default = WindowConfig { name: "test".to_string(), ..Default::default() };
+        assert_eq!(format!("{:?}", window), format!("{:?}", default));
+    }
+
+    #[test]
+    fn test_parse_terminal() {
+        let yaml = r#"
+wsl_distribution: Ubuntu
+terminal: wezterm
+layout:
+  grid: "1x1"
+windows:
+  - name: "a"
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(config.terminal, TerminalKind::Wezterm);
+        assert!(validate(&config).is_ok());
+
+        let yaml = yaml.replace("wezterm", "wt");
+        let config: Config = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(config.terminal, TerminalKind::WindowsTerminal);
+    }
+
+    #[test]
+    fn test_validate_tabs_requires_windows_terminal() {
+        let config = Config {
+            wsl_distribution: "Ubuntu".to_string(),
+            terminal: TerminalKind::Alacritty,
+            layout: LayoutConfig { grid: "1x1".to_string(), mode: LaunchMode::Tabs },
+            windows: vec![WindowConfig { name: "a".to_string(), ..Default::default() }],
+            ..Default::default()
+        };
+        assert!(validate(&config).is_err());
+    }
+
+    #[test]
+    fn test_parse_font() {
+        let yaml = r#"
+name: "monitor"
+font: {face: "Cascadia Mono", size: 9}
+"#;
+        let window: WindowConfig = serde_yaml::from_str(yaml).unwrap();
+        let font = window.font.unwrap();
+        assert_eq!(font.face.as_deref(), Some("Cascadia Mono"));
+        assert_eq!(font.size, Some(9.0));
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("30s").unwrap(), Duration::from_secs(30));
+        assert_eq!(parse_duration("5m").unwrap(), Duration::from_secs(300));
+        assert_eq!(parse_duration("1h").unwrap(), Duration::from_secs(3600));
+        assert_eq!(parse_duration("45").unwrap(), Duration::from_secs(45));
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("5d").is_err());
+        assert!(parse_duration("fast").is_err());
+    }
+
+    #[test]
+    fn test_parse_healthcheck() {
+        let yaml = r#"
+name: "server"
+command: "npm run dev"
+healthcheck: {command: "curl -sf localhost:3000", interval: 1m}
+"#;
+        let window: WindowConfig = serde_yaml::from_str(yaml).unwrap();
+        let check = window.healthcheck.clone().unwrap();
+        assert_eq!(check.command, "curl -sf localhost:3000");
+        assert_eq!(check.interval().unwrap(), Duration::from_secs(60));
+        assert_eq!(check.timeout().unwrap(), Duration::from_secs(10));
+        assert_eq!(check.retries, 3);
+        assert!(!check.restart);
+
+        let mut config = Config {
+            wsl_distribution: "Ubuntu".to_string(),
+            layout: LayoutConfig { grid: "1x1".to_string(), ..Default::default() },
+            windows: vec![window],
+            ..Default::default()
+        };
+        assert!(validate(&config).is_ok());
+        config.windows[0].healthcheck.as_mut().unwrap().interval = "0s".to_string();
+        assert!(validate(&config).is_err());
+    }
+
+    #[test]
+    fn test_parse_watch_idle() {
+        let yaml = r#"
+interval_secs: 10
+idle_after: 30m
+idle_action: close
+"#;
+        let watch: WatchConfig = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(watch.idle_after().unwrap(), Some(Duration::from_secs(1800)));
+        assert_eq!(watch.idle_action, IdleAction::Close);
+        assert_eq!(WatchConfig::default().idle_after().unwrap(), None);
+    }
+
+    #[test]
+    fn test_parse_hooks() {
+        let yaml = r#"
+pre_launch: "~/bin/sync-dotfiles"
+post_launch:
+  - "echo up"
+  - windows: "Start-Process slack:"
+"#;
+        let hooks: HooksConfig = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(hooks.pre_launch, vec![HookCommand::Wsl("~/bin/sync-dotfiles".to_string())]);
+        assert_eq!(
+            hooks.post_launch,
+            vec![
+                HookCommand::Wsl("echo up".to_string()),
+                HookCommand::Windows { windows: "Start-Process slack:".to_string() },
+            ]
+        );
+        assert!(hooks.post_arrange.is_empty() && hooks.pre_stop.is_empty());
+
+        let empty = HooksConfig { pre_stop: vec![HookCommand::Wsl(" ".to_string())], ..Default::default() };
+        assert!(validate_hooks("hooks", &empty).is_err());
+    }
+
+    #[test]
+    fn test_has_any_tag() {
+        let window = WindowConfig { tags: vec!["claude".to_string(), "api".to_string()], ..Default::default() };
+        assert!(window.has_any_tag(&[]));
+        assert!(window.has_any_tag(&["web".to_string(), "api".to_string()]));
+        assert!(!window.has_any_tag(&["web".to_string()]));
+    }
+
+    #[test]
+    fn test_expand_queue() {
+        let yaml = r#"
+wsl_distribution: Ubuntu
+layout:
+  grid: "2x2"
+queue:
+  dir: "~/queue"
+  workers: 3
+  claude:
+    project: "~/src/api"
+    permission_mode: acceptEdits
+"#;
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        expand_queue(&mut config).unwrap();
+        assert!(validate(&config).is_ok());
+        expand_presets(&mut config);
+
+        let names: Vec<_> = config.windows.iter().map(|w| w.name.as_str()).collect();
+        assert_eq!(names, vec!["queue-1", "queue-2", "queue-3"]);
+        let window = &config.windows[0];
+        assert_eq!(window.tags, vec!["queue"]);
+        assert_eq!(window.working_dir.as_deref(), Some("~/src/api"));
+        assert_eq!(window.queue, Some(QueueWorker { dir: "~/queue".to_string(), poll_secs: 5 }));
+        let command = window.command.as_deref().unwrap();
+        assert!(command.starts_with("q=\"$HOME\"/queue; w=queue-1;"));
+        assert!(command.contains("claude --permission-mode acceptEdits -p <"));
+
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        config.queue.as_mut().unwrap().claude.continue_last = true;
+        assert!(expand_queue(&mut config).unwrap_err().to_string().contains("remove 'continue'"));
+    }
+
+    #[test]
+    fn test_expand_worktrees() {
+        let yaml = r#"
+wsl_distribution: Ubuntu
+layout:
+  grid: "2x2"
+windows:
+  - name: "shell"
+worktrees:
+  repo: "~/src/app/"
+  base: main
+  claude:
+    model: sonnet
+  branches:
+    - feature/login
+    - branch: fix/flaky-tests
+      name: tests
+      prompt: "Find and fix the flaky tests"
+"#;
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        expand_worktrees(&mut config).unwrap();
+        assert!(validate(&config).is_ok());
+        expand_presets(&mut config);
+
+        let names: Vec<_> = config.windows.iter().map(|w| w.name.as_str()).collect();
+        assert_eq!(names, vec!["shell", "feature-login", "tests"]);
+        let window = &config.windows[1];
+        assert_eq!(window.working_dir.as_deref(), Some("~/src/app-worktrees/feature-login"));
+        assert_eq!(window.command.as_deref(), Some("claude --model sonnet"));
+        assert_eq!(
+            window.worktree,
+            Some(Worktree {
+                repo: "~/src/app/".to_string(),
+                branch: "feature/login".to_string(),
+                base: Some("main".to_string()),
+                path: "~/src/app-worktrees/feature-login".to_string(),
+            })
+        );
+        assert_eq!(config.windows[2].worktree.as_ref().unwrap().branch, "fix/flaky-tests");
+        assert_eq!(config.windows[2].command.as_deref(), Some("claude --model sonnet 'Find and fix the flaky tests'"));
+
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        config.worktrees.as_mut().unwrap().summary = Some(WorktreeSummary::default());
+        expand_worktrees(&mut config).unwrap();
+        assert!(validate(&config).is_ok());
+        let summary = config.windows.last().unwrap();
+        assert_eq!((summary.name.as_str(), summary.tags.as_slice()), ("changes", ["summary".to_string()].as_slice()));
+        assert_eq!(summary.working_dir.as_deref(), Some("~/src/app/"));
+        let command = summary.command.as_deref().unwrap();
+        assert!(command.contains("b=main;"));
+        assert!(command.contains("; s tests fix/flaky-tests \"$HOME\"/src/app-worktrees/tests; sleep 10; done"));
+
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        config.worktrees.as_mut().unwrap().branches.push(WorktreeBranch::Branch("tests".to_string()));
+        expand_worktrees(&mut config).unwrap();
+        assert!(validate(&config).unwrap_err().to_string().contains("Duplicate window name"));
+    }
+
+    #[test]
+    fn test_load_with() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("issues.yaml");
+        std::fs::write(
+            &path,
+            "wsl_distribution: Ubuntu\nlayout:\n  grid: \"2x1\"\nworktrees:\n  repo: ~/app\n  branches: []\n",
+        )
+        .unwrap();
+        assert!(load(&path).unwrap_err().to_string().contains("at least one branch"));
+
+        let config = load_with(&path, |config| -> Result<()> {
+            let branch = WorktreeBranch::Named { branch: "issue-7".to_string(), name: "issue-7".to_string(), prompt: None };
+            config.worktrees.as_mut().unwrap().branches.push(branch);
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(config.windows[0].name, "issue-7");
+        assert_eq!(config.windows[0].command.as_deref(), Some("claude"));
+    }
+
+    #[test]
+    fn test_claude_preset() {
+        let yaml = r#"
+wsl_distribution: Ubuntu
+layout:
+  grid: "2x1"
+windows:
+  - name: "api"
+    claude:
+      project: "~/src/api"
+      model: sonnet
+      resume: true
+  - name: "web"
+    claude:
+      project: "~/src/web"
+      resume: "abc-123"
+"#;
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert!(validate(&config).is_ok());
+        expand_presets(&mut config);
+        assert_eq!(config.windows[0].command.as_deref(), Some("claude --model sonnet --resume"));
+        assert_eq!(config.windows[0].working_dir.as_deref(), Some("~/src/api"));
+        assert_eq!(config.windows[1].command.as_deref(), Some("claude --resume abc-123"));
+
+        let mut window = config.windows[0].clone();
+        window.command = Some("bash".to_string());
+        let config = Config { windows: vec![window], ..config };
+        assert!(validate(&config).unwrap_err().to_string().contains("remove 'command'"));
+    }
+
+    #[test]
+    fn test_claude_role() {
+        let yaml = r#"
+wsl_distribution: Ubuntu
+layout:
+  grid: "2x1"
+roles:
+  reviewer: "Review only. Don't edit files."
+windows:
+  - name: "architect"
+    claude:
+      project: "~/src/api"
+      role: architect
+  - name: "reviewer"
+    claude:
+      project: "~/src/api"
+      role: reviewer
+      append_system_prompt: "Focus on the API."
+"#;
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert!(validate(&config).is_ok());
+        let mut expanded = config.clone();
+        expand_presets(&mut expanded);
+        assert!(expanded.windows[0].command.as_deref().unwrap().starts_with("claude --append-system-prompt 'You are the architect"));
+        assert_eq!(
+            expanded.windows[1].command.as_deref(),
+            Some("claude --append-system-prompt $'Review only. Don\\'t edit files.\\n\\nFocus on the API.'")
+        );
+
+        config.windows[0].claude.as_mut().unwrap().role = Some("tester".to_string());
+        let err = validate(&config).unwrap_err().to_string();
+        assert!(err.contains("unknown claude.role 'tester' (known: reviewer, architect, implementer)"), "{}", err);
+    }
+
+    #[test]
+    fn test_claude_settings() {
+        let yaml = r#"
+wsl_distribution: Ubuntu
+layout:
+  grid: "2x1"
+windows:
+  - name: "implementer"
+    claude:
+      project: "~/src/api"
+      model: opus
+      settings: "~/.claude/implementer.json"
+  - name: "reviewer"
+    claude:
+      project: "~/src/api"
+      model: sonnet
+      settings:
+        permissions:
+          deny: ["Edit", "Write"]
+"#;
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert!(validate(&config).is_ok());
+        let reviewer = config.windows[1].claude.as_ref().unwrap();
+        assert!(matches!(reviewer.settings, Some(ClaudeSettings::Inline(ref settings)) if settings.contains_key("permissions")));
+
+        config.windows[0].claude.as_mut().unwrap().settings = Some(ClaudeSettings::File(" ".to_string()));
+        assert!(validate(&config).unwrap_err().to_string().contains("claude.settings must be"));
+        config.windows[0].claude.as_mut().unwrap().settings = None;
+        config.windows[0].claude.as_mut().unwrap().model = Some(String::new());
+        assert!(validate(&config).unwrap_err().to_string().contains("claude.model must not be empty"));
+    }
+
+    #[test]
+    fn test_claude_budget() {
+        let yaml = r#"
+wsl_distribution: Ubuntu
+layout:
+  grid: "2x1"
+windows:
+  - name: "implementer"
+    claude:
+      project: "~/src/api"
+      budget:
+        tokens: 2000000
+        cost_usd: 20
+        pause_dispatch: true
+"#;
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert!(validate(&config).is_ok());
+        let budget = config.windows[0].claude.as_mut().unwrap().budget.as_mut().unwrap();
+        assert_eq!(budget.period().unwrap(), Duration::from_secs(24 * 3600));
+        assert_eq!(budget.cost_usd, Some(20.0));
+
+        budget.period = "0h".to_string();
+        assert!(validate(&config).unwrap_err().to_string().contains("budget.period must be greater than 0"));
+        let budget = config.windows[0].claude.as_mut().unwrap().budget.as_mut().unwrap();
+        budget.period = "168h".to_string();
+        budget.tokens = None;
+        budget.cost_usd = None;
+        assert!(validate(&config).unwrap_err().to_string().contains("needs tokens or cost_usd"));
+    }
+
+    #[test]
+    fn test_provision() {
+        let yaml = r#"
+wsl_distribution: Ubuntu
+layout:
+  grid: "2x1"
+windows:
+  - name: "reviewer"
+    claude:
+      project: "~/src/api"
+    provision:
+      - ~/agents/reviewer/CLAUDE.md
+      - from: "~/agents/reviewer/commands/*.md"
+        to: .claude/commands
+        link: true
+  - name: "shell"
+    working_dir: "~/src/api"
+"#;
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert!(validate(&config).is_ok());
+        let files = &config.windows[0].provision;
+        assert_eq!((files[0].to(), files[0].link()), (".", false));
+        assert_eq!((files[1].from(), files[1].to(), files[1].link()), ("~/agents/reviewer/commands/*.md", ".claude/commands", true));
+
+        config.windows[1].provision = vec![ProvisionFile::Path("agents/CLAUDE.md".to_string())];
+        assert!(validate(&config).unwrap_err().to_string().contains("must be an absolute path"));
+        config.windows[1].provision =
+            vec![ProvisionFile::Entry { from: "/etc/a".to_string(), to: Some("../other".to_string()), link: false }];
+        assert!(validate(&config).unwrap_err().to_string().contains("inside the working directory"));
+        config.windows[1].working_dir = None;
+        assert!(validate(&config).unwrap_err().to_string().contains("'provision' requires 'working_dir'"));
+    }
+
+    #[test]
+    fn test_claude_resume_policy() {
+        let yaml = r#"
+wsl_distribution: Ubuntu
+layout:
+  grid: "2x2"
+windows:
+  - name: "a"
+    claude: { project: "~/a", resume: always }
+  - name: "b"
+    claude: { project: "~/b", resume: ask }
+  - name: "c"
+    claude: { project: "~/c", resume: never, continue: true }
+  - name: "d"
+    claude: { project: "~/d", resume: "always-2" }
+"#;
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        let resume: Vec<_> = config.windows.iter().map(|w| w.claude.as_ref().unwrap().resume.clone().unwrap()).collect();
+        assert_eq!(resume[0], ClaudeResume::Policy(ResumePolicy::Always));
+        assert_eq!(resume[0].recorded_policy(), Some(ResumePolicy::Always));
+        assert_eq!(resume[1].recorded_policy(), Some(ResumePolicy::Ask));
+        assert_eq!(resume[2].recorded_policy(), None);
+        assert_eq!(resume[3], ClaudeResume::Session("always-2".to_string()));
+        assert!(validate(&config).is_ok());
+
+        let mut expanded = config.clone();
+        expand_presets(&mut expanded);
+        assert_eq!(expanded.windows[0].command.as_deref(), Some("claude"));
+
+        config.windows[0].claude.as_mut().unwrap().continue_last = true;
+        assert!(validate(&config).unwrap_err().to_string().contains("cannot be used together"));
+    }
+
+    #[test]
+    fn test_rate_limit() {
+        let yaml = r#"
+wsl_distribution: Ubuntu
+layout:
+  grid: "1x1"
+rate_limit:
+  requests_per_minute: 50
+windows:
+  - name: "api"
+    claude: { project: "~/api" }
+"#;
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert!(validate(&config).is_ok());
+        let limits = config.rate_limit.as_mut().unwrap();
+        assert_eq!((limits.threshold, limits.stagger_secs), (0.8, 5));
+
+        limits.threshold = 1.5;
+        assert!(validate(&config).unwrap_err().to_string().contains("threshold"));
+        config.rate_limit = Some(RateLimitConfig {
+            requests_per_minute: None,
+            tokens_per_minute: None,
+            threshold: 0.8,
+            stagger_secs: 5,
+        });
+        assert!(validate(&config).unwrap_err().to_string().contains("needs requests_per_minute"));
+    }
+
+    #[test]
+    fn test_aliases() {
+        let yaml = r#"
+wsl_distribution: Ubuntu
+layout:
+  grid: "1x1"
+aliases:
+  up: launch --exclude-tags slow
+  down: stop --all
+windows:
+  - name: "api"
+"#;
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert!(validate(&config).is_ok());
+        assert_eq!(config.aliases["down"], "stop --all");
+
+        config.aliases.insert("--up".to_string(), "launch".to_string());
+        assert!(validate(&config).unwrap_err().to_string().contains("one word"));
+        config.aliases.remove("--up");
+        config.aliases.insert("noop".to_string(), " ".to_string());
+        assert!(validate(&config).unwrap_err().to_string().contains("empty command"));
+    }
+
+    #[test]
+    fn test_expand_projects() {
+        let yaml = r#"
+wsl_distribution: Ubuntu
+layout:
+  grid: "2x2"
+project_template:
+  tags: [service]
+  claude:
+    append_system_prompt: "You own the {name} service on {branch}. Task: {task}"
+  commands: ["echo ${HOME} {x}"]
+projects:
+  - repo: ~/services/auth
+    branch: main
+    task: "Fix token refresh"
+  - repo: ~/services/billing/
+    name: pay
+    branch: dev
+    task: "Add invoices"
+windows: []
+"#;
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        expand_projects(&mut config).unwrap();
+        let names: Vec<_> = config.windows.iter().map(|w| w.name.as_str()).collect();
+        assert_eq!(names, vec!["auth", "pay"]);
+        let window = &config.windows[0];
+        assert_eq!(window.tags, vec!["service", "project"]);
+        let claude = window.claude.as_ref().unwrap();
+        assert_eq!(claude.project, "~/services/auth");
+        assert_eq!(claude.append_system_prompt.as_deref(), Some("You own the auth service on main. Task: Fix token refresh"));
+        assert_eq!(window.commands, vec!["echo ${HOME} {x}"]);
+        assert_eq!(config.windows[1].claude.as_ref().unwrap().project, "~/services/billing/");
+
+        // Without a template: a shell in each repo
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        config.project_template = None;
+        config.projects = vec![ProjectEntry::Repo("~/services/auth".to_string())];
+        expand_projects(&mut config).unwrap();
+        assert_eq!(config.windows[0].name, "auth");
+        assert_eq!(config.windows[0].working_dir.as_deref(), Some("~/services/auth"));
+
+        // {branch} is used but the entry has none
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        config.projects = vec![ProjectEntry::Repo("~/services/auth".to_string())];
+        let err = format!("{:#}", expand_projects(&mut config).unwrap_err());
+        assert!(err.contains("{branch} is used but not set"), "{}", err);
+    }
+}
@@ -0,0 +1,60 @@
+//! `--dry-run`: print what would be done instead of doing it
+//!
+//! Everything that changes something outside this process checks here
+//! first: terminals and wt.exe being started, window scripts that move,
+//! close or retitle windows, hooks, worktree and provision scripts, and
+//! processes being signalled or killed. In a dry run they print the exact
+//! command line instead, and the state file and event log aren't written.
+//! Queries (displays, window handles, versions) still run, so the commands
+//! shown use real coordinates.
+
+use std::fmt::Display;
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::shell;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+pub fn set(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Print an operation a dry run skips
+pub fn show(what: impl Display) {
+    println!("[dry-run] {}", what);
+}
+
+/// `cmd` as a shell command line
+pub fn command_line(cmd: &Command) -> String {
+    std::iter::once(cmd.get_program())
+        .chain(cmd.get_args())
+        .map(|arg| shell::quote(&arg.to_string_lossy()))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// In a dry run, print `cmd` instead of running it; true if it must be
+/// skipped
+pub fn skip(cmd: &Command) -> bool {
+    if is_enabled() {
+        show(command_line(cmd));
+    }
+    is_enabled()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_line() {
+        let mut cmd = Command::new("wt.exe");
+        cmd.args(["-w", "wcl-work-1", "new-tab", "--title", "my api", "bash", "-c", "echo 'hi'"]);
+        assert_eq!(command_line(&cmd), r#"wt.exe -w wcl-work-1 new-tab --title 'my api' bash -c 'echo '\''hi'\'''"#);
+    }
+}
@@ -0,0 +1,334 @@
+//! Failure classes of the launcher
+//!
+//! Every fallible function of this crate returns a `LauncherError`, so
+//! callers and `--output json` can tell why something failed: each variant
+//! carries what it failed on (a path, a window, a program) and the error
+//! underneath, if any. [`LauncherError::kind`] groups the variants into the
+//! classes of the JSON error output.
+//!
+//! `{}` prints the error's own message; `{:#}` appends its causes, as
+//! anyhow does (`Failed to launch 'api': Failed to run wt.exe: not found`).
+
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+type Source = Box<dyn Error + Send + Sync + 'static>;
+
+/// Result of the functions of this crate
+pub type Result<T, E = LauncherError> = std::result::Result<T, E>;
+
+/// Why an operation of the launcher failed
+#[derive(Debug, thiserror::Error)]
+pub enum LauncherError {
+    /// The config file couldn't be read
+    ConfigRead {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+
+    /// The config file isn't valid YAML or doesn't fit the config's fields
+    ConfigParse {
+        path: PathBuf,
+        #[source]
+        source: serde_yaml::Error,
+    },
+
+    /// A setting of the config is invalid
+    InvalidConfig(String),
+
+    /// A setting of one window is invalid
+    InvalidWindow { window: String, reason: String },
+
+    /// The value of `setting` is invalid, for the reason in `source`
+    InvalidSetting {
+        setting: String,
+        #[source]
+        source: Source,
+    },
+
+    /// A grid isn't `COLSxROWS`
+    InvalidGrid { grid: String },
+
+    /// A duration isn't e.g. "30s"
+    InvalidDuration { value: String, reason: &'static str },
+
+    /// A hotkey isn't e.g. "Win+Alt+R"
+    InvalidHotkey { hotkey: String, reason: String },
+
+    /// More windows than `grid` has cells
+    TooManyWindows { windows: usize, grid: String, cells: u32 },
+
+    /// No configured window has this name
+    UnknownWindow(String),
+
+    /// `--only` / `--exclude` / `--exclude-tags` left no window
+    NoWindowsLeft,
+
+    /// The displays couldn't be queried
+    DisplayQuery(#[source] Box<LauncherError>),
+
+    /// There is no display with this index
+    DisplayNotFound(u32),
+
+    /// A terminal window couldn't be launched
+    Launch {
+        window: String,
+        #[source]
+        source: Box<LauncherError>,
+    },
+
+    /// A window (title or handle) couldn't be moved into its cell
+    Arrange {
+        window: String,
+        #[source]
+        source: Box<LauncherError>,
+    },
+
+    /// wsl.exe couldn't be run
+    WslRun(#[source] io::Error),
+
+    /// wsl.exe couldn't list the distributions
+    Distributions { stderr: String },
+
+    /// A task queue couldn't be read or changed inside WSL
+    Queue { dir: String, action: &'static str, stderr: String },
+
+    /// A git worktree couldn't be created or removed inside WSL
+    Worktree { path: String, branch: String, action: &'static str, stderr: String },
+
+    /// A file of the launcher couldn't be read or written
+    Io {
+        action: &'static str,
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+
+    /// The state file is corrupt
+    StateParse {
+        path: PathBuf,
+        #[source]
+        source: serde_json::Error,
+    },
+
+    /// Something couldn't be written as JSON
+    Serialize {
+        what: &'static str,
+        #[source]
+        source: serde_json::Error,
+    },
+
+    /// HOME isn't set, so there is nowhere to keep the state
+    HomeNotSet,
+
+    /// A Windows environment variable isn't set
+    WindowsEnvNotSet(String),
+
+    /// The scripts directory can't be found
+    ScriptsDir(#[source] io::Error),
+
+    /// A program couldn't be started
+    Spawn {
+        program: String,
+        #[source]
+        source: io::Error,
+    },
+
+    /// A program couldn't be talked to over its stdin/stdout
+    Pipe {
+        program: String,
+        #[source]
+        source: io::Error,
+    },
+
+    /// A program or script ran and failed
+    Command { program: String, reason: String },
+
+    /// A program printed something that doesn't parse
+    Output {
+        program: String,
+        #[source]
+        source: serde_json::Error,
+    },
+
+    /// The UAC prompt of an elevated launch was declined, or the launch failed
+    Elevation { program: String },
+
+    /// The installed Windows Terminal is too old for a feature
+    WtTooOld { feature: &'static str, min_version: String },
+
+    /// No window of `session` named `window` is running
+    NotRunning { session: String, window: String },
+
+    /// Closing `window` would close the windows sharing its terminal window too
+    SharedWindow { window: String, sharing: Vec<String> },
+
+    /// Another launcher serves the control socket at this path
+    SocketInUse(PathBuf),
+
+    /// A control request doesn't parse
+    Request(#[source] serde_json::Error),
+}
+
+impl fmt::Display for LauncherError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ConfigRead { path, .. } => write!(f, "Failed to read config file: {}", path.display())?,
+            Self::ConfigParse { path, .. } => write!(f, "Failed to parse config file: {}", path.display())?,
+            Self::InvalidConfig(reason) => write!(f, "{}", reason)?,
+            Self::InvalidWindow { window, reason } => write!(f, "Window '{}': {}", window, reason)?,
+            Self::InvalidSetting { setting, .. } => write!(f, "{}", setting)?,
+            Self::InvalidGrid { grid } => {
+                write!(f, "Invalid grid format: {}. Expected format: 'COLSxROWS' (e.g., '2x4')", grid)?
+            }
+            Self::InvalidDuration { value, reason } => write!(f, "Invalid duration '{}': {}", value, reason)?,
+            Self::InvalidHotkey { hotkey, reason } => write!(f, "Invalid hotkey '{}': {}", hotkey, reason)?,
+            Self::TooManyWindows { windows, grid, cells } => {
+                write!(f, "{} windows don't fit in a {} grid ({} cells)", windows, grid, cells)?
+            }
+            Self::UnknownWindow(name) => write!(f, "No window named '{}'", name)?,
+            Self::NoWindowsLeft => write!(f, "No windows left after --only/--exclude/--exclude-tags")?,
+            Self::DisplayQuery(_) => write!(f, "Failed to query displays")?,
+            Self::DisplayNotFound(index) => write!(f, "Display {} not found", index)?,
+            Self::Launch { window, .. } => write!(f, "Failed to launch '{}'", window)?,
+            Self::Arrange { window, .. } => write!(f, "Failed to move '{}'", window)?,
+            Self::WslRun(_) => write!(f, "Failed to run wsl.exe")?,
+            Self::Distributions { stderr } => write!(f, "Failed to list WSL distributions: {}", stderr)?,
+            Self::Queue { dir, action, stderr } => write!(f, "Failed to {} queue {}: {}", action, dir, stderr)?,
+            Self::Worktree { path, branch, action, stderr } => {
+                write!(f, "Failed to {} worktree {} (branch {}): {}", action, path, branch, stderr)?
+            }
+            Self::Io { action, path, .. } => write!(f, "Failed to {} {}", action, path.display())?,
+            Self::StateParse { path, .. } => write!(f, "Failed to parse state file: {}", path.display())?,
+            Self::Serialize { what, .. } => write!(f, "Failed to serialize {}", what)?,
+            Self::HomeNotSet => write!(f, "HOME is not set")?,
+            Self::WindowsEnvNotSet(var) => write!(f, "Windows environment variable {} is not set", var)?,
+            Self::ScriptsDir(_) => write!(f, "Failed to find the scripts directory")?,
+            Self::Spawn { program, .. } => write!(f, "Failed to run {}", program)?,
+            Self::Pipe { program, .. } => write!(f, "Failed to talk to {}", program)?,
+            Self::Command { program, reason } => write!(f, "{} failed: {}", program, reason)?,
+            Self::Output { program, .. } => write!(f, "Unexpected output from {}", program)?,
+            Self::Elevation { program } => write!(f, "Elevated launch of {} was cancelled or failed", program)?,
+            Self::WtTooOld { feature, min_version } => {
+                write!(f, "{} requires Windows Terminal {} or newer", feature, min_version)?
+            }
+            Self::NotRunning { session, window } => {
+                write!(f, "No running window named '{}' in session '{}'", window, session)?
+            }
+            Self::SharedWindow { window, sharing } => write!(
+                f,
+                "'{}' shares its terminal window with {} (tabs/panes mode); select them too or use --all",
+                window,
+                sharing.join(", ")
+            )?,
+            Self::SocketInUse(path) => write!(f, "Another launcher is already listening on {}", path.display())?,
+            Self::Request(_) => write!(f, "Invalid request")?,
+        }
+        if f.alternate() {
+            let mut cause = self.source();
+            while let Some(error) = cause {
+                write!(f, ": {}", error)?;
+                cause = error.source();
+            }
+        }
+        Ok(())
+    }
+}
+
+impl LauncherError {
+    /// Class of the error, as in the `kind` of the JSON error output
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::ConfigRead { .. }
+            | Self::ConfigParse { .. }
+            | Self::InvalidConfig(_)
+            | Self::InvalidWindow { .. }
+            | Self::InvalidSetting { .. }
+            | Self::InvalidGrid { .. }
+            | Self::InvalidDuration { .. }
+            | Self::InvalidHotkey { .. }
+            | Self::TooManyWindows { .. } => "config",
+            Self::DisplayQuery(_) | Self::DisplayNotFound(_) => "display_query",
+            Self::Launch { .. } => "launch",
+            Self::Arrange { .. } => "arrange",
+            Self::WslRun(_) | Self::Distributions { .. } | Self::Queue { .. } | Self::Worktree { .. } => "wsl",
+            _ => "other",
+        }
+    }
+
+    /// The first launcher error in the cause chain of `error`
+    pub fn find<'a>(error: &'a (dyn Error + 'static)) -> Option<&'a LauncherError> {
+        std::iter::successors(Some(error), |&e| e.source()).find_map(|e| e.downcast_ref::<LauncherError>())
+    }
+
+    /// Window setting error, e.g. `Window 'api': font size must be positive`
+    pub(crate) fn window(window: &str, reason: impl Into<String>) -> Self {
+        Self::InvalidWindow { window: window.to_string(), reason: reason.into() }
+    }
+
+    /// Error in the value of `setting`
+    pub(crate) fn setting(setting: impl Into<String>, source: impl Into<Source>) -> Self {
+        Self::InvalidSetting { setting: setting.into(), source: source.into() }
+    }
+
+    pub(crate) fn io(action: &'static str, path: impl Into<PathBuf>) -> impl FnOnce(io::Error) -> Self {
+        let path = path.into();
+        move |source| Self::Io { action, path, source }
+    }
+
+    pub(crate) fn spawn(program: &str) -> impl FnOnce(io::Error) -> Self {
+        let program = program.to_string();
+        move |source| Self::Spawn { program, source }
+    }
+
+    pub(crate) fn command(program: &str, reason: impl Into<String>) -> Self {
+        Self::Command { program: program.to_string(), reason: reason.into() }
+    }
+}
+
+/// `kind` of an error in the JSON output: that of the first classified
+/// launcher error in its cause chain, or "other"
+pub fn kind(error: &(dyn Error + 'static)) -> &'static str {
+    std::iter::successors(Some(error), |&e| e.source())
+        .filter_map(|e| e.downcast_ref::<LauncherError>())
+        .map(LauncherError::kind)
+        .find(|kind| *kind != "other")
+        .unwrap_or("other")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn failing() -> Result<(), LauncherError> {
+        let source = LauncherError::spawn("wt.exe")(io::Error::new(io::ErrorKind::NotFound, "not found"));
+        Err(LauncherError::Launch { window: "api".to_string(), source: Box::new(source) })
+    }
+
+    #[test]
+    fn test_message() {
+        let error = failing().unwrap_err();
+        assert_eq!(error.to_string(), "Failed to launch 'api'");
+        assert_eq!(format!("{:#}", error), "Failed to launch 'api': Failed to run wt.exe: not found");
+        assert_eq!(
+            LauncherError::window("api", "font size must be positive").to_string(),
+            "Window 'api': font size must be positive"
+        );
+    }
+
+    #[test]
+    fn test_kind() {
+        let error = failing().unwrap_err();
+        assert_eq!(error.kind(), "launch");
+        assert_eq!(kind(&error), "launch");
+        assert!(matches!(LauncherError::find(&error), Some(LauncherError::Launch { .. })));
+        // A launch failure is a launch failure whatever went wrong underneath
+        let Err(LauncherError::Launch { source, .. }) = failing() else { unreachable!() };
+        assert_eq!(source.kind(), "other");
+        assert_eq!(kind(&io::Error::other("Window 'x' not found")), "other");
+        assert_eq!(LauncherError::InvalidGrid { grid: "2y4".to_string() }.kind(), "config");
+    }
+}
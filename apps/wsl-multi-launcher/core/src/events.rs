@@ -0,0 +1,184 @@
+//! Append-only event log (`events` subcommand)
+//!
+//! Launcher actions (windows launched, moves retried or failed, restarts)
+//! are appended as one JSON object per line to `events.jsonl` next to the
+//! state file, so flaky arrangements can be debugged after the fact.
+//! Recording is best effort: a log that can't be written never fails the
+//! action being logged.
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tracing::debug;
+
+use crate::error::{LauncherError, Result};
+use crate::state::SessionState;
+
+/// Session the running command acts on, stamped on every event
+static SESSION: Mutex<String> = Mutex::new(String::new());
+
+/// Kinds of event recorded by this process (dry runs included)
+static RECORDED: Mutex<Vec<EventKind>> = Mutex::new(Vec::new());
+
+/// What happened
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EventKind {
+    WindowLaunched,
+    LaunchFailed,
+    WindowMoved,
+    MoveRetried,
+    MoveFailed,
+    WindowRestarted,
+    WindowClosed,
+    TaskDispatched,
+    BudgetExceeded,
+}
+
+impl std::fmt::Display for EventKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            EventKind::WindowLaunched => "window-launched",
+            EventKind::LaunchFailed => "launch-failed",
+            EventKind::WindowMoved => "window-moved",
+            EventKind::MoveRetried => "move-retried",
+            EventKind::MoveFailed => "move-failed",
+            EventKind::WindowRestarted => "window-restarted",
+            EventKind::WindowClosed => "window-closed",
+            EventKind::TaskDispatched => "task-dispatched",
+            EventKind::BudgetExceeded => "budget-exceeded",
+        };
+        f.write_str(s)
+    }
+}
+
+/// One line of the event log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Event {
+    pub time: DateTime<Local>,
+    pub session: String,
+    pub kind: EventKind,
+    pub window: String,
+
+    /// Error message, handle or other context
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+/// Set the session stamped on events recorded by this process
+pub fn init(session: &str) {
+    if let Ok(mut current) = SESSION.lock() {
+        *current = session.to_string();
+    }
+}
+
+/// Whether this process recorded an event of `kind`, e.g. to fail a command
+/// that carried on past a failed window
+pub fn recorded(kind: EventKind) -> bool {
+    RECORDED.lock().is_ok_and(|recorded| recorded.contains(&kind))
+}
+
+/// Path of the event log
+pub fn path() -> Result<PathBuf> {
+    Ok(SessionState::default_dir()?.join("events.jsonl"))
+}
+
+/// Append an event to the log, logging (not returning) any error
+///
+/// A dry run doesn't write the log.
+pub fn record(kind: EventKind, window: &str, detail: Option<String>) {
+    if let Ok(mut recorded) = RECORDED.lock()
+        && !recorded.contains(&kind)
+    {
+        recorded.push(kind);
+    }
+    if crate::dry_run::is_enabled() {
+        return;
+    }
+    let event = Event {
+        time: Local::now(),
+        session: SESSION.lock().map(|s| s.clone()).unwrap_or_default(),
+        kind,
+        window: window.to_string(),
+        detail,
+    };
+    if let Err(e) = path().and_then(|path| append_to(&path, &event)) {
+        debug!("Failed to record event: {:#}", e);
+    }
+}
+
+fn append_to(path: &Path, event: &Event) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(LauncherError::io("create", dir))?;
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(LauncherError::io("open", path))?;
+    let line = serde_json::to_string(event).map_err(|source| LauncherError::Serialize { what: "an event", source })?;
+    writeln!(file, "{}", line).map_err(LauncherError::io("write", path))
+}
+
+/// Events at or after `since` (all if None), oldest first
+///
+/// Lines that don't parse (e.g. cut short by a crash) are skipped.
+pub fn load_from(path: &Path, since: Option<DateTime<Local>>) -> Result<Vec<Event>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(path).map_err(LauncherError::io("read", path))?;
+    Ok(content
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Event>(line).ok())
+        .filter(|event| since.is_none_or(|since| event.time >= since))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(minutes_ago: i64, kind: EventKind) -> Event {
+        Event {
+            time: Local::now() - chrono::Duration::minutes(minutes_ago),
+            session: "work".to_string(),
+            kind,
+            window: "claude-1".to_string(),
+            detail: None,
+        }
+    }
+
+    #[test]
+    fn test_append_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state").join("events.jsonl");
+
+        append_to(&path, &event(90, EventKind::WindowLaunched)).unwrap();
+        append_to(&path, &Event { detail: Some("not found".to_string()), ..event(5, EventKind::MoveRetried) })
+            .unwrap();
+        fs::OpenOptions::new().append(true).open(&path).unwrap().write_all(b"{\"broken\n").unwrap();
+
+        let all = load_from(&path, None).unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].kind, EventKind::WindowLaunched);
+        assert_eq!(all[1].detail.as_deref(), Some("not found"));
+
+        let recent = load_from(&path, Some(Local::now() - chrono::Duration::hours(1))).unwrap();
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0].kind, EventKind::MoveRetried);
+
+        assert!(load_from(&dir.path().join("missing.jsonl"), None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_event_json() {
+        let json = serde_json::to_string(&event(0, EventKind::MoveFailed)).unwrap();
+        assert!(json.contains("\"kind\":\"move-failed\""));
+        assert!(!json.contains("detail"));
+        assert_eq!(EventKind::MoveFailed.to_string(), "move-failed");
+    }
+}
@@ -0,0 +1,93 @@
+//! Exit status of window commands
+//!
+//! Each WSL window's command is wrapped so that, when it exits, its exit
+//! code and time are written to `<state dir>/exit-status/<session>/<window>`
+//! inside the distribution. `ps` shows the code, so a failed startup
+//! command doesn't just leave an empty-looking terminal behind.
+
+use chrono::{DateTime, Local, TimeZone};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::error::Result;
+use crate::shell;
+use crate::state::SessionState;
+
+/// How a window's command last exited
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExitStatus {
+    pub code: i32,
+    pub at: DateTime<Local>,
+}
+
+/// Status file name for a window; names may contain characters paths can't
+pub fn file_name(window: &str) -> String {
+    window.replace(['/', '\\'], "_")
+}
+
+/// Path of a window's status file
+pub fn status_path(session: &str, window: &str) -> Result<PathBuf> {
+    Ok(SessionState::default_dir()?.join("exit-status").join(session).join(file_name(window)))
+}
+
+/// Wrap a shell command so its exit status is written to the window's
+/// status file; the wrapped command still ends with that status
+///
+/// The previous status is removed first, so a window that is running
+/// again never shows a stale code.
+pub fn wrap_command(session: &str, window: &str, command: &str) -> String {
+    format!(
+        "__wml_d=\"${{XDG_STATE_HOME:-$HOME/.local/state}}\"/wsl-multi-launcher/exit-status/{}; \
+         __wml_f=\"$__wml_d\"/{}; rm -f \"$__wml_f\"; {{ {}; }}; __wml_rc=$?; \
+         mkdir -p \"$__wml_d\" && echo \"$__wml_rc $(date +%s)\" > \"$__wml_f\"; (exit $__wml_rc)",
+        shell::quote(session),
+        shell::quote(&file_name(window)),
+        command
+    )
+}
+
+/// Last exit status of a window's command; None while it is still running
+/// or if it was never recorded
+pub fn read(session: &str, window: &str) -> Option<ExitStatus> {
+    let content = fs::read_to_string(status_path(session, window).ok()?).ok()?;
+    parse(&content)
+}
+
+fn parse(content: &str) -> Option<ExitStatus> {
+    let mut fields = content.split_whitespace();
+    let code = fields.next()?.parse().ok()?;
+    let secs = fields.next()?.parse().ok()?;
+    let at = Local.timestamp_opt(secs, 0).single()?;
+    Some(ExitStatus { code, at })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap_command() {
+        let wrapped = wrap_command("work", "claude 1", "cd ~/app && claude");
+        assert!(wrapped.starts_with(
+            "__wml_d=\"${XDG_STATE_HOME:-$HOME/.local/state}\"/wsl-multi-launcher/exit-status/work; \
+             __wml_f=\"$__wml_d\"/'claude 1'; rm -f \"$__wml_f\"; "
+        ));
+        assert!(wrapped.contains("{ cd ~/app && claude; }; __wml_rc=$?; "));
+        assert!(wrapped.ends_with("> \"$__wml_f\"; (exit $__wml_rc)"));
+    }
+
+    #[test]
+    fn test_parse() {
+        let status = parse("127 1700000000\n").unwrap();
+        assert_eq!(status.code, 127);
+        assert_eq!(status.at.timestamp(), 1_700_000_000);
+        assert!(parse("").is_none());
+        assert!(parse("abc 1").is_none());
+        assert!(parse("0").is_none());
+    }
+
+    #[test]
+    fn test_file_name() {
+        assert_eq!(file_name("api/server"), "api_server");
+    }
+}
@@ -0,0 +1,173 @@
+//! Per-window health checks run by `watch`
+//!
+//! A window's `healthcheck.command` runs inside the WSL distribution every
+//! `interval`; after `retries` consecutive failures the window is marked
+//! unhealthy (shown by `ps`) and, with `restart: true`, relaunched.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::process::Command;
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+use crate::config::{HealthCheckConfig, WindowConfig, WindowKind};
+use crate::error::{LauncherError, Result};
+use crate::shell;
+
+/// Result of a window's health checks so far
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Health {
+    Healthy,
+    Unhealthy,
+}
+
+impl std::fmt::Display for Health {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Health::Healthy => "healthy",
+            Health::Unhealthy => "unhealthy",
+        };
+        f.write_str(s)
+    }
+}
+
+#[derive(Debug, Default)]
+struct Entry {
+    next_check: Option<Instant>,
+    failures: u32,
+}
+
+/// Schedules probes and counts consecutive failures per window
+#[derive(Debug, Default)]
+pub struct HealthTracker {
+    windows: HashMap<String, Entry>,
+}
+
+impl HealthTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether `name` should be probed now; schedules the next probe if so
+    pub fn due(&mut self, name: &str, interval: Duration, now: Instant) -> bool {
+        let entry = self.windows.entry(name.to_string()).or_default();
+        if entry.next_check.is_some_and(|next| now < next) {
+            return false;
+        }
+        entry.next_check = Some(now + interval);
+        true
+    }
+
+    /// Record a probe result and return the window's health
+    pub fn report(&mut self, name: &str, passed: bool, retries: u32) -> Health {
+        let entry = self.windows.entry(name.to_string()).or_default();
+        if passed {
+            entry.failures = 0;
+        } else {
+            entry.failures += 1;
+        }
+        if entry.failures >= retries { Health::Unhealthy } else { Health::Healthy }
+    }
+
+    /// Consecutive failed probes of `name`
+    pub fn failures(&self, name: &str) -> u32 {
+        self.windows.get(name).map_or(0, |e| e.failures)
+    }
+
+    /// Forget failures after a restart and give the new window `grace` to start
+    pub fn reset(&mut self, name: &str, now: Instant, grace: Duration) {
+        self.windows.insert(
+            name.to_string(),
+            Entry { next_check: Some(now + grace), failures: 0 },
+        );
+    }
+}
+
+/// Run a window's health check inside `distribution`; true if it passed
+///
+/// The probe runs in the window's working directory (WSL windows only) and
+/// is killed after `timeout`. Errors mean wsl.exe itself couldn't run.
+pub fn probe(distribution: &str, window: &WindowConfig, check: &HealthCheckConfig) -> Result<bool> {
+    let script = probe_script(window, check)?;
+    debug!("Health check of '{}': {}", window.name, script);
+    let status = Command::new("wsl.exe")
+        .args(["-d", distribution, "--", "bash", "-c", &script])
+        .output()
+        .map_err(LauncherError::WslRun)?
+        .status;
+    Ok(status.success())
+}
+
+fn probe_script(window: &WindowConfig, check: &HealthCheckConfig) -> Result<String> {
+    let probe = format!(
+        "timeout {} bash -c {}",
+        check.timeout()?.as_secs(),
+        shell::quote(&check.command)
+    );
+    Ok(match window.working_dir {
+        Some(ref dir) if window.kind == WindowKind::Wsl => {
+            format!("cd {} && {}", shell::quote_path(dir), probe)
+        }
+        _ => probe,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(command: &str) -> HealthCheckConfig {
+        HealthCheckConfig {
+            command: command.to_string(),
+            interval: "30s".to_string(),
+            timeout: "5s".to_string(),
+            retries: 2,
+            restart: false,
+        }
+    }
+
+    #[test]
+    fn test_due_schedules_next_probe() {
+        let mut tracker = HealthTracker::new();
+        let start = Instant::now();
+        let interval = Duration::from_secs(30);
+
+        assert!(tracker.due("a", interval, start));
+        assert!(!tracker.due("a", interval, start + Duration::from_secs(10)));
+        assert!(tracker.due("a", interval, start + Duration::from_secs(30)));
+        assert!(tracker.due("b", interval, start));
+    }
+
+    #[test]
+    fn test_report_counts_consecutive_failures() {
+        let mut tracker = HealthTracker::new();
+        assert_eq!(tracker.report("a", false, 2), Health::Healthy);
+        assert_eq!(tracker.failures("a"), 1);
+        assert_eq!(tracker.report("a", false, 2), Health::Unhealthy);
+        assert_eq!(tracker.report("a", true, 2), Health::Healthy);
+        assert_eq!(tracker.failures("a"), 0);
+
+        tracker.report("a", false, 1);
+        let now = Instant::now();
+        tracker.reset("a", now, Duration::from_secs(60));
+        assert_eq!(tracker.failures("a"), 0);
+        assert!(!tracker.due("a", Duration::from_secs(30), now));
+    }
+
+    #[test]
+    fn test_probe_script() {
+        let window = WindowConfig {
+            name: "server".to_string(),
+            working_dir: Some("~/app".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            probe_script(&window, &check("curl -sf localhost:3000")).unwrap(),
+            "cd \"$HOME\"/app && timeout 5 bash -c 'curl -sf localhost:3000'"
+        );
+
+        let window = WindowConfig { kind: WindowKind::Powershell, ..window };
+        assert_eq!(probe_script(&window, &check("true")).unwrap(), "timeout 5 bash -c true");
+    }
+}
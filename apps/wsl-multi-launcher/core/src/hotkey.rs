@@ -0,0 +1,219 @@
+//! Global hotkeys while `watch` runs
+//!
+//! The hotkeys are registered with RegisterHotKey by `hotkeys.ps1`, a
+//! long-running PowerShell process that prints the id of every hotkey
+//! pressed. Each press is turned into a control request and handled by the
+//! watch loop like a request from the control socket.
+
+use std::io::{BufRead, BufReader};
+use std::process::Child;
+use std::sync::mpsc;
+use tracing::{debug, info, warn};
+
+use crate::config::Config;
+use crate::error::{LauncherError, Result};
+use crate::ipc::{Pending, Request};
+use crate::windows;
+
+const MOD_ALT: u32 = 0x0001;
+const MOD_CONTROL: u32 = 0x0002;
+const MOD_SHIFT: u32 = 0x0004;
+const MOD_WIN: u32 = 0x0008;
+/// Holding the keys down doesn't repeat the hotkey
+const MOD_NOREPEAT: u32 = 0x4000;
+
+/// A key combination as RegisterHotKey takes it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Hotkey {
+    pub modifiers: u32,
+    pub vk: u32,
+}
+
+/// Parse modifiers such as "Win+Alt" (case-insensitive) of `hotkey`
+fn parse_modifiers(s: &str, hotkey: &str) -> Result<u32> {
+    let mut modifiers = 0;
+    for part in s.split('+').map(str::trim) {
+        modifiers |= match part.to_ascii_lowercase().as_str() {
+            "win" => MOD_WIN,
+            "alt" => MOD_ALT,
+            "ctrl" | "control" => MOD_CONTROL,
+            "shift" => MOD_SHIFT,
+            _ => {
+                return Err(LauncherError::InvalidHotkey {
+                    hotkey: hotkey.to_string(),
+                    reason: format!("unknown modifier '{}' (use Win, Alt, Ctrl, Shift)", part),
+                });
+            }
+        };
+    }
+    Ok(modifiers)
+}
+
+/// Virtual-key code of a key name: A-Z, 0-9 or F1-F24
+fn parse_key(key: &str) -> Option<u32> {
+    let upper = key.to_ascii_uppercase();
+    let mut chars = upper.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if c.is_ascii_uppercase() || c.is_ascii_digit() => Some(c as u32),
+        (Some('F'), Some(_)) => match upper[1..].parse::<u32>() {
+            Ok(n @ 1..=24) => Some(0x70 + n - 1),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Parse a hotkey such as "Win+Alt+R"; at least one modifier is required
+pub fn parse(s: &str) -> Result<Hotkey> {
+    let invalid = |reason: String| LauncherError::InvalidHotkey { hotkey: s.to_string(), reason };
+    let (modifiers, key) = s
+        .rsplit_once('+')
+        .ok_or_else(|| invalid("expected e.g. \"Win+Alt+R\"".to_string()))?;
+    let vk = parse_key(key.trim())
+        .ok_or_else(|| invalid(format!("unknown key '{}' (use A-Z, 0-9 or F1-F24)", key.trim())))?;
+    Ok(Hotkey { modifiers: parse_modifiers(modifiers, s)? | MOD_NOREPEAT, vk })
+}
+
+/// Every configured hotkey with the request it sends
+pub fn bindings(config: &Config) -> Result<Vec<(Hotkey, Request)>> {
+    let mut bindings = Vec::new();
+    if let Some(ref focus) = config.hotkeys.focus {
+        let modifiers =
+            parse_modifiers(focus, focus).map_err(|e| LauncherError::setting("hotkeys.focus", e))? | MOD_NOREPEAT;
+        for (i, window) in config.windows.iter().take(9).enumerate() {
+            let hotkey = Hotkey { modifiers, vk: '1' as u32 + i as u32 };
+            bindings.push((hotkey, Request::Focus { name: window.name.clone() }));
+        }
+    }
+    if let Some(ref arrange) = config.hotkeys.arrange {
+        let hotkey = parse(arrange).map_err(|e| LauncherError::setting("hotkeys.arrange", e))?;
+        bindings.push((hotkey, Request::Arrange));
+    }
+    for window in &config.windows {
+        if let Some(ref keys) = window.hotkey {
+            let hotkey =
+                parse(keys).map_err(|e| LauncherError::setting(format!("Window '{}': hotkey", window.name), e))?;
+            bindings.push((hotkey, Request::Focus { name: window.name.clone() }));
+        }
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    for (hotkey, request) in &bindings {
+        if !seen.insert(hotkey) {
+            return Err(LauncherError::InvalidConfig(format!("Hotkey bound twice (second use: {:?})", request)));
+        }
+    }
+    Ok(bindings)
+}
+
+/// `-Keys` argument of hotkeys.ps1: `<id>:<modifiers>:<vk>` per binding,
+/// where the id is the binding's index
+fn keys_arg(bindings: &[(Hotkey, Request)]) -> String {
+    bindings
+        .iter()
+        .enumerate()
+        .map(|(id, (hotkey, _))| format!("{}:{}:{}", id, hotkey.modifiers, hotkey.vk))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Listener process; killed when dropped so the hotkeys are released
+pub struct Listener {
+    child: Child,
+}
+
+impl Drop for Listener {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Register the configured hotkeys and forward presses to `requests`;
+/// None if no hotkeys are configured
+pub fn listen(config: &Config, requests: mpsc::Sender<Pending>) -> Result<Option<Listener>> {
+    let bindings = bindings(config)?;
+    if bindings.is_empty() {
+        return Ok(None);
+    }
+    let mut child = windows::spawn_hotkey_listener(&keys_arg(&bindings))?;
+    let stdout = child.stdout.take().expect("hotkeys.ps1 is started with its stdout piped");
+
+    std::thread::spawn(move || {
+        for line in BufReader::new(stdout).lines() {
+            let Ok(line) = line else { break };
+            let line = line.trim();
+            if line == "ready" {
+                info!("Registered {} hotkey(s)", bindings.len());
+                continue;
+            }
+            if let Some(id) = line.strip_prefix("failed ") {
+                if let Some((_, request)) = id.parse::<usize>().ok().and_then(|id| bindings.get(id)) {
+                    warn!("Hotkey for {:?} is already taken by another program", request);
+                }
+                continue;
+            }
+            let Some((_, request)) = line.parse::<usize>().ok().and_then(|id| bindings.get(id)) else {
+                continue;
+            };
+            debug!("Hotkey pressed: {:?}", request);
+            let (pending, answer) = Pending::new(request.clone());
+            if requests.send(pending).is_err() {
+                break;
+            }
+            if let Ok(response) = answer.recv()
+                && !response.ok
+            {
+                warn!("Hotkey {:?} failed: {}", request, response.message.unwrap_or_default());
+            }
+        }
+    });
+    Ok(Some(Listener { child }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{HotkeysConfig, WindowConfig};
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            parse("Win+Alt+R").unwrap(),
+            Hotkey { modifiers: MOD_WIN | MOD_ALT | MOD_NOREPEAT, vk: 'R' as u32 }
+        );
+        assert_eq!(parse("ctrl+shift+f12").unwrap().vk, 0x7B);
+        assert_eq!(parse("Alt+1").unwrap().vk, 0x31);
+        assert!(parse("R").is_err());
+        assert!(parse("Win+Alt+Space").is_err());
+        assert!(parse("Hyper+R").is_err());
+        assert!(parse("Win+F25").is_err());
+    }
+
+    #[test]
+    fn test_bindings() {
+        let config = Config {
+            windows: vec![
+                WindowConfig { name: "a".to_string(), ..Default::default() },
+                WindowConfig { name: "b".to_string(), hotkey: Some("Ctrl+Alt+B".to_string()), ..Default::default() },
+            ],
+            hotkeys: HotkeysConfig { focus: Some("Win+Alt".to_string()), arrange: Some("Win+Alt+R".to_string()) },
+            ..Default::default()
+        };
+        let bound = bindings(&config).unwrap();
+        assert_eq!(bound.len(), 4);
+        assert_eq!(bound[0].0.vk, '1' as u32);
+        assert_eq!(bound[1], (bound[1].0, Request::Focus { name: "b".to_string() }));
+        assert_eq!(bound[2].1, Request::Arrange);
+        assert_eq!(bound[3].0.modifiers, MOD_CONTROL | MOD_ALT | MOD_NOREPEAT);
+
+        let arg = keys_arg(&bound);
+        assert!(arg.starts_with(&format!("0:{}:49,1:{}:50,", MOD_WIN | MOD_ALT | MOD_NOREPEAT, MOD_WIN | MOD_ALT | MOD_NOREPEAT)));
+
+        let clash = Config {
+            hotkeys: HotkeysConfig { focus: None, arrange: Some("Ctrl+Alt+B".to_string()) },
+            ..config
+        };
+        assert!(bindings(&clash).unwrap_err().to_string().contains("bound twice"));
+    }
+}
@@ -0,0 +1,186 @@
+//! Control socket for a running `watch`
+//!
+//! `watch` listens on a Unix socket next to the state file and accepts one
+//! JSON request per line, e.g. `{"cmd": "focus", "name": "claude-1"}`, and
+//! answers each with one JSON line. Requests are handed to the watch loop
+//! over a channel so all window operations stay on its thread.
+
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use tracing::{debug, warn};
+
+use crate::error::{LauncherError, Result};
+use crate::state::SessionState;
+
+/// A command sent to the running launcher
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "cmd", rename_all = "kebab-case")]
+pub enum Request {
+    /// Bring a window to the foreground
+    Focus { name: String },
+    /// Close a window and launch it again in its cell
+    Restart { name: String },
+    /// Move every window back into its cell
+    Arrange,
+    /// Launch a configured window that isn't running
+    LaunchWindow { name: String },
+    /// Live status of all windows (same data as `ps` with `--output json`)
+    Status,
+}
+
+/// Answer to a request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Response {
+    pub ok: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<serde_json::Value>,
+}
+
+impl Response {
+    pub fn ok(message: impl Into<String>) -> Self {
+        Self { ok: true, message: Some(message.into()), data: None }
+    }
+
+    pub fn data(data: serde_json::Value) -> Self {
+        Self { ok: true, message: None, data: Some(data) }
+    }
+
+    pub fn error(message: impl Into<String>) -> Self {
+        Self { ok: false, message: Some(message.into()), data: None }
+    }
+}
+
+/// A request waiting for the watch loop to handle it
+pub struct Pending {
+    pub request: Request,
+    reply: mpsc::Sender<Response>,
+}
+
+impl Pending {
+    /// A request from inside the launcher (e.g. a hotkey) and the
+    /// receiver its response is sent to
+    pub fn new(request: Request) -> (Self, mpsc::Receiver<Response>) {
+        let (reply, answer) = mpsc::channel();
+        (Self { request, reply }, answer)
+    }
+
+    pub fn reply(self, response: Response) {
+        // The client may have hung up; nothing to do then
+        let _ = self.reply.send(response);
+    }
+}
+
+/// Socket path for a session: next to the state file, `<session>.sock`
+pub fn socket_path(session: &str) -> Result<PathBuf> {
+    Ok(SessionState::default_dir()?.join(format!("{}.sock", session)))
+}
+
+/// Listen on `path` and forward parsed requests to `tx`
+///
+/// A socket left behind by a crashed launcher is replaced; one that still
+/// accepts connections means another launcher is serving this session.
+pub fn serve(path: &Path, tx: mpsc::Sender<Pending>) -> Result<()> {
+    if path.exists() {
+        if UnixStream::connect(path).is_ok() {
+            return Err(LauncherError::SocketInUse(path.to_path_buf()));
+        }
+        std::fs::remove_file(path).map_err(LauncherError::io("remove the stale socket", path))?;
+    }
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(LauncherError::io("create", dir))?;
+    }
+    let listener = UnixListener::bind(path).map_err(LauncherError::io("listen on", path))?;
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    let tx = tx.clone();
+                    std::thread::spawn(move || handle_client(stream, tx));
+                }
+                Err(e) => warn!("Control socket accept failed: {}", e),
+            }
+        }
+    });
+    Ok(())
+}
+
+/// Serve one client: a response line for every request line
+fn handle_client(stream: UnixStream, requests: mpsc::Sender<Pending>) {
+    let Ok(mut writer) = stream.try_clone() else { return };
+    for line in BufReader::new(stream).lines() {
+        let Ok(line) = line else { break };
+        if line.trim().is_empty() {
+            continue;
+        }
+        debug!("Control request: {}", line);
+
+        let response = match parse_request(&line) {
+            Ok(request) => {
+                let (pending, answer) = Pending::new(request);
+                if requests.send(pending).is_err() {
+                    break;
+                }
+                answer.recv().unwrap_or_else(|_| Response::error("launcher stopped"))
+            }
+            Err(e) => Response::error(format!("{:#}", e)),
+        };
+
+        let Ok(json) = serde_json::to_string(&response) else { break };
+        if writeln!(writer, "{}", json).is_err() {
+            break;
+        }
+    }
+}
+
+fn parse_request(line: &str) -> Result<Request> {
+    serde_json::from_str(line).map_err(LauncherError::Request)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_request() {
+        assert_eq!(
+            parse_request(r#"{"cmd": "focus", "name": "claude-1"}"#).unwrap(),
+            Request::Focus { name: "claude-1".to_string() }
+        );
+        assert_eq!(
+            parse_request(r#"{"cmd": "launch-window", "name": "logs"}"#).unwrap(),
+            Request::LaunchWindow { name: "logs".to_string() }
+        );
+        assert_eq!(parse_request(r#"{"cmd": "status"}"#).unwrap(), Request::Status);
+        assert!(parse_request(r#"{"cmd": "reboot"}"#).is_err());
+        assert!(parse_request("focus claude-1").is_err());
+    }
+
+    #[test]
+    fn test_serve_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("work.sock");
+        let (tx, requests) = mpsc::channel();
+        serve(&path, tx).unwrap();
+
+        let mut client = UnixStream::connect(&path).unwrap();
+        writeln!(client, r#"{{"cmd": "arrange"}}"#).unwrap();
+        writeln!(client, "not json").unwrap();
+
+        let pending = requests.recv().unwrap();
+        assert_eq!(pending.request, Request::Arrange);
+        pending.reply(Response::ok("arranged"));
+
+        let mut lines = BufReader::new(client).lines();
+        let first: Response = serde_json::from_str(&lines.next().unwrap().unwrap()).unwrap();
+        assert!(first.ok);
+        assert_eq!(first.message.as_deref(), Some("arranged"));
+        let second: Response = serde_json::from_str(&lines.next().unwrap().unwrap()).unwrap();
+        assert!(!second.ok);
+    }
+}
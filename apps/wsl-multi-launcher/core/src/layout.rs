@@ -0,0 +1,328 @@
+use serde::{Deserialize, Serialize};
+
+/// Rectangle representing position and size
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl Rect {
+    pub fn new(x: i32, y: i32, width: i32, height: i32) -> Self {
+        Self { x, y, width, height }
+    }
+}
+
+impl std::fmt::Display for Rect {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}x{} at ({}, {})", self.width, self.height, self.x, self.y)
+    }
+}
+
+/// Display information
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DisplayInfo {
+    #[serde(rename = "DeviceName")]
+    pub device_name: String,
+
+    #[serde(rename = "Primary")]
+    pub primary: bool,
+
+    #[serde(rename = "Bounds")]
+    pub bounds: BoundsInfo,
+
+    #[serde(rename = "WorkingArea")]
+    pub working_area: BoundsInfo,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BoundsInfo {
+    #[serde(rename = "X")]
+    pub x: i32,
+
+    #[serde(rename = "Y")]
+    pub y: i32,
+
+    #[serde(rename = "Width")]
+    pub width: i32,
+
+    #[serde(rename = "Height")]
+    pub height: i32,
+}
+
+/// Grid layout calculator
+pub struct GridLayout {
+    cols: u32,
+    rows: u32,
+    display_area: Rect,
+}
+
+impl GridLayout {
+    /// Create a new grid layout for the given display area
+    pub fn new(cols: u32, rows: u32, display_area: Rect) -> Self {
+        Self {
+            cols,
+            rows,
+            display_area,
+        }
+    }
+
+    /// Calculate the rectangle for a window at the given grid position
+    /// Position is 0-indexed, starting from top-left
+    pub fn calculate_position(&self, index: usize) -> Rect {
+        let col = (index as u32) % self.cols;
+        let row = (index as u32) / self.cols;
+
+        let cell_width = self.display_area.width / self.cols as i32;
+        let cell_height = self.display_area.height / self.rows as i32;
+
+        Rect {
+            x: self.display_area.x + (col as i32 * cell_width),
+            y: self.display_area.y + (row as i32 * cell_height),
+            width: cell_width,
+            height: cell_height,
+        }
+    }
+
+    /// Calculate positions for all windows
+    #[allow(dead_code)]
+    pub fn calculate_all_positions(&self, count: usize) -> Vec<Rect> {
+        (0..count).map(|i| self.calculate_position(i)).collect()
+    }
+
+    /// Area of a dropdown window: the top half of the display, full width
+    pub fn dropdown_area(&self) -> Rect {
+        Rect {
+            height: self.display_area.height / 2,
+            ..self.display_area
+        }
+    }
+
+    /// Get the maximum number of windows this grid can hold
+    #[allow(dead_code)]
+    pub fn max_windows(&self) -> u32 {
+        self.cols * self.rows
+    }
+}
+
+/// Smallest near-square grid holding `count` windows, with at least as
+/// many columns as rows (displays are wider than tall)
+pub fn fitting_grid(count: usize) -> (u32, u32) {
+    let cols = (1..).find(|c| c * c >= count).unwrap_or(1).max(1);
+    let rows = count.div_ceil(cols).max(1);
+    (cols as u32, rows as u32)
+}
+
+/// Index of the cell closest to `rect` (by center) that isn't in `occupied`
+pub fn nearest_free_cell(cells: &[Rect], occupied: &[Rect], rect: &Rect) -> Option<usize> {
+    let center = |r: &Rect| (r.x as i64 + r.width as i64 / 2, r.y as i64 + r.height as i64 / 2);
+    let (cx, cy) = center(rect);
+    cells
+        .iter()
+        .enumerate()
+        .filter(|(_, cell)| !occupied.contains(cell))
+        .min_by_key(|(_, cell)| {
+            let (x, y) = center(cell);
+            (x - cx).pow(2) + (y - cy).pow(2)
+        })
+        .map(|(i, _)| i)
+}
+
+/// One step in building a grid out of split panes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PaneAction {
+    /// Split the focused pane, putting window `index` in a new pane to the right
+    SplitRight { index: usize, size: f64 },
+    /// Split the focused pane, putting window `index` in a new pane below
+    SplitDown { index: usize, size: f64 },
+    /// Move focus to the pane on the left
+    FocusLeft,
+}
+
+/// Compute the split sequence that turns a single pane (running window 0)
+/// into a `cols`-column grid of `count` panes
+///
+/// Columns are created first, then each column is split into rows from
+/// right to left. `size` is the fraction of the focused pane given to the
+/// new pane, so every column/row ends up the same size.
+pub fn pane_splits(cols: u32, count: usize) -> Vec<PaneAction> {
+    let cols = cols.max(1) as usize;
+    let used_cols = cols.min(count);
+    let mut actions = Vec::new();
+
+    for c in 1..used_cols {
+        let remaining = used_cols - c;
+        actions.push(PaneAction::SplitRight {
+            index: c,
+            size: remaining as f64 / (remaining + 1) as f64,
+        });
+    }
+
+    for c in (0..used_cols).rev() {
+        let rows_in_col = (count - c).div_ceil(cols);
+        for r in 1..rows_in_col {
+            let remaining = rows_in_col - r;
+            actions.push(PaneAction::SplitDown {
+                index: r * cols + c,
+                size: remaining as f64 / (remaining + 1) as f64,
+            });
+        }
+        if c > 0 {
+            actions.push(PaneAction::FocusLeft);
+        }
+    }
+
+    // Focus moves are only useful when a split follows them
+    while actions.last() == Some(&PaneAction::FocusLeft) {
+        actions.pop();
+    }
+
+    actions
+}
+
+/// Windows Terminal pane ID of each window in a pane grid
+///
+/// wt numbers panes in creation order, which differs from the grid order
+/// once rows are split: index `i` of the result is window `i`'s pane ID.
+pub fn pane_ids(cols: u32, count: usize) -> Vec<usize> {
+    let mut ids = vec![0; count];
+    let created = pane_splits(cols, count).into_iter().filter_map(|action| match action {
+        PaneAction::SplitRight { index, .. } | PaneAction::SplitDown { index, .. } => Some(index),
+        PaneAction::FocusLeft => None,
+    });
+    for (id, index) in created.enumerate() {
+        ids[index] = id + 1;
+    }
+    ids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grid_layout_2x2() {
+        let display = Rect::new(0, 0, 1920, 1080);
+        let layout = GridLayout::new(2, 2, display);
+
+        assert_eq!(layout.max_windows(), 4);
+
+        // Top-left
+        assert_eq!(layout.calculate_position(0), Rect::new(0, 0, 960, 540));
+        // Top-right
+        assert_eq!(layout.calculate_position(1), Rect::new(960, 0, 960, 540));
+        // Bottom-left
+        assert_eq!(layout.calculate_position(2), Rect::new(0, 540, 960, 540));
+        // Bottom-right
+        assert_eq!(layout.calculate_position(3), Rect::new(960, 540, 960, 540));
+    }
+
+    #[test]
+    fn test_grid_layout_2x4() {
+        let display = Rect::new(0, 0, 1920, 1080);
+        let layout = GridLayout::new(2, 4, display);
+
+        assert_eq!(layout.max_windows(), 8);
+
+        // First row
+        assert_eq!(layout.calculate_position(0), Rect::new(0, 0, 960, 270));
+        assert_eq!(layout.calculate_position(1), Rect::new(960, 0, 960, 270));
+
+        // Second row
+        assert_eq!(layout.calculate_position(2), Rect::new(0, 270, 960, 270));
+        assert_eq!(layout.calculate_position(3), Rect::new(960, 270, 960, 270));
+    }
+
+    #[test]
+    fn test_grid_layout_with_offset() {
+        // Secondary display at x=1920
+        let display = Rect::new(1920, 0, 1920, 1080);
+        let layout = GridLayout::new(2, 2, display);
+
+        // Top-left of secondary display
+        assert_eq!(layout.calculate_position(0), Rect::new(1920, 0, 960, 540));
+        // Top-right of secondary display
+        assert_eq!(layout.calculate_position(1), Rect::new(2880, 0, 960, 540));
+    }
+
+    #[test]
+    fn test_calculate_all_positions() {
+        let display = Rect::new(0, 0, 800, 600);
+        let layout = GridLayout::new(2, 2, display);
+
+        let positions = layout.calculate_all_positions(3);
+        assert_eq!(positions.len(), 3);
+        assert_eq!(positions[0], Rect::new(0, 0, 400, 300));
+        assert_eq!(positions[1], Rect::new(400, 0, 400, 300));
+        assert_eq!(positions[2], Rect::new(0, 300, 400, 300));
+    }
+
+    #[test]
+    fn test_pane_splits_2x2() {
+        use PaneAction::*;
+        assert_eq!(
+            pane_splits(2, 4),
+            vec![
+                SplitRight { index: 1, size: 0.5 },
+                SplitDown { index: 3, size: 0.5 },
+                FocusLeft,
+                SplitDown { index: 2, size: 0.5 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pane_splits_3_columns_partial_row() {
+        use PaneAction::*;
+        let actions = pane_splits(3, 4);
+        assert_eq!(actions.len(), 5);
+        assert_eq!(actions[0], SplitRight { index: 1, size: 2.0 / 3.0 });
+        assert_eq!(actions[1], SplitRight { index: 2, size: 0.5 });
+        // Column 2 and 1 have a single row, column 0 gets window 3 below
+        assert_eq!(actions[2], FocusLeft);
+        assert_eq!(actions[3], FocusLeft);
+        assert_eq!(actions[4], SplitDown { index: 3, size: 0.5 });
+    }
+
+    #[test]
+    fn test_pane_splits_single() {
+        assert!(pane_splits(2, 1).is_empty());
+    }
+
+    #[test]
+    fn test_pane_ids() {
+        // 2x2: columns 0,1 first, then the right column's row, then the left's
+        assert_eq!(pane_ids(2, 4), vec![0, 1, 3, 2]);
+        assert_eq!(pane_ids(3, 3), vec![0, 1, 2]);
+        assert_eq!(pane_ids(1, 1), vec![0]);
+    }
+
+    #[test]
+    fn test_dropdown_area() {
+        let grid = GridLayout::new(2, 2, Rect::new(1920, 0, 1920, 1032));
+        assert_eq!(grid.dropdown_area(), Rect::new(1920, 0, 1920, 516));
+    }
+
+    #[test]
+    fn test_fitting_grid() {
+        assert_eq!(fitting_grid(0), (1, 1));
+        assert_eq!(fitting_grid(2), (2, 1));
+        assert_eq!(fitting_grid(6), (3, 2));
+        assert_eq!(fitting_grid(7), (3, 3));
+        assert_eq!(fitting_grid(10), (4, 3));
+    }
+
+    #[test]
+    fn test_nearest_free_cell() {
+        let grid = GridLayout::new(2, 2, Rect::new(0, 0, 1000, 1000));
+        let cells: Vec<Rect> = (0..4).map(|i| grid.calculate_position(i)).collect();
+        // Dropped near the bottom-right corner
+        let rect = Rect::new(600, 700, 400, 300);
+
+        assert_eq!(nearest_free_cell(&cells, &[], &rect), Some(3));
+        assert_eq!(nearest_free_cell(&cells, &[cells[3]], &rect), Some(2));
+        assert_eq!(nearest_free_cell(&cells, &cells, &rect), None);
+    }
+}
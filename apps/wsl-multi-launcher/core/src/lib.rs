@@ -0,0 +1,65 @@
+//! Building blocks of wsl-multi-launcher, for tools that embed it
+//!
+//! The `wsl-multi-launcher` CLI is a frontend over this crate; a GUI, an
+//! MCP server or a test harness can drive the same pieces:
+//!
+//! - [`config`]: loading and validating the YAML config (`Config`,
+//!   `WindowConfig`, `WindowFilter`)
+//! - [`layout`]: grid cells and pane splits on a display's working area
+//! - [`windows`]: displays and window handles through the PowerShell
+//!   scripts (query, move, hide, close)
+//! - [`terminal`]: terminal backends (`TerminalBackend`: Windows Terminal,
+//!   conhost, WezTerm, Alacritty) that launch one configured window each
+//! - [`wsl`]: the `wsl.exe` command lines behind a window, and
+//!   distribution queries
+//! - [`state`] / [`events`]: the record of launched windows and the event
+//!   log, shared with a running CLI
+//!
+//! Every fallible function returns an [`error::LauncherError`], which
+//! carries what failed (the config path, the window, the program) and tells
+//! a bad config from a failed launch or move.
+//!
+//! ```no_run
+//! use wsl_launcher_core::{config, layout, terminal, windows, wsl};
+//!
+//! # fn main() -> wsl_launcher_core::error::Result<()> {
+//! let config = config::load("config.yaml")?;
+//! let displays = windows::get_displays()?;
+//! let area = windows::get_display_working_area(&displays, config.target_display)?;
+//! let (cols, rows) = config.layout.parse_grid()?;
+//! let grid = layout::GridLayout::new(cols, rows, area);
+//!
+//! let kind = terminal::resolve(config.terminal);
+//! let launcher = wsl::WslLauncher::new(&config.wsl_distribution);
+//! let backend = terminal::create(kind, launcher, "my-session");
+//! for (i, window) in config.windows.iter().enumerate() {
+//!     backend.launch(window, i)?;
+//!     println!("{} goes to {}", window.name, grid.calculate_position(i));
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Commands go through [`dry_run`], so with `dry_run::set(true)` nothing is
+//! launched or moved and the commands are printed instead.
+
+pub mod claude;
+pub mod config;
+pub mod dry_run;
+pub mod error;
+pub mod events;
+pub mod exit_status;
+pub mod health;
+pub mod hotkey;
+pub mod ipc;
+pub mod layout;
+pub mod logs;
+mod ps_host;
+pub mod queue;
+pub mod shell;
+pub mod state;
+pub mod terminal;
+pub mod windows;
+pub mod worktree;
+pub mod wsl;
+pub mod wt_profiles;
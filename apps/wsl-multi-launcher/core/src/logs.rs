@@ -0,0 +1,85 @@
+//! Per-window output logs
+//!
+//! With `logs.enabled`, every WSL window runs its command under `script(1)`
+//! so everything shown in the terminal is also appended to
+//! `<state dir>/logs/<session>/<window>.log` inside the distribution. Logs
+//! are rotated when a window starts and the file has grown past
+//! `logs.max_size_mb`.
+
+use std::path::PathBuf;
+
+use crate::config::LogConfig;
+use crate::error::Result;
+use crate::shell;
+use crate::state::SessionState;
+
+/// Log file name for a window; names may contain characters paths can't
+fn file_name(window: &str) -> String {
+    format!("{}.log", window.replace(['/', '\\'], "_"))
+}
+
+/// Directory holding the log files of a session
+pub fn session_dir(session: &str) -> Result<PathBuf> {
+    Ok(SessionState::default_dir()?.join("logs").join(session))
+}
+
+/// Path of a window's log file
+pub fn log_path(session: &str, window: &str) -> Result<PathBuf> {
+    Ok(session_dir(session)?.join(file_name(window)))
+}
+
+/// Wrap a shell command so its terminal output is also written to the
+/// window's log, rotating the log first if it is too big
+pub fn wrap_command(session: &str, window: &str, command: &str, config: &LogConfig) -> String {
+    let max_bytes = config.max_size_mb * 1024 * 1024;
+    let rotate = format!(
+        "for i in $(seq {} -1 1); do [ -f \"$f.$i\" ] && mv -f \"$f.$i\" \"$f.$((i+1))\"; done; mv -f \"$f\" \"$f.1\"",
+        config.keep.saturating_sub(1)
+    );
+    format!(
+        "d=\"${{XDG_STATE_HOME:-$HOME/.local/state}}\"/wsl-multi-launcher/logs/{}; f=\"$d\"/{}; mkdir -p \"$d\"; \
+         if [ \"$(stat -c %s \"$f\" 2>/dev/null || echo 0)\" -gt {} ]; then {}; fi; \
+         exec script -qefa -c {} \"$f\"",
+        shell::quote(session),
+        shell::quote(&file_name(window)),
+        max_bytes,
+        rotate,
+        shell::quote(command)
+    )
+}
+
+/// Last `count` lines of `content`
+pub fn tail_lines(content: &str, count: usize) -> Vec<&str> {
+    let lines: Vec<&str> = content.lines().collect();
+    lines[lines.len().saturating_sub(count)..].to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_name() {
+        assert_eq!(file_name("claude-1"), "claude-1.log");
+        assert_eq!(file_name("api/server"), "api_server.log");
+    }
+
+    #[test]
+    fn test_wrap_command() {
+        let config = LogConfig { enabled: true, max_size_mb: 1, keep: 3 };
+        let wrapped = wrap_command("work", "claude 1", "cd ~/app && claude", &config);
+        assert!(wrapped.starts_with(
+            "d=\"${XDG_STATE_HOME:-$HOME/.local/state}\"/wsl-multi-launcher/logs/work; f=\"$d\"/'claude 1.log'; "
+        ));
+        assert!(wrapped.contains("-gt 1048576 ]"));
+        assert!(wrapped.contains("$(seq 2 -1 1)"));
+        assert!(wrapped.ends_with("exec script -qefa -c 'cd ~/app && claude' \"$f\""));
+    }
+
+    #[test]
+    fn test_tail_lines() {
+        assert_eq!(tail_lines("a\nb\nc\n", 2), vec!["b", "c"]);
+        assert_eq!(tail_lines("a\nb", 10), vec!["a", "b"]);
+        assert!(tail_lines("", 5).is_empty());
+    }
+}
@@ -0,0 +1,162 @@
+//! One PowerShell process running the window scripts of a command
+//!
+//! Starting powershell.exe takes 300–800ms, longer than most scripts take
+//! to run, so arranging many windows was mostly spent starting PowerShell.
+//! The first script call starts `host.ps1`, which runs each script it is
+//! sent (one JSON line per request and per answer), and later calls reuse
+//! it until this process exits and closes its stdin. If the host can't be
+//! started or stops answering, scripts run in their own powershell.exe.
+
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use std::sync::Mutex;
+
+use serde::Deserialize;
+use tracing::{debug, warn};
+
+use crate::error::{LauncherError, Result};
+use crate::windows;
+
+static HOST: Mutex<Host> = Mutex::new(Host::NotStarted);
+
+enum Host {
+    NotStarted,
+    Running(Process),
+    /// Failed to start or stopped answering; scripts run on their own
+    Unavailable,
+}
+
+struct Process {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
+
+/// How a script run by the host went
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Answer {
+    /// Exited with 0 and didn't throw
+    pub ok: bool,
+    /// What the script printed
+    pub output: String,
+    /// Errors the script wrote
+    pub error: String,
+}
+
+/// Run the script at `win_script_path` (a Windows path) with `args`
+/// ("-Name", "value" pairs) in the host
+///
+/// Returns None if the host isn't available (or `args` aren't all pairs),
+/// so the caller runs the script itself.
+pub fn run(win_script_path: &str, args: &[String]) -> Option<Answer> {
+    let params = params(args)?;
+    let mut host = HOST.lock().unwrap_or_else(|e| e.into_inner());
+    if let Host::NotStarted = *host {
+        *host = match start() {
+            Ok(process) => Host::Running(process),
+            Err(e) => {
+                debug!("PowerShell host not available, running scripts one by one: {:#}", e);
+                Host::Unavailable
+            }
+        };
+    }
+    let Host::Running(process) = &mut *host else {
+        return None;
+    };
+
+    let request = serde_json::json!({ "Script": win_script_path, "Params": params });
+    if writeln!(process.stdin, "{}", request).and_then(|()| process.stdin.flush()).is_err() {
+        // The request never got there, so it can still run on its own
+        warn!("PowerShell host exited; running scripts one by one");
+        stop(&mut host);
+        return None;
+    }
+    match read_answer(process) {
+        Ok(answer) => Some(answer),
+        Err(e) => {
+            warn!("PowerShell host stopped answering; running scripts one by one: {:#}", e);
+            stop(&mut host);
+            Some(Answer { ok: false, output: String::new(), error: format!("{:#}", e) })
+        }
+    }
+}
+
+fn start() -> Result<Process> {
+    let script = windows::get_scripts_dir()?.join("host.ps1");
+    let win_script_path = windows::wsl_to_windows_path(&script)?;
+    let mut child = Command::new("powershell.exe")
+        .args(["-NoProfile", "-ExecutionPolicy", "Bypass", "-File", &win_script_path])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(LauncherError::spawn("host.ps1"))?;
+    let stdin = child.stdin.take().expect("host.ps1 is started with its stdin piped");
+    let mut stdout = BufReader::new(child.stdout.take().expect("host.ps1 is started with its stdout piped"));
+
+    let mut line = String::new();
+    stdout.read_line(&mut line).map_err(pipe)?;
+    if line.trim() != "ready" {
+        let _ = child.kill();
+        let _ = child.wait();
+        return Err(LauncherError::command("host.ps1", format!("didn't start (printed {:?})", line.trim())));
+    }
+    debug!("Started PowerShell host (PID {})", child.id());
+    Ok(Process { child, stdin, stdout })
+}
+
+fn read_answer(process: &mut Process) -> Result<Answer> {
+    let mut line = String::new();
+    if process.stdout.read_line(&mut line).map_err(pipe)? == 0 {
+        return Err(LauncherError::command("host.ps1", "exited"));
+    }
+    serde_json::from_str(&line).map_err(|source| LauncherError::Output { program: "host.ps1".to_string(), source })
+}
+
+fn pipe(source: std::io::Error) -> LauncherError {
+    LauncherError::Pipe { program: "host.ps1".to_string(), source }
+}
+
+fn stop(host: &mut Host) {
+    if let Host::Running(process) = host {
+        let _ = process.child.kill();
+        let _ = process.child.wait();
+    }
+    *host = Host::Unavailable;
+}
+
+/// Script parameters from "-Name", "value" pairs
+fn params(args: &[String]) -> Option<serde_json::Map<String, serde_json::Value>> {
+    if !args.len().is_multiple_of(2) {
+        return None;
+    }
+    args.chunks(2)
+        .map(|pair| Some((pair[0].strip_prefix('-')?.to_string(), serde_json::Value::from(pair[1].as_str()))))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn test_params() {
+        let params = params(&strings(&["-Handle", "1234", "-X", "-8", "-Title", "api 2"])).unwrap();
+        assert_eq!(serde_json::Value::Object(params), serde_json::json!({"Handle": "1234", "X": "-8", "Title": "api 2"}));
+        assert!(super::params(&[]).unwrap().is_empty());
+        // Switches and stray values can't be sent
+        assert!(super::params(&strings(&["-Handle", "1", "-Force"])).is_none());
+        assert!(super::params(&strings(&["1234", "-Handle"])).is_none());
+    }
+
+    #[test]
+    fn test_answer() {
+        let answer: Answer = serde_json::from_str(r#"{"Error":"","Ok":true,"Output":"[11,12]\r\n"}"#).unwrap();
+        assert_eq!(answer, Answer { ok: true, output: "[11,12]\r\n".to_string(), error: String::new() });
+    }
+}
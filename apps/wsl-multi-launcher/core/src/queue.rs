@@ -0,0 +1,212 @@
+//! Shared task queue worked off by `queue:` windows
+//!
+//! The queue is a directory inside the WSL distribution:
+//!
+//! - `pending/`: task files (prompts), taken in name order
+//! - `running/<worker>/`: the task a worker is on, with its output so far
+//! - `done/`, `failed/`: finished tasks next to `<task>.out`, by claude's exit status
+//!
+//! Every worker window runs a shell loop that claims the next pending task
+//! by moving it into its own running directory (a rename, so two workers
+//! never get the same task), runs `claude -p` on it and files the result.
+//! A task left in `running/` by a worker that died is put back on start.
+//! While a `paused` file exists in the queue directory (`watch` creates it
+//! near the `rate_limit:`), workers finish their task but start no new one.
+
+use chrono::Local;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use tracing::debug;
+
+use crate::config::QueueWorker;
+use crate::error::{LauncherError, Result};
+use crate::shell;
+
+/// Shell loop of a worker window; `claude` is the preset's command line
+pub fn worker_command(worker: &QueueWorker, name: &str, claude: &str) -> String {
+    format!(
+        "q={dir}; w={name}; r=\"$q/running/$w\"; mkdir -p \"$q/pending\" \"$r\" \"$q/done\" \"$q/failed\" || exit 1; \
+         for f in \"$r\"/*; do [ -f \"$f\" ] || continue; case \"$f\" in *.out) rm -f \"$f\" ;; *) mv \"$f\" \"$q/pending/\" ;; esac; done; \
+         echo \"$w: waiting for tasks in $q/pending\"; \
+         while :; do \
+         if [ -e \"$q/paused\" ]; then sleep {poll}; continue; fi; \
+         t=; for f in \"$q/pending\"/*; do [ -f \"$f\" ] && mv \"$f\" \"$r/\" 2>/dev/null && {{ t=\"${{f##*/}}\"; break; }}; done; \
+         if [ -z \"$t\" ]; then sleep {poll}; continue; fi; \
+         echo \"==> $t\"; {claude} -p < \"$r/$t\" 2>&1 | tee \"$r/$t.out\"; s=${{PIPESTATUS[0]}}; \
+         if [ \"$s\" -eq 0 ]; then d=done; else d=failed; fi; \
+         mv \"$r/$t.out\" \"$q/$d/$t.out\"; mv \"$r/$t\" \"$q/$d/$t\"; echo \"<== $t ($d)\"; \
+         done",
+        dir = shell::quote_path(&worker.dir),
+        name = shell::quote(name),
+        poll = worker.poll_secs,
+        claude = claude,
+    )
+}
+
+/// File name for a new task: a timestamp, so tasks run in the order they
+/// were added, and a few words of the text
+fn task_name(text: &str) -> String {
+    let words: String = text
+        .split_whitespace()
+        .take(6)
+        .collect::<Vec<_>>()
+        .join("-")
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c.to_ascii_lowercase() } else { '-' })
+        .take(40)
+        .collect();
+    let words = words.trim_matches('-');
+    let stamp = Local::now().format("%Y%m%d-%H%M%S%.3f");
+    if words.is_empty() { format!("{}.md", stamp) } else { format!("{}-{}.md", stamp, words) }
+}
+
+/// Add a task to the queue in `dir`, returning its file name
+///
+/// The file is written next to the queue first and then moved in, so a
+/// worker never picks up half a task.
+pub fn add(distribution: &str, dir: &str, text: &str) -> Result<String> {
+    let name = task_name(text);
+    let script = format!(
+        "q={dir}; mkdir -p \"$q/tmp\" \"$q/pending\" && cat > \"$q/tmp/\"{name} && mv \"$q/tmp/\"{name} \"$q/pending/\"",
+        dir = shell::quote_path(dir),
+        name = shell::quote(&name),
+    );
+    debug!("Adding task {}: {}", name, script);
+    let mut child = Command::new("wsl.exe")
+        .args(["-d", distribution, "--", "bash", "-c", &script])
+        .stdin(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(LauncherError::WslRun)?;
+    let pipe = |source| LauncherError::Pipe { program: "wsl.exe".to_string(), source };
+    let stdin = child.stdin.as_mut().expect("wsl.exe is started with its stdin piped");
+    stdin.write_all(text.as_bytes()).map_err(pipe)?;
+    let output = child.wait_with_output().map_err(pipe)?;
+    if !output.status.success() {
+        return Err(failed(dir, "add a task to", &output.stderr));
+    }
+    Ok(name)
+}
+
+/// Pause the workers of the queue in `dir` (with the reason), or let them
+/// go on (`reason` None)
+pub fn set_paused(distribution: &str, dir: &str, reason: Option<&str>) -> Result<()> {
+    let script = match reason {
+        Some(reason) => format!(
+            "q={}; mkdir -p \"$q\" && printf '%s\\n' {} > \"$q/paused\"",
+            shell::quote_path(dir),
+            shell::quote(reason)
+        ),
+        None => format!("rm -f {}/paused", shell::quote_path(dir)),
+    };
+    let output = Command::new("wsl.exe")
+        .args(["-d", distribution, "--", "bash", "-c", &script])
+        .output()
+        .map_err(LauncherError::WslRun)?;
+    if !output.status.success() {
+        return Err(failed(dir, "update", &output.stderr));
+    }
+    Ok(())
+}
+
+/// Tasks of a queue by state
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Status {
+    /// Why the workers are paused, if they are
+    pub paused: Option<String>,
+    pub pending: Vec<String>,
+    /// (worker, task)
+    pub running: Vec<(String, String)>,
+    pub done: Vec<String>,
+    pub failed: Vec<String>,
+}
+
+/// Script listing the tasks of a queue as "<state> <task>" lines, with
+/// "running <worker> <task>" for running ones, after "paused <reason>" if
+/// the workers are paused
+fn status_script(dir: &str) -> String {
+    format!(
+        "q={}; if [ -f \"$q/paused\" ]; then printf 'paused %s\\n' \"$(head -n 1 \"$q/paused\")\"; fi; \
+         for d in pending done failed; do for f in \"$q/$d\"/*; do [ -f \"$f\" ] || continue; \
+         case \"$f\" in *.out) ;; *) printf '%s %s\\n' \"$d\" \"${{f##*/}}\" ;; esac; done; done; \
+         for r in \"$q/running\"/*/; do for f in \"$r\"*; do [ -f \"$f\" ] || continue; \
+         case \"$f\" in *.out) ;; *) w=\"${{r%/}}\"; printf 'running %s %s\\n' \"${{w##*/}}\" \"${{f##*/}}\" ;; esac; done; done",
+        shell::quote_path(dir)
+    )
+}
+
+fn parse_status(output: &str) -> Status {
+    let mut status = Status::default();
+    for line in output.lines() {
+        let Some((state, rest)) = line.split_once(' ') else { continue };
+        match state {
+            "paused" => status.paused = Some(rest.to_string()),
+            "pending" => status.pending.push(rest.to_string()),
+            "done" => status.done.push(rest.to_string()),
+            "failed" => status.failed.push(rest.to_string()),
+            "running" => {
+                if let Some((worker, task)) = rest.split_once(' ') {
+                    status.running.push((worker.to_string(), task.to_string()));
+                }
+            }
+            _ => {}
+        }
+    }
+    status
+}
+
+/// Tasks of the queue in `dir`
+pub fn status(distribution: &str, dir: &str) -> Result<Status> {
+    let output = Command::new("wsl.exe")
+        .args(["-d", distribution, "--", "bash", "-c", &status_script(dir)])
+        .output()
+        .map_err(LauncherError::WslRun)?;
+    if !output.status.success() {
+        return Err(failed(dir, "read", &output.stderr));
+    }
+    Ok(parse_status(&String::from_utf8_lossy(&output.stdout)))
+}
+
+fn failed(dir: &str, action: &'static str, stderr: &[u8]) -> LauncherError {
+    LauncherError::Queue {
+        dir: dir.to_string(),
+        action,
+        stderr: String::from_utf8_lossy(stderr).trim().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_worker_command() {
+        let worker = QueueWorker { dir: "~/queue".to_string(), poll_secs: 5 };
+        let command = worker_command(&worker, "queue-1", "claude --model sonnet");
+        assert!(command.starts_with("q=\"$HOME\"/queue; w=queue-1; r=\"$q/running/$w\"; "));
+        assert!(command.contains("mv \"$f\" \"$r/\" 2>/dev/null && { t=\"${f##*/}\"; break; }"));
+        assert!(command.contains("while :; do if [ -e \"$q/paused\" ]; then sleep 5; continue; fi; t=;"));
+        assert!(command.contains("claude --model sonnet -p < \"$r/$t\" 2>&1 | tee \"$r/$t.out\"; s=${PIPESTATUS[0]};"));
+        assert!(!command.contains('\n'));
+    }
+
+    #[test]
+    fn test_task_name() {
+        let name = task_name("Fix the flaky login test, then open a PR please");
+        assert!(name.ends_with("-fix-the-flaky-login-test--then.md"), "{}", name);
+        assert_eq!(name.len(), "20260101-120000.000-fix-the-flaky-login-test--then.md".len());
+        assert!(!task_name("!!!").contains("-.md"));
+    }
+
+    #[test]
+    fn test_parse_status() {
+        let output = "paused 48/50 requests in the last minute\npending b.md\npending c.md\ndone a.md\nfailed x.md\nrunning queue-1 d.md\nnoise\n";
+        let status = parse_status(output);
+        assert_eq!(status.pending, vec!["b.md", "c.md"]);
+        assert_eq!(status.running, vec![("queue-1".to_string(), "d.md".to_string())]);
+        assert_eq!(status.done, vec!["a.md"]);
+        assert_eq!(status.failed, vec!["x.md"]);
+        assert_eq!(status.paused.as_deref(), Some("48/50 requests in the last minute"));
+        assert_eq!(parse_status("pending b.md\n").paused, None);
+    }
+}
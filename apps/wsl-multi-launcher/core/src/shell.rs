@@ -0,0 +1,248 @@
+//! Quoting helpers for commands passed through wt.exe -> wsl.exe -> bash
+//!
+//! Generated commands take two hops that each interpret special characters:
+//! Windows Terminal splits its command line on `;` (subcommand separator),
+//! and bash interprets quotes, `$`, spaces and operators. Values that must
+//! arrive verbatim (paths, titles) are quoted for bash first and the final
+//! command string is then escaped for wt.
+
+/// Characters that never need quoting in a POSIX shell word
+fn is_safe_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "_@%+=:,./-".contains(c)
+}
+
+/// Quote a string as a single POSIX shell word
+///
+/// Strings made only of safe characters are returned unchanged so simple
+/// paths stay readable in logs.
+pub fn quote(s: &str) -> String {
+    if s.is_empty() {
+        return "''".to_string();
+    }
+    if s.chars().all(is_safe_char) {
+        return s.to_string();
+    }
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+/// Quote a Linux path, keeping a leading `~` expandable as `$HOME`
+pub fn quote_path(path: &str) -> String {
+    if path == "~" {
+        "\"$HOME\"".to_string()
+    } else if let Some(rest) = path.strip_prefix("~/") {
+        if rest.is_empty() {
+            "\"$HOME\"/".to_string()
+        } else {
+            format!("\"$HOME\"/{}", quote(rest))
+        }
+    } else {
+        quote(path)
+    }
+}
+
+/// Quote a Linux path like `quote_path`, but leave `*` and `?` unquoted
+/// so bash expands them as a glob
+pub fn quote_glob(path: &str) -> String {
+    if path == "~" {
+        return quote_path(path);
+    }
+    let (mut quoted, rest) = match path.strip_prefix("~/") {
+        Some(rest) => ("\"$HOME\"/".to_string(), rest),
+        None => (String::new(), path),
+    };
+    let mut literal = String::new();
+    for c in rest.chars() {
+        if c == '*' || c == '?' {
+            if !literal.is_empty() {
+                quoted.push_str(&quote(&std::mem::take(&mut literal)));
+            }
+            quoted.push(c);
+        } else {
+            literal.push(c);
+        }
+    }
+    if !literal.is_empty() {
+        quoted.push_str(&quote(&literal));
+    }
+    quoted
+}
+
+/// Escape a command line argument for Windows Terminal
+///
+/// wt.exe treats an unescaped `;` as the start of a new subcommand, even
+/// inside the command passed to a new tab.
+pub fn escape_wt(s: &str) -> String {
+    s.replace(';', r"\;")
+}
+
+/// Quote an argument for a Windows command line (CommandLineToArgvW rules)
+///
+/// Used where a whole command line is stored as one string, such as a
+/// Windows Terminal profile's `commandline`.
+pub fn quote_windows(arg: &str) -> String {
+    if !arg.is_empty() && !arg.contains([' ', '\t', '"']) {
+        return arg.to_string();
+    }
+
+    let mut quoted = String::from("\"");
+    let mut backslashes = 0;
+    for c in arg.chars() {
+        match c {
+            '\\' => backslashes += 1,
+            '"' => {
+                // Backslashes before a quote must be doubled, plus one for the quote
+                quoted.push_str(&"\\".repeat(backslashes * 2 + 1));
+                quoted.push('"');
+                backslashes = 0;
+            }
+            _ => {
+                quoted.push_str(&"\\".repeat(backslashes));
+                quoted.push(c);
+                backslashes = 0;
+            }
+        }
+    }
+    // Trailing backslashes are doubled so the closing quote stays a quote
+    quoted.push_str(&"\\".repeat(backslashes * 2));
+    quoted.push('"');
+    quoted
+}
+
+/// Join arguments into a Windows command line
+pub fn join_windows(args: &[String]) -> String {
+    args.iter().map(|a| quote_windows(a)).collect::<Vec<_>>().join(" ")
+}
+
+/// Quote a string as a PowerShell single-quoted literal
+pub fn quote_powershell(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Minimal POSIX word splitter used to check that quoting round-trips
+    fn split(input: &str, home: &str) -> Vec<String> {
+        let mut words = Vec::new();
+        let mut current = String::new();
+        let mut in_word = false;
+        let mut chars = input.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '\'' => {
+                    in_word = true;
+                    for c in chars.by_ref() {
+                        if c == '\'' {
+                            break;
+                        }
+                        current.push(c);
+                    }
+                }
+                '"' => {
+                    in_word = true;
+                    let mut inner = String::new();
+                    for c in chars.by_ref() {
+                        if c == '"' {
+                            break;
+                        }
+                        inner.push(c);
+                    }
+                    current.push_str(&inner.replace("$HOME", home));
+                }
+                '\\' => {
+                    in_word = true;
+                    if let Some(next) = chars.next() {
+                        current.push(next);
+                    }
+                }
+                ' ' => {
+                    if in_word {
+                        words.push(std::mem::take(&mut current));
+                        in_word = false;
+                    }
+                }
+                _ => {
+                    in_word = true;
+                    current.push(c);
+                }
+            }
+        }
+        if in_word {
+            words.push(current);
+        }
+        words
+    }
+
+    #[test]
+    fn test_quote_round_trip() {
+        let cases = [
+            "simple",
+            "/home/user/my project",
+            "it's",
+            "$HOME",
+            "a && b",
+            "semi;colon",
+            "\"double\"",
+            "back\\slash",
+            "`whoami`",
+            "$(rm -rf /)",
+            "tab\there",
+            "'''",
+            "",
+            "日本語 ディレクトリ",
+        ];
+        for case in cases {
+            assert_eq!(split(&quote(case), "/home/u"), vec![case.to_string()], "input: {:?}", case);
+        }
+    }
+
+    #[test]
+    fn test_quote_safe_unchanged() {
+        assert_eq!(quote("/tmp"), "/tmp");
+        assert_eq!(quote("project-1/src"), "project-1/src");
+        assert_eq!(quote("a b"), "'a b'");
+    }
+
+    #[test]
+    fn test_quote_path_home() {
+        assert_eq!(quote_path("~"), "\"$HOME\"");
+        assert_eq!(quote_path("~/workspace"), "\"$HOME\"/workspace");
+        assert_eq!(quote_path("~/my dir"), "\"$HOME\"/'my dir'");
+        assert_eq!(split(&quote_path("~/it's here"), "/home/u"), vec!["/home/u/it's here"]);
+        // A ~ that is not a prefix is literal
+        assert_eq!(quote_path("/tmp/~x"), "'/tmp/~x'");
+    }
+
+    #[test]
+    fn test_quote_glob() {
+        assert_eq!(quote_glob("~/agents/reviewer/*.md"), "\"$HOME\"/agents/reviewer/*.md");
+        assert_eq!(quote_glob("/opt/my agents/a?.md"), "'/opt/my agents/a'?.md");
+        assert_eq!(quote_glob("/etc/CLAUDE.md"), "/etc/CLAUDE.md");
+        assert_eq!(quote_glob("~"), "\"$HOME\"");
+        assert_eq!(quote_glob("*"), "*");
+    }
+
+    #[test]
+    fn test_escape_wt() {
+        assert_eq!(escape_wt("make; bash"), r"make\; bash");
+        assert_eq!(escape_wt("a && b"), "a && b");
+    }
+
+    #[test]
+    fn test_quote_windows() {
+        assert_eq!(quote_windows("wsl.exe"), "wsl.exe");
+        assert_eq!(quote_windows(""), "\"\"");
+        assert_eq!(quote_windows("a b"), "\"a b\"");
+        assert_eq!(quote_windows("say \"hi\""), r#""say \"hi\"""#);
+        assert_eq!(quote_windows(r"C:\My Dir\"), r#""C:\My Dir\\""#);
+        assert_eq!(quote_windows(r"C:\path"), r"C:\path");
+    }
+
+    #[test]
+    fn test_quote_powershell() {
+        assert_eq!(quote_powershell("wt.exe"), "'wt.exe'");
+        assert_eq!(quote_powershell("it's"), "'it''s'");
+    }
+}
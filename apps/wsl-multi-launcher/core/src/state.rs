@@ -0,0 +1,387 @@
+//! Persistent record of launched windows
+//!
+//! Every launch writes what it opened (window handle, terminal PID, wt
+//! window name, target rectangle) to a JSON state file, so later commands
+//! can find the windows again without matching titles.
+
+use crate::error::{LauncherError, Result};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::health::Health;
+use crate::layout::Rect;
+
+/// A window opened by `launch`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WindowRecord {
+    /// Session (config file name) the window belongs to
+    pub session: String,
+
+    /// Window name from the config
+    pub name: String,
+
+    /// Windows Terminal window name (`wt -w`), if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wt_window: Option<String>,
+
+    /// Top-level window handle (HWND)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub handle: Option<i64>,
+
+    /// PID of the terminal process, when it lives as long as the window
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pid: Option<u32>,
+
+    /// Rectangle the window was arranged into
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rect: Option<Rect>,
+
+    pub launched_at: DateTime<Local>,
+
+    /// Result of the window's health checks, updated by `watch`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub health: Option<Health>,
+
+    /// When the window went idle, as detected by `watch`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idle_since: Option<DateTime<Local>>,
+
+    /// ID of the claude conversation the window runs (`claude.resume: always|ask`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub claude_session: Option<String>,
+}
+
+/// Pixels a window may differ from its recorded rect before counting as
+/// moved (window borders make the actual rect slightly larger)
+const MOVE_TOLERANCE: i32 = 16;
+
+/// Live status of a recorded window
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WindowStatus {
+    /// Open where it was placed
+    Running,
+    /// Open but hidden (e.g. a dropdown)
+    Hidden,
+    /// Open, but moved or resized by the user
+    Moved,
+    /// The window no longer exists
+    Missing,
+}
+
+impl std::fmt::Display for WindowStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            WindowStatus::Running => "running",
+            WindowStatus::Hidden => "hidden",
+            WindowStatus::Moved => "moved",
+            WindowStatus::Missing => "missing",
+        };
+        f.write_str(s)
+    }
+}
+
+impl WindowRecord {
+    /// Status given the window's current rect and visibility, if it exists
+    pub fn status(&self, current: Option<(Rect, bool)>) -> WindowStatus {
+        let Some((rect, visible)) = current else {
+            return WindowStatus::Missing;
+        };
+        if !visible {
+            return WindowStatus::Hidden;
+        }
+        let moved = self.rect.is_some_and(|placed| !same_place(&placed, &rect));
+        if moved { WindowStatus::Moved } else { WindowStatus::Running }
+    }
+}
+
+/// Whether a window at `actual` is still where it was placed (`placed`),
+/// within the border tolerance
+pub fn same_place(placed: &Rect, actual: &Rect) -> bool {
+    (placed.x - actual.x).abs() <= MOVE_TOLERANCE
+        && (placed.y - actual.y).abs() <= MOVE_TOLERANCE
+        && (placed.width - actual.width).abs() <= MOVE_TOLERANCE * 2
+        && (placed.height - actual.height).abs() <= MOVE_TOLERANCE * 2
+}
+
+/// Claude conversation last run by a window; kept after the window is
+/// closed, so the next launch can resume it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClaudeSessionRecord {
+    pub session: String,
+    pub name: String,
+    pub id: String,
+}
+
+/// Contents of the state file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SessionState {
+    #[serde(default)]
+    pub windows: Vec<WindowRecord>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub claude_sessions: Vec<ClaudeSessionRecord>,
+}
+
+impl SessionState {
+    /// Default state file location
+    ///
+    /// `$XDG_STATE_HOME/wsl-multi-launcher/session.json`, falling back to
+    /// `~/.local/state/wsl-multi-launcher/session.json`.
+    pub fn default_path() -> Result<PathBuf> {
+        Ok(Self::default_dir()?.join("session.json"))
+    }
+
+    /// Directory of the state file, also holding the event log, the logs
+    /// and the control socket
+    pub fn default_dir() -> Result<PathBuf> {
+        let base = match std::env::var_os("XDG_STATE_HOME").filter(|v| !v.is_empty()) {
+            Some(dir) => PathBuf::from(dir),
+            None => {
+                let home = std::env::var_os("HOME").ok_or(LauncherError::HomeNotSet)?;
+                PathBuf::from(home).join(".local").join("state")
+            }
+        };
+        Ok(base.join("wsl-multi-launcher"))
+    }
+
+    /// Load the state file, or an empty state if it doesn't exist yet
+    pub fn load() -> Result<Self> {
+        Self::load_from(&Self::default_path()?)
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path).map_err(LauncherError::io("read", path))?;
+        serde_json::from_str(&content)
+            .map_err(|source| LauncherError::StateParse { path: path.to_path_buf(), source })
+    }
+
+    /// Write the state file (not in a dry run)
+    pub fn save(&self) -> Result<()> {
+        if crate::dry_run::is_enabled() {
+            return Ok(());
+        }
+        self.save_to(&Self::default_path()?)
+    }
+
+    /// Write the state file atomically (temp file + rename)
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(LauncherError::io("create", dir))?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|source| LauncherError::Serialize { what: "the state", source })?;
+        fs::write(&tmp, json).map_err(LauncherError::io("write", &tmp))?;
+        fs::rename(&tmp, path).map_err(LauncherError::io("write", path))?;
+        Ok(())
+    }
+
+    /// Add a launched window, replacing an older record with the same name
+    pub fn record(&mut self, record: WindowRecord) {
+        self.remove(&record.session, &record.name);
+        if let Some(ref id) = record.claude_session {
+            self.claude_sessions.retain(|c| c.session != record.session || c.name != record.name);
+            self.claude_sessions.push(ClaudeSessionRecord {
+                session: record.session.clone(),
+                name: record.name.clone(),
+                id: id.clone(),
+            });
+        }
+        self.windows.push(record);
+    }
+
+    /// Claude conversation last run by a window, even if it is closed now
+    pub fn claude_session(&self, session: &str, name: &str) -> Option<&str> {
+        self.claude_sessions
+            .iter()
+            .find(|c| c.session == session && c.name == name)
+            .map(|c| c.id.as_str())
+    }
+
+    /// Remove the record of a window, returning it if it existed
+    pub fn remove(&mut self, session: &str, name: &str) -> Option<WindowRecord> {
+        let index = self
+            .windows
+            .iter()
+            .position(|w| w.session == session && w.name == name)?;
+        Some(self.windows.remove(index))
+    }
+
+    /// Records belonging to a session
+    pub fn session_windows<'a>(&'a self, session: &'a str) -> impl Iterator<Item = &'a WindowRecord> {
+        self.windows.iter().filter(move |w| w.session == session)
+    }
+
+    /// Records of `session` named in `names`, or all of them if `names` is empty
+    ///
+    /// Fails for unknown names, and for windows that share their terminal
+    /// window (tabs/panes mode) with windows that weren't selected, since
+    /// closing one would close the others too.
+    pub fn select(&self, session: &str, names: &[String]) -> Result<Vec<&WindowRecord>> {
+        let windows: Vec<&WindowRecord> = self.windows.iter().filter(|w| w.session == session).collect();
+        if names.is_empty() {
+            return Ok(windows);
+        }
+
+        let mut selected = Vec::new();
+        for name in names {
+            let record = windows.iter().find(|w| &w.name == name).ok_or_else(|| LauncherError::NotRunning {
+                session: session.to_string(),
+                window: name.clone(),
+            })?;
+            selected.push(*record);
+        }
+
+        for record in &selected {
+            let Some(handle) = record.handle else { continue };
+            let sharing: Vec<String> = windows
+                .iter()
+                .filter(|w| w.handle == Some(handle) && !names.contains(&w.name))
+                .map(|w| w.name.clone())
+                .collect();
+            if !sharing.is_empty() {
+                return Err(LauncherError::SharedWindow { window: record.name.clone(), sharing });
+            }
+        }
+        Ok(selected)
+    }
+
+    /// Drop records of `session` whose window handle is no longer open
+    ///
+    /// Returns the number of records removed.
+    pub fn prune(&mut self, session: &str, open_handles: &HashSet<i64>) -> usize {
+        let before = self.windows.len();
+        self.windows.retain(|w| {
+            w.session != session || w.handle.is_some_and(|h| open_handles.contains(&h))
+        });
+        before - self.windows.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(session: &str, name: &str, handle: Option<i64>) -> WindowRecord {
+        WindowRecord {
+            session: session.to_string(),
+            name: name.to_string(),
+            wt_window: Some(format!("wcl-{}-1", session)),
+            handle,
+            pid: None,
+            rect: Some(Rect::new(0, 0, 960, 540)),
+            launched_at: Local::now(),
+            health: None,
+            idle_since: None,
+            claude_session: None,
+        }
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state").join("session.json");
+
+        let mut state = SessionState::default();
+        state.record(record("work", "claude-1", Some(1234)));
+        state.save_to(&path).unwrap();
+
+        let loaded = SessionState::load_from(&path).unwrap();
+        assert_eq!(loaded.windows, state.windows);
+    }
+
+    #[test]
+    fn test_load_missing_is_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = SessionState::load_from(&dir.path().join("none.json")).unwrap();
+        assert!(state.windows.is_empty());
+    }
+
+    #[test]
+    fn test_record_replaces_same_window() {
+        let mut state = SessionState::default();
+        state.record(record("work", "a", Some(1)));
+        state.record(record("other", "a", Some(2)));
+        state.record(record("work", "a", Some(3)));
+
+        let handles: Vec<_> = state.windows.iter().map(|w| w.handle).collect();
+        assert_eq!(handles, vec![Some(2), Some(3)]);
+    }
+
+    #[test]
+    fn test_claude_session_outlives_window() {
+        let mut state = SessionState::default();
+        state.record(WindowRecord { claude_session: Some("id-1".to_string()), ..record("work", "a", Some(1)) });
+        state.record(WindowRecord { claude_session: Some("id-2".to_string()), ..record("work", "a", Some(2)) });
+        state.record(record("work", "b", Some(3)));
+        state.remove("work", "a");
+
+        assert_eq!(state.claude_session("work", "a"), Some("id-2"));
+        assert_eq!(state.claude_session("work", "b"), None);
+        assert_eq!(state.claude_session("other", "a"), None);
+        assert_eq!(state.claude_sessions.len(), 1);
+    }
+
+    #[test]
+    fn test_select() {
+        let mut state = SessionState::default();
+        state.record(record("work", "a", Some(1)));
+        state.record(record("work", "b", Some(2)));
+        state.record(record("other", "c", Some(3)));
+
+        assert_eq!(state.select("work", &[]).unwrap().len(), 2);
+        let selected = state.select("work", &["b".to_string()]).unwrap();
+        assert_eq!(selected[0].name, "b");
+        assert!(matches!(
+            state.select("work", &["c".to_string()]),
+            Err(LauncherError::NotRunning { ref window, .. }) if window == "c"
+        ));
+    }
+
+    #[test]
+    fn test_select_shared_window() {
+        let mut state = SessionState::default();
+        state.record(record("work", "tab-1", Some(7)));
+        state.record(record("work", "tab-2", Some(7)));
+
+        assert!(matches!(
+            state.select("work", &["tab-1".to_string()]),
+            Err(LauncherError::SharedWindow { ref sharing, .. }) if sharing == &["tab-2"]
+        ));
+        assert!(state.select("work", &["tab-1".to_string(), "tab-2".to_string()]).is_ok());
+    }
+
+    #[test]
+    fn test_window_status() {
+        let rec = record("work", "a", Some(1));
+        let placed = Rect::new(0, 0, 960, 540);
+
+        assert_eq!(rec.status(None), WindowStatus::Missing);
+        assert_eq!(rec.status(Some((placed, true))), WindowStatus::Running);
+        // Invisible borders make the real rect a few pixels larger
+        assert_eq!(rec.status(Some((Rect::new(-7, 0, 974, 547), true))), WindowStatus::Running);
+        assert_eq!(rec.status(Some((Rect::new(400, 300, 960, 540), true))), WindowStatus::Moved);
+        assert_eq!(rec.status(Some((placed, false))), WindowStatus::Hidden);
+    }
+
+    #[test]
+    fn test_prune_closed_windows() {
+        let mut state = SessionState::default();
+        state.record(record("work", "open", Some(1)));
+        state.record(record("work", "closed", Some(2)));
+        state.record(record("work", "unknown", None));
+        state.record(record("other", "closed", Some(3)));
+
+        let removed = state.prune("work", &HashSet::from([1]));
+        assert_eq!(removed, 2);
+        let names: Vec<_> = state.windows.iter().map(|w| (w.session.as_str(), w.name.as_str())).collect();
+        assert_eq!(names, vec![("work", "open"), ("other", "closed")]);
+    }
+}
@@ -0,0 +1,58 @@
+use super::{TerminalBackend, spawn_window};
+use crate::config::WindowConfig;
+use crate::error::Result;
+use crate::wsl::WslLauncher;
+
+/// Alacritty (alacritty.exe)
+pub struct Alacritty {
+    launcher: WslLauncher,
+}
+
+impl Alacritty {
+    pub fn new(launcher: WslLauncher) -> Self {
+        Self { launcher }
+    }
+
+    fn args(&self, window: &WindowConfig) -> Vec<String> {
+        let mut args = vec!["--title".to_string(), window.name.clone()];
+        if let Some(dir) = window.native_working_dir() {
+            args.extend(["--working-directory".to_string(), dir.to_string()]);
+        }
+        args.push("-e".to_string());
+        args.extend(self.launcher.command_args(window));
+        args
+    }
+}
+
+impl TerminalBackend for Alacritty {
+    fn name(&self) -> &'static str {
+        "Alacritty"
+    }
+
+    fn launch(&self, window: &WindowConfig, _slot: usize) -> Result<Option<u32>> {
+        spawn_window("alacritty.exe", &self.args(window), window)
+    }
+
+    fn window_identity(&self) -> (&'static str, &'static str) {
+        ("alacritty", "")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alacritty_args() {
+        let backend = Alacritty::new(WslLauncher::new("Ubuntu"));
+        let window = WindowConfig {
+            name: "logs".to_string(),
+            command: Some("tail -f app.log".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            backend.args(&window),
+            vec!["--title", "logs", "-e", "wsl.exe", "-d", "Ubuntu", "--", "bash", "-c", "tail -f app.log"]
+        );
+    }
+}
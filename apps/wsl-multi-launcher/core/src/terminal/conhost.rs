@@ -0,0 +1,84 @@
+use super::{TerminalBackend, spawn_window};
+use crate::config::WindowConfig;
+use crate::error::Result;
+use crate::wsl::WslLauncher;
+
+/// Plain console windows (conhost) opened with `cmd.exe /c start`
+///
+/// Used when Windows Terminal isn't installed, e.g. on Server editions or
+/// locked-down machines.
+pub struct Conhost {
+    launcher: WslLauncher,
+}
+
+impl Conhost {
+    pub fn new(launcher: WslLauncher) -> Self {
+        Self { launcher }
+    }
+
+    fn args(&self, window: &WindowConfig) -> Vec<String> {
+        // `start` takes its first quoted argument as the window title, but
+        // a name without spaces reaches cmd.exe unquoted and would be run as
+        // the program; an empty argument is always passed quoted (`""`), so
+        // the title is left empty here and set after launch
+        let mut args = ["/c", "start", ""].map(String::from).to_vec();
+        if let Some(dir) = window.native_working_dir() {
+            args.extend(["/D".to_string(), dir.to_string()]);
+        }
+        args.extend(self.launcher.command_args(window));
+        args
+    }
+}
+
+impl TerminalBackend for Conhost {
+    fn name(&self) -> &'static str {
+        "console window"
+    }
+
+    fn launch(&self, window: &WindowConfig, _slot: usize) -> Result<Option<u32>> {
+        // `start` returns as soon as the console is created, so its PID
+        // doesn't track the window
+        spawn_window("cmd.exe", &self.args(window), window)?;
+        Ok(None)
+    }
+
+    fn window_identity(&self) -> (&'static str, &'static str) {
+        // Console windows are matched by class regardless of owning process
+        ("", "ConsoleWindowClass")
+    }
+
+    fn titles_on_launch(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conhost_args() {
+        let backend = Conhost::new(WslLauncher::new("Ubuntu"));
+        let window = WindowConfig {
+            name: "build".to_string(),
+            command: Some("make && make test".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            backend.args(&window),
+            vec!["/c", "start", "", "wsl.exe", "-d", "Ubuntu", "--", "bash", "-c", "make && make test"]
+        );
+    }
+
+    #[test]
+    fn test_conhost_args_native_window() {
+        let backend = Conhost::new(WslLauncher::new("Ubuntu"));
+        let window = WindowConfig {
+            name: "cmd".to_string(),
+            kind: crate::config::WindowKind::Cmd,
+            working_dir: Some(r"C:\src".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(backend.args(&window), vec!["/c", "start", "", "/D", r"C:\src", "cmd.exe"]);
+    }
+}
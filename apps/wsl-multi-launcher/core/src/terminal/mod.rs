@@ -0,0 +1,190 @@
+//! Terminal emulator backends
+//!
+//! Each backend knows how to open a window running a WSL command and how to
+//! find its top-level windows, so launch/arrange work the same regardless of
+//! which terminal the user runs WSL in.
+
+use std::sync::Arc;
+
+use tracing::warn;
+
+use crate::config::{TerminalKind, WindowConfig};
+use crate::error::{LauncherError, Result};
+use crate::windows;
+use crate::wsl::WslLauncher;
+
+mod alacritty;
+mod conhost;
+mod wezterm;
+mod windows_terminal;
+pub mod wt_version;
+
+pub use alacritty::Alacritty;
+pub use conhost::Conhost;
+pub use wezterm::WezTerm;
+pub use windows_terminal::{WindowsTerminal, wt_window_name};
+
+/// A terminal emulator that can host launched sessions
+///
+/// Backends are shared between the threads launching windows side by side.
+pub trait TerminalBackend: Send + Sync {
+    /// Human readable name for messages
+    fn name(&self) -> &'static str;
+
+    /// Open a new terminal window running the window's command
+    ///
+    /// `slot` is the window's index in the config. Returns the PID of the
+    /// spawned process when it lives as long as the window (wt.exe hands
+    /// the window to an existing WindowsTerminal process and exits, so it
+    /// returns None).
+    fn launch(&self, window: &WindowConfig, slot: usize) -> Result<Option<u32>>;
+
+    /// Terminal-level name of the window launched into `slot`, for
+    /// terminals that support addressing windows by name
+    fn window_name(&self, _slot: usize) -> Option<String> {
+        None
+    }
+
+    /// Process name and window class substring identifying this terminal's
+    /// top-level windows ("" matches any class)
+    fn window_identity(&self) -> (&'static str, &'static str);
+
+    /// Whether `launch` already titles the window with its configured name
+    fn titles_on_launch(&self) -> bool {
+        true
+    }
+
+    /// Handles of all currently open windows of this terminal
+    fn window_handles(&self) -> Result<Vec<i64>> {
+        let (process, class) = self.window_identity();
+        windows::get_terminal_window_handles(process, class)
+    }
+
+    /// Set the title of a launched window
+    fn set_title(&self, handle: i64, title: &str) -> Result<()> {
+        windows::set_window_title(handle, title)
+    }
+
+    /// Bring the window launched into `slot` to the foreground
+    ///
+    /// Windows are titled with their configured name, so the default
+    /// looks the window up by title.
+    fn focus(&self, window: &WindowConfig, _slot: usize) -> Result<()> {
+        windows::focus_window(&window.name)
+    }
+}
+
+/// Create the backend for the configured terminal
+pub fn create(kind: TerminalKind, launcher: WslLauncher, session: &str) -> Arc<dyn TerminalBackend> {
+    match kind {
+        TerminalKind::WindowsTerminal => Arc::new(WindowsTerminal::new(launcher, session)),
+        TerminalKind::Alacritty => Arc::new(Alacritty::new(launcher)),
+        TerminalKind::Wezterm => Arc::new(WezTerm::new(launcher)),
+        TerminalKind::Conhost => Arc::new(Conhost::new(launcher)),
+    }
+}
+
+/// Pick the terminal to use, falling back to console windows when
+/// Windows Terminal is configured but wt.exe isn't installed
+pub fn resolve(kind: TerminalKind) -> TerminalKind {
+    if kind == TerminalKind::WindowsTerminal && !is_available("wt.exe") {
+        warn!("wt.exe not found; falling back to plain console windows");
+        return TerminalKind::Conhost;
+    }
+    kind
+}
+
+/// Whether an executable can be found on PATH
+///
+/// WSL appends the Windows PATH by default, which includes the
+/// WindowsApps directory that hosts the wt.exe alias.
+pub fn is_available(program: &str) -> bool {
+    std::env::var_os("PATH")
+        .map(|paths| std::env::split_paths(&paths).any(|dir| dir.join(program).is_file()))
+        .unwrap_or(false)
+}
+
+/// Build a command that starts `program` elevated through a UAC prompt
+///
+/// `Start-Process -Verb RunAs` receives the whole argument list as one
+/// Windows command line string.
+pub fn elevated_command(program: &str, args: &[String]) -> std::process::Command {
+    let script = format!(
+        "Start-Process -FilePath {} -Verb RunAs -ArgumentList {}",
+        crate::shell::quote_powershell(program),
+        crate::shell::quote_powershell(&crate::shell::join_windows(args))
+    );
+    let mut cmd = std::process::Command::new("powershell.exe");
+    cmd.args(["-NoProfile", "-Command", &script]);
+    cmd
+}
+
+/// Spawn a terminal executable for a window and return its PID
+///
+/// Elevated windows go through a UAC prompt, and the PID of the
+/// short-lived PowerShell wrapper isn't useful, so None is returned.
+fn spawn_window(program: &str, args: &[String], window: &WindowConfig) -> Result<Option<u32>> {
+    spawn_maybe_elevated(program, args, window)
+        .map_err(|e| LauncherError::Launch { window: window.name.clone(), source: Box::new(e) })
+}
+
+fn spawn_maybe_elevated(program: &str, args: &[String], window: &WindowConfig) -> Result<Option<u32>> {
+    if window.elevated {
+        let mut cmd = elevated_command(program, args);
+        if crate::dry_run::skip(&cmd) {
+            return Ok(None);
+        }
+        let status = cmd.status().map_err(LauncherError::spawn(program))?;
+        if !status.success() {
+            return Err(LauncherError::Elevation { program: program.to_string() });
+        }
+        return Ok(None);
+    }
+    spawn(program, args)
+}
+
+/// Spawn a terminal executable directly (no cmd.exe hop) and return its PID
+fn spawn(program: &str, args: &[String]) -> Result<Option<u32>> {
+    use tracing::debug;
+
+    let mut cmd = std::process::Command::new(program);
+    cmd.args(args);
+    debug!("Executing: {:?}", cmd);
+    if crate::dry_run::skip(&cmd) {
+        return Ok(None);
+    }
+
+    // GUI terminals keep running while the window is open, so don't wait
+    let child = cmd.spawn().map_err(LauncherError::spawn(program))?;
+    Ok(Some(child.id()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_available() {
+        assert!(!is_available("definitely-not-a-real-program.exe"));
+    }
+
+    #[test]
+    fn test_resolve_keeps_non_wt_terminals() {
+        assert_eq!(resolve(TerminalKind::Alacritty), TerminalKind::Alacritty);
+        assert_eq!(resolve(TerminalKind::Conhost), TerminalKind::Conhost);
+    }
+
+    #[test]
+    fn test_elevated_command() {
+        let cmd = elevated_command("wt.exe", &["-w".to_string(), "my window".to_string()]);
+        let args: Vec<_> = cmd.get_args().map(|a| a.to_string_lossy().to_string()).collect();
+        assert_eq!(
+            args,
+            vec![
+                "-NoProfile",
+                "-Command",
+                "Start-Process -FilePath 'wt.exe' -Verb RunAs -ArgumentList '-w \"my window\"'",
+            ]
+        );
+    }
+}
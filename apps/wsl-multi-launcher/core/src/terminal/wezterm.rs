@@ -0,0 +1,60 @@
+use super::{TerminalBackend, spawn_window};
+use crate::config::WindowConfig;
+use crate::error::Result;
+use crate::wsl::WslLauncher;
+
+/// WezTerm (wezterm.exe)
+pub struct WezTerm {
+    launcher: WslLauncher,
+}
+
+impl WezTerm {
+    pub fn new(launcher: WslLauncher) -> Self {
+        Self { launcher }
+    }
+
+    fn args(&self, window: &WindowConfig) -> Vec<String> {
+        // A new process guarantees a new top-level window we can detect
+        let mut args = ["start", "--always-new-process"].map(String::from).to_vec();
+        if let Some(dir) = window.native_working_dir() {
+            args.extend(["--cwd".to_string(), dir.to_string()]);
+        }
+        args.push("--".to_string());
+        args.extend(self.launcher.command_args(window));
+        args
+    }
+}
+
+impl TerminalBackend for WezTerm {
+    fn name(&self) -> &'static str {
+        "WezTerm"
+    }
+
+    fn launch(&self, window: &WindowConfig, _slot: usize) -> Result<Option<u32>> {
+        spawn_window("wezterm.exe", &self.args(window), window)
+    }
+
+    fn window_identity(&self) -> (&'static str, &'static str) {
+        ("wezterm-gui", "")
+    }
+
+    fn titles_on_launch(&self) -> bool {
+        // `wezterm start` has no title flag; the title is set after launch
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wezterm_args() {
+        let backend = WezTerm::new(WslLauncher::new("Ubuntu"));
+        let window = WindowConfig { name: "shell".to_string(), ..Default::default() };
+        assert_eq!(
+            backend.args(&window),
+            vec!["start", "--always-new-process", "--", "wsl.exe", "-d", "Ubuntu", "--", "bash", "-c", "bash"]
+        );
+    }
+}
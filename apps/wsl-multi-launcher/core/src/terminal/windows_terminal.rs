@@ -0,0 +1,81 @@
+use super::TerminalBackend;
+use crate::config::WindowConfig;
+use crate::error::Result;
+use crate::wsl::WslLauncher;
+
+/// Windows Terminal (wt.exe)
+///
+/// Every session is launched into a uniquely named wt window
+/// (`wt -w wcl-<session>-<n>`), so later operations can target it by name
+/// instead of matching titles.
+pub struct WindowsTerminal {
+    launcher: WslLauncher,
+    session: String,
+}
+
+impl WindowsTerminal {
+    pub fn new(launcher: WslLauncher, session: &str) -> Self {
+        Self {
+            launcher,
+            session: session.to_string(),
+        }
+    }
+}
+
+impl TerminalBackend for WindowsTerminal {
+    fn name(&self) -> &'static str {
+        "Windows Terminal"
+    }
+
+    fn launch(&self, window: &WindowConfig, slot: usize) -> Result<Option<u32>> {
+        let wt_window = wt_window_name(&self.session, &(slot + 1).to_string());
+        self.launcher.launch_tab(window, &wt_window)?;
+        Ok(None)
+    }
+
+    fn window_name(&self, slot: usize) -> Option<String> {
+        Some(wt_window_name(&self.session, &(slot + 1).to_string()))
+    }
+
+    fn window_identity(&self) -> (&'static str, &'static str) {
+        // Main windows have class CASCADIA_HOSTING_WINDOW_CLASS
+        ("WindowsTerminal", "CASCADIA")
+    }
+
+    fn focus(&self, _window: &WindowConfig, slot: usize) -> Result<()> {
+        // Targeting the named window with an action brings it to the front
+        let wt_window = wt_window_name(&self.session, &(slot + 1).to_string());
+        self.launcher.focus_tab(&wt_window, 0)
+    }
+}
+
+/// Name of a managed wt window: `wcl-<session>-<suffix>`
+///
+/// Characters wt might misinterpret are replaced so the name is always a
+/// single plain token (and never purely numeric, which wt treats as an ID).
+pub fn wt_window_name(session: &str, suffix: &str) -> String {
+    let sanitize = |s: &str| -> String {
+        s.chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+            .collect()
+    };
+    format!("wcl-{}-{}", sanitize(session), sanitize(suffix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wt_window_name() {
+        assert_eq!(wt_window_name("config", "1"), "wcl-config-1");
+        assert_eq!(wt_window_name("client a;b", "tabs"), "wcl-client_a_b-tabs");
+    }
+
+    #[test]
+    fn test_window_name_per_slot() {
+        let wt = WindowsTerminal::new(WslLauncher::new("Ubuntu"), "work");
+        assert_eq!(wt.window_name(0).as_deref(), Some("wcl-work-1"));
+        assert_eq!(wt.window_name(7).as_deref(), Some("wcl-work-8"));
+    }
+}
@@ -0,0 +1,150 @@
+use std::fmt;
+
+use crate::config::{Config, LaunchMode};
+use crate::windows;
+
+/// Installed Windows Terminal version (major.minor)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct WtVersion {
+    pub major: u32,
+    pub minor: u32,
+}
+
+impl WtVersion {
+    pub const fn new(major: u32, minor: u32) -> Self {
+        Self { major, minor }
+    }
+
+    /// Parse a package version like "1.21.2361.0"
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut parts = s.trim().split('.');
+        let major = parts.next()?.parse().ok()?;
+        let minor = parts.next()?.parse().ok()?;
+        Some(Self { major, minor })
+    }
+
+    pub fn supports(&self, feature: WtFeature) -> bool {
+        *self >= feature.min_version()
+    }
+}
+
+impl fmt::Display for WtVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+/// Command line features whose availability depends on the wt version
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WtFeature {
+    /// `split-pane --size` and `move-focus`
+    PaneSizing,
+    /// `-M` / `-F` / `--focus`
+    LaunchModeFlags,
+    /// `--tabColor`
+    TabColor,
+    /// `--colorScheme`
+    ColorScheme,
+    /// `-w <name>` window targeting
+    WindowTarget,
+    /// `--suppressApplicationTitle`
+    SuppressApplicationTitle,
+    /// `focus-pane --target`
+    FocusPane,
+}
+
+impl WtFeature {
+    /// First Windows Terminal release with the feature
+    pub fn min_version(self) -> WtVersion {
+        match self {
+            WtFeature::PaneSizing => WtVersion::new(1, 4),
+            WtFeature::LaunchModeFlags => WtVersion::new(1, 6),
+            WtFeature::TabColor => WtVersion::new(1, 6),
+            WtFeature::ColorScheme => WtVersion::new(1, 7),
+            WtFeature::WindowTarget => WtVersion::new(1, 7),
+            WtFeature::SuppressApplicationTitle => WtVersion::new(1, 12),
+            WtFeature::FocusPane => WtVersion::new(1, 13),
+        }
+    }
+
+    pub fn describe(self) -> &'static str {
+        match self {
+            WtFeature::PaneSizing => "split-pane --size (panes mode)",
+            WtFeature::LaunchModeFlags => "-M/-F/--focus (wt_maximized, wt_fullscreen, wt_focus)",
+            WtFeature::TabColor => "--tabColor (tab_color)",
+            WtFeature::ColorScheme => "--colorScheme (color_scheme)",
+            WtFeature::WindowTarget => "-w <name> (named windows, tabs mode)",
+            WtFeature::SuppressApplicationTitle => "--suppressApplicationTitle (suppress_app_title)",
+            WtFeature::FocusPane => "focus-pane (focus in panes mode)",
+        }
+    }
+}
+
+/// Probe the installed Windows Terminal version
+///
+/// Returns None when it can't be determined; callers then assume every
+/// feature is available rather than refusing to launch.
+pub fn detect() -> Option<WtVersion> {
+    windows::get_wt_version().ok().flatten().and_then(|v| WtVersion::parse(&v))
+}
+
+/// Features the config asks for that `version` doesn't support
+pub fn unsupported_features(config: &Config, version: WtVersion) -> Vec<WtFeature> {
+    let mut wanted = vec![WtFeature::WindowTarget];
+    if config.layout.mode == LaunchMode::Panes {
+        wanted.push(WtFeature::PaneSizing);
+    }
+    let any = |f: fn(&crate::config::WindowConfig) -> bool| config.windows.iter().any(f);
+    if any(|w| w.wt_maximized || w.wt_fullscreen || w.wt_focus) {
+        wanted.push(WtFeature::LaunchModeFlags);
+    }
+    if any(|w| w.tab_color.is_some()) {
+        wanted.push(WtFeature::TabColor);
+    }
+    if any(|w| w.color_scheme.is_some()) {
+        wanted.push(WtFeature::ColorScheme);
+    }
+    if any(|w| w.suppress_app_title) {
+        wanted.push(WtFeature::SuppressApplicationTitle);
+    }
+
+    wanted.into_iter().filter(|f| !version.supports(*f)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::WindowConfig;
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(WtVersion::parse("1.21.2361.0"), Some(WtVersion::new(1, 21)));
+        assert_eq!(WtVersion::parse("1.6\r\n"), Some(WtVersion::new(1, 6)));
+        assert_eq!(WtVersion::parse(""), None);
+        assert_eq!(WtVersion::parse("preview"), None);
+    }
+
+    #[test]
+    fn test_supports() {
+        assert!(WtVersion::new(1, 21).supports(WtFeature::SuppressApplicationTitle));
+        assert!(!WtVersion::new(1, 11).supports(WtFeature::SuppressApplicationTitle));
+        assert!(WtVersion::new(2, 0).supports(WtFeature::WindowTarget));
+    }
+
+    #[test]
+    fn test_unsupported_features() {
+        let config = Config {
+            windows: vec![WindowConfig {
+                name: "a".to_string(),
+                tab_color: Some("#fff".to_string()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        assert!(unsupported_features(&config, WtVersion::new(1, 21)).is_empty());
+        assert_eq!(
+            unsupported_features(&config, WtVersion::new(1, 6)),
+            vec![WtFeature::WindowTarget, WtFeature::SuppressApplicationTitle]
+        );
+    }
+}
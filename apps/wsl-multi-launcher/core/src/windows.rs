@@ -0,0 +1,675 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Command;
+use tracing::{debug, info};
+
+use crate::dry_run;
+use crate::error::{LauncherError, Result};
+use crate::events::{self, EventKind};
+use crate::layout::{DisplayInfo, Rect};
+use crate::ps_host;
+
+/// Get the path to the scripts directory
+pub fn get_scripts_dir() -> Result<std::path::PathBuf> {
+    // Try to find scripts relative to the executable
+    let exe_path = std::env::current_exe().map_err(LauncherError::ScriptsDir)?;
+    let exe_dir = exe_path.parent().unwrap_or(Path::new("/"));
+
+    // Check common locations
+    let possible_paths = [
+        exe_dir.join("scripts"),
+        exe_dir.join("../scripts"),
+        exe_dir.join("../../scripts"),
+        std::path::PathBuf::from("scripts"),
+        std::path::PathBuf::from("./scripts"),
+    ];
+
+    for path in &possible_paths {
+        if path.exists() {
+            return Ok(path.clone());
+        }
+    }
+
+    // Fall back to current directory
+    Ok(std::path::PathBuf::from("scripts"))
+}
+
+/// Convert WSL path to Windows path
+pub fn wsl_to_windows_path(wsl_path: &Path) -> Result<String> {
+    let output = Command::new("wslpath")
+        .args(["-w", wsl_path.to_str().unwrap()])
+        .output()
+        .map_err(LauncherError::spawn("wslpath"))?;
+
+    if !output.status.success() {
+        return Err(LauncherError::command("wslpath", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Resolve a Windows environment variable holding a path (e.g. LOCALAPPDATA)
+/// to its WSL path
+pub fn windows_env_path(var: &str) -> Result<std::path::PathBuf> {
+    let output = Command::new("cmd.exe")
+        .args(["/c", &format!("echo %{}%", var)])
+        .output()
+        .map_err(LauncherError::spawn("cmd.exe"))?;
+
+    let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if !output.status.success() || value.is_empty() || value == format!("%{}%", var) {
+        return Err(LauncherError::WindowsEnvNotSet(var.to_string()));
+    }
+
+    let output = Command::new("wslpath")
+        .args(["-u", &value])
+        .output()
+        .map_err(LauncherError::spawn("wslpath"))?;
+
+    if !output.status.success() {
+        return Err(LauncherError::command("wslpath", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+
+    Ok(std::path::PathBuf::from(String::from_utf8_lossy(&output.stdout).trim()))
+}
+
+/// Get display information using PowerShell
+pub fn get_displays() -> Result<Vec<DisplayInfo>> {
+    query_displays().map_err(|e| LauncherError::DisplayQuery(Box::new(e)))
+}
+
+fn query_displays() -> Result<Vec<DisplayInfo>> {
+    let json = run_script("get-displays.ps1", &[])?;
+    debug!("Display info JSON: {}", json);
+
+    // Parse JSON - can be single object or array
+    let displays: Vec<DisplayInfo> = if json.trim().starts_with('[') {
+        serde_json::from_str(&json).map_err(output_error("get-displays.ps1"))?
+    } else {
+        // Single display
+        let single: DisplayInfo = serde_json::from_str(&json).map_err(output_error("get-displays.ps1"))?;
+        vec![single]
+    };
+
+    info!("Found {} display(s)", displays.len());
+    Ok(displays)
+}
+
+/// Get the working area for a specific display
+pub fn get_display_working_area(displays: &[DisplayInfo], display_index: u32) -> Result<Rect> {
+    let display = displays
+        .get(display_index as usize)
+        .ok_or(LauncherError::DisplayNotFound(display_index))?;
+
+    Ok(Rect::new(
+        display.working_area.x,
+        display.working_area.y,
+        display.working_area.width,
+        display.working_area.height,
+    ))
+}
+
+/// Move a window to the specified position
+pub fn move_window(title: &str, rect: &Rect) -> Result<()> {
+    move_by_title(title, rect).map_err(|e| LauncherError::Arrange { window: title.to_string(), source: Box::new(e) })
+}
+
+fn move_by_title(title: &str, rect: &Rect) -> Result<()> {
+    debug!(
+        "Moving window '{}' to ({}, {}, {}x{})",
+        title, rect.x, rect.y, rect.width, rect.height
+    );
+
+    let result = run_script(
+        "move-window.ps1",
+        &[
+            "-Title".to_string(), title.to_string(),
+            "-X".to_string(), rect.x.to_string(),
+            "-Y".to_string(), rect.y.to_string(),
+            "-Width".to_string(), rect.width.to_string(),
+            "-Height".to_string(), rect.height.to_string(),
+        ],
+    );
+    if let Err(e) = result {
+        // Don't fail if window not found - it might not be ready yet
+        if matches!(e, LauncherError::Command { ref reason, .. } if reason.contains("Window not found")) {
+            debug!("Window '{}' not found yet, will retry", title);
+            return Ok(());
+        }
+        return Err(e);
+    }
+
+    info!("Window '{}' moved successfully", title);
+    Ok(())
+}
+
+/// Move a window with retries (for windows that may not be ready yet)
+pub fn move_window_with_retry(title: &str, rect: &Rect, max_retries: u32) -> Result<()> {
+    for attempt in 0..max_retries {
+        match move_window(title, rect) {
+            Ok(()) => return Ok(()),
+            Err(e) => {
+                if attempt < max_retries - 1 {
+                    events::record(
+                        EventKind::MoveRetried,
+                        title,
+                        Some(format!("attempt {}: {:#}", attempt + 1, e)),
+                    );
+                    debug!(
+                        "Attempt {} failed for window '{}': {}, retrying...",
+                        attempt + 1,
+                        title,
+                        e
+                    );
+                    std::thread::sleep(std::time::Duration::from_millis(500));
+                } else {
+                    return Err(e);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Window moved by `move_windows`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MoveTarget {
+    Handle(i64),
+    /// The first window whose title contains this, as with `move_window`
+    Title(String),
+}
+
+impl std::fmt::Display for MoveTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MoveTarget::Handle(handle) => write!(f, "{}", handle),
+            MoveTarget::Title(title) => write!(f, "{}", title),
+        }
+    }
+}
+
+/// Result of one move of `move-windows.ps1`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct MoveResult {
+    ok: bool,
+    error: String,
+}
+
+/// Move several windows in one script call, returning the result of each
+/// move in order
+///
+/// Windows looked up by title that aren't there yet (not ready after
+/// launch) are looked for again, together, up to `max_retries` calls in all.
+pub fn move_windows(moves: &[(MoveTarget, Rect)], max_retries: u32) -> Vec<Result<()>> {
+    let mut results: Vec<Result<(), String>> = vec![Ok(()); moves.len()];
+    let mut pending: Vec<usize> = (0..moves.len()).collect();
+    let max_retries = max_retries.max(1);
+    for attempt in 1..=max_retries {
+        if pending.is_empty() {
+            break;
+        }
+        debug!("Moving {} window(s) in one call", pending.len());
+        let batch: Vec<&(MoveTarget, Rect)> = pending.iter().map(|&i| &moves[i]).collect();
+        let answers = match move_batch(&batch) {
+            Ok(answers) => answers,
+            Err(e) => {
+                for &i in &pending {
+                    results[i] = Err(format!("{:#}", e));
+                }
+                break;
+            }
+        };
+
+        let mut retry = Vec::new();
+        for (&i, answer) in pending.iter().zip(answers) {
+            results[i] = if answer.ok { Ok(()) } else { Err(answer.error) };
+            if let (MoveTarget::Title(title), Err(e)) = (&moves[i].0, &results[i])
+                && e.contains("Window not found")
+                && attempt < max_retries
+            {
+                events::record(EventKind::MoveRetried, title, Some(format!("attempt {}: {}", attempt, e)));
+                retry.push(i);
+            }
+        }
+        if !retry.is_empty() {
+            debug!("{} window(s) not found yet, retrying...", retry.len());
+            std::thread::sleep(std::time::Duration::from_millis(500));
+        }
+        pending = retry;
+    }
+    results
+        .into_iter()
+        .zip(moves)
+        .map(|(result, (target, _))| {
+            result.map_err(|e| LauncherError::Arrange {
+                window: target.to_string(),
+                source: Box::new(LauncherError::command("move-windows.ps1", e)),
+            })
+        })
+        .collect()
+}
+
+fn move_batch(moves: &[&(MoveTarget, Rect)]) -> Result<Vec<MoveResult>> {
+    let output = run_script("move-windows.ps1", &["-Moves".to_string(), encode_moves(moves)])?;
+    // A dry run only prints the call
+    if output.trim().is_empty() && dry_run::is_enabled() {
+        return Ok(moves.iter().map(|_| MoveResult { ok: true, error: String::new() }).collect());
+    }
+    let results: Vec<MoveResult> =
+        serde_json::from_str(output.trim()).map_err(output_error("move-windows.ps1"))?;
+    if results.len() != moves.len() {
+        let reason = format!("answered {} of {} moves", results.len(), moves.len());
+        return Err(LauncherError::command("move-windows.ps1", reason));
+    }
+    Ok(results)
+}
+
+fn output_error(script: &str) -> impl FnOnce(serde_json::Error) -> LauncherError {
+    let program = script.to_string();
+    move |source| LauncherError::Output { program, source }
+}
+
+/// `-Moves` of `move-windows.ps1`: "<target>,<x>,<y>,<w>,<h>" per move,
+/// separated by ";", with titles hex encoded
+fn encode_moves(moves: &[&(MoveTarget, Rect)]) -> String {
+    moves
+        .iter()
+        .map(|(target, rect)| {
+            let target = match target {
+                MoveTarget::Handle(handle) => handle.to_string(),
+                MoveTarget::Title(title) => {
+                    format!("title:{}", title.bytes().map(|b| format!("{:02x}", b)).collect::<String>())
+                }
+            };
+            format!("{},{},{},{},{}", target, rect.x, rect.y, rect.width, rect.height)
+        })
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+/// Scripts that only query windows and displays, so a dry run still runs
+/// them
+const QUERY_SCRIPTS: &[&str] = &[
+    "get-displays.ps1",
+    "get-window-rects.ps1",
+    "get-wt-version.ps1",
+    "get-wt-windows.ps1",
+];
+
+/// Scripts that write to the console themselves, so they always run in
+/// their own powershell.exe instead of the PowerShell host
+const OWN_PROCESS_SCRIPTS: &[&str] = &["service.ps1", "startup.ps1"];
+
+/// Run a PowerShell script from the scripts directory and return its stdout
+///
+/// Scripts run in the command's PowerShell host ([`crate::ps_host`]) when
+/// it is available. In a dry run, scripts that change something are only
+/// printed and return empty output.
+fn run_script(script: &str, args: &[String]) -> Result<String> {
+    let scripts_dir = get_scripts_dir()?;
+    let script_path = scripts_dir.join(script);
+    let win_script_path = wsl_to_windows_path(&script_path)?;
+
+    let mut cmd = Command::new("powershell.exe");
+    cmd.args([
+        "-NoProfile",
+        "-ExecutionPolicy", "Bypass",
+        "-File", &win_script_path,
+    ]);
+    cmd.args(args);
+    if !QUERY_SCRIPTS.contains(&script) && dry_run::skip(&cmd) {
+        return Ok(String::new());
+    }
+    if !OWN_PROCESS_SCRIPTS.contains(&script)
+        && let Some(answer) = ps_host::run(&win_script_path, args)
+    {
+        if !answer.ok {
+            return Err(LauncherError::command(script, answer.error));
+        }
+        return Ok(answer.output);
+    }
+    let output = cmd
+        .output()
+        .map_err(LauncherError::spawn(script))?;
+
+    if !output.status.success() {
+        return Err(LauncherError::command(script, String::from_utf8_lossy(&output.stderr)));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Start the hotkey listener (`hotkeys.ps1`) with its stdout piped; it
+/// runs until killed and prints the id of each hotkey pressed
+pub fn spawn_hotkey_listener(keys: &str) -> Result<std::process::Child> {
+    let scripts_dir = get_scripts_dir()?;
+    let win_script_path = wsl_to_windows_path(&scripts_dir.join("hotkeys.ps1"))?;
+    Command::new("powershell.exe")
+        .args([
+            "-NoProfile",
+            "-ExecutionPolicy", "Bypass",
+            "-File", &win_script_path,
+            "-Keys", keys,
+        ])
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::piped())
+        .spawn()
+        .map_err(LauncherError::spawn("hotkeys.ps1"))
+}
+
+/// Get visible top-level window handles owned by `process_name`
+///
+/// `class_name` filters by a window class substring; pass "" to accept any.
+pub fn get_terminal_window_handles(process_name: &str, class_name: &str) -> Result<Vec<i64>> {
+    let json = run_script(
+        "get-wt-windows.ps1",
+        &[
+            "-ProcessName".to_string(), process_name.to_string(),
+            "-ClassName".to_string(), class_name.to_string(),
+        ],
+    )?;
+    Ok(parse_handles(&json))
+}
+
+/// Current state of an open window
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct WindowInfo {
+    pub handle: i64,
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+    pub visible: bool,
+    #[serde(default)]
+    pub title: String,
+    /// PID of the process owning the window
+    #[serde(default)]
+    pub process_id: u32,
+}
+
+impl WindowInfo {
+    pub fn rect(&self) -> Rect {
+        Rect::new(self.x, self.y, self.width, self.height)
+    }
+}
+
+/// Look up the windows among `handles` that still exist (hidden or not)
+pub fn get_window_info(handles: &[i64]) -> Result<HashMap<i64, WindowInfo>> {
+    if handles.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let list = handles.iter().map(|h| h.to_string()).collect::<Vec<_>>().join(",");
+    let json = run_script("get-window-rects.ps1", &["-Handles".to_string(), list])?;
+    parse_window_info(&json)
+}
+
+fn parse_window_info(json: &str) -> Result<HashMap<i64, WindowInfo>> {
+    let json = json.trim();
+    if json.is_empty() || json == "null" {
+        return Ok(HashMap::new());
+    }
+    let infos: Vec<WindowInfo> =
+        serde_json::from_str(json).map_err(output_error("get-window-rects.ps1"))?;
+    Ok(infos.into_iter().map(|info| (info.handle, info)).collect())
+}
+
+/// Parse handle JSON - can be single number, array, or null
+fn parse_handles(json: &str) -> Vec<i64> {
+    let json = json.trim();
+
+    if json.is_empty() || json == "null" {
+        return vec![];
+    }
+
+    if json.starts_with('[') {
+        serde_json::from_str(json).unwrap_or_default()
+    } else {
+        // Single handle
+        json.parse::<i64>().map(|h| vec![h]).unwrap_or_default()
+    }
+}
+
+/// Get the installed Windows Terminal package version, if any
+pub fn get_wt_version() -> Result<Option<String>> {
+    let version = run_script("get-wt-version.ps1", &[])?;
+    let version = version.trim();
+    Ok(if version.is_empty() { None } else { Some(version.to_string()) })
+}
+
+/// Set the title of a window by its handle
+pub fn set_window_title(handle: i64, title: &str) -> Result<()> {
+    debug!("Setting title of window handle {} to '{}'", handle, title);
+    run_script(
+        "set-window-title.ps1",
+        &[
+            "-Handle".to_string(), handle.to_string(),
+            "-Title".to_string(), title.to_string(),
+        ],
+    )?;
+    Ok(())
+}
+
+/// Show a Windows toast notification
+pub fn notify(title: &str, message: &str) -> Result<()> {
+    debug!("Notifying '{}': {}", title, message);
+    run_script(
+        "notify.ps1",
+        &[
+            "-Title".to_string(), title.to_string(),
+            "-Message".to_string(), message.to_string(),
+        ],
+    )?;
+    Ok(())
+}
+
+/// Open a directory of a WSL distribution in Explorer
+pub fn open_folder(distribution: &str, path: &str) -> Result<()> {
+    let unc = format!("\\\\wsl.localhost\\{}{}", distribution, path.replace('/', "\\"));
+    debug!("Opening {} in Explorer", unc);
+    // explorer.exe exits with 1 even when it opened the folder
+    Command::new("explorer.exe")
+        .arg(&unc)
+        .status()
+        .map_err(LauncherError::spawn("explorer.exe"))?;
+    Ok(())
+}
+
+/// Bring the window with exactly this title to the foreground
+pub fn focus_window(title: &str) -> Result<()> {
+    debug!("Focusing window '{}'", title);
+    run_script("focus-window.ps1", &["-Title".to_string(), title.to_string()])?;
+    Ok(())
+}
+
+/// Hide a window by its handle (it stays alive and can be toggled back)
+pub fn hide_window(handle: i64) -> Result<()> {
+    debug!("Hiding window handle {}", handle);
+    run_script(
+        "toggle-window.ps1",
+        &["-Handle".to_string(), handle.to_string(), "-Action".to_string(), "Hide".to_string()],
+    )?;
+    Ok(())
+}
+
+/// Toggle the window with exactly this title between hidden and focused
+///
+/// Returns true if the window is now shown.
+pub fn toggle_window(title: &str) -> Result<bool> {
+    debug!("Toggling window '{}'", title);
+    let output = run_script("toggle-window.ps1", &["-Title".to_string(), title.to_string()])?;
+    Ok(output.trim() == "shown")
+}
+
+/// Send WM_CLOSE to a window and wait up to `timeout_ms` for it to close
+///
+/// Returns true if the window is gone.
+pub fn close_window(handle: i64, timeout_ms: u64) -> Result<bool> {
+    debug!("Closing window handle {}", handle);
+    let output = run_script(
+        "close-window.ps1",
+        &[
+            "-Handle".to_string(), handle.to_string(),
+            "-TimeoutMs".to_string(), timeout_ms.to_string(),
+        ],
+    )?;
+    Ok(output.trim() == "closed" || dry_run::is_enabled())
+}
+
+/// Type `text` followed by Enter into a window (the foreground window if
+/// `handle` is None), as keyboard input
+pub fn type_text(handle: Option<i64>, text: &str) -> Result<()> {
+    debug!("Typing {} byte(s) into window {:?}", text.len(), handle);
+    let hex: String = text.bytes().map(|b| format!("{:02x}", b)).collect();
+    let mut args = vec!["-TextHex".to_string(), hex];
+    if let Some(handle) = handle {
+        args.extend(["-Handle".to_string(), handle.to_string()]);
+    }
+    run_script("type-text.ps1", &args)?;
+    Ok(())
+}
+
+/// Register `wsl.exe <arguments>` to run at logon, as a Startup folder
+/// shortcut or a Scheduled Task (`method` is "Shortcut" or "Task")
+pub fn install_startup(method: &str, name: &str, arguments: &str) -> Result<()> {
+    debug!("Registering startup entry '{}' ({}): wsl.exe {}", name, method, arguments);
+    run_script(
+        "startup.ps1",
+        &[
+            "-Action".to_string(), "Install".to_string(),
+            "-Method".to_string(), method.to_string(),
+            "-Name".to_string(), name.to_string(),
+            "-Arguments".to_string(), arguments.to_string(),
+        ],
+    )?;
+    Ok(())
+}
+
+/// Remove a startup entry registered by `install_startup`
+///
+/// Returns false if there was nothing to remove.
+pub fn remove_startup(name: &str) -> Result<bool> {
+    debug!("Removing startup entry '{}'", name);
+    let output = run_script(
+        "startup.ps1",
+        &["-Action".to_string(), "Remove".to_string(), "-Name".to_string(), name.to_string()],
+    )?;
+    Ok(output.trim() == "removed")
+}
+
+/// Register a Windows service that runs `wsl.exe <arguments>`
+///
+/// Needs an elevated terminal; Windows asks for the password of the
+/// account the service runs as.
+pub fn install_service(name: &str, arguments: &str) -> Result<()> {
+    debug!("Installing service '{}': wsl.exe {}", name, arguments);
+    run_script(
+        "service.ps1",
+        &[
+            "-Action".to_string(), "Install".to_string(),
+            "-Name".to_string(), name.to_string(),
+            "-Arguments".to_string(), arguments.to_string(),
+        ],
+    )?;
+    Ok(())
+}
+
+/// Uninstall, start or stop a service registered by `install_service`, or
+/// get its status (`action` is "Uninstall", "Start", "Stop" or "Status")
+///
+/// Returns the script's answer, e.g. "stopped", "Running" or "not-found".
+pub fn service_control(action: &str, name: &str) -> Result<String> {
+    debug!("Service '{}': {}", name, action);
+    let output = run_script(
+        "service.ps1",
+        &["-Action".to_string(), action.to_string(), "-Name".to_string(), name.to_string()],
+    )?;
+    Ok(output.trim().to_string())
+}
+
+/// Terminate a Windows process (and its children) by PID
+pub fn kill_process(pid: u32) -> Result<()> {
+    debug!("Killing process {}", pid);
+    let mut cmd = Command::new("taskkill.exe");
+    cmd.args(["/PID", &pid.to_string(), "/T", "/F"]);
+    if dry_run::skip(&cmd) {
+        return Ok(());
+    }
+    let output = cmd
+        .output()
+        .map_err(LauncherError::spawn("taskkill.exe"))?;
+
+    if !output.status.success() {
+        return Err(LauncherError::command("taskkill.exe", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(())
+}
+
+/// Move a window by its handle
+pub fn move_window_by_handle(handle: i64, rect: &Rect) -> Result<()> {
+    move_by_handle(handle, rect)
+        .map_err(|e| LauncherError::Arrange { window: handle.to_string(), source: Box::new(e) })
+}
+
+fn move_by_handle(handle: i64, rect: &Rect) -> Result<()> {
+    debug!(
+        "Moving window handle {} to ({}, {}, {}x{})",
+        handle, rect.x, rect.y, rect.width, rect.height
+    );
+
+    run_script(
+        "move-window.ps1",
+        &[
+            "-Handle".to_string(), handle.to_string(),
+            "-X".to_string(), rect.x.to_string(),
+            "-Y".to_string(), rect.y.to_string(),
+            "-Width".to_string(), rect.width.to_string(),
+            "-Height".to_string(), rect.height.to_string(),
+        ],
+    )?;
+
+    info!("Window handle {} moved successfully", handle);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_scripts_dir() {
+        // This test just ensures the function doesn't panic
+        let _ = get_scripts_dir();
+    }
+
+    #[test]
+    fn test_parse_handles() {
+        assert_eq!(parse_handles(""), Vec::<i64>::new());
+        assert_eq!(parse_handles("null\r\n"), Vec::<i64>::new());
+        assert_eq!(parse_handles("12345"), vec![12345]);
+        assert_eq!(parse_handles("[1,2,3]"), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_parse_window_info() {
+        let json = r#"[{"Handle":42,"X":0,"Y":0,"Width":960,"Height":516,"Visible":false,"Title":"claude-1","ProcessId":1234}]"#;
+        let info = parse_window_info(json).unwrap();
+        assert_eq!(info[&42].rect(), Rect::new(0, 0, 960, 516));
+        assert!(!info[&42].visible);
+        assert_eq!(info[&42].title, "claude-1");
+        assert_eq!(info[&42].process_id, 1234);
+        assert!(parse_window_info("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_encode_moves() {
+        let moves = [
+            (MoveTarget::Handle(4242), Rect::new(-8, 0, 976, 528)),
+            (MoveTarget::Title("api;1".to_string()), Rect::new(960, 0, 960, 520)),
+        ];
+        let moves: Vec<&(MoveTarget, Rect)> = moves.iter().collect();
+        assert_eq!(encode_moves(&moves), "4242,-8,0,976,528;title:6170693b31,960,0,960,520");
+    }
+}
@@ -0,0 +1,240 @@
+//! Git worktrees of `worktrees:` windows
+//!
+//! Before a worktree window is launched, its worktree is added to the repo
+//! inside the WSL distribution: on the existing branch if there is one,
+//! otherwise on a new branch from `worktrees.base`. A worktree directory
+//! that already exists is reused as is.
+//!
+//! `stop --cleanup` removes the worktrees again once their branches are
+//! merged into the base, but never one with uncommitted changes.
+
+use std::fmt;
+use std::process::Command;
+use tracing::debug;
+
+use crate::config::{WindowConfig, Worktree, WorktreeSummary};
+use crate::dry_run;
+use crate::error::{LauncherError, Result};
+use crate::shell;
+
+/// Script adding one worktree unless its directory exists; prints
+/// "created <path>" when it adds one
+fn ensure_script(worktree: &Worktree) -> String {
+    let path = shell::quote_path(&worktree.path);
+    let branch = shell::quote(&worktree.branch);
+    let base = worktree.base.as_deref().map(shell::quote).unwrap_or_else(|| "HEAD".to_string());
+    format!(
+        "if [ ! -e {path} ]; then \
+         cd {repo} && mkdir -p \"$(dirname {path})\" && \
+         if git show-ref --verify --quiet refs/heads/{branch}; then git worktree add {path} {branch}; \
+         else git worktree add -b {branch} {path} {base}; fi >&2 && echo created {path}; fi",
+        repo = shell::quote_path(&worktree.repo),
+    )
+}
+
+/// Make sure the worktrees of `windows` exist in `distribution`, returning
+/// the paths of the ones created
+pub fn ensure(distribution: &str, windows: &[&WindowConfig]) -> Result<Vec<String>> {
+    let mut created = Vec::new();
+    for window in windows {
+        let Some(ref worktree) = window.worktree else { continue };
+        let script = ensure_script(worktree);
+        debug!("Ensuring worktree of '{}': {}", window.name, script);
+        let mut cmd = Command::new("wsl.exe");
+        cmd.args(["-d", distribution, "--", "bash", "-c", &script]);
+        if dry_run::skip(&cmd) {
+            continue;
+        }
+        let output = cmd.output().map_err(LauncherError::WslRun)?;
+        if !output.status.success() {
+            return Err(failed(worktree, "create", &output.stderr));
+        }
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        created.extend(stdout.lines().filter_map(|l| l.strip_prefix("created ")).map(str::to_string));
+    }
+    Ok(created)
+}
+
+/// Shell loop of the `worktrees.summary` window: every `interval_secs`, the
+/// summary command's output in each worktree (window name, worktree) under
+/// a heading
+///
+/// Without a `base`, `$WT_BASE` is the commit the repo is on, like the
+/// base new branches start from.
+pub fn summary_command(
+    repo: &str,
+    base: Option<&str>,
+    summary: &WorktreeSummary,
+    worktrees: &[(&str, &Worktree)],
+) -> String {
+    let base = match base {
+        Some(base) => shell::quote(base),
+        None => format!("\"$(git -C {} rev-parse HEAD)\"", shell::quote_path(repo)),
+    };
+    let sections: Vec<String> = worktrees
+        .iter()
+        .map(|(name, worktree)| {
+            format!("s {} {} {}", shell::quote(name), shell::quote(&worktree.branch), shell::quote_path(&worktree.path))
+        })
+        .collect();
+    format!(
+        "s() {{ echo; echo \"== $1 ($2)\"; if [ -d \"$3\" ]; then \
+         (cd \"$3\" && export WT_NAME=\"$1\" WT_BRANCH=\"$2\" WT_BASE=\"$b\" && {command}) 2>&1; \
+         else echo '(no worktree yet)'; fi; }}; \
+         while :; do b={base}; clear; echo \"Worktrees of {repo} at $(date +%H:%M:%S)\"; {sections}; sleep {interval}; done",
+        command = summary.command,
+        base = base,
+        repo = repo,
+        sections = sections.join("; "),
+        interval = summary.interval_secs,
+    )
+}
+
+/// Outcome of cleaning up one worktree
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cleanup {
+    /// Worktree and its branch were removed
+    Removed,
+    /// Worktree has uncommitted changes or untracked files
+    Dirty,
+    /// Branch isn't merged into the base yet
+    Unmerged,
+    /// Worktree directory doesn't exist
+    Missing,
+}
+
+impl fmt::Display for Cleanup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Cleanup::Removed => "REMOVED",
+            Cleanup::Dirty => "KEPT (uncommitted changes)",
+            Cleanup::Unmerged => "KEPT (branch not merged)",
+            Cleanup::Missing => "ALREADY GONE",
+        })
+    }
+}
+
+/// Script removing one worktree and its branch if the tree is clean and the
+/// branch is merged into the base; prints "removed", "dirty", "unmerged" or
+/// "missing"
+fn cleanup_script(worktree: &Worktree) -> String {
+    let path = shell::quote_path(&worktree.path);
+    let branch = shell::quote(&worktree.branch);
+    let base = worktree.base.as_deref().map(shell::quote).unwrap_or_else(|| "HEAD".to_string());
+    // The branch is known to be merged by then, so -D only skips git's own
+    // check against the current branch of the repo
+    format!(
+        "cd {repo} || exit 1; \
+         if [ ! -e {path} ]; then echo missing; exit 0; fi; \
+         changes=$(git -C {path} status --porcelain) || exit 1; \
+         if [ -n \"$changes\" ]; then echo dirty; \
+         elif ! git merge-base --is-ancestor refs/heads/{branch} {base} 2>/dev/null; then echo unmerged; \
+         else git worktree remove {path} >&2 && git branch -D {branch} >&2 && echo removed; fi",
+        repo = shell::quote_path(&worktree.repo),
+    )
+}
+
+fn parse_cleanup(stdout: &str) -> Option<Cleanup> {
+    match stdout.trim() {
+        "removed" => Some(Cleanup::Removed),
+        "dirty" => Some(Cleanup::Dirty),
+        "unmerged" => Some(Cleanup::Unmerged),
+        "missing" => Some(Cleanup::Missing),
+        _ => None,
+    }
+}
+
+/// Remove the worktree of `worktree` in `distribution` if that's safe
+pub fn cleanup(distribution: &str, worktree: &Worktree) -> Result<Cleanup> {
+    let script = cleanup_script(worktree);
+    debug!("Cleaning up worktree {}: {}", worktree.path, script);
+    let mut cmd = Command::new("wsl.exe");
+    cmd.args(["-d", distribution, "--", "bash", "-c", &script]);
+    if dry_run::skip(&cmd) {
+        return Ok(Cleanup::Removed);
+    }
+    let output = cmd.output().map_err(LauncherError::WslRun)?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    match parse_cleanup(&stdout) {
+        Some(outcome) if output.status.success() => Ok(outcome),
+        _ => Err(failed(worktree, "remove", &output.stderr)),
+    }
+}
+
+fn failed(worktree: &Worktree, action: &'static str, stderr: &[u8]) -> LauncherError {
+    LauncherError::Worktree {
+        path: worktree.path.clone(),
+        branch: worktree.branch.clone(),
+        action,
+        stderr: String::from_utf8_lossy(stderr).trim().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ensure_script() {
+        let worktree = Worktree {
+            repo: "~/src/app".to_string(),
+            branch: "feature/login".to_string(),
+            base: Some("origin/main".to_string()),
+            path: "~/src/app-worktrees/feature-login".to_string(),
+        };
+        let script = ensure_script(&worktree);
+        assert!(script.starts_with("if [ ! -e \"$HOME\"/src/app-worktrees/feature-login ]; then cd \"$HOME\"/src/app && "));
+        assert!(script.contains("git show-ref --verify --quiet refs/heads/feature/login"));
+        assert!(script.contains("git worktree add -b feature/login \"$HOME\"/src/app-worktrees/feature-login origin/main"));
+
+        let script = ensure_script(&Worktree { base: None, ..worktree });
+        assert!(script.contains("feature-login HEAD;"));
+    }
+
+    #[test]
+    fn test_summary_command() {
+        let worktree = Worktree {
+            repo: "~/src/app".to_string(),
+            branch: "feature/login".to_string(),
+            base: None,
+            path: "~/src/app-worktrees/feature-login".to_string(),
+        };
+        let summary = WorktreeSummary { interval_secs: 30, ..Default::default() };
+        let command = summary_command("~/src/app", None, &summary, &[("feature-login", &worktree)]);
+        assert!(command.contains("WT_BASE=\"$b\" && git diff --stat \"$(git merge-base \"$WT_BASE\" HEAD)\")"));
+        assert!(command.contains("while :; do b=\"$(git -C \"$HOME\"/src/app rev-parse HEAD)\"; clear;"));
+        assert!(command.contains("; s feature-login feature/login \"$HOME\"/src/app-worktrees/feature-login; sleep 30; done"));
+        assert!(!command.contains('\n'));
+
+        let command = summary_command("~/src/app", Some("origin/main"), &summary, &[]);
+        assert!(command.contains("b=origin/main;"));
+    }
+
+    #[test]
+    fn test_cleanup_script() {
+        let worktree = Worktree {
+            repo: "~/src/app".to_string(),
+            branch: "feature/login".to_string(),
+            base: Some("origin/main".to_string()),
+            path: "~/src/app-worktrees/feature-login".to_string(),
+        };
+        let script = cleanup_script(&worktree);
+        assert!(script.starts_with("cd \"$HOME\"/src/app || exit 1;"));
+        assert!(script.contains("git -C \"$HOME\"/src/app-worktrees/feature-login status --porcelain"));
+        assert!(script.contains("git merge-base --is-ancestor refs/heads/feature/login origin/main"));
+        assert!(script.contains("git worktree remove \"$HOME\"/src/app-worktrees/feature-login >&2"));
+        assert!(!script.contains("--force"));
+
+        let script = cleanup_script(&Worktree { base: None, ..worktree });
+        assert!(script.contains("refs/heads/feature/login HEAD"));
+    }
+
+    #[test]
+    fn test_parse_cleanup() {
+        assert_eq!(parse_cleanup("removed\n"), Some(Cleanup::Removed));
+        assert_eq!(parse_cleanup("dirty\n"), Some(Cleanup::Dirty));
+        assert_eq!(parse_cleanup("unmerged\n"), Some(Cleanup::Unmerged));
+        assert_eq!(parse_cleanup("missing\n"), Some(Cleanup::Missing));
+        assert_eq!(parse_cleanup(""), None);
+    }
+}
@@ -0,0 +1,1064 @@
+use std::collections::{HashMap, HashSet};
+use std::process::Command;
+use tracing::{debug, info};
+
+use crate::config::{LogConfig, WindowConfig, WindowKind};
+use crate::dry_run;
+use crate::error::{LauncherError, Result};
+use crate::exit_status;
+use crate::layout::{self, PaneAction};
+use crate::logs;
+use crate::shell;
+use crate::terminal::{self, wt_version::{WtFeature, WtVersion}};
+use crate::wt_profiles;
+
+/// Get list of available WSL distributions
+pub fn distributions() -> Result<Vec<String>> {
+    let output = Command::new("wsl.exe")
+        .args(["-l", "-q"])
+        .output()
+        .map_err(LauncherError::WslRun)?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).replace('\0', "").trim().to_string();
+        return Err(LauncherError::Distributions { stderr });
+    }
+
+    // Parse output (UTF-16 LE encoded on Windows)
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let distros: Vec<String> = stdout
+        .lines()
+        .map(|s| s.trim().replace('\0', ""))
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    Ok(distros)
+}
+
+/// Launcher for WSL windows
+#[derive(Clone)]
+pub struct WslLauncher {
+    distribution: String,
+    use_wsl_cd: bool,
+    wt_version: Option<WtVersion>,
+    session: Option<String>,
+    logs: Option<LogConfig>,
+}
+
+/// Environment variable marking the processes started for a window
+/// (`<session>/<window name>`), so `stop` can signal them inside WSL
+pub const WINDOW_ENV: &str = "WSL_MULTI_LAUNCHER_WINDOW";
+
+impl WslLauncher {
+    /// Create a new WSL launcher
+    pub fn new(distribution: &str) -> Self {
+        Self {
+            distribution: distribution.to_string(),
+            use_wsl_cd: false,
+            wt_version: None,
+            session: None,
+            logs: None,
+        }
+    }
+
+    /// Record window output to log files (needs `with_session`)
+    pub fn with_logs(mut self, config: &LogConfig) -> Self {
+        self.logs = config.enabled.then(|| config.clone());
+        self
+    }
+
+    /// Tag launched commands with the session they belong to (see `WINDOW_ENV`)
+    pub fn with_session(mut self, session: &str) -> Self {
+        self.session = Some(session.to_string());
+        self
+    }
+
+    /// Adapt generated wt arguments to the installed Windows Terminal version
+    /// (None = assume everything is supported)
+    pub fn with_wt_version(mut self, version: Option<WtVersion>) -> Self {
+        self.wt_version = version;
+        self
+    }
+
+    /// Whether the installed wt supports a feature
+    fn wt_supports(&self, feature: WtFeature) -> bool {
+        let supported = self.wt_version.is_none_or(|v| v.supports(feature));
+        if !supported {
+            debug!("Skipping {} (not supported by wt {:?})", feature.describe(), self.wt_version);
+        }
+        supported
+    }
+
+    /// Leading `-w <name>` arguments, omitted when wt can't target windows
+    fn wt_target_args(&self, wt_window: &str) -> Vec<String> {
+        if self.wt_supports(WtFeature::WindowTarget) {
+            vec!["-w".to_string(), wt_window.to_string()]
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Pass working directories via `wsl.exe --cd` where possible
+    pub fn with_wsl_cd(mut self, enabled: bool) -> Self {
+        self.use_wsl_cd = enabled;
+        self
+    }
+
+    /// Launch a single WSL window using Windows Terminal
+    pub fn launch_window(&self, window: &WindowConfig) -> Result<()> {
+        self.launch_into(window, "new")
+    }
+
+    /// Launch a window as a new tab of the named Windows Terminal window
+    ///
+    /// The named window is created by the first tab launched into it.
+    pub fn launch_tab(&self, window: &WindowConfig, wt_window: &str) -> Result<()> {
+        self.launch_into(window, wt_window)
+    }
+
+    /// Launch all windows as split panes of the named Windows Terminal window
+    pub fn launch_panes(&self, windows: &[WindowConfig], cols: u32, wt_window: &str) -> Result<()> {
+        info!("Launching {} panes", windows.len());
+        self.run_wt(self.panes_args(windows, cols, wt_window)).map_err(|e| LauncherError::Launch {
+            window: windows.iter().map(|w| w.name.as_str()).collect::<Vec<_>>().join(", "),
+            source: Box::new(e),
+        })
+    }
+
+    /// Bring tab `index` of the named Windows Terminal window to the front
+    pub fn focus_tab(&self, wt_window: &str, index: usize) -> Result<()> {
+        self.run_wt(self.focus_args(wt_window, "focus-tab", index))
+    }
+
+    /// Focus pane `pane_id` of the named Windows Terminal window
+    pub fn focus_pane(&self, wt_window: &str, pane_id: usize) -> Result<()> {
+        if !self.wt_supports(WtFeature::FocusPane) {
+            return Err(LauncherError::WtTooOld {
+                feature: "Focusing panes",
+                min_version: WtFeature::FocusPane.min_version().to_string(),
+            });
+        }
+        self.run_wt(self.focus_args(wt_window, "focus-pane", pane_id))
+    }
+
+    /// Run wt.exe targeting `wt_window` ("new" for a fresh window)
+    fn launch_into(&self, window: &WindowConfig, wt_window: &str) -> Result<()> {
+        info!("Launching window: {}", window.name);
+        let result = if window.elevated {
+            self.run_wt_elevated(self.wt_args(window, wt_window))
+        } else {
+            self.run_wt(self.wt_args(window, wt_window))
+        };
+        result.map_err(|e| LauncherError::Launch { window: window.name.clone(), source: Box::new(e) })?;
+        info!("Window '{}' launched successfully", window.name);
+        Ok(())
+    }
+
+    /// Execute wt.exe with the given arguments
+    fn run_wt(&self, args: Vec<String>) -> Result<()> {
+        let mut cmd = Command::new("wt.exe");
+        cmd.args(args);
+
+        debug!("Executing: {:?}", cmd);
+        if dry_run::skip(&cmd) {
+            return Ok(());
+        }
+
+        let status = cmd
+            .status()
+            .map_err(LauncherError::spawn("wt.exe"))?;
+
+        if !status.success() {
+            return Err(LauncherError::command("wt.exe", format!("exited with {}", status)));
+        }
+
+        Ok(())
+    }
+
+    /// Execute wt.exe elevated (UAC prompt)
+    fn run_wt_elevated(&self, args: Vec<String>) -> Result<()> {
+        let mut cmd = terminal::elevated_command("wt.exe", &args);
+        debug!("Executing: {:?}", cmd);
+        if dry_run::skip(&cmd) {
+            return Ok(());
+        }
+
+        let status = cmd
+            .status()
+            .map_err(LauncherError::spawn("wt.exe"))?;
+
+        if !status.success() {
+            return Err(LauncherError::Elevation { program: "wt.exe".to_string() });
+        }
+
+        Ok(())
+    }
+
+    /// Launch multiple windows with a delay between each
+    #[allow(dead_code)]
+    pub fn launch_windows(&self, windows: &[WindowConfig]) -> Result<()> {
+        for (i, window) in windows.iter().enumerate() {
+            self.launch_window(window)?;
+
+            // Add a small delay between window launches to prevent race conditions
+            if i < windows.len() - 1 {
+                std::thread::sleep(std::time::Duration::from_millis(500));
+            }
+        }
+        Ok(())
+    }
+
+    /// Build the wt.exe arguments that open a tab running the window's command
+    fn wt_args(&self, window: &WindowConfig, wt_window: &str) -> Vec<String> {
+        let mut args = self.wt_target_args(wt_window);
+
+        // Window-level launch flags go before the subcommand
+        if self.wt_supports(WtFeature::LaunchModeFlags) {
+            if window.wt_maximized {
+                args.push("-M".to_string());
+            }
+            if window.wt_fullscreen {
+                args.push("-F".to_string());
+            }
+            if window.wt_focus {
+                args.push("--focus".to_string());
+            }
+        }
+
+        // new-tab (nt) subcommand
+        args.push("nt".to_string());
+        args.extend(self.pane_command_args(window));
+        args
+    }
+
+    /// Build a single wt.exe invocation that creates a tab and splits it
+    /// into a pane per window following the grid
+    fn panes_args(&self, windows: &[WindowConfig], cols: u32, wt_window: &str) -> Vec<String> {
+        let mut args = self.wt_target_args(wt_window);
+        args.push("nt".to_string());
+        if let Some(first) = windows.first() {
+            args.extend(self.pane_command_args(first));
+        }
+
+        for action in layout::pane_splits(cols, windows.len()) {
+            // A bare ";" separates wt subcommands
+            args.push(";".to_string());
+            match action {
+                PaneAction::SplitRight { index, size } | PaneAction::SplitDown { index, size } => {
+                    let direction = if matches!(action, PaneAction::SplitRight { .. }) { "-V" } else { "-H" };
+                    args.extend(["split-pane", direction, "--size"].map(String::from));
+                    args.push(format!("{:.4}", size));
+                    args.extend(self.pane_command_args(&windows[index]));
+                }
+                PaneAction::FocusLeft => {
+                    args.extend(["move-focus", "left"].map(String::from));
+                }
+            }
+        }
+        args
+    }
+
+    /// Build `-w <window> <action> -t <target>` for focus-tab / focus-pane
+    fn focus_args(&self, wt_window: &str, action: &str, target: usize) -> Vec<String> {
+        let mut args = self.wt_target_args(wt_window);
+        args.extend([action.to_string(), "-t".to_string(), target.to_string()]);
+        args
+    }
+
+    /// Arguments shared by new-tab and split-pane: title and the wsl.exe commandline
+    fn pane_command_args(&self, window: &WindowConfig) -> Vec<String> {
+        // Build the command to run inside WSL
+        let wsl_command = self.build_wsl_command(window);
+        debug!("WSL command: {}", wsl_command);
+        let wt_command = shell::escape_wt(&wsl_command);
+
+        let mut args = Vec::new();
+        // The profile supplies appearance; the commandline still runs our command.
+        // wt has no font flag, so fonts come from the generated profile
+        // (installed with `export wt-profiles --install`).
+        let profile = window
+            .wt_profile
+            .clone()
+            .or_else(|| window.font.as_ref().map(|_| wt_profiles::profile_name(&window.name)));
+        if let Some(profile) = profile {
+            args.push("-p".to_string());
+            args.push(profile);
+        }
+        if let Some(ref scheme) = window.color_scheme
+            && self.wt_supports(WtFeature::ColorScheme)
+        {
+            args.push("--colorScheme".to_string());
+            args.push(scheme.clone());
+        }
+        if let Some(ref color) = window.tab_color
+            && self.wt_supports(WtFeature::TabColor)
+        {
+            args.push("--tabColor".to_string());
+            args.push(color.clone());
+        }
+        args.extend(["--title", &window.name].map(String::from));
+        if window.suppress_app_title && self.wt_supports(WtFeature::SuppressApplicationTitle) {
+            args.push("--suppressApplicationTitle".to_string());
+        }
+        if window.kind != WindowKind::Wsl {
+            if let Some(dir) = window.native_working_dir() {
+                args.push("-d".to_string());
+                args.push(dir.to_string());
+            }
+            args.extend(native_args(window).iter().map(|a| shell::escape_wt(a)));
+            return args;
+        }
+        args.push("wsl.exe".to_string());
+        args.extend(self.wsl_args(window, &wt_command));
+        args
+    }
+
+    /// Full Windows command line running the window, for a Windows Terminal profile
+    ///
+    /// Unlike `wt_args`, this is not parsed by wt's CLI so `;` needs no escaping.
+    pub fn profile_commandline(&self, window: &WindowConfig) -> String {
+        shell::join_windows(&self.command_args(window))
+    }
+
+    /// Program and arguments (`wsl.exe ...`) that run the window's command,
+    /// for terminals that take the command as separate arguments
+    pub fn command_args(&self, window: &WindowConfig) -> Vec<String> {
+        if window.kind != WindowKind::Wsl {
+            return native_args(window);
+        }
+        let command = self.build_wsl_command(window);
+        let mut args = vec!["wsl.exe".to_string()];
+        args.extend(self.wsl_args(window, &command));
+        args
+    }
+
+    /// Build the wsl.exe arguments that run `command` for the window
+    fn wsl_args(&self, window: &WindowConfig, command: &str) -> Vec<String> {
+        let mut args = vec!["-d".to_string(), self.distribution.clone()];
+        if let Some(dir) = self.wsl_cd_dir(window) {
+            args.push("--cd".to_string());
+            args.push(dir);
+        }
+        // A login shell sources .profile/.bash_profile (PATH for nvm, claude, ...)
+        let flag = if window.login_shell { "-lc" } else { "-c" };
+        args.extend(["--", "bash", flag, command].map(String::from));
+        args
+    }
+
+    /// Working directory to pass via `wsl.exe --cd`, if it understands the path
+    ///
+    /// wsl.exe accepts `~`, absolute Linux paths and absolute Windows paths.
+    /// Anything else (`~/project`, relative paths) falls back to a `cd` in the
+    /// shell command.
+    fn wsl_cd_dir(&self, window: &WindowConfig) -> Option<String> {
+        if !self.use_wsl_cd {
+            return None;
+        }
+        let dir = window.working_dir.as_deref()?;
+        let is_windows_path = {
+            let bytes = dir.as_bytes();
+            bytes.len() >= 3
+                && bytes[0].is_ascii_alphabetic()
+                && bytes[1] == b':'
+                && (bytes[2] == b'\\' || bytes[2] == b'/')
+        };
+        if dir == "~" || dir.starts_with('/') || is_windows_path {
+            Some(dir.to_string())
+        } else {
+            None
+        }
+    }
+
+    /// Build the command to run inside WSL
+    fn build_wsl_command(&self, window: &WindowConfig) -> String {
+        let mut parts = Vec::new();
+
+        // Change to working directory if wsl.exe --cd can't handle it
+        if let Some(ref dir) = window.working_dir
+            && self.wsl_cd_dir(window).is_none()
+        {
+            // Quote the path, keeping ~ expandable as $HOME
+            parts.push(format!("cd {}", shell::quote_path(dir)));
+        }
+
+        // Add the main command(s)
+        let steps = window.steps();
+        let body = steps.join(window.step_separator());
+        if window.continue_on_error && steps.len() > 1 && !parts.is_empty() {
+            // Group the steps so a failed cd still skips all of them
+            parts.push(format!("{{ {}; }}", body));
+        } else {
+            parts.push(body);
+        }
+
+        // Join with && to execute sequentially
+        let command = if parts.len() > 1 {
+            parts.join(" && ")
+        } else {
+            parts.into_iter().next().unwrap_or_else(|| "bash".to_string())
+        };
+
+        // Record how the command exits so `ps` can report failures
+        let command = match self.session {
+            Some(ref session) => exit_status::wrap_command(session, &window.name, &command),
+            None => command,
+        };
+
+        // Decide what happens once the command exits
+        let command = if window.keep_open {
+            format!("{}; exec $SHELL", command)
+        } else if window.close_on_exit {
+            // A zero exit status lets Windows Terminal close the tab on its default closeOnExit setting
+            format!("{}; exit 0", command)
+        } else {
+            command
+        };
+
+        let command = if let Some(ref session) = window.tmux_session {
+            // -A attaches to a surviving session, in which case the command is ignored.
+            // Panes get their environment from the tmux server, which may have been
+            // started by another window, so the marker is set on the session itself
+            let marker = match self.session {
+                Some(ref launch) => format!(
+                    " -e {}",
+                    shell::quote(&format!("{}={}", WINDOW_ENV, window_marker(launch, &window.name)))
+                ),
+                None => String::new(),
+            };
+            format!(
+                "tmux new-session -A -s {}{} {}",
+                shell::quote(session),
+                marker,
+                shell::quote(&command)
+            )
+        } else if let Some(ref session) = window.zellij_session {
+            Self::zellij_command(session, window.zellij_layout.as_deref(), &command)
+        } else {
+            command
+        };
+
+        let command = match (&self.session, &self.logs) {
+            (Some(session), Some(logs)) => logs::wrap_command(session, &window.name, &command, logs),
+            _ => command,
+        };
+
+        match self.session {
+            Some(ref session) => format!(
+                "export {}={}; {}",
+                WINDOW_ENV,
+                shell::quote(&window_marker(session, &window.name)),
+                command
+            ),
+            None => command,
+        }
+    }
+
+    /// Total CPU ticks used by the live processes of each launched window of
+    /// `session`, by window name
+    pub fn window_cpu_ticks(&self, session: &str) -> Result<HashMap<String, u64>> {
+        let output = Command::new("wsl.exe")
+            .args(["-d", &self.distribution, "--exec", "bash", "-c", CPU_TICKS_SCRIPT])
+            .output()
+            .map_err(LauncherError::WslRun)?;
+        let prefix = format!("{}/", session);
+        Ok(parse_cpu_ticks(&String::from_utf8_lossy(&output.stdout))
+            .into_iter()
+            .filter_map(|(marker, ticks)| Some((marker.strip_prefix(&prefix)?.to_string(), ticks)))
+            .collect())
+    }
+
+    /// Names of the launched windows of `session` whose processes used CPU
+    /// during a `sample` long wait
+    pub fn busy_windows(&self, session: &str, sample: std::time::Duration) -> Result<HashSet<String>> {
+        let script = format!(
+            "{}; echo --; sleep {:.1}; {}",
+            CPU_TICKS_SCRIPT,
+            sample.as_secs_f64(),
+            CPU_TICKS_SCRIPT
+        );
+        let output = Command::new("wsl.exe")
+            .args(["-d", &self.distribution, "--exec", "bash", "-c", &script])
+            .output()
+            .map_err(LauncherError::WslRun)?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let (before, after) = stdout.split_once("--\n").unwrap_or((&stdout, ""));
+        let before = parse_cpu_ticks(before);
+        let prefix = format!("{}/", session);
+        Ok(parse_cpu_ticks(after)
+            .into_iter()
+            .filter(|(marker, ticks)| before.get(marker).is_some_and(|b| ticks > b))
+            .filter_map(|(marker, _)| Some(marker.strip_prefix(&prefix)?.to_string()))
+            .collect())
+    }
+
+    /// Send SIGTERM to the processes of a launched window and wait up to
+    /// `grace` for them to exit
+    ///
+    /// Returns true once none are left (or there were none to begin with).
+    pub fn terminate_window(&self, session: &str, name: &str, grace: std::time::Duration) -> Result<bool> {
+        let script = terminate_script(&window_marker(session, name), grace);
+        debug!("Terminating '{}' inside {}", name, self.distribution);
+        let mut cmd = Command::new("wsl.exe");
+        cmd.args(["-d", &self.distribution, "--exec", "bash", "-c", &script]);
+        if dry_run::skip(&cmd) {
+            return Ok(true);
+        }
+        let output = cmd
+            .output()
+            .map_err(LauncherError::WslRun)?;
+        Ok(output.status.success())
+    }
+
+    /// Attach to a surviving Zellij session, or create it and run `command`
+    ///
+    /// A layout file, when given, defines the new session's panes instead
+    /// of `command`. Without one, the session is created in the background
+    /// and the command is started in a pane before attaching.
+    fn zellij_command(session: &str, layout: Option<&str>, command: &str) -> String {
+        let name = shell::quote(session);
+        let create = match layout {
+            Some(layout) => format!(
+                "zellij --session {} --layout {}",
+                name,
+                shell::quote_path(layout)
+            ),
+            None => format!(
+                "zellij attach --create-background {name} && zellij --session {name} run -- bash -c {}; zellij attach {name}",
+                shell::quote(command)
+            ),
+        };
+        format!(
+            "if zellij list-sessions --short --no-formatting 2>/dev/null | grep -qxF {name}; then zellij attach {name}; else {create}; fi"
+        )
+    }
+}
+
+/// Program and arguments for a native Windows window (PowerShell, cmd, exe)
+///
+/// Without a command, shells start interactively; `keep_open` keeps them
+/// running after the command like `exec $SHELL` does for WSL windows.
+fn native_args(window: &WindowConfig) -> Vec<String> {
+    let command = window.native_command();
+    let mut args = Vec::new();
+    match window.kind {
+        WindowKind::Powershell => {
+            args.extend(["powershell.exe", "-NoLogo"].map(String::from));
+            if let Some(command) = command {
+                if window.keep_open {
+                    args.push("-NoExit".to_string());
+                }
+                args.push("-Command".to_string());
+                args.push(command);
+            }
+        }
+        WindowKind::Cmd => {
+            args.push("cmd.exe".to_string());
+            if let Some(command) = command {
+                args.push(if window.keep_open { "/k" } else { "/c" }.to_string());
+                args.push(command);
+            }
+        }
+        WindowKind::WindowsExe => {
+            args.extend(command);
+            args.extend(window.args.iter().cloned());
+        }
+        WindowKind::Wsl => unreachable!("native_args called for a WSL window"),
+    }
+    args
+}
+
+/// Lists `<marker>\t<utime+stime>` for every process carrying `WINDOW_ENV`
+/// (the comm field of /proc/<pid>/stat may contain spaces, so it's cut off first)
+///
+/// The tmux server carries the marker of whichever window started it and
+/// serves every tmux window, so it's left out.
+const CPU_TICKS_SCRIPT: &str = "for p in /proc/[0-9]*; do \
+    m=$(tr '\\0' '\\n' < \"$p/environ\" 2>/dev/null | grep -m1 '^WSL_MULTI_LAUNCHER_WINDOW=') || continue; \
+    case $(cat \"$p/comm\" 2>/dev/null) in 'tmux: server'*) continue;; esac; \
+    t=$(sed 's/.*) //' \"$p/stat\" 2>/dev/null | awk '{print $12 + $13}') || continue; \
+    printf '%s\\t%s\\n' \"${m#*=}\" \"$t\"; done";
+
+/// Sum the CPU ticks per window marker from `CPU_TICKS_SCRIPT` output
+fn parse_cpu_ticks(output: &str) -> HashMap<String, u64> {
+    let mut ticks = HashMap::new();
+    for line in output.lines() {
+        if let Some((marker, value)) = line.rsplit_once('\t')
+            && let Ok(value) = value.trim().parse::<u64>()
+        {
+            *ticks.entry(marker.to_string()).or_insert(0) += value;
+        }
+    }
+    ticks
+}
+
+/// Value of `WINDOW_ENV` for a window
+fn window_marker(session: &str, name: &str) -> String {
+    format!("{}/{}", session, name)
+}
+
+/// Script signalling every process whose environment carries the window's
+/// marker, then polling until they are gone; exits 1 if some outlive `grace`
+///
+/// Processes in tmux / Zellij panes are left running so the session can be
+/// resumed, and so is the tmux server, which may carry the marker of the
+/// window that started it but serves every tmux window.
+fn terminate_script(marker: &str, grace: std::time::Duration) -> String {
+    let entry = shell::quote(&format!("{}={}", WINDOW_ENV, marker));
+    let polls = (grace.as_millis() / 100).max(1);
+    format!(
+        "pids() {{ for p in /proc/[0-9]*; do \
+         grep -qzxF {} \"$p/environ\" 2>/dev/null || continue; \
+         grep -qzE '^(TMUX|ZELLIJ)=' \"$p/environ\" 2>/dev/null && continue; \
+         case $(cat \"$p/comm\" 2>/dev/null) in 'tmux: server'*) continue;; esac; \
+         echo \"${{p#/proc/}}\"; done; }}; \
+         p=$(pids); [ -z \"$p\" ] && exit 0; kill -TERM $p 2>/dev/null; \
+         for i in $(seq {}); do sleep 0.1; [ -z \"$(pids)\" ] && exit 0; done; exit 1",
+        entry, polls
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_wsl_command_simple() {
+        let launcher = WslLauncher::new("Ubuntu-24.04");
+        let window = WindowConfig {
+            name: "test".to_string(),
+            command: Some("htop".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(launcher.build_wsl_command(&window), "htop");
+    }
+
+    #[test]
+    fn test_build_wsl_command_with_dir() {
+        let launcher = WslLauncher::new("Ubuntu-24.04");
+        let window = WindowConfig {
+            name: "test".to_string(),
+            command: Some("claude".to_string()),
+            working_dir: Some("~/workspace".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            launcher.build_wsl_command(&window),
+            "cd \"$HOME\"/workspace && claude"
+        );
+    }
+
+    #[test]
+    fn test_build_wsl_command_dir_with_spaces() {
+        let launcher = WslLauncher::new("Ubuntu-24.04");
+        let window = WindowConfig {
+            name: "test".to_string(),
+            command: Some("claude".to_string()),
+            working_dir: Some("/mnt/c/My Projects/it's".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            launcher.build_wsl_command(&window),
+            "cd '/mnt/c/My Projects/it'\\''s' && claude"
+        );
+    }
+
+    #[test]
+    fn test_build_wsl_command_absolute_dir() {
+        let launcher = WslLauncher::new("Ubuntu-24.04");
+        let window = WindowConfig {
+            name: "test".to_string(),
+            command: Some("bash".to_string()),
+            working_dir: Some("/tmp".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(launcher.build_wsl_command(&window), "cd /tmp && bash");
+    }
+
+    #[test]
+    fn test_build_wsl_command_multiple_steps() {
+        let launcher = WslLauncher::new("Ubuntu-24.04");
+        let window = WindowConfig {
+            name: "test".to_string(),
+            commands: vec!["nvm use 20".to_string(), "npm run dev".to_string()],
+            working_dir: Some("/srv/app".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            launcher.build_wsl_command(&window),
+            "cd /srv/app && nvm use 20 && npm run dev"
+        );
+    }
+
+    #[test]
+    fn test_build_wsl_command_continue_on_error() {
+        let launcher = WslLauncher::new("Ubuntu-24.04");
+        let window = WindowConfig {
+            name: "test".to_string(),
+            commands: vec!["make test".to_string(), "bash".to_string()],
+            continue_on_error: true,
+            working_dir: Some("/srv/app".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            launcher.build_wsl_command(&window),
+            "cd /srv/app && { make test; bash; }"
+        );
+    }
+
+    #[test]
+    fn test_build_wsl_command_keep_open() {
+        let launcher = WslLauncher::new("Ubuntu-24.04");
+        let window = WindowConfig {
+            name: "test".to_string(),
+            command: Some("cargo test".to_string()),
+            keep_open: true,
+            ..Default::default()
+        };
+        assert_eq!(launcher.build_wsl_command(&window), "cargo test; exec $SHELL");
+    }
+
+    #[test]
+    fn test_build_wsl_command_close_on_exit() {
+        let launcher = WslLauncher::new("Ubuntu-24.04");
+        let window = WindowConfig {
+            name: "test".to_string(),
+            command: Some("cargo test".to_string()),
+            close_on_exit: true,
+            ..Default::default()
+        };
+        assert_eq!(launcher.build_wsl_command(&window), "cargo test; exit 0");
+    }
+
+    #[test]
+    fn test_build_wsl_command_tmux_session() {
+        let launcher = WslLauncher::new("Ubuntu-24.04");
+        let window = WindowConfig {
+            name: "test".to_string(),
+            command: Some("claude".to_string()),
+            working_dir: Some("~/my project".to_string()),
+            keep_open: true,
+            tmux_session: Some("claude-1".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            launcher.build_wsl_command(&window),
+            r#"tmux new-session -A -s claude-1 'cd "$HOME"/'\''my project'\'' && claude; exec $SHELL'"#
+        );
+    }
+
+    #[test]
+    fn test_build_wsl_command_tmux_session_marker() {
+        let launcher = WslLauncher::new("Ubuntu-24.04").with_session("work");
+        let window = WindowConfig {
+            name: "claude 1".to_string(),
+            command: Some("claude".to_string()),
+            tmux_session: Some("claude-1".to_string()),
+            ..Default::default()
+        };
+        let command = launcher.build_wsl_command(&window);
+        assert!(command.contains("tmux new-session -A -s claude-1 -e 'WSL_MULTI_LAUNCHER_WINDOW=work/claude 1' '"));
+    }
+
+    #[test]
+    fn test_build_wsl_command_zellij_session() {
+        let launcher = WslLauncher::new("Ubuntu-24.04");
+        let mut window = WindowConfig {
+            name: "test".to_string(),
+            command: Some("claude".to_string()),
+            zellij_session: Some("claude-1".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            launcher.build_wsl_command(&window),
+            "if zellij list-sessions --short --no-formatting 2>/dev/null | grep -qxF claude-1; \
+             then zellij attach claude-1; \
+             else zellij attach --create-background claude-1 && zellij --session claude-1 run -- bash -c claude; \
+             zellij attach claude-1; fi"
+        );
+
+        window.zellij_layout = Some("~/layouts/dev.kdl".to_string());
+        assert!(
+            launcher
+                .build_wsl_command(&window)
+                .ends_with(r#"else zellij --session claude-1 --layout "$HOME"/layouts/dev.kdl; fi"#)
+        );
+    }
+
+    #[test]
+    fn test_focus_args() {
+        let launcher = WslLauncher::new("Ubuntu-24.04");
+        assert_eq!(
+            launcher.focus_args("wcl-work-tabs", "focus-tab", 2),
+            vec!["-w", "wcl-work-tabs", "focus-tab", "-t", "2"]
+        );
+    }
+
+    #[test]
+    fn test_command_args_native() {
+        let launcher = WslLauncher::new("Ubuntu-24.04");
+        let mut window = WindowConfig {
+            name: "ps".to_string(),
+            kind: WindowKind::Powershell,
+            command: Some("Get-Date; Get-Process".to_string()),
+            keep_open: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            launcher.command_args(&window),
+            vec!["powershell.exe", "-NoLogo", "-NoExit", "-Command", "Get-Date; Get-Process"]
+        );
+
+        window.kind = WindowKind::Cmd;
+        window.command = None;
+        assert_eq!(launcher.command_args(&window), vec!["cmd.exe"]);
+
+        window.kind = WindowKind::WindowsExe;
+        window.command = Some(r"C:\tools\watch.exe".to_string());
+        window.args = vec!["--dir".to_string(), r"C:\src".to_string()];
+        assert_eq!(
+            launcher.command_args(&window),
+            vec![r"C:\tools\watch.exe", "--dir", r"C:\src"]
+        );
+    }
+
+    #[test]
+    fn test_wt_args_native_window() {
+        let launcher = WslLauncher::new("Ubuntu-24.04");
+        let window = WindowConfig {
+            name: "ps".to_string(),
+            kind: WindowKind::Powershell,
+            command: Some("npm run watch; pause".to_string()),
+            working_dir: Some(r"C:\src\app".to_string()),
+            suppress_app_title: false,
+            ..Default::default()
+        };
+        assert_eq!(
+            launcher.pane_command_args(&window),
+            vec![
+                "--title", "ps", "-d", r"C:\src\app",
+                "powershell.exe", "-NoLogo", "-Command", r"npm run watch\; pause",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_wsl_cd_absolute_dir() {
+        let launcher = WslLauncher::new("Ubuntu-24.04").with_wsl_cd(true);
+        let window = WindowConfig {
+            name: "test".to_string(),
+            command: Some("claude".to_string()),
+            working_dir: Some("/home/me/my project".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(launcher.build_wsl_command(&window), "claude");
+        assert_eq!(
+            launcher.wsl_args(&window, "claude"),
+            vec!["-d", "Ubuntu-24.04", "--cd", "/home/me/my project", "--", "bash", "-c", "claude"]
+        );
+    }
+
+    #[test]
+    fn test_wsl_cd_windows_and_home_dirs() {
+        let launcher = WslLauncher::new("Ubuntu-24.04").with_wsl_cd(true);
+        let mut window = WindowConfig {
+            name: "test".to_string(),
+            working_dir: Some("C:\\Users\\me".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(launcher.wsl_cd_dir(&window), Some("C:\\Users\\me".to_string()));
+
+        window.working_dir = Some("~".to_string());
+        assert_eq!(launcher.wsl_cd_dir(&window), Some("~".to_string()));
+    }
+
+    #[test]
+    fn test_wsl_cd_falls_back_to_shell_cd() {
+        let launcher = WslLauncher::new("Ubuntu-24.04").with_wsl_cd(true);
+        let window = WindowConfig {
+            name: "test".to_string(),
+            command: Some("claude".to_string()),
+            working_dir: Some("~/workspace".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(launcher.wsl_cd_dir(&window), None);
+        assert_eq!(launcher.build_wsl_command(&window), "cd \"$HOME\"/workspace && claude");
+    }
+
+    #[test]
+    fn test_wsl_args_login_shell() {
+        let launcher = WslLauncher::new("Ubuntu-24.04");
+        let window = WindowConfig {
+            name: "test".to_string(),
+            command: Some("claude".to_string()),
+            login_shell: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            launcher.wsl_args(&window, "claude"),
+            vec!["-d", "Ubuntu-24.04", "--", "bash", "-lc", "claude"]
+        );
+    }
+
+    #[test]
+    fn test_wt_args_named_window() {
+        let launcher = WslLauncher::new("Ubuntu-24.04");
+        let window = WindowConfig {
+            name: "tests".to_string(),
+            command: Some("make; bash".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            launcher.wt_args(&window, "workspace"),
+            vec![
+                "-w", "workspace", "nt", "--title", "tests", "--suppressApplicationTitle", "wsl.exe",
+                "-d", "Ubuntu-24.04", "--", "bash", "-c", "make\\; bash",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_panes_args_2x1() {
+        let launcher = WslLauncher::new("Ubuntu");
+        let windows = vec![
+            WindowConfig { name: "a".to_string(), command: Some("htop".to_string()), ..Default::default() },
+            WindowConfig { name: "b".to_string(), command: Some("bash".to_string()), ..Default::default() },
+        ];
+        assert_eq!(
+            launcher.panes_args(&windows, 2, "new"),
+            vec![
+                "-w", "new", "nt", "--title", "a", "--suppressApplicationTitle",
+                "wsl.exe", "-d", "Ubuntu", "--", "bash", "-c", "htop",
+                ";", "split-pane", "-V", "--size", "0.5000",
+                "--title", "b", "--suppressApplicationTitle",
+                "wsl.exe", "-d", "Ubuntu", "--", "bash", "-c", "bash",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_wt_args_profile() {
+        let launcher = WslLauncher::new("Ubuntu-24.04");
+        let window = WindowConfig {
+            name: "prod".to_string(),
+            wt_profile: Some("Ubuntu-24.04".to_string()),
+            ..Default::default()
+        };
+        let args = launcher.wt_args(&window, "new");
+        assert_eq!(&args[..7], &["-w", "new", "nt", "-p", "Ubuntu-24.04", "--title", "prod"]);
+    }
+
+    #[test]
+    fn test_wt_args_colors() {
+        let launcher = WslLauncher::new("Ubuntu-24.04");
+        let window = WindowConfig {
+            name: "prod".to_string(),
+            color_scheme: Some("Red Sands".to_string()),
+            tab_color: Some("#ff0000".to_string()),
+            ..Default::default()
+        };
+        let args = launcher.wt_args(&window, "new");
+        assert_eq!(
+            &args[..9],
+            &["-w", "new", "nt", "--colorScheme", "Red Sands", "--tabColor", "#ff0000", "--title", "prod"]
+        );
+    }
+
+    #[test]
+    fn test_wt_args_allow_app_title() {
+        let launcher = WslLauncher::new("Ubuntu-24.04");
+        let window = WindowConfig {
+            name: "free".to_string(),
+            suppress_app_title: false,
+            ..Default::default()
+        };
+        let args = launcher.wt_args(&window, "new");
+        assert!(!args.contains(&"--suppressApplicationTitle".to_string()));
+    }
+
+    #[test]
+    fn test_wt_args_window_flags() {
+        let launcher = WslLauncher::new("Ubuntu-24.04");
+        let window = WindowConfig {
+            name: "main".to_string(),
+            wt_maximized: true,
+            wt_focus: true,
+            ..Default::default()
+        };
+        let args = launcher.wt_args(&window, "new");
+        assert_eq!(&args[..5], &["-w", "new", "-M", "--focus", "nt"]);
+    }
+
+    #[test]
+    fn test_profile_commandline() {
+        let launcher = WslLauncher::new("Ubuntu-24.04");
+        let window = WindowConfig {
+            name: "dev".to_string(),
+            commands: vec!["make".to_string(), "bash".to_string()],
+            continue_on_error: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            launcher.profile_commandline(&window),
+            "wsl.exe -d Ubuntu-24.04 -- bash -c \"make; bash\""
+        );
+    }
+
+    #[test]
+    fn test_wt_args_font_uses_generated_profile() {
+        let launcher = WslLauncher::new("Ubuntu-24.04");
+        let window = WindowConfig {
+            name: "monitor".to_string(),
+            font: Some(crate::config::FontConfig { face: None, size: Some(8.0) }),
+            ..Default::default()
+        };
+        let args = launcher.wt_args(&window, "new");
+        assert_eq!(&args[..5], &["-w", "new", "nt", "-p", "wsl-multi-launcher: monitor"]);
+    }
+
+    #[test]
+    fn test_wt_args_old_version() {
+        let launcher = WslLauncher::new("Ubuntu").with_wt_version(Some(WtVersion::new(1, 5)));
+        let window = WindowConfig {
+            name: "a".to_string(),
+            tab_color: Some("#fff".to_string()),
+            wt_maximized: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            launcher.wt_args(&window, "wcl-config-1"),
+            vec!["nt", "--title", "a", "wsl.exe", "-d", "Ubuntu", "--", "bash", "-c", "bash"]
+        );
+    }
+
+    #[test]
+    fn test_build_wsl_command_marks_session() {
+        let launcher = WslLauncher::new("Ubuntu").with_session("work");
+        let window = WindowConfig {
+            name: "claude 1".to_string(),
+            command: Some("claude".to_string()),
+            ..Default::default()
+        };
+        let command = launcher.build_wsl_command(&window);
+        assert!(command.starts_with("export WSL_MULTI_LAUNCHER_WINDOW='work/claude 1'; "));
+        assert!(command.contains("{ claude; }; __wml_rc=$?;"));
+    }
+
+    #[test]
+    fn test_terminate_script() {
+        let script = terminate_script("work/claude-1", std::time::Duration::from_secs(5));
+        assert!(script.contains("grep -qzxF WSL_MULTI_LAUNCHER_WINDOW=work/claude-1 \"$p/environ\""));
+        assert!(script.contains("grep -qzE '^(TMUX|ZELLIJ)=' \"$p/environ\" 2>/dev/null && continue;"));
+        assert!(script.contains("in 'tmux: server'*) continue;;"));
+        assert!(script.contains("kill -TERM $p"));
+        assert!(script.contains("$(seq 50)"));
+    }
+
+    #[test]
+    fn test_parse_cpu_ticks() {
+        let output = "work/claude-1\t120\nwork/claude-1\t30\nwork/logs\t7\nbroken\n";
+        let ticks = parse_cpu_ticks(output);
+        assert_eq!(ticks["work/claude-1"], 150);
+        assert_eq!(ticks["work/logs"], 7);
+        assert_eq!(ticks.len(), 2);
+    }
+}
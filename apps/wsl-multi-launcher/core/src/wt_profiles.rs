@@ -0,0 +1,104 @@
+use serde_json::{Value, json};
+
+use crate::config::Config;
+use crate::error::{LauncherError, Result};
+use crate::wsl::WslLauncher;
+
+/// Prefix for generated profile names, so they group together in the WT dropdown
+const PROFILE_PREFIX: &str = "wsl-multi-launcher";
+
+/// Fragment directory name under `%LOCALAPPDATA%\Microsoft\Windows Terminal\Fragments`
+pub const FRAGMENT_APP_NAME: &str = "wsl-multi-launcher";
+
+/// Name of the generated profile for a window
+pub fn profile_name(window_name: &str) -> String {
+    format!("{}: {}", PROFILE_PREFIX, window_name)
+}
+
+/// Build a Windows Terminal JSON fragment with one profile per configured window
+///
+/// Fragments are loaded from
+/// `%LOCALAPPDATA%\Microsoft\Windows Terminal\Fragments\<app>\*.json`, which
+/// avoids editing the user's settings.json directly.
+pub fn build_fragment(config: &Config) -> Value {
+    let launcher = WslLauncher::new(&config.wsl_distribution).with_wsl_cd(config.use_wsl_cd);
+
+    let profiles: Vec<Value> = config
+        .windows
+        .iter()
+        .map(|window| {
+            let mut profile = json!({
+                "name": profile_name(&window.name),
+                "commandline": launcher.profile_commandline(window),
+                "tabTitle": window.name,
+                "suppressApplicationTitle": window.suppress_app_title,
+                "hidden": false,
+            });
+            if let Some(dir) = window.native_working_dir() {
+                profile["startingDirectory"] = json!(dir);
+            }
+            if let Some(ref scheme) = window.color_scheme {
+                profile["colorScheme"] = json!(scheme);
+            }
+            if let Some(ref color) = window.tab_color {
+                profile["tabColor"] = json!(color);
+            }
+            if let Some(ref font) = window.font {
+                let mut font_json = json!({});
+                if let Some(ref face) = font.face {
+                    font_json["face"] = json!(face);
+                }
+                if let Some(size) = font.size {
+                    font_json["size"] = json!(size);
+                }
+                profile["font"] = font_json;
+            }
+            profile
+        })
+        .collect();
+
+    json!({ "profiles": profiles })
+}
+
+/// Render the fragment as pretty-printed JSON
+pub fn render_fragment(config: &Config) -> Result<String> {
+    serde_json::to_string_pretty(&build_fragment(config))
+        .map_err(|source| LauncherError::Serialize { what: "Windows Terminal profiles", source })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{FontConfig, LayoutConfig, WindowConfig};
+
+    #[test]
+    fn test_build_fragment() {
+        let config = Config {
+            wsl_distribution: "Ubuntu".to_string(),
+            layout: LayoutConfig { grid: "1x2".to_string(), ..Default::default() },
+            windows: vec![
+                WindowConfig {
+                    name: "claude-1".to_string(),
+                    command: Some("claude".to_string()),
+                    tab_color: Some("#ff8800".to_string()),
+                    ..Default::default()
+                },
+                WindowConfig {
+                    name: "shell".to_string(),
+                    font: Some(FontConfig { face: None, size: Some(9.0) }),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let fragment = build_fragment(&config);
+        let profiles = fragment["profiles"].as_array().unwrap();
+        assert_eq!(profiles.len(), 2);
+        assert_eq!(profiles[0]["name"], "wsl-multi-launcher: claude-1");
+        assert_eq!(profiles[0]["commandline"], "wsl.exe -d Ubuntu -- bash -c claude");
+        assert_eq!(profiles[0]["tabTitle"], "claude-1");
+        assert_eq!(profiles[0]["tabColor"], "#ff8800");
+        assert!(profiles[1].get("tabColor").is_none());
+        assert_eq!(profiles[1]["font"], json!({ "size": 9.0 }));
+    }
+}
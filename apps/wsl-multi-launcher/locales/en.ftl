@@ -0,0 +1,71 @@
+# Messages of the command line output (English)
+#
+# Every message here needs a translation in ja.ftl. Status words such as
+# OK / FAILED, logs and JSON output stay in English.
+
+## init
+
+init-exists = Config file '{ $path }' already exists. Use --force to overwrite.
+init-confirm-overwrite = Overwrite { $path } with a new config?
+init-created = Created config file: { $path }
+init-next-steps = Next steps:
+init-step-edit = 1. Edit { $path } to customize your windows
+init-step-displays = 2. Run 'wsl-multi-launcher displays' to see available displays
+init-step-validate = 3. Run 'wsl-multi-launcher validate' to check your config
+init-step-launch = 4. Run 'wsl-multi-launcher launch' to start!
+
+## Config
+
+config-not-found =
+    Config file '{ $path }' not found.
+
+    Hint: Run 'wsl-multi-launcher init' to create a new config file,
+    or use '-c <path>' to specify a different config file.
+config-valid = Configuration is valid!
+config-invalid = Configuration error!
+config-init-hint = Hint: Run 'wsl-multi-launcher init' to create a new config file.
+
+## edit
+
+edit-unchanged = { $path } wasn't changed.
+
+## Session
+
+session-no-windows = No windows recorded for session '{ $session }'.
+
+## launch
+
+launch-windows = Launching { $count } windows in { $terminal }...
+launch-elevated = Note: { $count } window(s) run as administrator; confirm the UAC prompt for each.
+launch-elevated-arrange = Elevated windows can only be arranged if this tool is elevated too.
+launch-arranging = Arranging windows...
+launch-done = Done! { $count } windows launched.
+launch-tabs = Launching { $count } tabs...
+launch-tabs-done = Done! { $count } tabs launched.
+launch-panes-done = Done! { $count } panes launched.
+launch-partial = Finished with problems: { $failed } of { $count } windows had problems; { $running } are running.
+
+## arrange
+
+arrange-windows = Arranging { $count } windows...
+arrange-done = Window arrangement complete.
+
+## stop
+
+stop-windows = Stopping { $count } window(s)...
+stop-done = Done! { $closed } closed, { $open } still open.
+stop-confirm-all = Close all { $count } window(s) of session '{ $session }'?
+cleanup-confirm = Remove the worktrees of { $names } whose branches are merged?
+
+## reconcile
+
+reconcile-confirm-close = Close { $count } window(s) that aren't in the config ({ $names })?
+
+## doctor
+
+doctor-summary = { $passed } passed, { $warned } warning(s), { $failed } failed
+
+## Confirmation
+
+confirm-no-terminal = { $question } Pass --yes to confirm without a terminal.
+confirm-cancelled = Cancelled.
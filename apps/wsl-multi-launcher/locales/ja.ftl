@@ -0,0 +1,71 @@
+# コマンドラインの出力メッセージ（日本語）
+#
+# en.ftl のメッセージと同じIDをすべて訳します。OK / FAILED などの状態、
+# ログ、JSON出力は英語のままです。
+
+## init
+
+init-exists = 設定ファイル '{ $path }' はすでにあります。上書きするには --force を指定してください。
+init-confirm-overwrite = { $path } を新しい設定で上書きしますか？
+init-created = 設定ファイルを作成しました: { $path }
+init-next-steps = 次の手順:
+init-step-edit = 1. { $path } を編集してウィンドウを設定する
+init-step-displays = 2. 'wsl-multi-launcher displays' で使えるディスプレイを確認する
+init-step-validate = 3. 'wsl-multi-launcher validate' で設定を検証する
+init-step-launch = 4. 'wsl-multi-launcher launch' で起動する
+
+## Config
+
+config-not-found =
+    設定ファイル '{ $path }' が見つかりません。
+
+    ヒント: 'wsl-multi-launcher init' で新しい設定ファイルを作成するか、
+    '-c <パス>' で別の設定ファイルを指定してください。
+config-valid = 設定は正しいです！
+config-invalid = 設定にエラーがあります！
+config-init-hint = ヒント: 'wsl-multi-launcher init' で新しい設定ファイルを作成できます。
+
+## edit
+
+edit-unchanged = { $path } は変更されていません。
+
+## Session
+
+session-no-windows = セッション '{ $session }' に記録されたウィンドウはありません。
+
+## launch
+
+launch-windows = { $terminal } で { $count } 個のウィンドウを起動しています...
+launch-elevated = 注意: { $count } 個のウィンドウを管理者として実行します。それぞれのUACプロンプトで許可してください。
+launch-elevated-arrange = 管理者のウィンドウは、このツールも管理者として実行しているときだけ配置できます。
+launch-arranging = ウィンドウを配置しています...
+launch-done = 完了！ { $count } 個のウィンドウを起動しました。
+launch-tabs = { $count } 個のタブを起動しています...
+launch-tabs-done = 完了！ { $count } 個のタブを起動しました。
+launch-panes-done = 完了！ { $count } 個のペインを起動しました。
+launch-partial = 問題があります：{ $count } 個のうち { $failed } 個のウィンドウで問題が起きました（{ $running } 個が起動中）。
+
+## arrange
+
+arrange-windows = { $count } 個のウィンドウを配置しています...
+arrange-done = ウィンドウの配置が完了しました。
+
+## stop
+
+stop-windows = { $count } 個のウィンドウを停止しています...
+stop-done = 完了！ { $closed } 個を閉じ、{ $open } 個がまだ開いています。
+stop-confirm-all = セッション '{ $session }' のウィンドウ { $count } 個をすべて閉じますか？
+cleanup-confirm = { $names } のworktreeのうち、ブランチがマージ済みのものを削除しますか？
+
+## reconcile
+
+reconcile-confirm-close = 設定にないウィンドウ { $count } 個（{ $names }）を閉じますか？
+
+## doctor
+
+doctor-summary = 成功 { $passed } 件、警告 { $warned } 件、失敗 { $failed } 件
+
+## Confirmation
+
+confirm-no-terminal = { $question } 端末がない場合は --yes で確認を省略してください。
+confirm-cancelled = 中止しました。
@@ -0,0 +1,49 @@
+# Ask a window to close (WM_CLOSE) and wait for it to go away
+# Usage: close-window.ps1 -Handle <hwnd> [-TimeoutMs <ms>]
+#
+# Prints "closed" once the window is gone, or "open" if it is still there
+# after the timeout (e.g. a "close all tabs?" prompt is showing).
+
+param(
+    [Parameter(Mandatory=$true)]
+    [long]$Handle,
+
+    [Parameter(Mandatory=$false)]
+    [int]$TimeoutMs = 3000
+)
+
+Add-Type @"
+using System;
+using System.Runtime.InteropServices;
+
+public class CloseHelper {
+    [DllImport("user32.dll", SetLastError = true)]
+    public static extern bool PostMessage(IntPtr hWnd, uint Msg, IntPtr wParam, IntPtr lParam);
+
+    [DllImport("user32.dll")]
+    public static extern bool IsWindow(IntPtr hWnd);
+
+    public const uint WM_CLOSE = 0x0010;
+}
+"@
+
+$hwnd = [IntPtr]$Handle
+
+if (-not [CloseHelper]::IsWindow($hwnd)) {
+    Write-Output "closed"
+    exit 0
+}
+
+[CloseHelper]::PostMessage($hwnd, [CloseHelper]::WM_CLOSE, [IntPtr]::Zero, [IntPtr]::Zero) | Out-Null
+
+$deadline = (Get-Date).AddMilliseconds($TimeoutMs)
+while ((Get-Date) -lt $deadline) {
+    if (-not [CloseHelper]::IsWindow($hwnd)) {
+        Write-Output "closed"
+        exit 0
+    }
+    Start-Sleep -Milliseconds 100
+}
+
+Write-Output "open"
+exit 0
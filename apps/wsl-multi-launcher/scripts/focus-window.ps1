@@ -0,0 +1,75 @@
+# Bring a window to the foreground by title
+# Usage: focus-window.ps1 -Title <title>
+
+param(
+    [Parameter(Mandatory=$true)]
+    [string]$Title
+)
+
+Add-Type @"
+using System;
+using System.Runtime.InteropServices;
+
+public class FocusHelper {
+    [DllImport("user32.dll")]
+    [return: MarshalAs(UnmanagedType.Bool)]
+    public static extern bool EnumWindows(EnumWindowsProc lpEnumFunc, IntPtr lParam);
+
+    [DllImport("user32.dll", CharSet = CharSet.Unicode)]
+    public static extern int GetWindowText(IntPtr hWnd, System.Text.StringBuilder lpString, int nMaxCount);
+
+    [DllImport("user32.dll")]
+    public static extern bool IsWindowVisible(IntPtr hWnd);
+
+    [DllImport("user32.dll")]
+    public static extern bool IsIconic(IntPtr hWnd);
+
+    [DllImport("user32.dll", SetLastError = true)]
+    public static extern bool ShowWindow(IntPtr hWnd, int nCmdShow);
+
+    [DllImport("user32.dll")]
+    public static extern bool SetForegroundWindow(IntPtr hWnd);
+
+    public delegate bool EnumWindowsProc(IntPtr hWnd, IntPtr lParam);
+
+    public const int SW_RESTORE = 9;
+}
+"@
+
+$script:hwnd = [IntPtr]::Zero
+
+# Exact title match (or the title followed by watch's status) so "claude-1"
+# doesn't pick up "claude-10"
+$callback = [FocusHelper+EnumWindowsProc]{
+    param([IntPtr]$hWnd, [IntPtr]$lParam)
+
+    if ([FocusHelper]::IsWindowVisible($hWnd)) {
+        $sb = New-Object System.Text.StringBuilder 256
+        [FocusHelper]::GetWindowText($hWnd, $sb, $sb.Capacity) | Out-Null
+        $text = $sb.ToString()
+        if ($text -eq $Title -or $text.StartsWith("$Title ")) {
+            $script:hwnd = $hWnd
+            return $false  # Stop enumeration
+        }
+    }
+    return $true  # Continue enumeration
+}
+
+[FocusHelper]::EnumWindows($callback, [IntPtr]::Zero) | Out-Null
+
+if ($script:hwnd -eq [IntPtr]::Zero) {
+    Write-Error "Window not found: $Title"
+    exit 1
+}
+
+if ([FocusHelper]::IsIconic($script:hwnd)) {
+    [FocusHelper]::ShowWindow($script:hwnd, [FocusHelper]::SW_RESTORE) | Out-Null
+}
+
+if ([FocusHelper]::SetForegroundWindow($script:hwnd)) {
+    Write-Output "Focused"
+    exit 0
+} else {
+    Write-Error "Failed to focus window"
+    exit 1
+}
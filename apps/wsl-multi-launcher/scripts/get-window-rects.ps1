@@ -0,0 +1,66 @@
+# Get the current rectangle, title and owner of windows that still exist
+# Returns JSON array of { Handle, X, Y, Width, Height, Visible, Title, ProcessId }
+# Usage: get-window-rects.ps1 -Handles <hwnd,hwnd,...>
+#        Closed windows are left out; hidden windows are included.
+
+param(
+    [Parameter(Mandatory=$true)]
+    [string]$Handles
+)
+
+Add-Type @"
+using System;
+using System.Runtime.InteropServices;
+using System.Text;
+
+public class RectHelper {
+    [StructLayout(LayoutKind.Sequential)]
+    public struct RECT {
+        public int Left;
+        public int Top;
+        public int Right;
+        public int Bottom;
+    }
+
+    [DllImport("user32.dll")]
+    public static extern bool IsWindow(IntPtr hWnd);
+
+    [DllImport("user32.dll")]
+    public static extern bool IsWindowVisible(IntPtr hWnd);
+
+    [DllImport("user32.dll")]
+    public static extern bool GetWindowRect(IntPtr hWnd, out RECT lpRect);
+
+    [DllImport("user32.dll", CharSet = CharSet.Unicode)]
+    public static extern int GetWindowText(IntPtr hWnd, StringBuilder lpString, int nMaxCount);
+
+    [DllImport("user32.dll")]
+    public static extern uint GetWindowThreadProcessId(IntPtr hWnd, out uint lpdwProcessId);
+}
+"@
+
+$result = @()
+foreach ($value in $Handles.Split(",")) {
+    if (-not $value.Trim()) { continue }
+    $hwnd = [IntPtr][long]$value
+    if (-not [RectHelper]::IsWindow($hwnd)) { continue }
+
+    $rect = New-Object RectHelper+RECT
+    [RectHelper]::GetWindowRect($hwnd, [ref]$rect) | Out-Null
+    $title = New-Object System.Text.StringBuilder 512
+    [RectHelper]::GetWindowText($hwnd, $title, $title.Capacity) | Out-Null
+    $processId = [uint32]0
+    [RectHelper]::GetWindowThreadProcessId($hwnd, [ref]$processId) | Out-Null
+    $result += [PSCustomObject]@{
+        Handle  = [long]$value
+        X       = $rect.Left
+        Y       = $rect.Top
+        Width   = $rect.Right - $rect.Left
+        Height  = $rect.Bottom - $rect.Top
+        Visible = [RectHelper]::IsWindowVisible($hwnd)
+        Title   = $title.ToString()
+        ProcessId = $processId
+    }
+}
+
+ConvertTo-Json -Compress -InputObject @($result)
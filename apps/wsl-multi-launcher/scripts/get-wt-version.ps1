@@ -0,0 +1,10 @@
+# Get the installed Windows Terminal version
+# Returns the package version (e.g. 1.21.2361.0), or nothing if not installed
+
+$package = Get-AppxPackage -Name "Microsoft.WindowsTerminal*" |
+    Sort-Object -Property Version -Descending |
+    Select-Object -First 1
+
+if ($package) {
+    Write-Output $package.Version
+}
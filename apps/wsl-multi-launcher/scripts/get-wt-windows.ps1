@@ -1,5 +1,17 @@
 # Get all Windows Terminal window handles
 # Returns JSON array of window handles
+# Usage: get-wt-windows.ps1 [-ProcessName <name>] [-ClassName <substring>]
+#        Other terminals are matched by passing their process name
+#        (e.g. alacritty, wezterm-gui) and an empty class name, or an empty
+#        process name and a class (e.g. ConsoleWindowClass)
+
+param(
+    [Parameter(Mandatory=$false)]
+    [string]$ProcessName = "WindowsTerminal",
+
+    [Parameter(Mandatory=$false)]
+    [string]$ClassName = "CASCADIA"
+)
 
 Add-Type @"
 using System;
@@ -25,7 +37,7 @@ public class WTWindowFinder {
 
     public delegate bool EnumWindowsProc(IntPtr hWnd, IntPtr lParam);
 
-    public static List<IntPtr> GetWindowsTerminalHandles() {
+    public static List<IntPtr> GetWindowsTerminalHandles(string processName, string classFilter) {
         var handles = new List<IntPtr>();
 
         EnumWindows((hWnd, lParam) => {
@@ -36,11 +48,11 @@ public class WTWindowFinder {
 
             try {
                 var proc = System.Diagnostics.Process.GetProcessById((int)pid);
-                if (proc.ProcessName == "WindowsTerminal") {
+                if (processName == "" || proc.ProcessName == processName) {
                     var className = new StringBuilder(256);
                     GetClassName(hWnd, className, className.Capacity);
                     // Windows Terminal main windows have class CASCADIA_HOSTING_WINDOW_CLASS
-                    if (className.ToString().Contains("CASCADIA")) {
+                    if (className.ToString().Contains(classFilter)) {
                         handles.Add(hWnd);
                     }
                 }
@@ -54,6 +66,6 @@ public class WTWindowFinder {
 }
 "@
 
-$handles = [WTWindowFinder]::GetWindowsTerminalHandles()
+$handles = [WTWindowFinder]::GetWindowsTerminalHandles($ProcessName, $ClassName)
 $result = $handles | ForEach-Object { $_.ToInt64() }
 $result | ConvertTo-Json -Compress
@@ -0,0 +1,54 @@
+# Run the window scripts of one wsl-multi-launcher command in a single
+# PowerShell process, so each call doesn't pay for starting PowerShell
+# Usage: host.ps1 (requests on stdin, answers on stdout)
+#
+# Prints "ready", then reads one JSON request per line:
+#   {"Script":"C:\...\move-window.ps1","Params":{"Handle":"1234","X":"0",...}}
+# and answers each with one JSON line:
+#   {"Ok":true,"Output":"<what the script printed>","Error":"<its errors>"}
+# Exits when stdin is closed.
+
+$utf8 = New-Object System.Text.UTF8Encoding $false
+[Console]::InputEncoding = $utf8
+[Console]::OutputEncoding = $utf8
+
+[Console]::Out.WriteLine("ready")
+[Console]::Out.Flush()
+
+while ($null -ne ($line = [Console]::In.ReadLine())) {
+    $request = $line | ConvertFrom-Json
+    $params = @{}
+    if ($request.Params) {
+        foreach ($param in $request.Params.PSObject.Properties) {
+            $params[$param.Name] = $param.Value
+        }
+    }
+
+    $ok = $true
+    $output = @()
+    $errors = @()
+    $global:LASTEXITCODE = 0
+    try {
+        foreach ($item in (& $request.Script @params 2>&1)) {
+            if ($item -is [System.Management.Automation.ErrorRecord]) {
+                $errors += $item.ToString()
+            } else {
+                $output += $item
+            }
+        }
+    } catch {
+        $errors += $_.ToString()
+        $ok = $false
+    }
+    if ($LASTEXITCODE -ne 0) {
+        $ok = $false
+    }
+
+    $answer = @{
+        Ok = $ok
+        Output = ($output | Out-String)
+        Error = ($errors -join "`n")
+    }
+    [Console]::Out.WriteLine(($answer | ConvertTo-Json -Compress))
+    [Console]::Out.Flush()
+}
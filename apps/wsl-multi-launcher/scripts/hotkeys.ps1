@@ -0,0 +1,56 @@
+# Register global hotkeys and report presses until killed
+# Usage: hotkeys.ps1 -Keys "<id>:<modifiers>:<vk>,<id>:<modifiers>:<vk>,..."
+#
+# Modifiers and virtual-key codes are the RegisterHotKey values (decimal).
+# Prints "ready" once registration is done, then the id of every hotkey
+# pressed, one per line. A hotkey that can't be registered (usually because
+# another program owns it) is reported as "failed <id>" and skipped.
+
+param(
+    [Parameter(Mandatory=$true)]
+    [string]$Keys
+)
+
+Add-Type @"
+using System;
+using System.Runtime.InteropServices;
+
+public class HotkeyHelper {
+    [DllImport("user32.dll", SetLastError = true)]
+    public static extern bool RegisterHotKey(IntPtr hWnd, int id, uint fsModifiers, uint vk);
+
+    [DllImport("user32.dll")]
+    public static extern int GetMessage(out MSG lpMsg, IntPtr hWnd, uint wMsgFilterMin, uint wMsgFilterMax);
+
+    [StructLayout(LayoutKind.Sequential)]
+    public struct MSG {
+        public IntPtr hwnd;
+        public uint message;
+        public IntPtr wParam;
+        public IntPtr lParam;
+        public uint time;
+        public int ptX;
+        public int ptY;
+    }
+
+    public const uint WM_HOTKEY = 0x0312;
+}
+"@
+
+foreach ($key in $Keys.Split(",")) {
+    $id, $modifiers, $vk = $key.Split(":")
+    if (-not [HotkeyHelper]::RegisterHotKey([IntPtr]::Zero, [int]$id, [uint32]$modifiers, [uint32]$vk)) {
+        [Console]::Out.WriteLine("failed $id")
+    }
+}
+[Console]::Out.WriteLine("ready")
+[Console]::Out.Flush()
+
+# Hotkeys registered without a window are posted to this thread's queue
+$msg = New-Object HotkeyHelper+MSG
+while ([HotkeyHelper]::GetMessage([ref]$msg, [IntPtr]::Zero, 0, 0) -gt 0) {
+    if ($msg.message -eq [HotkeyHelper]::WM_HOTKEY) {
+        [Console]::Out.WriteLine([int]$msg.wParam)
+        [Console]::Out.Flush()
+    }
+}
@@ -0,0 +1,113 @@
+# Move and resize several windows in one call
+# Usage: move-windows.ps1 -Moves "<target>,<x>,<y>,<w>,<h>;<target>,<x>,<y>,<w>,<h>;..."
+#
+# <target> is a window handle, or "title:" followed by the window title
+# (UTF-8, hex encoded), matched as by move-window.ps1 -Title. Titles are
+# looked up in a single EnumWindows pass.
+# Prints a JSON array with the result of each move, in order:
+#   [{"Ok":true,"Error":""},{"Ok":false,"Error":"Window not found"}]
+
+param(
+    [Parameter(Mandatory=$true)]
+    [string]$Moves
+)
+
+Add-Type @"
+using System;
+using System.Collections.Generic;
+using System.Runtime.InteropServices;
+using System.Text;
+
+public class BatchMoveHelper {
+    [DllImport("user32.dll", SetLastError = true)]
+    public static extern bool SetWindowPos(IntPtr hWnd, IntPtr hWndInsertAfter, int X, int Y, int cx, int cy, uint uFlags);
+
+    [DllImport("user32.dll", SetLastError = true)]
+    public static extern bool ShowWindow(IntPtr hWnd, int nCmdShow);
+
+    [DllImport("user32.dll")]
+    public static extern bool IsWindow(IntPtr hWnd);
+
+    [DllImport("user32.dll")]
+    public static extern bool IsWindowVisible(IntPtr hWnd);
+
+    [DllImport("user32.dll", CharSet = CharSet.Unicode)]
+    public static extern int GetWindowText(IntPtr hWnd, StringBuilder lpString, int nMaxCount);
+
+    [DllImport("user32.dll")]
+    [return: MarshalAs(UnmanagedType.Bool)]
+    public static extern bool EnumWindows(EnumWindowsProc lpEnumFunc, IntPtr lParam);
+
+    public delegate bool EnumWindowsProc(IntPtr hWnd, IntPtr lParam);
+
+    public const uint SWP_NOZORDER = 0x0004;
+    public const uint SWP_SHOWWINDOW = 0x0040;
+    public const int SW_RESTORE = 9;
+
+    // Handle and title of every visible top-level window
+    public static List<KeyValuePair<IntPtr, string>> VisibleWindows() {
+        var windows = new List<KeyValuePair<IntPtr, string>>();
+        EnumWindows((hWnd, lParam) => {
+            if (IsWindowVisible(hWnd)) {
+                var sb = new StringBuilder(256);
+                GetWindowText(hWnd, sb, sb.Capacity);
+                windows.Add(new KeyValuePair<IntPtr, string>(hWnd, sb.ToString()));
+            }
+            return true;
+        }, IntPtr.Zero);
+        return windows;
+    }
+}
+"@
+
+$entries = $Moves.Split(";", [StringSplitOptions]::RemoveEmptyEntries)
+$visible = $null
+if ($entries | Where-Object { $_.StartsWith("title:") }) {
+    $visible = [BatchMoveHelper]::VisibleWindows()
+}
+
+$results = foreach ($entry in $entries) {
+    $fields = $entry.Split(",")
+    $target = $fields[0]
+    $hwnd = [IntPtr]::Zero
+
+    if ($target.StartsWith("title:")) {
+        $hex = $target.Substring(6)
+        $bytes = [byte[]]::new($hex.Length / 2)
+        for ($i = 0; $i -lt $bytes.Length; $i++) {
+            $bytes[$i] = [Convert]::ToByte($hex.Substring($i * 2, 2), 16)
+        }
+        $title = [Text.Encoding]::UTF8.GetString($bytes)
+        foreach ($window in $visible) {
+            if ($window.Value -like "*$title*") {
+                $hwnd = $window.Key
+                break
+            }
+        }
+    } elseif ([BatchMoveHelper]::IsWindow([IntPtr][long]$target)) {
+        $hwnd = [IntPtr][long]$target
+    }
+
+    if ($hwnd -eq [IntPtr]::Zero) {
+        @{ Ok = $false; Error = "Window not found" }
+        continue
+    }
+
+    [BatchMoveHelper]::ShowWindow($hwnd, [BatchMoveHelper]::SW_RESTORE) | Out-Null
+    $moved = [BatchMoveHelper]::SetWindowPos(
+        $hwnd,
+        [IntPtr]::Zero,
+        [int]$fields[1],
+        [int]$fields[2],
+        [int]$fields[3],
+        [int]$fields[4],
+        [BatchMoveHelper]::SWP_NOZORDER -bor [BatchMoveHelper]::SWP_SHOWWINDOW
+    )
+    if ($moved) {
+        @{ Ok = $true; Error = "" }
+    } else {
+        @{ Ok = $false; Error = "Failed to move window" }
+    }
+}
+
+ConvertTo-Json -InputObject @($results) -Compress
@@ -0,0 +1,33 @@
+# Show a Windows toast notification
+# Usage: notify.ps1 -Title <title> -Message <message>
+#
+# The toast is raised under PowerShell's app id, so it needs no app
+# registration of its own. Prints "notified".
+
+param(
+    [Parameter(Mandatory=$true)]
+    [string]$Title,
+
+    [Parameter(Mandatory=$true)]
+    [string]$Message
+)
+
+$null = [Windows.UI.Notifications.ToastNotificationManager, Windows.UI.Notifications, ContentType = WindowsRuntime]
+$null = [Windows.Data.Xml.Dom.XmlDocument, Windows.Data.Xml.Dom.XmlDocument, ContentType = WindowsRuntime]
+
+$appId = '{1AC14E77-02E7-4E5D-B744-2EB1AE5198B7}\WindowsPowerShell\v1.0\powershell.exe'
+$title = [Security.SecurityElement]::Escape($Title)
+$message = [Security.SecurityElement]::Escape($Message)
+
+$xml = New-Object Windows.Data.Xml.Dom.XmlDocument
+$xml.LoadXml("<toast><visual><binding template=""ToastGeneric""><text>$title</text><text>$message</text></binding></visual></toast>")
+
+try {
+    $toast = [Windows.UI.Notifications.ToastNotification]::new($xml)
+    [Windows.UI.Notifications.ToastNotificationManager]::CreateToastNotifier($appId).Show($toast)
+    Write-Output "notified"
+    exit 0
+} catch {
+    Write-Error "Failed to show notification: $_"
+    exit 1
+}
@@ -0,0 +1,126 @@
+# Manage a Windows service that keeps wsl.exe running
+# Usage: service.ps1 -Action Install -Name <name> -Arguments <args>
+#        service.ps1 -Action Uninstall|Start|Stop|Status -Name <name>
+#
+# A PowerShell script can't talk to the service manager itself, so Install
+# compiles a small service host into %ProgramData%\wsl-multi-launcher and
+# registers it. The host starts wsl.exe with the given arguments and stops
+# (with a failure exit code, so the recovery actions restart it) when
+# wsl.exe exits. WSL distributions belong to a user, so the service runs as
+# the current user; Install asks for the password.
+# Install, Uninstall, Start and Stop need an elevated PowerShell.
+# Prints "installed", "uninstalled", "started", "stopped", "not-found" or
+# the service status (e.g. "Running").
+
+param(
+    [Parameter(Mandatory=$true)]
+    [ValidateSet("Install", "Uninstall", "Start", "Stop", "Status")]
+    [string]$Action,
+
+    [Parameter(Mandatory=$true)]
+    [string]$Name,
+
+    [Parameter(Mandatory=$false)]
+    [string]$Arguments = ""
+)
+
+$ErrorActionPreference = "Stop"
+
+$service = Get-Service -Name $Name -ErrorAction SilentlyContinue
+if ($Action -eq "Status") {
+    if ($service) { Write-Output $service.Status } else { Write-Output "not-found" }
+    exit 0
+}
+if (-not $service -and $Action -ne "Install") {
+    Write-Output "not-found"
+    exit 0
+}
+
+$principal = New-Object Security.Principal.WindowsPrincipal([Security.Principal.WindowsIdentity]::GetCurrent())
+if (-not $principal.IsInRole([Security.Principal.WindowsBuiltInRole]::Administrator)) {
+    [Console]::Error.WriteLine("Managing services requires an elevated (Run as administrator) terminal")
+    exit 1
+}
+
+$hostSource = @"
+using System;
+using System.Diagnostics;
+using System.ServiceProcess;
+
+public class WslService : ServiceBase {
+    private Process process;
+    private bool stopping;
+
+    public static void Main() {
+        ServiceBase.Run(new WslService());
+    }
+
+    // Everything after this executable's path is passed to wsl.exe
+    private static string WslArguments() {
+        string line = Environment.CommandLine.Trim();
+        int end = line.StartsWith("\"") ? line.IndexOf('"', 1) + 1 : line.IndexOf(' ');
+        return end <= 0 || end >= line.Length ? "" : line.Substring(end).Trim();
+    }
+
+    protected override void OnStart(string[] args) {
+        var info = new ProcessStartInfo(Environment.ExpandEnvironmentVariables(@"%SystemRoot%\System32\wsl.exe"), WslArguments());
+        info.UseShellExecute = false;
+        info.CreateNoWindow = true;
+        process = Process.Start(info);
+        process.EnableRaisingEvents = true;
+        process.Exited += (sender, e) => {
+            if (!stopping) {
+                ExitCode = 1;
+                Stop();
+            }
+        };
+    }
+
+    protected override void OnStop() {
+        stopping = true;
+        if (process != null && !process.HasExited) {
+            Process.Start(new ProcessStartInfo("taskkill.exe", "/PID " + process.Id + " /T /F") {
+                UseShellExecute = false, CreateNoWindow = true
+            }).WaitForExit();
+        }
+    }
+}
+"@
+
+switch ($Action) {
+    "Install" {
+        $dir = Join-Path $env:ProgramData "wsl-multi-launcher"
+        New-Item -ItemType Directory -Force -Path $dir | Out-Null
+        $exe = Join-Path $dir "$Name.exe"
+        if ($service) {
+            Stop-Service -Name $Name -ErrorAction SilentlyContinue
+            sc.exe delete $Name | Out-Null
+        }
+        Add-Type -TypeDefinition $hostSource -ReferencedAssemblies System.ServiceProcess -OutputAssembly $exe -OutputType ConsoleApplication
+
+        $credential = Get-Credential -UserName "$env:USERDOMAIN\$env:USERNAME" -Message "Account to run the $Name service as"
+        New-Service -Name $Name -BinaryPathName "`"$exe`" $Arguments" -DisplayName $Name `
+            -Description "wsl-multi-launcher watch (wsl.exe $Arguments)" -StartupType Automatic -Credential $credential | Out-Null
+        # Restart after 5s when wsl.exe dies; forget failures after a day
+        sc.exe failure $Name reset= 86400 actions= restart/5000/restart/5000/restart/60000 | Out-Null
+        # The host stops itself with a non-zero exit code rather than
+        # crashing; the actions only apply to such stops with this flag
+        sc.exe failureflag $Name 1 | Out-Null
+        Write-Output "installed"
+    }
+    "Uninstall" {
+        Stop-Service -Name $Name -ErrorAction SilentlyContinue
+        sc.exe delete $Name | Out-Null
+        $exe = Join-Path $env:ProgramData "wsl-multi-launcher\$Name.exe"
+        Remove-Item $exe -ErrorAction SilentlyContinue
+        Write-Output "uninstalled"
+    }
+    "Start" {
+        Start-Service -Name $Name
+        Write-Output "started"
+    }
+    "Stop" {
+        Stop-Service -Name $Name
+        Write-Output "stopped"
+    }
+}
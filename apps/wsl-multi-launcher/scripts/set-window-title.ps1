@@ -0,0 +1,28 @@
+# Set the title of a window by handle
+# Usage: set-window-title.ps1 -Handle <hwnd> -Title <title>
+
+param(
+    [Parameter(Mandatory=$true)]
+    [long]$Handle,
+
+    [Parameter(Mandatory=$true)]
+    [string]$Title
+)
+
+Add-Type @"
+using System;
+using System.Runtime.InteropServices;
+
+public class TitleHelper {
+    [DllImport("user32.dll", CharSet = CharSet.Unicode, SetLastError = true)]
+    public static extern bool SetWindowText(IntPtr hWnd, string lpString);
+}
+"@
+
+if ([TitleHelper]::SetWindowText([IntPtr]$Handle, $Title)) {
+    Write-Output "Title set"
+    exit 0
+} else {
+    Write-Error "Failed to set window title"
+    exit 1
+}
@@ -0,0 +1,64 @@
+# Register or unregister a command to run at Windows logon
+# Usage: startup.ps1 -Action Install -Method Shortcut|Task -Name <name> -Arguments <args>
+#        startup.ps1 -Action Remove -Name <name>
+#
+# The command is always wsl.exe with the given arguments. Shortcut puts a
+# .lnk in the user's Startup folder; Task registers a Scheduled Task with a
+# logon trigger for the current user. Remove deletes both, if present.
+# Prints "installed", "removed" or "not-found".
+
+param(
+    [Parameter(Mandatory=$true)]
+    [ValidateSet("Install", "Remove")]
+    [string]$Action,
+
+    [Parameter(Mandatory=$false)]
+    [ValidateSet("Shortcut", "Task")]
+    [string]$Method = "Shortcut",
+
+    [Parameter(Mandatory=$true)]
+    [string]$Name,
+
+    [Parameter(Mandatory=$false)]
+    [string]$Arguments = ""
+)
+
+$ErrorActionPreference = "Stop"
+
+$wsl = Join-Path $env:SystemRoot "System32\wsl.exe"
+$shortcut = Join-Path ([Environment]::GetFolderPath("Startup")) "$Name.lnk"
+
+if ($Action -eq "Install") {
+    if ($Method -eq "Shortcut") {
+        $shell = New-Object -ComObject WScript.Shell
+        $link = $shell.CreateShortcut($shortcut)
+        $link.TargetPath = $wsl
+        $link.Arguments = $Arguments
+        $link.WindowStyle = 7  # minimized
+        $link.Description = "Start $Name"
+        $link.Save()
+    } else {
+        $taskAction = New-ScheduledTaskAction -Execute $wsl -Argument $Arguments
+        $trigger = New-ScheduledTaskTrigger -AtLogOn -User $env:USERNAME
+        $settings = New-ScheduledTaskSettingsSet -AllowStartIfOnBatteries -DontStopIfGoingOnBatteries -ExecutionTimeLimit 0
+        Register-ScheduledTask -TaskName $Name -Action $taskAction -Trigger $trigger -Settings $settings -Force | Out-Null
+    }
+    Write-Output "installed"
+    exit 0
+}
+
+$removed = $false
+if (Test-Path $shortcut) {
+    Remove-Item $shortcut
+    $removed = $true
+}
+if (Get-ScheduledTask -TaskName $Name -ErrorAction SilentlyContinue) {
+    Unregister-ScheduledTask -TaskName $Name -Confirm:$false
+    $removed = $true
+}
+
+if ($removed) {
+    Write-Output "removed"
+} else {
+    Write-Output "not-found"
+}
@@ -0,0 +1,94 @@
+# Show, hide or toggle a window by handle or exact title
+# Usage: toggle-window.ps1 -Handle <hwnd> -Action Hide
+#        toggle-window.ps1 -Title <title> [-Action Toggle|Show|Hide]
+#
+# Hidden windows are included when searching by title, so a window hidden
+# earlier can be found again. Prints the resulting state ("shown"/"hidden").
+
+param(
+    [Parameter(Mandatory=$false)]
+    [long]$Handle,
+
+    [Parameter(Mandatory=$false)]
+    [string]$Title,
+
+    [Parameter(Mandatory=$false)]
+    [ValidateSet("Toggle", "Show", "Hide")]
+    [string]$Action = "Toggle"
+)
+
+Add-Type @"
+using System;
+using System.Runtime.InteropServices;
+
+public class ToggleHelper {
+    [DllImport("user32.dll")]
+    [return: MarshalAs(UnmanagedType.Bool)]
+    public static extern bool EnumWindows(EnumWindowsProc lpEnumFunc, IntPtr lParam);
+
+    [DllImport("user32.dll", CharSet = CharSet.Unicode)]
+    public static extern int GetWindowText(IntPtr hWnd, System.Text.StringBuilder lpString, int nMaxCount);
+
+    [DllImport("user32.dll")]
+    public static extern bool IsWindowVisible(IntPtr hWnd);
+
+    [DllImport("user32.dll")]
+    public static extern IntPtr GetForegroundWindow();
+
+    [DllImport("user32.dll", SetLastError = true)]
+    public static extern bool ShowWindow(IntPtr hWnd, int nCmdShow);
+
+    [DllImport("user32.dll")]
+    public static extern bool SetForegroundWindow(IntPtr hWnd);
+
+    public delegate bool EnumWindowsProc(IntPtr hWnd, IntPtr lParam);
+
+    public const int SW_HIDE = 0;
+    public const int SW_SHOW = 5;
+}
+"@
+
+$script:hwnd = [IntPtr]::Zero
+
+if ($Handle -gt 0) {
+    $script:hwnd = [IntPtr]$Handle
+} elseif ($Title) {
+    $callback = [ToggleHelper+EnumWindowsProc]{
+        param([IntPtr]$hWnd, [IntPtr]$lParam)
+
+        $sb = New-Object System.Text.StringBuilder 256
+        [ToggleHelper]::GetWindowText($hWnd, $sb, $sb.Capacity) | Out-Null
+        $text = $sb.ToString()
+        if ($text -eq $Title -or $text.StartsWith("$Title ")) {
+            $script:hwnd = $hWnd
+            return $false  # Stop enumeration
+        }
+        return $true  # Continue enumeration
+    }
+    [ToggleHelper]::EnumWindows($callback, [IntPtr]::Zero) | Out-Null
+} else {
+    Write-Error "Either -Handle or -Title must be specified"
+    exit 1
+}
+
+if ($script:hwnd -eq [IntPtr]::Zero) {
+    Write-Error "Window not found"
+    exit 1
+}
+
+if ($Action -eq "Toggle") {
+    # A visible window in the background is brought forward rather than hidden
+    $visible = [ToggleHelper]::IsWindowVisible($script:hwnd)
+    $foreground = [ToggleHelper]::GetForegroundWindow() -eq $script:hwnd
+    $Action = if ($visible -and $foreground) { "Hide" } else { "Show" }
+}
+
+if ($Action -eq "Hide") {
+    [ToggleHelper]::ShowWindow($script:hwnd, [ToggleHelper]::SW_HIDE) | Out-Null
+    Write-Output "hidden"
+} else {
+    [ToggleHelper]::ShowWindow($script:hwnd, [ToggleHelper]::SW_SHOW) | Out-Null
+    [ToggleHelper]::SetForegroundWindow($script:hwnd) | Out-Null
+    Write-Output "shown"
+}
+exit 0
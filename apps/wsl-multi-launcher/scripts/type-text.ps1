@@ -0,0 +1,58 @@
+# Type text into a window as if entered on the keyboard, then press Enter
+# Usage: type-text.ps1 -TextHex <hex> [-Handle <hwnd>]
+#
+# The text is passed hex-encoded (UTF-8) so quotes and spaces survive the
+# trip through wsl.exe and powershell.exe. With -Handle the window is
+# brought to the foreground first; without it the text goes to whichever
+# window has focus. Prints "typed".
+
+param(
+    [Parameter(Mandatory=$true)]
+    [string]$TextHex,
+
+    [Parameter(Mandatory=$false)]
+    [long]$Handle = 0
+)
+
+Add-Type -AssemblyName System.Windows.Forms
+Add-Type @"
+using System;
+using System.Runtime.InteropServices;
+
+public class TypeHelper {
+    [DllImport("user32.dll")]
+    public static extern bool IsIconic(IntPtr hWnd);
+
+    [DllImport("user32.dll")]
+    public static extern bool ShowWindow(IntPtr hWnd, int nCmdShow);
+
+    [DllImport("user32.dll")]
+    public static extern bool SetForegroundWindow(IntPtr hWnd);
+
+    public const int SW_RESTORE = 9;
+}
+"@
+
+$bytes = [byte[]]::new($TextHex.Length / 2)
+for ($i = 0; $i -lt $bytes.Length; $i++) {
+    $bytes[$i] = [Convert]::ToByte($TextHex.Substring($i * 2, 2), 16)
+}
+$text = [Text.Encoding]::UTF8.GetString($bytes)
+
+if ($Handle -ne 0) {
+    $hwnd = [IntPtr]$Handle
+    if ([TypeHelper]::IsIconic($hwnd)) {
+        [TypeHelper]::ShowWindow($hwnd, [TypeHelper]::SW_RESTORE) | Out-Null
+    }
+    if (-not [TypeHelper]::SetForegroundWindow($hwnd)) {
+        Write-Error "Failed to focus window $Handle"
+        exit 1
+    }
+    Start-Sleep -Milliseconds 100
+}
+
+# SendKeys treats these characters as key codes unless wrapped in braces
+$escaped = [regex]::Replace($text, '[+^%~(){}\[\]]', '{$0}')
+[System.Windows.Forms.SendKeys]::SendWait($escaped)
+[System.Windows.Forms.SendKeys]::SendWait("{ENTER}")
+Write-Output "typed"
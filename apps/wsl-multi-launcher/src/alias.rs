@@ -0,0 +1,210 @@
+//! Command aliases from the config's `aliases:` section
+//!
+//! Aliases are expanded before the command line is parsed, so
+//! `wsl-multi-launcher down` with `down: stop --all` runs exactly
+//! `wsl-multi-launcher stop --all`. Options given after the alias are
+//! appended to its expansion. Built-in commands always win over an alias of
+//! the same name, and an expansion isn't expanded again.
+
+use anyhow::{Context, Result};
+use clap::Command;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// Just the `aliases:` of a config, so a config that doesn't load still
+/// reports its own error from the command
+#[derive(Deserialize, Default)]
+struct Aliases {
+    #[serde(default)]
+    aliases: BTreeMap<String, String>,
+}
+
+/// Split an alias into words like a shell: whitespace separates them,
+/// quotes group them and a backslash escapes the next character
+fn split(command: &str) -> Result<Vec<String>> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut chars = command.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => words.extend(word.take()),
+            '\'' => {
+                let current = word.get_or_insert_default();
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => current.push(c),
+                        None => anyhow::bail!("Unclosed ' in '{}'", command),
+                    }
+                }
+            }
+            '"' => {
+                let current = word.get_or_insert_default();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => current.extend(chars.next()),
+                        Some(c) => current.push(c),
+                        None => anyhow::bail!("Unclosed \" in '{}'", command),
+                    }
+                }
+            }
+            '\\' => word.get_or_insert_default().extend(chars.next()),
+            c => word.get_or_insert_default().push(c),
+        }
+    }
+    words.extend(word);
+    Ok(words)
+}
+
+/// Whether a top-level option takes the next argument as its value
+fn takes_value(arg: &str, cmd: &Command) -> bool {
+    let option = match arg.strip_prefix("--") {
+        Some(long) if !long.contains('=') => cmd.get_arguments().find(|a| a.get_long() == Some(long)),
+        Some(_) => None,
+        // Only a lone short option; `-cFILE` carries its value
+        None => arg
+            .strip_prefix('-')
+            .and_then(|short| short.parse::<char>().ok())
+            .and_then(|short| cmd.get_arguments().find(|a| a.get_short() == Some(short))),
+    };
+    option.is_some_and(|a| a.get_action().takes_values())
+}
+
+/// Index of the command in `args`, after the top-level options and their
+/// values
+fn command_index(args: &[String], cmd: &Command) -> Option<usize> {
+    let mut i = 1;
+    while let Some(arg) = args.get(i) {
+        if arg == "--" {
+            return None;
+        }
+        if !arg.starts_with('-') || arg == "-" {
+            return Some(i);
+        }
+        i += if takes_value(arg, cmd) { 2 } else { 1 };
+    }
+    None
+}
+
+/// Config file given before the command (`-c` / `--config`), or the default
+fn config_path(args: &[String], end: usize, cmd: &Command) -> Option<String> {
+    let mut path = None;
+    let mut i = 1;
+    while i < end {
+        let arg = &args[i];
+        if arg == "-c" || arg == "--config" {
+            path = args.get(i + 1).cloned();
+            i += 1;
+        } else if let Some(value) = arg.strip_prefix("--config=").or_else(|| arg.strip_prefix("-c")) {
+            path = Some(value.to_string());
+        }
+        i += 1;
+    }
+    path.or_else(|| {
+        let config = cmd.get_arguments().find(|a| a.get_id() == "config")?;
+        Some(config.get_default_values().first()?.to_string_lossy().into_owned())
+    })
+}
+
+/// `args` with an alias in the command position replaced by its words
+fn expand(mut args: Vec<String>, index: usize, aliases: &BTreeMap<String, String>, cmd: &Command) -> Result<Vec<String>> {
+    let name = &args[index];
+    if cmd.find_subcommand(name).is_some() {
+        return Ok(args);
+    }
+    let Some(command) = aliases.get(name) else {
+        return Ok(args);
+    };
+    let words = split(command).with_context(|| format!("Invalid alias '{}'", name))?;
+    args.splice(index..=index, words);
+    Ok(args)
+}
+
+/// The command line with a config alias expanded, ready for `cmd` to parse
+pub fn resolve(args: Vec<String>, mut cmd: Command) -> Result<Vec<String>> {
+    cmd.build();
+    let Some(index) = command_index(&args, &cmd) else {
+        return Ok(args);
+    };
+    let Some(path) = config_path(&args, index, &cmd) else {
+        return Ok(args);
+    };
+    let aliases = std::fs::read_to_string(&path)
+        .ok()
+        .and_then(|content| serde_yaml::from_str::<Aliases>(&content).ok())
+        .unwrap_or_default()
+        .aliases;
+    expand(args, index, &aliases, &cmd)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{Arg, ArgAction};
+
+    fn cli() -> Command {
+        let mut cmd = Command::new("demo")
+            .arg(Arg::new("config").short('c').long("config").default_value("config.yaml"))
+            .arg(Arg::new("verbose").short('v').long("verbose").action(ArgAction::Count))
+            .arg(Arg::new("session").long("session"))
+            .subcommand(Command::new("launch"))
+            .subcommand(Command::new("stop").arg(Arg::new("all").long("all").action(ArgAction::SetTrue)));
+        cmd.build();
+        cmd
+    }
+
+    fn args(line: &str) -> Vec<String> {
+        line.split(' ').map(String::from).collect()
+    }
+
+    #[test]
+    fn test_split() {
+        assert_eq!(split("stop --all").unwrap(), ["stop", "--all"]);
+        assert_eq!(split("  prompt api  'run the tests' ").unwrap(), ["prompt", "api", "run the tests"]);
+        assert_eq!(split(r#"broadcast "say \"hi\"" a\ b"#).unwrap(), ["broadcast", r#"say "hi""#, "a b"]);
+        assert_eq!(split("x ''").unwrap(), ["x", ""]);
+        assert!(split("prompt 'oops").is_err());
+        assert!(split("prompt \"oops").is_err());
+    }
+
+    #[test]
+    fn test_command_index() {
+        let cmd = cli();
+        assert_eq!(command_index(&args("demo down"), &cmd), Some(1));
+        assert_eq!(command_index(&args("demo -c work.yaml -vv --session a down"), &cmd), Some(6));
+        assert_eq!(command_index(&args("demo --config=work.yaml down"), &cmd), Some(2));
+        assert_eq!(command_index(&args("demo -v"), &cmd), None);
+        assert_eq!(command_index(&args("demo -- down"), &cmd), None);
+    }
+
+    #[test]
+    fn test_config_path() {
+        let cmd = cli();
+        let path = |line: &str| {
+            let args = args(line);
+            config_path(&args, command_index(&args, &cmd).unwrap(), &cmd)
+        };
+        assert_eq!(path("demo down").as_deref(), Some("config.yaml"));
+        assert_eq!(path("demo -c work.yaml down").as_deref(), Some("work.yaml"));
+        assert_eq!(path("demo --config=work.yaml down").as_deref(), Some("work.yaml"));
+        assert_eq!(path("demo -cwork.yaml down").as_deref(), Some("work.yaml"));
+    }
+
+    #[test]
+    fn test_expand() {
+        let cmd = cli();
+        let aliases: BTreeMap<String, String> =
+            [("down", "stop --all"), ("launch", "stop"), ("up", "launch")].map(|(k, v)| (k.to_string(), v.to_string())).into();
+        let expand = |line: &str| {
+            let args = args(line);
+            let index = command_index(&args, &cmd).unwrap();
+            expand(args, index, &aliases, &cmd).unwrap().join(" ")
+        };
+        assert_eq!(expand("demo -c work.yaml down --session b"), "demo -c work.yaml stop --all --session b");
+        // Built-in commands win, and expansions aren't expanded again
+        assert_eq!(expand("demo launch"), "demo launch");
+        assert_eq!(expand("demo up"), "demo launch");
+        assert_eq!(expand("demo unknown"), "demo unknown");
+    }
+}
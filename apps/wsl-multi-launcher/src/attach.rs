@@ -0,0 +1,140 @@
+//! Matching already open terminal windows to configured windows
+//!
+//! `attach` adopts windows the launcher didn't start (or whose state was
+//! lost) instead of launching duplicates. A window is matched by its title:
+//! an exact window name is the best match (titles the launcher set itself),
+//! then a title containing the name, then one mentioning the program the
+//! window is configured to run.
+
+use crate::config::{WindowConfig, WindowKind};
+use crate::windows::WindowInfo;
+
+/// Programs too generic to identify a window by
+const GENERIC_PROGRAMS: &[&str] = &["bash", "zsh", "sh", "fish", "cmd", "powershell", "pwsh"];
+
+/// How well an open window's title matches a configured window (0 = not at all)
+pub fn score(window: &WindowConfig, title: &str) -> u8 {
+    let title = title.trim().to_lowercase();
+    let name = window.name.to_lowercase();
+    if title.is_empty() {
+        0
+    } else if title == name {
+        3
+    } else if title.contains(&name) {
+        2
+    } else if program(window).is_some_and(|program| title.contains(&program)) {
+        1
+    } else {
+        0
+    }
+}
+
+/// Lowercase name of the program a window runs, if it's specific enough
+fn program(window: &WindowConfig) -> Option<String> {
+    let command = match window.kind {
+        WindowKind::Wsl => window.steps().last().cloned().unwrap_or_default(),
+        _ => window.command.clone().unwrap_or_default(),
+    };
+    let word = command.split_whitespace().next()?;
+    let base = word.rsplit(['/', '\\']).next().unwrap_or(word).to_lowercase();
+    let base = base.strip_suffix(".exe").unwrap_or(&base).to_string();
+    (!base.is_empty() && !GENERIC_PROGRAMS.contains(&base.as_str())).then_some(base)
+}
+
+/// Pair configured windows (config index, window) with open windows
+///
+/// Best scores are assigned first, ties in config order; every open window
+/// is used at most once. Returns (config index, handle) pairs.
+pub fn match_windows(windows: &[(usize, &WindowConfig)], candidates: &[WindowInfo]) -> Vec<(usize, i64)> {
+    let mut pairs: Vec<(u8, usize, usize)> = Vec::new();
+    for (w, &(_, window)) in windows.iter().enumerate() {
+        for (c, candidate) in candidates.iter().enumerate() {
+            let score = score(window, &candidate.title);
+            if score > 0 {
+                pairs.push((score, w, c));
+            }
+        }
+    }
+    pairs.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)).then(a.2.cmp(&b.2)));
+
+    let mut window_used = vec![false; windows.len()];
+    let mut candidate_used = vec![false; candidates.len()];
+    let mut matches = Vec::new();
+    for (_, w, c) in pairs {
+        if window_used[w] || candidate_used[c] {
+            continue;
+        }
+        window_used[w] = true;
+        candidate_used[c] = true;
+        matches.push((windows[w].0, candidates[c].handle));
+    }
+    matches.sort();
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(name: &str, command: &str) -> WindowConfig {
+        WindowConfig {
+            name: name.to_string(),
+            command: Some(command.to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn open(handle: i64, title: &str) -> WindowInfo {
+        WindowInfo {
+            handle,
+            x: 0,
+            y: 0,
+            width: 800,
+            height: 600,
+            visible: true,
+            title: title.to_string(),
+            process_id: 0,
+        }
+    }
+
+    #[test]
+    fn test_score() {
+        let w = window("claude-1", "claude --continue");
+        assert_eq!(score(&w, "claude-1"), 3);
+        assert_eq!(score(&w, "Claude-1"), 3);
+        assert_eq!(score(&w, "claude-1: ~/project"), 2);
+        assert_eq!(score(&w, "✳ Claude Code"), 1);
+        assert_eq!(score(&w, "Ubuntu"), 0);
+        assert_eq!(score(&w, ""), 0);
+
+        // Shells say nothing about which window it is
+        assert_eq!(score(&window("logs", "bash"), "bash"), 0);
+    }
+
+    #[test]
+    fn test_program() {
+        assert_eq!(program(&window("a", "/usr/bin/htop -d 5")), Some("htop".to_string()));
+        assert_eq!(program(&window("a", "zsh")), None);
+
+        let exe = WindowConfig {
+            name: "a".to_string(),
+            kind: WindowKind::WindowsExe,
+            command: Some(r"C:\Tools\lazygit.exe".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(program(&exe), Some("lazygit".to_string()));
+    }
+
+    #[test]
+    fn test_match_windows() {
+        let a = window("claude-1", "claude");
+        let b = window("claude-2", "claude");
+        let c = window("logs", "tail -f app.log");
+        let windows = [(0, &a), (1, &b), (2, &c)];
+        let candidates = [open(10, "Claude Code"), open(20, "claude-1"), open(30, "Ubuntu")];
+
+        // claude-1 takes its exact title; claude-2 gets the remaining claude window
+        assert_eq!(match_windows(&windows, &candidates), vec![(0, 20), (1, 10)]);
+        assert!(match_windows(&windows, &[]).is_empty());
+    }
+}
@@ -0,0 +1,108 @@
+//! Usage budgets of single claude windows (`claude.budget`)
+//!
+//! A window's spending is what the transcripts of its project recorded over
+//! the budget's period, counted like `usage` does. `watch` raises a toast
+//! when a window goes over its budget, `ps` shows how much of it each window
+//! used, and `dispatch` skips windows over a budget with `pause_dispatch`,
+//! so one runaway agent can't use up what was meant for all of them.
+
+use anyhow::Result;
+use chrono::Local;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use crate::config::{self, BudgetConfig, WindowConfig};
+use crate::usage::{self, Usage};
+
+/// How often `watch` checks the budgets
+pub const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Usage of a window with a budget over the budget's period
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spent {
+    pub window: String,
+    pub usage: Usage,
+    pub budget: BudgetConfig,
+}
+
+impl Spent {
+    /// Share of the budget used: the larger of the token and cost shares
+    pub fn used(&self) -> f64 {
+        let tokens = self.budget.tokens.map(|limit| self.usage.tokens() as f64 / limit as f64);
+        let cost = self.budget.cost_usd.map(|limit| self.usage.cost_usd / limit);
+        tokens.into_iter().chain(cost).fold(0.0, f64::max)
+    }
+
+    pub fn over(&self) -> bool {
+        self.used() >= 1.0
+    }
+
+    /// Usage against the limits, e.g. "1200000/1000000 tokens, $3.10/$5.00 in 24h"
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(limit) = self.budget.tokens {
+            parts.push(format!("{}/{} tokens", self.usage.tokens(), limit));
+        }
+        if let Some(limit) = self.budget.cost_usd {
+            parts.push(format!("${:.2}/${:.2}", self.usage.cost_usd, limit));
+        }
+        format!("{} in {}", parts.join(", "), self.budget.period)
+    }
+}
+
+/// Spending of the windows among `windows` that have a budget, in the
+/// order of `windows`; one usage scan per budget period
+pub fn check(distribution: &str, windows: &[&WindowConfig]) -> Result<Vec<Spent>> {
+    let mut by_period: BTreeMap<&str, Vec<&WindowConfig>> = BTreeMap::new();
+    for window in windows {
+        if let Some(budget) = window.claude.as_ref().and_then(|c| c.budget.as_ref()) {
+            by_period.entry(budget.period.as_str()).or_default().push(window);
+        }
+    }
+
+    let mut usages: BTreeMap<String, Usage> = BTreeMap::new();
+    for (period, windows) in by_period {
+        let since = Local::now() - chrono::Duration::from_std(config::parse_duration(period)?)?;
+        let report = usage::collect_windows(distribution, &windows, Some(since))?;
+        usages.extend(report.windows.into_iter().map(|w| (w.name, w.usage)));
+    }
+
+    Ok(windows
+        .iter()
+        .filter_map(|window| {
+            let budget = window.claude.as_ref()?.budget.clone()?;
+            let usage = usages.get(&window.name).copied().unwrap_or_default();
+            Some(Spent { window: window.name.clone(), usage, budget })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spent(tokens: Option<u64>, cost_usd: Option<f64>) -> Spent {
+        Spent {
+            window: "api".to_string(),
+            usage: Usage { input_tokens: 100, output_tokens: 200, cache_read_tokens: 5000, cost_usd: 1.5, ..Default::default() },
+            budget: BudgetConfig { tokens, cost_usd, period: "24h".to_string(), pause_dispatch: false },
+        }
+    }
+
+    #[test]
+    fn test_used() {
+        assert_eq!(spent(Some(600), None).used(), 0.5);
+        assert_eq!(spent(None, Some(1.0)).used(), 1.5);
+        // The limit used up most counts
+        let both = spent(Some(300), Some(3.0));
+        assert_eq!(both.used(), 1.0);
+        assert!(both.over());
+        assert!(!spent(Some(301), None).over());
+    }
+
+    #[test]
+    fn test_describe() {
+        assert_eq!(spent(Some(1000), Some(5.0)).describe(), "300/1000 tokens, $1.50/$5.00 in 24h");
+        assert_eq!(spent(None, Some(2.0)).describe(), "$1.50/$2.00 in 24h");
+    }
+}
@@ -0,0 +1,339 @@
+//! `config get` / `config set`: config values addressed by a path
+//!
+//! A path is keys joined by dots, with `[n]` for list items, e.g.
+//! `layout.grid` or `windows[2].command`. `set` replaces a plain scalar on
+//! its own line, so the comments and formatting of the file are kept.
+//! Anything else (a new key, a list or mapping value, a block scalar)
+//! rewrites the file from the parsed YAML, which drops its comments.
+
+use anyhow::{Context, Result};
+use serde_yaml::Value;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+fn parse_path(path: &str) -> Result<Vec<Segment>> {
+    let mut segments = Vec::new();
+    for part in path.split('.') {
+        let (key, mut rest) = part.split_at(part.find('[').unwrap_or(part.len()));
+        if key.is_empty() {
+            anyhow::bail!("Invalid config path '{}': expected a key before each '.' and '['", path);
+        }
+        segments.push(Segment::Key(key.to_string()));
+        while !rest.is_empty() {
+            let Some((index, after)) = rest.strip_prefix('[').and_then(|r| r.split_once(']')) else {
+                anyhow::bail!("Invalid config path '{}': expected '[<index>]' after '{}'", path, key);
+            };
+            let index = index
+                .parse()
+                .with_context(|| format!("Invalid config path '{}': '{}' is not a list index", path, index))?;
+            segments.push(Segment::Index(index));
+            rest = after;
+        }
+    }
+    Ok(segments)
+}
+
+fn lookup<'a>(value: &'a Value, path: &[Segment]) -> Option<&'a Value> {
+    path.iter().try_fold(value, |value, segment| match segment {
+        Segment::Key(key) => value.as_mapping()?.get(key.as_str()),
+        Segment::Index(i) => value.as_sequence()?.get(*i),
+    })
+}
+
+/// Value typed on the command line: YAML, but a string unless it's
+/// obviously meant otherwise (`echo: hi` stays a string, `[a, b]` is a list)
+fn parse_value(text: &str) -> Value {
+    let trimmed = text.trim();
+    match serde_yaml::from_str::<Value>(text) {
+        Ok(Value::Null) if !matches!(trimmed, "null" | "~") => Value::String(text.to_string()),
+        Ok(value @ (Value::Sequence(_) | Value::Mapping(_))) if trimmed.starts_with(['[', '{']) => value,
+        Ok(value @ (Value::Bool(_) | Value::Number(_) | Value::String(_) | Value::Null)) => value,
+        _ => Value::String(text.to_string()),
+    }
+}
+
+/// Set `path` in `root` to `new`, adding missing keys
+fn assign(root: &mut Value, path: &[Segment], new: Value, name: &str) -> Result<()> {
+    let mut value = root;
+    for segment in path {
+        value = match segment {
+            Segment::Key(key) => {
+                if value.is_null() {
+                    *value = Value::Mapping(Default::default());
+                }
+                value
+                    .as_mapping_mut()
+                    .with_context(|| format!("Can't set '{}': '{}' is inside a value that isn't a mapping", name, key))?
+                    .entry(Value::from(key.as_str()))
+                    .or_insert(Value::Null)
+            }
+            Segment::Index(i) => value
+                .as_sequence_mut()
+                .and_then(|items| items.get_mut(*i))
+                .with_context(|| format!("Can't set '{}': there is no list item [{}]", name, i))?,
+        };
+    }
+    *value = new;
+    Ok(())
+}
+
+/// A line of the config text
+struct Line<'a> {
+    text: &'a str,
+    /// Leading spaces
+    indent: usize,
+    /// Whether it's a list item (`- ...`)
+    dash: bool,
+    /// Column of the content, after the `- ` of a list item
+    col: usize,
+}
+
+impl<'a> Line<'a> {
+    fn new(text: &'a str) -> Self {
+        let indent = text.len() - text.trim_start_matches(' ').len();
+        let rest = &text[indent..];
+        let dash = rest == "-" || rest.starts_with("- ");
+        let col = if dash { text.len() - rest[1..].trim_start_matches(' ').len() } else { indent };
+        Line { text, indent, dash, col }
+    }
+
+    /// Whether the line holds YAML (not blank, a comment or `---`)
+    fn significant(&self) -> bool {
+        let content = self.text.trim();
+        !content.is_empty() && !content.starts_with('#') && content != "---"
+    }
+
+    fn content(&self) -> &'a str {
+        &self.text[self.col..]
+    }
+}
+
+/// Line and byte offset where the value of `path` starts, for values in
+/// block style (`key: value`, `- value`)
+fn locate(lines: &[Line], path: &[Segment]) -> Option<(usize, usize)> {
+    let (mut start, mut end) = (0, lines.len());
+    let mut found = None;
+    for segment in path {
+        let first = (start..end).find(|&i| lines[i].significant())?;
+        match segment {
+            Segment::Key(key) => {
+                let col = lines[first].col;
+                let line = (first..end).find(|&i| {
+                    let line = &lines[i];
+                    line.significant()
+                        && line.col == col
+                        && line.content().strip_prefix(key.as_str()).is_some_and(|r| r == ":" || r.starts_with(": "))
+                })?;
+                found = Some((line, col + key.len() + 1));
+                // A key's value: deeper lines, or a list at the key's indentation
+                start = line + 1;
+                end = (start..end)
+                    .find(|&i| {
+                        let l = &lines[i];
+                        l.significant() && (l.indent < col || (l.indent == col && !l.dash))
+                    })
+                    .unwrap_or(end);
+            }
+            Segment::Index(index) => {
+                let dash = &lines[first];
+                if !dash.dash {
+                    return None;
+                }
+                let indent = dash.indent;
+                let items: Vec<usize> = (first..end)
+                    .filter(|&i| lines[i].significant() && lines[i].indent == indent && lines[i].dash)
+                    .collect();
+                let item = *items.get(*index)?;
+                found = Some((item, lines[item].col));
+                start = item;
+                end = items.get(index + 1).copied().unwrap_or(end);
+            }
+        }
+    }
+    found
+}
+
+/// End of the scalar starting `text` (before any comment)
+fn scalar_end(text: &str) -> Option<usize> {
+    let end = match text.chars().next()? {
+        '"' => {
+            let mut escaped = false;
+            let close = text[1..].char_indices().find(|&(_, c)| {
+                let close = c == '"' && !escaped;
+                escaped = c == '\\' && !escaped;
+                close
+            })?;
+            close.0 + 2
+        }
+        '\'' => {
+            let mut i = 1;
+            loop {
+                let close = i + text[i..].find('\'')?;
+                if text[close + 1..].starts_with('\'') {
+                    i = close + 2;
+                } else {
+                    break close + 1;
+                }
+            }
+        }
+        '|' | '>' | '&' | '*' | '!' | '[' | '{' | '#' => return None,
+        _ => text.find(" #").unwrap_or(text.len()),
+    };
+    Some(text[..end].trim_end().len())
+}
+
+/// `new` as a scalar on one line, in the quoting style of `old`
+fn render(new: &Value, old: &str) -> Option<String> {
+    let rendered = match (new, old.chars().next()) {
+        (Value::String(s), Some('"')) => serde_json::to_string(s).ok()?,
+        (Value::String(s), Some('\'')) => format!("'{}'", s.replace('\'', "''")),
+        (Value::Sequence(_) | Value::Mapping(_) | Value::Tagged(_), _) => return None,
+        _ => serde_yaml::to_string(new).ok()?.trim_end().to_string(),
+    };
+    (!rendered.contains('\n')).then_some(rendered)
+}
+
+/// `content` with the scalar at `path` replaced on its line
+fn replace_in_place(content: &str, path: &[Segment], new: &Value) -> Option<String> {
+    let lines: Vec<Line> = content.lines().map(Line::new).collect();
+    let (line, offset) = locate(&lines, path)?;
+    let text = lines[line].text;
+    let value_start = offset + (text[offset..].len() - text[offset..].trim_start().len());
+    let old = &text[value_start..];
+    let end = value_start + scalar_end(old)?;
+    let replaced = format!("{} {}{}", text[..offset].trim_end_matches(' '), render(new, old)?, &text[end..]);
+
+    let mut result: Vec<&str> = content.lines().collect();
+    result[line] = &replaced;
+    let mut result = result.join("\n");
+    if content.ends_with('\n') {
+        result.push('\n');
+    }
+    Some(result)
+}
+
+/// Value at `path` of the config text: scalars as they are, lists and
+/// mappings as YAML
+pub fn get(content: &str, path: &str) -> Result<String> {
+    let segments = parse_path(path)?;
+    let root: Value = serde_yaml::from_str(content).context("Failed to parse the config")?;
+    let value = lookup(&root, &segments).with_context(|| format!("'{}' is not set in the config", path))?;
+    Ok(match value {
+        Value::String(s) => s.clone(),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::Null => "null".to_string(),
+        other => serde_yaml::to_string(other)?.trim_end().to_string(),
+    })
+}
+
+/// Config text after `config set`
+pub struct Edited {
+    pub content: String,
+    /// Whether only the value's line changed (comments kept)
+    pub in_place: bool,
+}
+
+/// Config text with `path` set to `value` (parsed as YAML)
+pub fn set(content: &str, path: &str, value: &str) -> Result<Edited> {
+    let segments = parse_path(path)?;
+    let mut root: Value = serde_yaml::from_str(content).context("Failed to parse the config")?;
+    let new = parse_value(value);
+    assign(&mut root, &segments, new.clone(), path)?;
+
+    // Only if the edited text parses to exactly the expected config
+    if let Some(edited) = replace_in_place(content, &segments, &new)
+        && serde_yaml::from_str::<Value>(&edited).ok().as_ref() == Some(&root)
+    {
+        return Ok(Edited { content: edited, in_place: true });
+    }
+    Ok(Edited { content: serde_yaml::to_string(&root)?, in_place: false })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = "\
+# Workspace
+wsl_distribution: Ubuntu
+
+layout:
+  # Grid format
+  grid: \"2x2\"  # cols x rows
+
+windows:
+  - name: \"api\"
+    command: 'npm run dev'
+    tags: [backend]
+
+  - name: web
+    command: bash
+    env:
+      PORT: 3000
+";
+
+    #[test]
+    fn test_parse_path() {
+        let key = |k: &str| Segment::Key(k.to_string());
+        assert_eq!(parse_path("layout.grid").unwrap(), [key("layout"), key("grid")]);
+        assert_eq!(parse_path("windows[2].command").unwrap(), [key("windows"), Segment::Index(2), key("command")]);
+        assert_eq!(parse_path("a[0][1]").unwrap(), [key("a"), Segment::Index(0), Segment::Index(1)]);
+        for invalid in ["", "layout.", "[0]", "windows[x]", "windows[0", "windows[0]x"] {
+            assert!(parse_path(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_get() {
+        assert_eq!(get(CONFIG, "layout.grid").unwrap(), "2x2");
+        assert_eq!(get(CONFIG, "windows[1].env.PORT").unwrap(), "3000");
+        assert_eq!(get(CONFIG, "windows[0].tags").unwrap(), "- backend");
+        assert!(get(CONFIG, "windows[5].name").is_err());
+        assert!(get(CONFIG, "layout.mode").is_err());
+    }
+
+    #[test]
+    fn test_set_in_place() {
+        let edited = set(CONFIG, "windows[1].command", "htop").unwrap();
+        assert!(edited.in_place);
+        assert_eq!(edited.content, CONFIG.replace("command: bash", "command: htop"));
+
+        // Quoting and trailing comments are kept
+        let edited = set(CONFIG, "layout.grid", "3x2").unwrap();
+        assert!(edited.in_place);
+        assert!(edited.content.contains("  grid: \"3x2\"  # cols x rows\n"));
+        let edited = set(CONFIG, "windows[0].command", "it's").unwrap();
+        assert!(edited.content.contains("    command: 'it''s'\n"));
+        assert!(edited.content.starts_with("# Workspace\n"));
+
+        // Strings that would read as other types are quoted
+        let edited = set(CONFIG, "windows[0].name", "true").unwrap();
+        assert_eq!(get(&edited.content, "windows[0].name").unwrap(), "true");
+        let edited = set(CONFIG, "windows[1].command", "echo: hi").unwrap();
+        assert!(edited.in_place);
+        assert_eq!(get(&edited.content, "windows[1].command").unwrap(), "echo: hi");
+        let edited = set(CONFIG, "windows[1].env.PORT", "8080").unwrap();
+        assert!(edited.content.contains("      PORT: 8080\n"));
+    }
+
+    #[test]
+    fn test_set_rewrite() {
+        // A new key can't be placed on an existing line
+        let edited = set(CONFIG, "layout.mode", "tabs").unwrap();
+        assert!(!edited.in_place);
+        assert!(!edited.content.contains('#'));
+        assert_eq!(get(&edited.content, "layout.mode").unwrap(), "tabs");
+        assert_eq!(get(&edited.content, "layout.grid").unwrap(), "2x2");
+
+        let edited = set(CONFIG, "windows[0].tags", "[backend, api]").unwrap();
+        assert_eq!(get(&edited.content, "windows[0].tags").unwrap(), "- backend\n- api");
+
+        assert!(set(CONFIG, "windows[2].command", "htop").is_err());
+        assert!(set(CONFIG, "wsl_distribution.name", "x").is_err());
+    }
+}
@@ -0,0 +1,89 @@
+//! Yes/no questions on the terminal, and `--yes` to answer them in scripts
+//!
+//! Destructive operations (closing every window, overwriting the config,
+//! closing windows missing from the config, removing worktrees) ask first.
+//! Without a terminal to ask on they fail instead of guessing, unless
+//! `--yes` is given.
+
+use anyhow::Result;
+use std::io::{IsTerminal, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::{dry_run, t};
+
+static ASSUME_YES: AtomicBool = AtomicBool::new(false);
+
+pub fn set(yes: bool) {
+    ASSUME_YES.store(yes, Ordering::Relaxed);
+}
+
+/// Answer typed to a `[y/n]` question; None if it's neither
+fn answer(input: &str, default: bool) -> Option<bool> {
+    match input.trim().to_lowercase().as_str() {
+        "" => Some(default),
+        "y" | "yes" => Some(true),
+        "n" | "no" => Some(false),
+        _ => None,
+    }
+}
+
+/// Ask on the terminal until the answer is yes or no; `default` on Enter
+/// or end of input
+fn read_answer(question: &str, default: bool) -> bool {
+    let choices = if default { "[Y/n]" } else { "[y/N]" };
+    loop {
+        eprint!("{} {} ", question, choices);
+        let _ = std::io::stderr().flush();
+        let mut input = String::new();
+        match std::io::stdin().read_line(&mut input) {
+            Ok(0) | Err(_) => return default,
+            Ok(_) => {
+                if let Some(answer) = answer(&input, default) {
+                    return answer;
+                }
+            }
+        }
+    }
+}
+
+/// Ask `question`: yes with `--yes`, `default` without a terminal
+pub fn ask(question: &str, default: bool) -> bool {
+    if ASSUME_YES.load(Ordering::Relaxed) {
+        return true;
+    }
+    if !std::io::stdin().is_terminal() {
+        return default;
+    }
+    read_answer(question, default)
+}
+
+/// Confirm a destructive operation (no by default): yes with `--yes` or in
+/// a dry run, an error without a terminal to ask on
+pub fn destructive(question: &str) -> Result<bool> {
+    if ASSUME_YES.load(Ordering::Relaxed) || dry_run::is_enabled() {
+        return Ok(true);
+    }
+    if !std::io::stdin().is_terminal() {
+        anyhow::bail!("{}", t!("confirm-no-terminal", question = question));
+    }
+    let confirmed = read_answer(question, false);
+    if !confirmed {
+        println!("{}", t!("confirm-cancelled"));
+    }
+    Ok(confirmed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_answer() {
+        assert_eq!(answer("\n", false), Some(false));
+        assert_eq!(answer("", true), Some(true));
+        assert_eq!(answer(" Y\n", false), Some(true));
+        assert_eq!(answer("yes", false), Some(true));
+        assert_eq!(answer("No", true), Some(false));
+        assert_eq!(answer("maybe", true), None);
+    }
+}
@@ -0,0 +1,315 @@
+//! Mission-control view of a session (`dashboard`)
+//!
+//! One pane per configured window shows its status, claude activity,
+//! uptime and the tail of its output log. Gathering that (PowerShell for
+//! window positions, a CPU sample for claude activity) takes a second or
+//! two, so snapshots are taken on a background thread and the UI only
+//! draws the newest one. Focus and restart are run as subcommands of this
+//! executable with their output captured, so they can't scribble over the
+//! screen.
+
+use anyhow::Result;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use std::sync::mpsc;
+use std::time::Duration;
+
+use crate::layout::fitting_grid;
+
+/// Bytes read from the end of a log for the tail
+const TAIL_BYTES: u64 = 64 * 1024;
+
+/// How often a new snapshot is taken
+const REFRESH: Duration = Duration::from_secs(2);
+
+/// What the dashboard shows of one window
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Pane {
+    pub name: String,
+    /// running / moved / hidden / missing, or "stopped" if not recorded
+    pub status: String,
+    pub activity: Option<String>,
+    pub uptime: Option<String>,
+    pub exit_code: Option<i64>,
+    /// Last lines of output, or a hint why there are none
+    pub tail: Vec<String>,
+}
+
+/// Action on the selected window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Focus,
+    Restart,
+}
+
+/// Drop escape sequences and control characters from terminal output; a
+/// carriage return starts the line over, like on the terminal
+pub fn strip_ansi(line: &str) -> String {
+    let mut out = String::new();
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\x1b' => match chars.next() {
+                // CSI: parameters up to a final byte in @..~
+                Some('[') => {
+                    for c in chars.by_ref() {
+                        if ('@'..='~').contains(&c) {
+                            break;
+                        }
+                    }
+                }
+                // OSC: up to BEL or ESC \
+                Some(']') => {
+                    while let Some(c) = chars.next() {
+                        if c == '\x07' || (c == '\x1b' && chars.next_if_eq(&'\\').is_some()) {
+                            break;
+                        }
+                    }
+                }
+                _ => {}
+            },
+            '\r' => out.clear(),
+            '\t' => out.push_str("    "),
+            c if c.is_control() => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Last `count` non-empty lines of a log file, cleaned up for display
+pub fn read_tail(path: &Path, count: usize) -> std::io::Result<Vec<String>> {
+    let mut file = std::fs::File::open(path)?;
+    let len = file.metadata()?.len();
+    file.seek(SeekFrom::Start(len.saturating_sub(TAIL_BYTES)))?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes)?;
+    let lines: Vec<String> = String::from_utf8_lossy(&bytes)
+        .lines()
+        .map(strip_ansi)
+        .filter(|line| !line.trim().is_empty())
+        .collect();
+    Ok(lines[lines.len().saturating_sub(count)..].to_vec())
+}
+
+/// Columns and rows of a pane grid that fits `count` panes
+fn grid(count: usize) -> (usize, usize) {
+    let (cols, rows) = fitting_grid(count);
+    (cols as usize, rows as usize)
+}
+
+/// Index reached by moving `(dx, dy)` cells from `selected` in the grid
+fn step(selected: usize, count: usize, (dx, dy): (isize, isize)) -> usize {
+    if count == 0 {
+        return 0;
+    }
+    let (cols, _) = grid(count);
+    let target = selected as isize + dx + dy * cols as isize;
+    if (0..count as isize).contains(&target) { target as usize } else { selected }
+}
+
+fn status_color(status: &str) -> Color {
+    match status {
+        "running" => Color::Green,
+        "moved" | "hidden" => Color::Yellow,
+        _ => Color::Red,
+    }
+}
+
+struct App {
+    panes: Vec<Pane>,
+    selected: usize,
+    /// Result of the last action, or an error taking a snapshot
+    message: String,
+    /// Window waiting for a restart confirmation
+    confirm_restart: Option<String>,
+}
+
+impl App {
+    fn draw(&self, frame: &mut Frame) {
+        let [body, footer] = Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+
+        if self.panes.is_empty() {
+            frame.render_widget(Paragraph::new("Waiting for the first snapshot..."), body);
+        } else {
+            let (cols, rows) = grid(self.panes.len());
+            let row_areas = Layout::vertical(vec![Constraint::Ratio(1, rows as u32); rows]).split(body);
+            for (i, pane) in self.panes.iter().enumerate() {
+                let cells = Layout::horizontal(vec![Constraint::Ratio(1, cols as u32); cols]).split(row_areas[i / cols]);
+                self.draw_pane(frame, pane, i == self.selected, cells[i % cols]);
+            }
+        }
+
+        let footer_text = match self.confirm_restart {
+            Some(ref name) => Line::from(Span::styled(
+                format!("Restart '{}'? (y/n)", name),
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            )),
+            None => Line::from(vec![
+                Span::styled(" arrows/hjkl", Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw(" select  "),
+                Span::styled("enter/f", Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw(" focus  "),
+                Span::styled("r", Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw(" restart  "),
+                Span::styled("q", Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw(" quit   "),
+                Span::raw(self.message.as_str()),
+            ]),
+        };
+        frame.render_widget(Paragraph::new(footer_text), footer);
+    }
+
+    fn draw_pane(&self, frame: &mut Frame, pane: &Pane, selected: bool, area: Rect) {
+        let mut title = vec![
+            Span::styled(format!(" {} ", pane.name), Style::default().add_modifier(Modifier::BOLD)),
+            Span::styled(pane.status.clone(), Style::default().fg(status_color(&pane.status))),
+        ];
+        if let Some(ref activity) = pane.activity {
+            title.push(Span::raw(format!(" · {}", activity)));
+        }
+        if let Some(code) = pane.exit_code {
+            title.push(Span::raw(format!(" · exit {}", code)));
+        }
+        if let Some(ref uptime) = pane.uptime {
+            title.push(Span::raw(format!(" · {} ", uptime)));
+        }
+        let border = if selected { Style::default().fg(Color::Cyan) } else { Style::default().fg(Color::DarkGray) };
+        let block = Block::bordered().title(Line::from(title)).border_style(border);
+
+        // Keep the newest lines in view
+        let visible = area.height.saturating_sub(2) as usize;
+        let lines: Vec<Line> = pane.tail[pane.tail.len().saturating_sub(visible)..]
+            .iter()
+            .map(|line| Line::raw(line.as_str()))
+            .collect();
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+}
+
+/// Run the dashboard until the user quits
+///
+/// `snapshot` is called on a background thread every couple of seconds;
+/// `act` runs an action on a window and returns a line to show.
+pub fn run<S, A>(snapshot: S, mut act: A) -> Result<()>
+where
+    S: Fn() -> Result<Vec<Pane>> + Send + 'static,
+    A: FnMut(Action, &str) -> String,
+{
+    let (sender, snapshots) = mpsc::channel();
+    std::thread::spawn(move || {
+        while sender.send(snapshot()).is_ok() {
+            std::thread::sleep(REFRESH);
+        }
+    });
+
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, &snapshots, &mut act);
+    ratatui::restore();
+    result
+}
+
+fn event_loop<A>(terminal: &mut DefaultTerminal, snapshots: &mpsc::Receiver<Result<Vec<Pane>>>, act: &mut A) -> Result<()>
+where
+    A: FnMut(Action, &str) -> String,
+{
+    let mut app = App { panes: Vec::new(), selected: 0, message: String::new(), confirm_restart: None };
+    loop {
+        while let Ok(snapshot) = snapshots.try_recv() {
+            match snapshot {
+                Ok(panes) => {
+                    app.selected = app.selected.min(panes.len().saturating_sub(1));
+                    app.panes = panes;
+                }
+                Err(e) => app.message = format!("{:#}", e),
+            }
+        }
+        terminal.draw(|frame| app.draw(frame))?;
+
+        if !event::poll(Duration::from_millis(200))? {
+            continue;
+        }
+        let Event::Key(key) = event::read()? else { continue };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        if let Some(name) = app.confirm_restart.take() {
+            if matches!(key.code, KeyCode::Char('y') | KeyCode::Char('Y')) {
+                app.message = format!("Restarting '{}'...", name);
+                terminal.draw(|frame| app.draw(frame))?;
+                app.message = act(Action::Restart, &name);
+            }
+            continue;
+        }
+
+        let count = app.panes.len();
+        let selected_name = app.panes.get(app.selected).map(|p| p.name.clone());
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(()),
+            KeyCode::Left | KeyCode::Char('h') => app.selected = step(app.selected, count, (-1, 0)),
+            KeyCode::Right | KeyCode::Char('l') => app.selected = step(app.selected, count, (1, 0)),
+            KeyCode::Up | KeyCode::Char('k') => app.selected = step(app.selected, count, (0, -1)),
+            KeyCode::Down | KeyCode::Char('j') => app.selected = step(app.selected, count, (0, 1)),
+            KeyCode::Tab if count > 0 => app.selected = (app.selected + 1) % count,
+            KeyCode::Enter | KeyCode::Char('f') => {
+                if let Some(name) = selected_name {
+                    app.message = act(Action::Focus, &name);
+                }
+            }
+            KeyCode::Char('r') => app.confirm_restart = selected_name,
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_ansi() {
+        assert_eq!(strip_ansi("\x1b[1;32mok\x1b[0m done"), "ok done");
+        assert_eq!(strip_ansi("\x1b]0;title\x07prompt$ "), "prompt$ ");
+        assert_eq!(strip_ansi("\x1b]8;;http://x\x1b\\link"), "link");
+        assert_eq!(strip_ansi("50%\r100%"), "100%");
+        assert_eq!(strip_ansi("a\tb\x08"), "a    b");
+    }
+
+    #[test]
+    fn test_read_tail() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("w.log");
+        std::fs::write(&path, "one\n\x1b[31mtwo\x1b[0m\n\nthree\n").unwrap();
+        assert_eq!(read_tail(&path, 2).unwrap(), vec!["two", "three"]);
+        assert!(read_tail(&dir.path().join("missing.log"), 2).is_err());
+    }
+
+    #[test]
+    fn test_grid() {
+        assert_eq!(grid(0), (1, 1));
+        assert_eq!(grid(1), (1, 1));
+        assert_eq!(grid(3), (2, 2));
+        assert_eq!(grid(4), (2, 2));
+        assert_eq!(grid(5), (3, 2));
+        assert_eq!(grid(8), (3, 3));
+    }
+
+    #[test]
+    fn test_step() {
+        // 5 panes in a 3x2 grid
+        assert_eq!(step(0, 5, (1, 0)), 1);
+        assert_eq!(step(1, 5, (0, 1)), 4);
+        assert_eq!(step(2, 5, (0, 1)), 2);
+        assert_eq!(step(0, 5, (-1, 0)), 0);
+        assert_eq!(step(4, 5, (0, -1)), 1);
+        assert_eq!(step(0, 0, (1, 0)), 0);
+    }
+}
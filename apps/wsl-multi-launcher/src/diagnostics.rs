@@ -0,0 +1,216 @@
+//! Diagnostics bundle of a failed launch (`launch --collect-diagnostics`)
+//!
+//! Everything useful for a bug report goes into one zip in the current
+//! directory: the error, the session's recent events, window logs, the
+//! recorded windows, display info, environment checks and the config with
+//! anything that looks like a secret redacted. Collection is best effort;
+//! a part that can't be gathered is replaced by the error it failed with.
+
+use anyhow::{Context, Result};
+use chrono::Local;
+use serde_yaml::Value;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::events;
+use crate::logs;
+use crate::state::SessionState;
+use crate::terminal;
+use crate::windows;
+
+const REDACTED: &str = "<redacted>";
+
+/// Lines of each window log included in the bundle
+const LOG_LINES: usize = 200;
+
+/// Whether a config key or variable name probably holds a secret
+fn is_secret_name(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    ["token", "secret", "password", "passwd", "api_key", "apikey", "credential", "auth"]
+        .iter()
+        .any(|word| name.contains(word))
+}
+
+/// Redact `NAME=value` assignments with secret-looking names inside a
+/// command line (e.g. `ANTHROPIC_API_KEY=sk-... claude`)
+fn redact_text(text: &str) -> String {
+    text.split(' ')
+        .map(|word| match word.split_once('=') {
+            Some((name, _)) if is_secret_name(name) => format!("{}={}", name, REDACTED),
+            _ => word.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn redact_value(value: &mut Value) {
+    match value {
+        Value::Mapping(map) => {
+            for (key, value) in map.iter_mut() {
+                if key.as_str().is_some_and(is_secret_name) && !value.is_mapping() {
+                    *value = Value::String(REDACTED.to_string());
+                } else {
+                    redact_value(value);
+                }
+            }
+        }
+        Value::Sequence(items) => items.iter_mut().for_each(redact_value),
+        Value::String(s) => *s = redact_text(s),
+        _ => {}
+    }
+}
+
+/// Config YAML with secret-looking values replaced by `<redacted>`
+pub fn redact(yaml: &str) -> Result<String> {
+    let mut value: Value = serde_yaml::from_str(yaml).context("Failed to parse config")?;
+    redact_value(&mut value);
+    Ok(serde_yaml::to_string(&value)?)
+}
+
+/// Text of a part, or the error it failed with
+fn or_error(part: Result<String>) -> String {
+    part.unwrap_or_else(|e| format!("error: {:#}\n", e))
+}
+
+fn environment() -> String {
+    let osrelease = fs::read_to_string("/proc/sys/kernel/osrelease").unwrap_or_default();
+    let mut out = format!(
+        "wsl-multi-launcher {}\nkernel: {}\nWSL_DISTRO_NAME: {}\n",
+        env!("CARGO_PKG_VERSION"),
+        osrelease.trim(),
+        std::env::var("WSL_DISTRO_NAME").unwrap_or_default(),
+    );
+    for program in ["wsl.exe", "wt.exe", "powershell.exe", "wslpath"] {
+        let found = terminal::is_available(program);
+        out.push_str(&format!("{}: {}\n", program, if found { "found" } else { "not found" }));
+    }
+    let wt = match terminal::wt_version::detect() {
+        Some(version) => version.to_string(),
+        None => "unknown".to_string(),
+    };
+    out.push_str(&format!("Windows Terminal version: {}\n", wt));
+    out
+}
+
+/// Parts of the bundle as (file name, contents)
+fn gather(config_path: &str, session: &str, failure: &str) -> Vec<(String, String)> {
+    let mut parts = vec![
+        ("failure.txt".to_string(), format!("{}\n", failure)),
+        ("environment.txt".to_string(), environment()),
+        (
+            "config.yaml".to_string(),
+            or_error(
+                fs::read_to_string(config_path)
+                    .with_context(|| format!("Failed to read {}", config_path))
+                    .and_then(|yaml| redact(&yaml)),
+            ),
+        ),
+        (
+            "displays.json".to_string(),
+            or_error(windows::get_displays().map_err(anyhow::Error::from).and_then(|d| Ok(serde_json::to_string_pretty(&d)?))),
+        ),
+    ];
+
+    let since = Local::now() - chrono::Duration::days(1);
+    let events = events::path().and_then(|path| events::load_from(&path, Some(since))).map_err(anyhow::Error::from);
+    let events = events.and_then(|events| {
+        let mut out = String::new();
+        for event in events.iter().filter(|e| e.session == session) {
+            out.push_str(&serde_json::to_string(event)?);
+            out.push('\n');
+        }
+        Ok(out)
+    });
+    parts.push(("events.jsonl".to_string(), or_error(events)));
+
+    let state = SessionState::load().map_err(anyhow::Error::from).and_then(|state| {
+        let records: Vec<_> = state.session_windows(session).collect();
+        Ok(serde_json::to_string_pretty(&records)?)
+    });
+    parts.push(("windows.json".to_string(), or_error(state)));
+
+    if let Ok(dir) = logs::session_dir(session)
+        && let Ok(entries) = fs::read_dir(dir)
+    {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if !name.ends_with(".log") {
+                continue;
+            }
+            if let Ok(content) = fs::read_to_string(entry.path()) {
+                let tail = logs::tail_lines(&content, LOG_LINES).join("\n");
+                parts.push((format!("logs/{}", name), redact_text(&tail)));
+            }
+        }
+    }
+    parts
+}
+
+/// File name of a bundle created now
+fn bundle_name() -> String {
+    format!("wsl-multi-launcher-diagnostics-{}.zip", Local::now().format("%Y%m%d-%H%M%S"))
+}
+
+fn write_zip(path: &Path, parts: &[(String, String)]) -> Result<()> {
+    let file = File::create(path).with_context(|| format!("Failed to create {}", path.display()))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default();
+    for (name, contents) in parts {
+        zip.start_file(name.as_str(), options)?;
+        zip.write_all(contents.as_bytes())?;
+    }
+    zip.finish().with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(())
+}
+
+/// Collect a bundle for a launch of `session` that failed with `failure`
+/// into the current directory, returning its path
+pub fn collect(config_path: &str, session: &str, failure: &str) -> Result<PathBuf> {
+    let path = PathBuf::from(bundle_name());
+    write_zip(&path, &gather(config_path, session, failure))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn test_redact() {
+        let yaml = r#"
+wsl_distribution: Ubuntu
+windows:
+  - name: claude-1
+    command: "ANTHROPIC_API_KEY=sk-123 GH_TOKEN=ghp_1 claude --model opus"
+    api_token: abc
+hooks:
+  post_launch: "curl -H 'x: y' https://example.com?password=hunter2"
+"#;
+        let redacted = redact(yaml).unwrap();
+        assert!(redacted.contains("ANTHROPIC_API_KEY=<redacted> GH_TOKEN=<redacted> claude --model opus"));
+        assert!(redacted.contains("api_token: <redacted>"));
+        assert!(redacted.contains("wsl_distribution: Ubuntu"));
+        assert!(!redacted.contains("sk-123"));
+        assert!(!redacted.contains("abc"));
+        assert!(!redacted.contains("hunter2"));
+    }
+
+    #[test]
+    fn test_write_zip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bundle.zip");
+        let parts = vec![
+            ("failure.txt".to_string(), "boom\n".to_string()),
+            ("logs/claude-1.log".to_string(), "hello".to_string()),
+        ];
+        write_zip(&path, &parts).unwrap();
+
+        let mut archive = zip::ZipArchive::new(File::open(&path).unwrap()).unwrap();
+        assert_eq!(archive.len(), 2);
+        let mut contents = String::new();
+        archive.by_name("logs/claude-1.log").unwrap().read_to_string(&mut contents).unwrap();
+        assert_eq!(contents, "hello");
+    }
+}
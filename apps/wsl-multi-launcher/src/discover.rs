@@ -0,0 +1,172 @@
+//! Layout inferred from open Windows Terminal windows (`init --from-running`)
+//!
+//! The display is the one most windows are on. The grid comes from the
+//! typical window size against the display's working area, and the windows
+//! are ordered by the cell nearest to their top-left corner. Configured
+//! windows fill the grid in order without gaps, so the result only
+//! approximates an arrangement with empty cells or windows spanning
+//! several cells.
+
+use crate::layout::{DisplayInfo, Rect};
+use crate::windows::WindowInfo;
+
+/// What `init --from-running` generates a config from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Discovered {
+    pub display: u32,
+    pub cols: u32,
+    pub rows: u32,
+    /// Windows in grid order, with the names derived from their titles
+    pub windows: Vec<(String, Rect)>,
+    /// Windows on other displays, left out
+    pub skipped: usize,
+}
+
+fn center(rect: &Rect) -> (i32, i32) {
+    (rect.x + rect.width / 2, rect.y + rect.height / 2)
+}
+
+fn contains(display: &DisplayInfo, (x, y): (i32, i32)) -> bool {
+    let b = &display.bounds;
+    x >= b.x && x < b.x + b.width && y >= b.y && y < b.y + b.height
+}
+
+fn median(mut values: Vec<i32>) -> i32 {
+    values.sort_unstable();
+    values[values.len() / 2]
+}
+
+/// Number of cells of `size` fitting in `total` (at least 1)
+fn cells(total: i32, size: i32) -> u32 {
+    ((total as f64 / size.max(1) as f64).round() as u32).max(1)
+}
+
+/// Window name from a title: the title itself, made unique
+fn name(title: &str, index: usize, taken: &[(String, Rect)]) -> String {
+    let base = match title.trim() {
+        "" => format!("window-{}", index + 1),
+        title => title.to_string(),
+    };
+    let mut name = base.clone();
+    let mut n = 1;
+    while taken.iter().any(|(other, _)| *other == name) {
+        n += 1;
+        name = format!("{}-{}", base, n);
+    }
+    name
+}
+
+/// Infer a layout from the visible `windows`; None if none is on a display
+pub fn discover(windows: &[WindowInfo], displays: &[DisplayInfo]) -> Option<Discovered> {
+    let open: Vec<&WindowInfo> = windows.iter().filter(|w| w.visible && w.width > 0 && w.height > 0).collect();
+    let on_display = |display: &DisplayInfo| -> Vec<&WindowInfo> {
+        open.iter().copied().filter(|w| contains(display, center(&w.rect()))).collect()
+    };
+    // Most windows wins; the lower index on a tie
+    let (index, display) = displays
+        .iter()
+        .enumerate()
+        .max_by_key(|(i, d)| (on_display(d).len(), std::cmp::Reverse(*i)))?;
+    let on = on_display(display);
+    if on.is_empty() {
+        return None;
+    }
+
+    let area = &display.working_area;
+    let mut cols = cells(area.width, median(on.iter().map(|w| w.width).collect()));
+    let mut rows = cells(area.height, median(on.iter().map(|w| w.height).collect()));
+    // Row and column nearest to the top-left corner, so a window spanning
+    // several cells sorts by the first one
+    let nearest = |offset: i32, total: i32, count: u32| {
+        let size = total.max(1) as f64 / count as f64;
+        ((offset as f64 / size).round().max(0.0) as u32).min(count - 1)
+    };
+    let mut on = on;
+    on.sort_by_key(|w| (nearest(w.y - area.y, area.height, rows), nearest(w.x - area.x, area.width, cols), w.y, w.x));
+    // Windows stacked in one cell still need a cell each
+    if on.len() > (cols * rows) as usize {
+        if cols == 1 && rows == 1 {
+            cols = (on.len() as f64).sqrt().ceil() as u32;
+        }
+        rows = (on.len() as u32).div_ceil(cols);
+    }
+
+    let mut named = Vec::new();
+    for (i, window) in on.iter().enumerate() {
+        let name = name(&window.title, i, &named);
+        named.push((name, window.rect()));
+    }
+    Some(Discovered {
+        display: index as u32,
+        cols,
+        rows,
+        windows: named,
+        skipped: open.len() - on.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::BoundsInfo;
+
+    fn display(x: i32, width: i32, height: i32) -> DisplayInfo {
+        let bounds = || BoundsInfo { x, y: 0, width, height };
+        DisplayInfo {
+            device_name: format!("DISPLAY{}", x),
+            primary: x == 0,
+            bounds: bounds(),
+            working_area: BoundsInfo { height: height - 40, ..bounds() },
+        }
+    }
+
+    fn window(title: &str, x: i32, y: i32, width: i32, height: i32) -> WindowInfo {
+        WindowInfo { handle: x as i64 * 10_000 + y as i64, x, y, width, height, visible: true, title: title.to_string(), process_id: 0 }
+    }
+
+    fn names(discovered: &Discovered) -> Vec<&str> {
+        discovered.windows.iter().map(|(name, _)| name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_discover_grid() {
+        let displays = [display(0, 1920, 1080), display(1920, 1920, 1080)];
+        // A 2x2 arrangement on the second display, listed out of order
+        let windows = [
+            window("web", 2880, 520, 960, 520),
+            window("api", 1920, 0, 960, 520),
+            window("", 1920, 520, 960, 520),
+            window("api", 2880, 0, 960, 520),
+            window("mail", 100, 100, 800, 600),
+            WindowInfo { visible: false, ..window("hidden", 0, 0, 960, 520) },
+        ];
+        let discovered = discover(&windows, &displays).unwrap();
+        assert_eq!((discovered.display, discovered.cols, discovered.rows), (1, 2, 2));
+        assert_eq!(names(&discovered), ["api", "api-2", "window-3", "web"]);
+        assert_eq!(discovered.windows[1].1, Rect::new(2880, 0, 960, 520));
+        assert_eq!(discovered.skipped, 1);
+    }
+
+    #[test]
+    fn test_discover_overlapping() {
+        let displays = [display(0, 1920, 1080)];
+        // Three maximized windows stacked on each other
+        let windows: Vec<WindowInfo> = ["a", "b", "c"].iter().map(|t| window(t, 0, 0, 1920, 1040)).collect();
+        let discovered = discover(&windows, &displays).unwrap();
+        assert_eq!((discovered.cols, discovered.rows), (2, 2));
+        assert_eq!(names(&discovered), ["a", "b", "c"]);
+
+        // A window spanning both rows of the first column comes first
+        let windows = [
+            window("web", 960, 0, 960, 520),
+            window("api", 0, 0, 960, 1040),
+            window("logs", 960, 520, 960, 520),
+        ];
+        let discovered = discover(&windows, &displays).unwrap();
+        assert_eq!((discovered.cols, discovered.rows), (2, 2));
+        assert_eq!(names(&discovered), ["api", "web", "logs"]);
+
+        assert_eq!(discover(&[], &displays), None);
+        assert_eq!(discover(&[window("far", 5000, 0, 800, 600)], &displays), None);
+    }
+}
@@ -0,0 +1,161 @@
+//! Task lists handed out to windows (`dispatch`)
+//!
+//! A task file is Markdown; every top-level list item that isn't checked
+//! is a queued task. Each task is sent as a prompt to one window, either
+//! round-robin over all target windows or only to idle claude sessions
+//! (one task each). Dispatched items are checked off in the file with the
+//! window that got them, so running `dispatch` again picks up the rest.
+
+use std::collections::HashSet;
+
+/// A queued task of a task file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Task {
+    /// Line index in the file
+    pub line: usize,
+    pub text: String,
+}
+
+/// How tasks are assigned to windows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    /// Every task, cycling through the windows
+    RoundRobin,
+    /// One task per idle window; the rest stay queued
+    Idle,
+}
+
+/// List marker of a top-level item ("- ", "* ", "+ ", "1. ") and the rest
+fn split_marker(line: &str) -> Option<(&str, &str)> {
+    for bullet in ["- ", "* ", "+ "] {
+        if let Some(rest) = line.strip_prefix(bullet) {
+            return Some((&line[..bullet.len()], rest));
+        }
+    }
+    let digits = line.find(|c: char| !c.is_ascii_digit())?;
+    if digits > 0 && line[digits..].starts_with(". ") {
+        return Some((&line[..digits + 2], &line[digits + 2..]));
+    }
+    None
+}
+
+/// Queued tasks of a task file, in order
+pub fn parse(markdown: &str) -> Vec<Task> {
+    let mut tasks = Vec::new();
+    let mut in_code = false;
+    for (line, text) in markdown.lines().enumerate() {
+        if text.starts_with("```") {
+            in_code = !in_code;
+        }
+        if in_code {
+            continue;
+        }
+        let Some((_, item)) = split_marker(text) else { continue };
+        let item = if let Some(rest) = item.strip_prefix("[ ] ") {
+            rest
+        } else if item.starts_with("[x]") || item.starts_with("[X]") {
+            continue;
+        } else {
+            item
+        };
+        let item = item.trim();
+        if !item.is_empty() {
+            tasks.push(Task { line, text: item.to_string() });
+        }
+    }
+    tasks
+}
+
+/// Pair tasks with windows; `idle` is only used by [`Strategy::Idle`]
+pub fn assign<'a>(
+    tasks: &'a [Task],
+    windows: &'a [String],
+    strategy: Strategy,
+    idle: &HashSet<String>,
+) -> Vec<(&'a Task, &'a str)> {
+    match strategy {
+        Strategy::RoundRobin if !windows.is_empty() => tasks
+            .iter()
+            .enumerate()
+            .map(|(i, task)| (task, windows[i % windows.len()].as_str()))
+            .collect(),
+        Strategy::RoundRobin => Vec::new(),
+        Strategy::Idle => tasks
+            .iter()
+            .zip(windows.iter().filter(|w| idle.contains(*w)))
+            .map(|(task, window)| (task, window.as_str()))
+            .collect(),
+    }
+}
+
+/// Check off dispatched tasks, given as (line index, window name)
+pub fn mark_dispatched(markdown: &str, dispatched: &[(usize, String)]) -> String {
+    let mut out: Vec<String> = markdown.lines().map(str::to_string).collect();
+    for (line, window) in dispatched {
+        let Some(text) = out.get(*line) else { continue };
+        let Some((marker, item)) = split_marker(text) else { continue };
+        let item = item.strip_prefix("[ ] ").unwrap_or(item).trim_end();
+        out[*line] = format!("{}[x] {} (dispatched to {})", marker, item, window);
+    }
+    let mut result = out.join("\n");
+    if markdown.ends_with('\n') {
+        result.push('\n');
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TASKS: &str = "# Sprint\n\
+        \n\
+        - [ ] Fix the login test\n\
+        - [x] Update deps\n\
+        * Add rate limiting\n\
+        \x20 - nested detail\n\
+        1. Write the changelog\n\
+        ```\n\
+        - not a task\n\
+        ```\n\
+        -no space\n";
+
+    #[test]
+    fn test_parse() {
+        let tasks = parse(TASKS);
+        assert_eq!(
+            tasks,
+            vec![
+                Task { line: 2, text: "Fix the login test".to_string() },
+                Task { line: 4, text: "Add rate limiting".to_string() },
+                Task { line: 6, text: "Write the changelog".to_string() },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_assign() {
+        let tasks = parse(TASKS);
+        let windows = vec!["a".to_string(), "b".to_string()];
+        let pairs = assign(&tasks, &windows, Strategy::RoundRobin, &HashSet::new());
+        let names: Vec<&str> = pairs.iter().map(|(_, w)| *w).collect();
+        assert_eq!(names, ["a", "b", "a"]);
+
+        let idle = HashSet::from(["b".to_string()]);
+        let pairs = assign(&tasks, &windows, Strategy::Idle, &idle);
+        assert_eq!(pairs.len(), 1);
+        assert_eq!((pairs[0].0.line, pairs[0].1), (2, "b"));
+
+        assert!(assign(&tasks, &[], Strategy::RoundRobin, &idle).is_empty());
+    }
+
+    #[test]
+    fn test_mark_dispatched() {
+        let marked = mark_dispatched(TASKS, &[(2, "a".to_string()), (4, "b".to_string()), (6, "a".to_string())]);
+        assert!(marked.contains("\n- [x] Fix the login test (dispatched to a)\n"));
+        assert!(marked.contains("\n* [x] Add rate limiting (dispatched to b)\n"));
+        assert!(marked.contains("\n1. [x] Write the changelog (dispatched to a)\n"));
+        assert!(marked.ends_with("-no space\n"));
+        assert!(parse(&marked).is_empty());
+    }
+}
@@ -0,0 +1,313 @@
+//! Environment checks of `doctor`
+//!
+//! Everything a launch depends on is checked on its own, so a broken setup
+//! shows which piece is missing instead of failing halfway through a
+//! launch: the PowerShell scripts, `wslpath` and `powershell.exe` (window
+//! placement), Windows Terminal, the config and its WSL distribution and
+//! display, and the claude CLI of the `claude:` windows. Each check passes,
+//! warns (launching works, with less) or fails, and says how to fix it.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::path::Path;
+use std::process::Command;
+
+use crate::config::{self, Config, LaunchMode, TerminalKind};
+use crate::preflight::{self, WindowCheck};
+use crate::terminal::{self, wt_version};
+use crate::windows;
+use crate::wsl;
+
+/// Scripts the launcher runs through powershell.exe
+const SCRIPTS: &[&str] = &[
+    "close-window.ps1",
+    "focus-window.ps1",
+    "get-displays.ps1",
+    "get-window-rects.ps1",
+    "get-wt-version.ps1",
+    "get-wt-windows.ps1",
+    "hotkeys.ps1",
+    "move-window.ps1",
+    "notify.ps1",
+    "service.ps1",
+    "set-window-title.ps1",
+    "startup.ps1",
+    "toggle-window.ps1",
+    "type-text.ps1",
+];
+
+/// Outcome of a check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    Pass,
+    /// Launching works, but without something
+    Warn,
+    Fail,
+}
+
+impl Level {
+    pub fn label(self) -> &'static str {
+        match self {
+            Level::Pass => "PASS",
+            Level::Warn => "WARN",
+            Level::Fail => "FAIL",
+        }
+    }
+}
+
+/// Result of one check
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Check {
+    pub name: String,
+    pub level: Level,
+    pub detail: String,
+    /// What to do about a warning or failure
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fix: Option<String>,
+}
+
+impl Check {
+    fn pass(name: &str, detail: impl Into<String>) -> Self {
+        Self { name: name.to_string(), level: Level::Pass, detail: detail.into(), fix: None }
+    }
+
+    fn warn(name: &str, detail: impl Into<String>, fix: &str) -> Self {
+        Self { name: name.to_string(), level: Level::Warn, detail: detail.into(), fix: Some(fix.to_string()) }
+    }
+
+    fn fail(name: &str, detail: impl Into<String>, fix: &str) -> Self {
+        Self { name: name.to_string(), level: Level::Fail, detail: detail.into(), fix: Some(fix.to_string()) }
+    }
+}
+
+fn scripts() -> Check {
+    let name = "scripts";
+    let dir = match windows::get_scripts_dir() {
+        Ok(dir) if dir.is_dir() => dir,
+        _ => {
+            return Check::fail(
+                name,
+                "scripts directory not found",
+                "Run from the repository directory, or put scripts/ next to the executable",
+            );
+        }
+    };
+    let missing: Vec<&str> = SCRIPTS.iter().copied().filter(|s| !dir.join(s).is_file()).collect();
+    if missing.is_empty() {
+        Check::pass(name, dir.display().to_string())
+    } else {
+        Check::fail(
+            name,
+            format!("{} is missing {}", dir.display(), missing.join(", ")),
+            "Copy the scripts/ directory of this version next to the executable",
+        )
+    }
+}
+
+fn wslpath() -> Check {
+    match windows::wsl_to_windows_path(Path::new("/")) {
+        Ok(path) => Check::pass("wslpath", format!("/ is {}", path)),
+        Err(e) => Check::fail("wslpath", format!("{:#}", e), "Run wsl-multi-launcher inside a WSL distribution"),
+    }
+}
+
+fn powershell() -> Check {
+    let name = "powershell.exe";
+    let fix = "Enable Windows interop in WSL ([interop] enabled=true and appendWindowsPath=true in /etc/wsl.conf)";
+    let output = Command::new("powershell.exe")
+        .args(["-NoProfile", "-Command", "$PSVersionTable.PSVersion.ToString()"])
+        .output();
+    match output {
+        Ok(output) if output.status.success() => {
+            Check::pass(name, format!("version {}", String::from_utf8_lossy(&output.stdout).trim()))
+        }
+        Ok(output) => Check::fail(name, String::from_utf8_lossy(&output.stderr).trim().to_string(), fix),
+        Err(e) => Check::fail(name, format!("not reachable: {}", e), fix),
+    }
+}
+
+fn windows_terminal(config: Option<&Config>) -> Check {
+    let name = "wt.exe";
+    let install = "Install Windows Terminal (e.g. 'winget install Microsoft.WindowsTerminal')";
+    let used = config.is_none_or(|c| c.terminal == TerminalKind::WindowsTerminal);
+    if !terminal::is_available("wt.exe") {
+        // Tabs and panes can't do without it
+        return match config {
+            Some(c) if used && c.layout.mode != LaunchMode::Windows => {
+                Check::fail(name, "not found; layout.mode tabs / panes need Windows Terminal", install)
+            }
+            _ if used => Check::fail(name, "not found", install),
+            _ => Check::warn(name, "not found (not used by the configured terminal)", install),
+        };
+    }
+    let Some(version) = wt_version::detect() else {
+        return Check::pass(name, "installed (version unknown)");
+    };
+    let unsupported = config.map(|c| wt_version::unsupported_features(c, version)).unwrap_or_default();
+    if unsupported.is_empty() {
+        Check::pass(name, format!("version {}", version))
+    } else {
+        let features: Vec<&str> = unsupported.iter().map(|f| f.describe()).collect();
+        Check::warn(
+            name,
+            format!("version {} lacks {}", version, features.join(", ")),
+            "Update Windows Terminal",
+        )
+    }
+}
+
+fn configuration(path: &str) -> (Check, Option<Config>) {
+    let name = "config";
+    if !Path::new(path).exists() {
+        let check = Check::fail(name, format!("{} not found", path), "Run 'wsl-multi-launcher init' to create one");
+        return (check, None);
+    }
+    match config::load(path) {
+        Ok(config) => (Check::pass(name, format!("{} ({} windows)", path, config.windows.len())), Some(config)),
+        Err(e) => {
+            let check = Check::fail(name, format!("{:#}", e), "Fix the config; 'wsl-multi-launcher validate' shows the error");
+            (check, None)
+        }
+    }
+}
+
+/// Whether `wanted` is among the installed distributions
+fn distribution(installed: Result<Vec<String>>, wanted: &str) -> Check {
+    let name = "distribution";
+    match installed {
+        Ok(names) if names.iter().any(|n| n == wanted) => Check::pass(name, wanted),
+        Ok(names) => Check::fail(
+            name,
+            format!("'{}' is not installed (installed: {})", wanted, names.join(", ")),
+            "Set wsl_distribution to one of the installed distributions ('wsl.exe -l -q')",
+        ),
+        Err(e) => Check::fail(name, format!("{:#}", e), "Make sure wsl.exe can be run from WSL"),
+    }
+}
+
+/// Whether the displays could be queried and the target display exists
+fn displays(found: Result<usize>, target: Option<u32>) -> Check {
+    let name = "displays";
+    match (found, target) {
+        (Err(e), _) => Check::fail(name, format!("{:#}", e), "Check the powershell.exe and scripts checks above"),
+        (Ok(count), Some(target)) if target as usize >= count => Check::fail(
+            name,
+            format!("target_display {} doesn't exist ({} display(s))", target, count),
+            &format!("Set target_display to 0-{} ('wsl-multi-launcher displays' lists them)", count.saturating_sub(1)),
+        ),
+        (Ok(count), Some(target)) => Check::pass(name, format!("{} display(s), target_display {}", count, target)),
+        (Ok(count), None) => Check::pass(name, format!("{} display(s)", count)),
+    }
+}
+
+/// One check per claude window
+fn claude(checks: &[WindowCheck]) -> Vec<Check> {
+    checks
+        .iter()
+        .map(|check| {
+            let name = format!("claude ({})", check.window);
+            let shell = preflight::shell(check.login_shell);
+            match check.cli.problem() {
+                None => Check::pass(
+                    &name,
+                    format!(
+                        "{} {} ({})",
+                        check.cli.path.as_deref().unwrap_or_default(),
+                        check.cli.version.as_deref().unwrap_or_default(),
+                        shell
+                    ),
+                ),
+                Some(problem) => {
+                    let fix = if check.cli.path.is_none() && !check.login_shell {
+                        "claude installed via nvm or ~/.profile is only on the PATH of a login shell; set 'login_shell: true'"
+                    } else if check.cli.path.is_none() {
+                        "Install claude in the distribution ('npm install -g @anthropic-ai/claude-code')"
+                    } else if !check.cli.logged_in && problem.contains("not logged in") {
+                        "Run 'claude' in the distribution and log in"
+                    } else {
+                        "Update claude ('claude update')"
+                    };
+                    Check::fail(&name, format!("{} ({})", problem, shell), fix)
+                }
+            }
+        })
+        .collect()
+}
+
+/// Run every check with the config at `config_path`
+pub fn run(config_path: &str) -> Vec<Check> {
+    let mut checks = vec![scripts(), wslpath(), powershell()];
+    let (config_check, config) = configuration(config_path);
+    checks.push(windows_terminal(config.as_ref()));
+    checks.push(config_check);
+    if let Some(ref config) = config {
+        checks.push(distribution(wsl::distributions().map_err(anyhow::Error::from), &config.wsl_distribution));
+    }
+    checks.push(displays(windows::get_displays().map(|d| d.len()).map_err(anyhow::Error::from), config.as_ref().map(|c| c.target_display)));
+
+    if let Some(ref config) = config {
+        let windows: Vec<&config::WindowConfig> = config.windows.iter().collect();
+        match preflight::check_windows(config, &windows) {
+            Ok(found) => checks.extend(claude(&found)),
+            Err(e) => checks.push(Check::fail(
+                "claude",
+                format!("{:#}", e),
+                "Check the distribution check above",
+            )),
+        }
+    }
+    checks
+}
+
+/// Number of checks per level: (passed, warnings, failed)
+pub fn count(checks: &[Check]) -> (usize, usize, usize) {
+    let of = |level: Level| checks.iter().filter(|c| c.level == level).count();
+    (of(Level::Pass), of(Level::Warn), of(Level::Fail))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::preflight::ClaudeCli;
+
+    #[test]
+    fn test_distribution() {
+        let installed = || Ok(vec!["Ubuntu".to_string(), "Debian".to_string()]);
+        assert_eq!(distribution(installed(), "Ubuntu").level, Level::Pass);
+        let check = distribution(installed(), "Ubuntu-24.04");
+        assert_eq!(check.level, Level::Fail);
+        assert_eq!(check.detail, "'Ubuntu-24.04' is not installed (installed: Ubuntu, Debian)");
+        assert_eq!(distribution(Err(anyhow::anyhow!("wsl.exe failed")), "Ubuntu").level, Level::Fail);
+    }
+
+    #[test]
+    fn test_displays() {
+        assert_eq!(displays(Ok(2), Some(1)).level, Level::Pass);
+        let check = displays(Ok(2), Some(2));
+        assert_eq!(check.level, Level::Fail);
+        assert_eq!(check.fix.as_deref(), Some("Set target_display to 0-1 ('wsl-multi-launcher displays' lists them)"));
+        assert_eq!(displays(Ok(1), None).detail, "1 display(s)");
+        assert_eq!(displays(Err(anyhow::anyhow!("no powershell")), Some(0)).level, Level::Fail);
+    }
+
+    #[test]
+    fn test_claude() {
+        let ready = ClaudeCli {
+            path: Some("/usr/bin/claude".to_string()),
+            version: Some("2.0.14".to_string()),
+            logged_in: true,
+        };
+        let checks = claude(&[
+            WindowCheck { window: "a".to_string(), login_shell: false, cli: ready },
+            WindowCheck { window: "b".to_string(), login_shell: false, cli: ClaudeCli::default() },
+            WindowCheck { window: "c".to_string(), login_shell: true, cli: ClaudeCli::default() },
+        ]);
+        assert_eq!(checks[0].level, Level::Pass);
+        assert_eq!(checks[0].detail, "/usr/bin/claude 2.0.14 (bash -c)");
+        assert_eq!(checks[1].name, "claude (b)");
+        assert!(checks[1].fix.as_deref().unwrap().contains("login_shell: true"));
+        assert!(checks[2].fix.as_deref().unwrap().starts_with("Install claude"));
+        assert_eq!(count(&checks), (1, 0, 2));
+    }
+}
@@ -0,0 +1,65 @@
+//! Opening the config in an editor (`edit`)
+//!
+//! The editor is `$VISUAL` or `$EDITOR`, run through `sh` so values with
+//! arguments such as `code --wait` work. Without either (e.g. when started
+//! from Windows through wsl.exe) the file opens in Notepad.
+
+use anyhow::{Context, Result};
+use std::path::Path;
+use std::process::Command;
+
+use crate::{dry_run, windows};
+
+/// Editor command from `$VISUAL` or `$EDITOR` (read with `var`)
+fn configured(var: impl Fn(&str) -> Option<String>) -> Option<String> {
+    ["VISUAL", "EDITOR"].iter().find_map(|name| var(name).filter(|v| !v.trim().is_empty()))
+}
+
+/// Command opening `path` in `editor`
+fn command(editor: &str, path: &Path) -> Command {
+    let mut cmd = Command::new("sh");
+    cmd.args(["-c", &format!("{} \"$1\"", editor), "sh"]).arg(path);
+    cmd
+}
+
+/// Open `path` in the editor and wait for it to exit
+pub fn open(path: &Path) -> Result<()> {
+    let (mut cmd, name) = match configured(|name| std::env::var(name).ok()) {
+        Some(editor) => (command(&editor, path), editor),
+        None => {
+            let mut cmd = Command::new("notepad.exe");
+            cmd.arg(windows::wsl_to_windows_path(path)?);
+            (cmd, "notepad.exe".to_string())
+        }
+    };
+    if dry_run::skip(&cmd) {
+        return Ok(());
+    }
+    let status = cmd.status().with_context(|| format!("Failed to run {}", name))?;
+    if !status.success() {
+        anyhow::bail!("{} exited with {}", name, status);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_configured() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| vars.iter().find(|(n, _)| *n == name).map(|(_, v)| v.to_string())
+        };
+        assert_eq!(configured(env(&[])), None);
+        assert_eq!(configured(env(&[("EDITOR", "vim")])).as_deref(), Some("vim"));
+        assert_eq!(configured(env(&[("VISUAL", "code --wait"), ("EDITOR", "vim")])).as_deref(), Some("code --wait"));
+        assert_eq!(configured(env(&[("VISUAL", " "), ("EDITOR", "vim")])).as_deref(), Some("vim"));
+    }
+
+    #[test]
+    fn test_command() {
+        let cmd = command("code --wait", Path::new("/tmp/my config.yaml"));
+        assert_eq!(dry_run::command_line(&cmd), r#"sh -c 'code --wait "$1"' sh '/tmp/my config.yaml'"#);
+    }
+}
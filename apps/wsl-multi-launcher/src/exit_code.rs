@@ -0,0 +1,86 @@
+//! Exit code of the launcher itself
+//!
+//! Scripts and CI can branch on why a run failed without reading its
+//! output:
+//!
+//! | Code | Meaning |
+//! |------|---------|
+//! | 0 | Everything worked |
+//! | 1 | Any other error |
+//! | 2 | Some windows failed to launch |
+//! | 3 | The windows launched, but some couldn't be arranged |
+//! | 4 | The config is missing or invalid |
+//!
+//! Launch and move failures are read from the events the process recorded,
+//! so a command that carries on past a failed window still exits non-zero.
+
+use std::process::ExitCode;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::events::{self, EventKind};
+
+/// Why a run failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Failure {
+    Other,
+    PartialLaunch,
+    Arrangement,
+    Config,
+}
+
+impl Failure {
+    pub fn code(self) -> u8 {
+        match self {
+            Failure::Other => 1,
+            Failure::PartialLaunch => 2,
+            Failure::Arrangement => 3,
+            Failure::Config => 4,
+        }
+    }
+}
+
+static CONFIG: AtomicBool = AtomicBool::new(false);
+
+/// Note that the config couldn't be loaded
+pub fn config_failed() {
+    CONFIG.store(true, Ordering::Relaxed);
+}
+
+/// The failure of a run that ended in `error` (if any), from what was noted
+fn failure(error: bool, config: bool, launch_failed: bool, move_failed: bool) -> Option<Failure> {
+    if config {
+        Some(Failure::Config)
+    } else if launch_failed {
+        Some(Failure::PartialLaunch)
+    } else if move_failed {
+        Some(Failure::Arrangement)
+    } else {
+        error.then_some(Failure::Other)
+    }
+}
+
+/// Exit code of a run that ended in `error` (if any)
+pub fn exit_code(error: bool) -> ExitCode {
+    let failure = failure(
+        error,
+        CONFIG.load(Ordering::Relaxed),
+        events::recorded(EventKind::LaunchFailed),
+        events::recorded(EventKind::MoveFailed),
+    );
+    ExitCode::from(failure.map_or(0, Failure::code))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failure() {
+        assert_eq!(failure(false, false, false, false), None);
+        assert_eq!(failure(true, false, false, false), Some(Failure::Other));
+        assert_eq!(failure(false, false, false, true), Some(Failure::Arrangement));
+        // A window that didn't launch matters more than one left unarranged
+        assert_eq!(failure(false, false, true, true), Some(Failure::PartialLaunch));
+        assert_eq!(failure(true, true, false, false), Some(Failure::Config));
+    }
+}
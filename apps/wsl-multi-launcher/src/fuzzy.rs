@@ -0,0 +1,70 @@
+//! Window names given on the command line, matched loosely
+//!
+//! Commands taking a window name accept anything that picks out one window:
+//! the exact name, or else a prefix, a part of it, or its letters in order
+//! ("impl" and "implwin" both find `implementer-window`). The first of
+//! those that matches anything decides; if it matches several windows the
+//! command fails and lists them.
+
+use anyhow::Result;
+
+/// How `query` matches `name` (ignoring case), loosest last
+fn matches(tier: usize, query: &str, name: &str) -> bool {
+    match tier {
+        0 => name.starts_with(query),
+        1 => name.contains(query),
+        _ => {
+            let mut letters = name.chars();
+            query.chars().all(|c| letters.any(|l| l == c))
+        }
+    }
+}
+
+/// Index of the name in `names` that `query` picks out
+pub fn find(query: &str, names: &[&str]) -> Result<usize> {
+    if let Some(index) = names.iter().position(|&name| name == query) {
+        return Ok(index);
+    }
+    let lowered_query = query.to_lowercase();
+    let lowered: Vec<String> = names.iter().map(|name| name.to_lowercase()).collect();
+    for tier in 0..3 {
+        let found: Vec<usize> = (0..names.len()).filter(|&i| matches(tier, &lowered_query, &lowered[i])).collect();
+        match found[..] {
+            [] => continue,
+            [index] => return Ok(index),
+            _ => {
+                let candidates: Vec<&str> = found.iter().map(|&i| names[i]).collect();
+                anyhow::bail!("'{}' matches several windows: {}", query, candidates.join(", "));
+            }
+        }
+    }
+    anyhow::bail!("No window named '{}' (available: {})", query, names.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NAMES: [&str; 5] = ["implementer-window", "reviewer", "api", "api-tests", "docs"];
+
+    #[test]
+    fn test_find() {
+        assert_eq!(find("api", &NAMES).unwrap(), 2);
+        assert_eq!(find("impl", &NAMES).unwrap(), 0);
+        assert_eq!(find("Review", &NAMES).unwrap(), 1);
+        assert_eq!(find("tests", &NAMES).unwrap(), 3);
+        assert_eq!(find("implwin", &NAMES).unwrap(), 0);
+        assert_eq!(find("dcs", &NAMES).unwrap(), 4);
+    }
+
+    #[test]
+    fn test_find_fails() {
+        assert_eq!(find("ap", &NAMES).unwrap_err().to_string(), "'ap' matches several windows: api, api-tests");
+        // A looser match isn't tried once a stricter one is ambiguous
+        assert!(find("e", &NAMES).is_err());
+        assert_eq!(
+            find("xyz", &NAMES).unwrap_err().to_string(),
+            "No window named 'xyz' (available: implementer-window, reviewer, api, api-tests, docs)"
+        );
+    }
+}
@@ -0,0 +1,142 @@
+//! GitHub issues turned into worktree windows (`launch --from-github`)
+//!
+//! The open issues of a repository (optionally with a label) are read with
+//! the GitHub CLI inside the WSL distribution, so it uses whatever `gh auth`
+//! is set up there. Each issue becomes a `worktrees.branches` entry named
+//! after its number, whose claude session starts on the issue.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::process::Command;
+use tracing::debug;
+
+use crate::config::WorktreeBranch;
+use crate::shell;
+
+/// Characters of an issue body put into the prompt; the command line
+/// carrying it to the window is limited
+const MAX_BODY: usize = 8000;
+
+/// An open issue
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Issue {
+    pub number: u64,
+    pub title: String,
+    #[serde(default)]
+    pub body: Option<String>,
+    /// Set for pull requests, which the issues API lists too
+    #[serde(default)]
+    pull_request: Option<serde_json::Value>,
+}
+
+/// Issues in the output of `gh api --paginate` (one JSON array per page),
+/// without pull requests
+fn parse_issues(json: &str) -> Result<Vec<Issue>> {
+    let mut issues = Vec::new();
+    for page in serde_json::Deserializer::from_str(json).into_iter::<Vec<Issue>>() {
+        issues.extend(page.context("Unexpected response from the GitHub API")?);
+    }
+    issues.retain(|issue| issue.pull_request.is_none());
+    issues.sort_by_key(|issue| issue.number);
+    Ok(issues)
+}
+
+/// Open issues of `repo` ("owner/name"), only those with `label` if given
+pub fn fetch(distribution: &str, repo: &str, label: Option<&str>) -> Result<Vec<Issue>> {
+    if repo.split('/').filter(|part| !part.is_empty()).count() != 2 {
+        anyhow::bail!("Expected a GitHub repository as owner/name, got '{}'", repo);
+    }
+    let mut script = format!(
+        "gh api --paginate -X GET {} -f state=open -f per_page=100",
+        shell::quote(&format!("repos/{}/issues", repo))
+    );
+    if let Some(label) = label {
+        script.push_str(&format!(" -f labels={}", shell::quote(label)));
+    }
+    debug!("Fetching issues: {}", script);
+    // A login shell, so gh installed under ~/.local/bin etc. is found
+    let output = Command::new("wsl.exe")
+        .args(["-d", distribution, "--", "bash", "-lc", &script])
+        .output()
+        .context("Failed to run wsl.exe")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to list the issues of {} (is the GitHub CLI installed and logged in? run 'gh auth login'): {}",
+            repo,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    parse_issues(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Window, worktree and branch name of an issue
+pub fn window_name(issue: &Issue) -> String {
+    format!("issue-{}", issue.number)
+}
+
+/// Prompt a session working on the issue starts with
+fn prompt(repo: &str, issue: &Issue) -> String {
+    let mut prompt = format!("Work on GitHub issue {}#{}: {}", repo, issue.number, issue.title.trim());
+    let body = issue.body.as_deref().unwrap_or_default().trim();
+    if !body.is_empty() {
+        prompt.push_str("\n\n");
+        match body.char_indices().nth(MAX_BODY) {
+            Some((end, _)) => {
+                prompt.push_str(&body[..end]);
+                prompt.push_str("\n\n(The issue continues; read it in full with 'gh issue view'.)");
+            }
+            None => prompt.push_str(body),
+        }
+    }
+    prompt
+}
+
+/// `worktrees.branches` entry of an issue
+pub fn branch(repo: &str, issue: &Issue) -> WorktreeBranch {
+    WorktreeBranch::Named {
+        branch: window_name(issue),
+        name: window_name(issue),
+        prompt: Some(prompt(repo, issue)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_issues() {
+        let json = r#"[{"number": 12, "title": "Fix login", "body": "Steps..."},
+                       {"number": 3, "title": "A PR", "body": null, "pull_request": {"url": "x"}}]
+                      [{"number": 7, "title": "Add docs", "body": null, "labels": []}]"#;
+        let issues = parse_issues(json).unwrap();
+        let numbers: Vec<u64> = issues.iter().map(|i| i.number).collect();
+        assert_eq!(numbers, [7, 12]);
+        assert_eq!(issues[1].body.as_deref(), Some("Steps..."));
+        assert!(parse_issues("").unwrap().is_empty());
+        assert!(parse_issues(r#"{"message": "Not Found"}"#).is_err());
+    }
+
+    #[test]
+    fn test_branch() {
+        let issue = Issue {
+            number: 42,
+            title: "Fix login ".to_string(),
+            body: Some("It fails.\n".to_string()),
+            pull_request: None,
+        };
+        assert_eq!(
+            branch("acme/app", &issue),
+            WorktreeBranch::Named {
+                branch: "issue-42".to_string(),
+                name: "issue-42".to_string(),
+                prompt: Some("Work on GitHub issue acme/app#42: Fix login\n\nIt fails.".to_string()),
+            }
+        );
+
+        let long = Issue { body: Some("é".repeat(MAX_BODY + 1)), ..issue };
+        let WorktreeBranch::Named { prompt: Some(prompt), .. } = branch("acme/app", &long) else { panic!() };
+        assert!(prompt.ends_with("read it in full with 'gh issue view'.)"));
+        assert_eq!(prompt.matches('é').count(), MAX_BODY);
+    }
+}
@@ -0,0 +1,173 @@
+//! Lifecycle hooks (`hooks:` at global and per-window scope)
+//!
+//! Hook commands run one after another with the terminal's stdout/stderr.
+//! WSL hooks run with bash in the configured distribution (per-window hooks
+//! of WSL windows start in the window's working directory); Windows hooks
+//! run with PowerShell. Both see `WML_HOOK` (the event), `WML_SESSION` and,
+//! for per-window hooks, `WML_WINDOW`.
+
+use anyhow::{Context, Result};
+use std::process::Command;
+use tracing::info;
+
+use crate::config::{Config, HookCommand, HooksConfig, WindowConfig, WindowKind};
+use crate::dry_run;
+use crate::shell;
+
+/// Point of the lifecycle a hook runs at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookEvent {
+    PreLaunch,
+    PostLaunch,
+    PostArrange,
+    PreStop,
+}
+
+impl HookEvent {
+    /// Config key of the event, also passed as `WML_HOOK`
+    pub fn name(self) -> &'static str {
+        match self {
+            HookEvent::PreLaunch => "pre_launch",
+            HookEvent::PostLaunch => "post_launch",
+            HookEvent::PostArrange => "post_arrange",
+            HookEvent::PreStop => "pre_stop",
+        }
+    }
+
+    fn commands(self, hooks: &HooksConfig) -> &[HookCommand] {
+        match self {
+            HookEvent::PreLaunch => &hooks.pre_launch,
+            HookEvent::PostLaunch => &hooks.post_launch,
+            HookEvent::PostArrange => &hooks.post_arrange,
+            HookEvent::PreStop => &hooks.pre_stop,
+        }
+    }
+}
+
+/// Run the global hooks of `event`; stops at the first failing command
+pub fn run_global(config: &Config, session: &str, event: HookEvent) -> Result<()> {
+    run(&config.wsl_distribution, session, None, event, event.commands(&config.hooks))
+}
+
+/// Run a window's own hooks of `event`; stops at the first failing command
+pub fn run_window(config: &Config, session: &str, window: &WindowConfig, event: HookEvent) -> Result<()> {
+    run(&config.wsl_distribution, session, Some(window), event, event.commands(&window.hooks))
+}
+
+fn run(
+    distribution: &str,
+    session: &str,
+    window: Option<&WindowConfig>,
+    event: HookEvent,
+    commands: &[HookCommand],
+) -> Result<()> {
+    let vars = env_vars(event, session, window.map(|w| w.name.as_str()));
+    for hook in commands {
+        match window {
+            Some(window) => info!("Running {} hook of '{}': {}", event.name(), window.name, hook.command()),
+            None => info!("Running {} hook: {}", event.name(), hook.command()),
+        }
+        let mut cmd = match hook {
+            HookCommand::Wsl(command) => {
+                let working_dir = window
+                    .filter(|w| w.kind == WindowKind::Wsl)
+                    .and_then(|w| w.working_dir.as_deref());
+                let script = wsl_script(command, &vars, working_dir);
+                let mut cmd = Command::new("wsl.exe");
+                cmd.args(["-d", distribution, "--", "bash", "-c", &script]);
+                cmd
+            }
+            HookCommand::Windows { windows: command } => {
+                let mut cmd = Command::new("powershell.exe");
+                cmd.args(["-NoProfile", "-NonInteractive", "-Command", &powershell_script(command, &vars)]);
+                cmd
+            }
+        };
+        if dry_run::skip(&cmd) {
+            continue;
+        }
+        let status = cmd
+            .status()
+            .with_context(|| format!("Failed to run {}", cmd.get_program().to_string_lossy()))?;
+        if !status.success() {
+            anyhow::bail!("{} hook '{}' failed ({})", event.name(), hook.command(), status);
+        }
+    }
+    Ok(())
+}
+
+fn env_vars(event: HookEvent, session: &str, window: Option<&str>) -> Vec<(&'static str, String)> {
+    let mut vars = vec![("WML_HOOK", event.name().to_string()), ("WML_SESSION", session.to_string())];
+    if let Some(window) = window {
+        vars.push(("WML_WINDOW", window.to_string()));
+    }
+    vars
+}
+
+fn wsl_script(command: &str, vars: &[(&str, String)], working_dir: Option<&str>) -> String {
+    let exports: Vec<String> = vars.iter().map(|(k, v)| format!("{}={}", k, shell::quote(v))).collect();
+    let mut script = format!("export {}; ", exports.join(" "));
+    if let Some(dir) = working_dir {
+        script.push_str(&format!("cd {} || exit; ", shell::quote_path(dir)));
+    }
+    script.push_str(command);
+    script
+}
+
+fn powershell_script(command: &str, vars: &[(&str, String)]) -> String {
+    let mut script: String = vars
+        .iter()
+        .map(|(k, v)| format!("$env:{} = {}; ", k, shell::quote_powershell(v)))
+        .collect();
+    script.push_str(command);
+    script
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_env_vars() {
+        let vars = env_vars(HookEvent::PostArrange, "work", None);
+        assert_eq!(
+            vars,
+            vec![("WML_HOOK", "post_arrange".to_string()), ("WML_SESSION", "work".to_string())]
+        );
+        let vars = env_vars(HookEvent::PreStop, "work", Some("claude-1"));
+        assert_eq!(vars[2], ("WML_WINDOW", "claude-1".to_string()));
+    }
+
+    #[test]
+    fn test_wsl_script() {
+        let vars = env_vars(HookEvent::PreLaunch, "my work", Some("api"));
+        assert_eq!(
+            wsl_script("git pull", &vars, Some("~/src/api")),
+            "export WML_HOOK=pre_launch WML_SESSION='my work' WML_WINDOW=api; \
+             cd \"$HOME\"/src/api || exit; git pull"
+        );
+        assert_eq!(
+            wsl_script("~/bin/sync", &vars[..2], None),
+            "export WML_HOOK=pre_launch WML_SESSION='my work'; ~/bin/sync"
+        );
+    }
+
+    #[test]
+    fn test_powershell_script() {
+        let vars = env_vars(HookEvent::PostLaunch, "it's", None);
+        assert_eq!(
+            powershell_script("Start-Process slack:", &vars),
+            "$env:WML_HOOK = 'post_launch'; $env:WML_SESSION = 'it''s'; Start-Process slack:"
+        );
+    }
+
+    #[test]
+    fn test_event_commands() {
+        let hooks = HooksConfig {
+            pre_stop: vec![HookCommand::Wsl("echo bye".to_string())],
+            ..Default::default()
+        };
+        assert_eq!(HookEvent::PreStop.commands(&hooks).len(), 1);
+        assert!(HookEvent::PreLaunch.commands(&hooks).is_empty());
+    }
+}
@@ -0,0 +1,139 @@
+//! Language of the command line output (`--lang`)
+//!
+//! Messages live in Fluent files (`locales/*.ftl`) compiled into the
+//! binary. The language is `--lang`, or else the first of `LC_ALL`,
+//! `LC_MESSAGES` and `LANG` that is set; Japanese for `ja*`, English
+//! otherwise. A message missing from a translation falls back to English.
+//!
+//! Only messages people read are translated: status words such as
+//! OK / FAILED, logs and JSON output stay in English so scripts can match
+//! them.
+
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource};
+use std::sync::OnceLock;
+use unic_langid::LanguageIdentifier;
+
+const EN: &str = include_str!("../locales/en.ftl");
+const JA: &str = include_str!("../locales/ja.ftl");
+
+/// Language of the output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Lang {
+    En,
+    Ja,
+}
+
+impl Lang {
+    fn id(self) -> LanguageIdentifier {
+        let id = match self {
+            Lang::En => "en",
+            Lang::Ja => "ja",
+        };
+        id.parse().expect("valid language identifier")
+    }
+}
+
+static BUNDLE: OnceLock<FluentBundle<FluentResource>> = OnceLock::new();
+
+/// Language from `--lang` or the locale variables (read with `var`)
+fn detect(lang: Option<Lang>, var: impl Fn(&str) -> Option<String>) -> Lang {
+    if let Some(lang) = lang {
+        return lang;
+    }
+    let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .find_map(|name| var(name).filter(|v| !v.is_empty()))
+        .unwrap_or_default();
+    if locale.starts_with("ja") { Lang::Ja } else { Lang::En }
+}
+
+fn bundle(lang: Lang) -> FluentBundle<FluentResource> {
+    let resource = |source: &str| FluentResource::try_new(source.to_string()).expect("valid Fluent file");
+    let mut bundle = FluentBundle::new_concurrent(vec![lang.id()]);
+    // Unicode isolation marks show up as garbage in some terminals
+    bundle.set_use_isolating(false);
+    bundle.add_resource(resource(EN)).expect("unique message ids");
+    if lang == Lang::Ja {
+        bundle.add_resource_overriding(resource(JA));
+    }
+    bundle
+}
+
+/// Pick the output language (`--lang`, if given)
+pub fn set(lang: Option<Lang>) {
+    let lang = detect(lang, |name| std::env::var(name).ok());
+    let _ = BUNDLE.set(bundle(lang));
+}
+
+/// Message `id` in the output language, with `args` filled in
+pub fn message(id: &str, args: Option<&FluentArgs>) -> String {
+    let bundle = BUNDLE.get_or_init(|| bundle(Lang::En));
+    let Some(pattern) = bundle.get_message(id).and_then(|m| m.value()) else {
+        return id.to_string();
+    };
+    let mut errors = Vec::new();
+    bundle.format_pattern(pattern, args, &mut errors).into_owned()
+}
+
+/// Localized message: `t!("launch-done", count = 4)`
+#[macro_export]
+macro_rules! t {
+    ($id:literal) => {
+        $crate::i18n::message($id, None)
+    };
+    ($id:literal, $($name:ident = $value:expr),+ $(,)?) => {{
+        let mut args = fluent_bundle::FluentArgs::new();
+        $(args.set(stringify!($name), $value);)+
+        $crate::i18n::message($id, Some(&args))
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fluent_bundle::FluentValue;
+    use std::collections::BTreeSet;
+
+    /// Ids of the messages in a Fluent file (lines starting with `id =`)
+    fn ids(source: &str) -> BTreeSet<&str> {
+        source
+            .lines()
+            .filter(|line| line.starts_with(|c: char| c.is_ascii_alphabetic()))
+            .filter_map(|line| line.split_once(" =").map(|(id, _)| id))
+            .collect()
+    }
+
+    #[test]
+    fn test_translations_complete() {
+        assert!(ids(EN).contains("launch-done"));
+        assert_eq!(ids(EN), ids(JA));
+    }
+
+    #[test]
+    fn test_detect() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| vars.iter().find(|(n, _)| *n == name).map(|(_, v)| v.to_string())
+        };
+        assert_eq!(detect(None, env(&[])), Lang::En);
+        assert_eq!(detect(None, env(&[("LANG", "ja_JP.UTF-8")])), Lang::Ja);
+        // LC_ALL wins over LANG; an empty one doesn't count
+        assert_eq!(detect(None, env(&[("LC_ALL", "C.UTF-8"), ("LANG", "ja_JP.UTF-8")])), Lang::En);
+        assert_eq!(detect(None, env(&[("LC_ALL", ""), ("LANG", "ja_JP.UTF-8")])), Lang::Ja);
+        assert_eq!(detect(Some(Lang::En), env(&[("LANG", "ja_JP.UTF-8")])), Lang::En);
+    }
+
+    #[test]
+    fn test_format() {
+        let format = |lang: Lang, id: &str| {
+            let bundle = bundle(lang);
+            let mut args = FluentArgs::new();
+            args.set("count", FluentValue::from(3));
+            let pattern = bundle.get_message(id).and_then(|m| m.value()).unwrap();
+            bundle.format_pattern(pattern, Some(&args), &mut Vec::new()).into_owned()
+        };
+        assert_eq!(format(Lang::En, "launch-done"), "Done! 3 windows launched.");
+        assert_eq!(format(Lang::Ja, "launch-done"), "完了！ 3 個のウィンドウを起動しました。");
+        assert_eq!(message("no-such-message", None), "no-such-message");
+    }
+}
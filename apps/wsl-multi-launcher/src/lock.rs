@@ -0,0 +1,91 @@
+//! Per-session lock held while windows are launched or arranged
+//!
+//! Two invocations at once (e.g. a hotkey pressed twice) would interleave
+//! window creation and fight over the arrangement, so commands that open or
+//! move windows take `<session>.lock` next to the state file first. The lock
+//! is an OS file lock, released when the process exits even if it crashes.
+
+use anyhow::{Context, Result};
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::io::{Read, Seek, Write};
+use std::path::{Path, PathBuf};
+
+use crate::state::SessionState;
+
+/// Held lock; released when dropped
+#[derive(Debug)]
+pub struct SessionLock {
+    _file: File,
+}
+
+impl SessionLock {
+    /// Path of the lock file of `session`
+    pub fn path(session: &str) -> Result<PathBuf> {
+        Ok(SessionState::default_dir()?.join(format!("{}.lock", session)))
+    }
+
+    /// Lock `session` for `action` (e.g. "launch"), failing right away if
+    /// another process holds the lock
+    pub fn acquire(session: &str, action: &str) -> Result<Self> {
+        Self::acquire_at(&Self::path(session)?, action).with_context(|| format!("Session '{}'", session))
+    }
+
+    fn acquire_at(path: &Path, action: &str) -> Result<Self> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)
+            .with_context(|| format!("Failed to open lock file {}", path.display()))?;
+
+        match file.try_lock() {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => {
+                let mut holder = String::new();
+                let _ = file.read_to_string(&mut holder);
+                let holder = holder.trim();
+                if holder.is_empty() {
+                    anyhow::bail!("Another launch is in progress; try again when it has finished");
+                }
+                anyhow::bail!(
+                    "Another launch is in progress ({}); try again when it has finished",
+                    holder
+                );
+            }
+            Err(TryLockError::Error(e)) => {
+                return Err(e).with_context(|| format!("Failed to lock {}", path.display()));
+            }
+        }
+
+        // Record the holder for the error message of the next invocation
+        file.set_len(0)?;
+        file.rewind()?;
+        write!(file, "{} by PID {}", action, std::process::id())?;
+        file.flush()?;
+        Ok(Self { _file: file })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lock_is_exclusive_until_dropped() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state").join("work.lock");
+
+        let lock = SessionLock::acquire_at(&path, "launch").unwrap();
+        let err = SessionLock::acquire_at(&path, "arrange").unwrap_err().to_string();
+        assert!(err.contains("Another launch is in progress"), "{}", err);
+        assert!(err.contains(&format!("launch by PID {}", std::process::id())), "{}", err);
+
+        drop(lock);
+        assert!(SessionLock::acquire_at(&path, "arrange").is_ok());
+        assert!(fs::read_to_string(&path).unwrap().starts_with("arrange by PID"));
+    }
+}
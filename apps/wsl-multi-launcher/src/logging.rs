@@ -0,0 +1,88 @@
+//! Log output: the console (`-v` / `-vv` / `-vvv`, `--quiet`) and an
+//! optional log file (`--log-file`)
+//!
+//! The log file has its own level, at least debug, so a flaky launch can be
+//! traced on disk while the terminal only shows warnings.
+
+use anyhow::{Context, Result};
+use std::fs::OpenOptions;
+use std::path::Path;
+use std::sync::Mutex;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer, Registry, fmt};
+
+use crate::output;
+
+type BoxedLayer = Box<dyn Layer<Registry> + Send + Sync>;
+
+fn level(verbose: u8) -> &'static str {
+    match verbose {
+        0 => "warn",
+        1 => "info",
+        2 => "debug",
+        _ => "trace",
+    }
+}
+
+/// Console level: warnings by default, one level more per `-v`, errors
+/// only with `--quiet`
+fn console_level(verbose: u8, quiet: bool) -> &'static str {
+    if quiet { "error" } else { level(verbose) }
+}
+
+/// Log file level: debug, or trace with `-vvv`
+fn file_level(verbose: u8) -> &'static str {
+    level(verbose.max(2))
+}
+
+/// Layer appending to the log file at `path`
+fn file_layer(path: &Path, verbose: u8) -> Result<BoxedLayer> {
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed to open log file {}", path.display()))?;
+    Ok(fmt::layer()
+        .with_writer(Mutex::new(file))
+        .with_ansi(false)
+        .with_thread_ids(true)
+        .with_filter(EnvFilter::new(file_level(verbose)))
+        .boxed())
+}
+
+/// Start logging for the rest of the process
+pub fn init(verbose: u8, quiet: bool, log_file: Option<&Path>) -> Result<()> {
+    // JSON output keeps stdout to the JSON document
+    let console = if output::is_json() {
+        fmt::layer().with_writer(std::io::stderr).with_ansi(false).boxed()
+    } else {
+        fmt::layer().with_ansi(output::is_color()).boxed()
+    };
+    let mut layers = vec![console.with_filter(EnvFilter::new(console_level(verbose, quiet))).boxed()];
+    if let Some(path) = log_file {
+        layers.push(file_layer(path, verbose)?);
+    }
+    tracing_subscriber::registry().with(layers).init();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_levels() {
+        assert_eq!(console_level(0, false), "warn");
+        assert_eq!(console_level(1, false), "info");
+        assert_eq!(console_level(2, false), "debug");
+        assert_eq!(console_level(5, false), "trace");
+        assert_eq!(console_level(0, true), "error");
+        // The file is independent of the console
+        assert_eq!(file_level(0), "debug");
+        assert_eq!(file_level(3), "trace");
+    }
+}
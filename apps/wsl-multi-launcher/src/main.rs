@@ -1,13 +1,57 @@
 use anyhow::{Context, Result};
-use clap::{Parser, Subcommand};
-use std::path::Path;
+use clap::{CommandFactory, Parser, Subcommand};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::Arc;
 use tracing::{debug, info, warn};
-use tracing_subscriber::EnvFilter;
 
-mod config;
-mod layout;
-mod windows;
-mod wsl;
+mod alias;
+mod attach;
+mod budget;
+mod config_edit;
+mod confirm;
+mod dashboard;
+mod diagnostics;
+mod discover;
+mod dispatch;
+mod doctor;
+mod editor;
+mod exit_code;
+mod fuzzy;
+mod github;
+mod hooks;
+mod i18n;
+mod lock;
+mod logging;
+mod metrics;
+mod multiplexer;
+mod output;
+mod parallel;
+mod plan;
+mod preflight;
+mod provision;
+mod ratelimit;
+mod reference;
+mod snapshot;
+mod startup;
+mod summary;
+mod swarm;
+mod timing;
+mod transcripts;
+mod tui;
+mod usage;
+mod watchdog;
+
+use wsl_launcher_core::{
+    claude, config, dry_run, error, events, exit_status, health, hotkey, ipc, layout, logs, queue, shell, state, terminal,
+    windows, worktree, wsl, wt_profiles,
+};
+use config::LaunchMode;
+use events::EventKind;
+use hooks::HookEvent;
+use summary::Outcome;
+use terminal::TerminalBackend;
 
 #[derive(Parser)]
 #[command(name = "wsl-multi-launcher")]
@@ -25,15 +69,52 @@ mod wsl;
 
   # Validate configuration
   wsl-multi-launcher -c config.yaml validate
+
+  # Run two workspaces side by side
+  wsl-multi-launcher -c client.yaml --session client-a launch
+  wsl-multi-launcher -c client.yaml --session client-b launch
 ")]
 struct Cli {
     /// Path to config file
     #[arg(short, long, default_value = "config.yaml")]
     config: String,
 
-    /// Enable verbose logging
-    #[arg(short, long)]
-    verbose: bool,
+    /// More logging: -v info, -vv debug, -vvv trace (warnings only by default)
+    #[arg(short, long, action = clap::ArgAction::Count)]
+    verbose: u8,
+
+    /// Only print results and errors (no progress messages or logs)
+    #[arg(short, long, conflicts_with = "verbose")]
+    quiet: bool,
+
+    /// Output format of displays, status, validate, ps, events, usage and
+    /// launch (json prints one JSON document on stdout)
+    #[arg(long, value_enum, default_value_t = output::Format::Text)]
+    output: output::Format,
+
+    /// Don't color the output (also off with NO_COLOR set or when stdout isn't a terminal)
+    #[arg(long)]
+    no_color: bool,
+
+    /// Session name that windows are tracked under (defaults to the config file name)
+    #[arg(long, global = true, value_parser = parse_session)]
+    session: Option<String>,
+
+    /// Print the commands and window moves instead of running them
+    #[arg(long, global = true)]
+    dry_run: bool,
+
+    /// Language of the messages (defaults to the locale: LC_ALL, LC_MESSAGES or LANG)
+    #[arg(long, value_enum, global = true)]
+    lang: Option<i18n::Lang>,
+
+    /// Also append the logs to this file, at debug level (trace with -vvv) whatever the console shows
+    #[arg(long, value_name = "PATH", global = true)]
+    log_file: Option<PathBuf>,
+
+    /// Answer yes to confirmation prompts (stop --all, init --force, reconcile --apply closing windows, worktree cleanup)
+    #[arg(short, long, global = true)]
+    yes: bool,
 
     #[command(subcommand)]
     command: Commands,
@@ -58,6 +139,10 @@ enum Commands {
         /// Force overwrite existing config
         #[arg(short, long)]
         force: bool,
+
+        /// Generate the windows, grid and display from the open Windows Terminal windows
+        #[arg(long, conflicts_with_all = ["windows", "grid", "display"])]
+        from_running: bool,
     },
 
     /// Launch all configured windows
@@ -65,390 +150,4195 @@ enum Commands {
         /// Skip window arrangement (just launch)
         #[arg(long)]
         no_arrange: bool,
+
+        /// Override the layout mode from the config
+        #[arg(long, value_enum)]
+        mode: Option<LaunchMode>,
+
+        /// Only launch the windows that aren't already running (after a
+        /// launch that failed halfway)
+        #[arg(long)]
+        resume: bool,
+
+        /// If the launch fails, write a diagnostics zip for a bug report
+        #[arg(long)]
+        collect_diagnostics: bool,
+
+        /// Don't check that claude is installed and logged in before launching claude: windows
+        #[arg(long)]
+        no_preflight: bool,
+
+        /// Also launch a worktree and claude window per open issue of this GitHub repository (owner/name)
+        #[arg(long, value_name = "REPO")]
+        from_github: Option<String>,
+
+        /// Only issues with this label (with --from-github)
+        #[arg(long, requires = "from_github")]
+        label: Option<String>,
+
+        #[command(flatten)]
+        filter: config::WindowFilter,
+
+        /// Only launch this window, by its exact name even with commas in it (used by `tui`)
+        #[arg(long = "window", value_name = "NAME", hide = true)]
+        windows: Vec<String>,
+    },
+
+    /// Relaunch windows whose tmux/zellij session outlived its terminal
+    Resume {
+        /// Skip window arrangement (just launch)
+        #[arg(long)]
+        no_arrange: bool,
+    },
+
+    /// Adopt already open terminal windows into the session instead of launching them
+    Attach {
+        /// Pick a window by hand for every window that couldn't be matched
+        #[arg(short, long)]
+        interactive: bool,
+
+        /// Record the windows without moving them into their cells
+        #[arg(long)]
+        no_arrange: bool,
+    },
+
+    /// Save the running windows as a named workspace, or restore one
+    Session {
+        #[command(subcommand)]
+        action: SessionAction,
+    },
+
+    /// Focus a launched window, tab or pane by name
+    Focus {
+        /// Window name from the config
+        name: String,
+
+        /// Layout mode the session was launched in (defaults to the config)
+        #[arg(long, value_enum)]
+        mode: Option<LaunchMode>,
+    },
+
+    /// Type a prompt into a window's running session (e.g. claude) and submit it
+    Prompt {
+        /// Window name from the config
+        name: String,
+
+        /// Text to send
+        text: String,
+
+        /// Layout mode the session was launched in (defaults to the config)
+        #[arg(long, value_enum)]
+        mode: Option<LaunchMode>,
+    },
+
+    /// Type the same text into every window's session (e.g. "/compact")
+    Broadcast {
+        /// Text to send
+        text: String,
+
+        /// Only windows with any of these tags (comma-separated)
+        #[arg(long, value_delimiter = ',')]
+        tags: Vec<String>,
+
+        /// Layout mode the session was launched in (defaults to the config)
+        #[arg(long, value_enum)]
+        mode: Option<LaunchMode>,
+    },
+
+    /// Send the queued tasks of a Markdown task list to windows, one task per prompt
+    Dispatch {
+        /// Task file; unchecked top-level list items are the queued tasks
+        #[arg(short, long)]
+        file: String,
+
+        /// Only windows with any of these tags (comma-separated; default: claude: windows)
+        #[arg(long, value_delimiter = ',')]
+        tags: Vec<String>,
+
+        /// Only send to idle claude sessions, one task each, instead of round-robin
+        #[arg(long)]
+        idle: bool,
+
+        /// Layout mode the session was launched in (defaults to the config)
+        #[arg(long, value_enum)]
+        mode: Option<LaunchMode>,
+    },
+
+    /// Show or hide a dropdown window
+    Toggle {
+        /// Dropdown window name from the config
+        name: String,
+    },
+
+    /// Close windows recorded by a previous launch
+    Stop {
+        /// Window names to close
+        #[arg(required_unless_present = "all")]
+        names: Vec<String>,
+
+        /// Close every window of the session
+        #[arg(long, conflicts_with = "names")]
+        all: bool,
+
+        /// Kill the terminal process if a window doesn't close
+        #[arg(long)]
+        force: bool,
+
+        /// Seconds to wait after SIGTERM before closing (overrides stop.grace_secs; 0 = no wait)
+        #[arg(long)]
+        grace: Option<u64>,
+
+        /// Remove the git worktrees of closed worktree windows whose branches are merged
+        #[arg(long)]
+        cleanup: bool,
+
+        /// Archive the transcripts of stopped claude windows (sets stop.archive_transcripts)
+        #[arg(long)]
+        archive: bool,
+    },
+
+    /// Close a window and launch it again in its grid cell
+    Restart {
+        /// Window name from the config
+        name: String,
+
+        /// Kill the terminal process if the window doesn't close
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// List the windows of the session and their live status
+    Ps {
+        /// Same as `--output json`
+        #[arg(long, hide = true)]
+        json: bool,
+    },
+
+    /// Full-screen view of every window: status, activity, uptime and output
+    Dashboard {
+        /// Layout mode the session was launched in (defaults to the config)
+        #[arg(long, value_enum)]
+        mode: Option<LaunchMode>,
+    },
+
+    /// Pick profiles and windows to launch interactively, with a grid preview and live progress
+    Tui,
+
+    /// Launch missing windows and keep relaunching any that exit
+    Watch {
+        /// Seconds between checks (overrides watch.interval_secs)
+        #[arg(long)]
+        interval: Option<u64>,
+
+        /// Restarts per window before giving up (overrides watch.max_restarts)
+        #[arg(long)]
+        max_restarts: Option<u32>,
+
+        /// Initial restart delay in seconds (overrides watch.backoff_secs)
+        #[arg(long)]
+        backoff: Option<u64>,
+
+        /// Serve Prometheus metrics on this address (overrides watch.metrics_addr)
+        #[arg(long, value_name = "ADDR")]
+        metrics: Option<String>,
+
+        /// Raise a Windows toast when a command exits or claude needs input (sets watch.notify)
+        #[arg(long)]
+        notify: bool,
+
+        /// Show model, branch and activity in the claude windows' titles (sets watch.status_titles)
+        #[arg(long)]
+        status_titles: bool,
+    },
+
+    /// Keep snapping windows the user moved back into their cells
+    Hold {
+        /// Seconds a moved window must stay put before it is snapped
+        #[arg(long, default_value = "3")]
+        grace: u64,
+
+        /// Snap to the nearest free cell instead of the assigned one
+        #[arg(long)]
+        nearest: bool,
+
+        /// Seconds between checks
+        #[arg(long, default_value = "1")]
+        interval: u64,
+    },
+
+    /// Show the output log of a window (requires logs.enabled)
+    Logs {
+        /// Window name from the config
+        name: String,
+
+        /// Keep printing new output as it is written
+        #[arg(short, long)]
+        follow: bool,
+
+        /// Number of lines to show
+        #[arg(short = 'n', long, default_value_t = 50)]
+        lines: usize,
+    },
+
+    /// Show the event log (launches, moves, retries, restarts)
+    Events {
+        /// Only events newer than this (e.g. "30m", "1h", "2h")
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Include events of every session
+        #[arg(long)]
+        all: bool,
+
+        /// Same as `--output json`
+        #[arg(long, hide = true)]
+        json: bool,
+    },
+
+    /// Launch N claude workers on one plan, each on its own branch and git worktree
+    Swarm {
+        /// Number of workers
+        #[arg(short = 'n', long)]
+        count: usize,
+
+        /// Plan every worker starts with, followed by its own part of it
+        #[arg(short, long)]
+        prompt_file: String,
+
+        /// Repository the worktrees are added to (default: the current directory)
+        #[arg(long)]
+        repo: Option<String>,
+
+        /// Commit the workers' branches start from (default: the repo's HEAD)
+        #[arg(long)]
+        base: Option<String>,
+
+        /// Name of the swarm: its session, config file (<name>.yaml) and window prefix
+        #[arg(long, default_value = "swarm", value_parser = parse_session)]
+        name: String,
+
+        /// Model of the workers (e.g. "sonnet")
+        #[arg(long)]
+        model: Option<String>,
+
+        /// Permission mode of the workers (e.g. "acceptEdits")
+        #[arg(long)]
+        permission_mode: Option<String>,
+
+        /// Overwrite an existing <name>.yaml
+        #[arg(short, long)]
+        force: bool,
+
+        /// Skip window arrangement (just launch)
+        #[arg(long)]
+        no_arrange: bool,
+    },
+
+    /// Add tasks to the queue: workers, or show what they are working on
+    Queue {
+        #[command(subcommand)]
+        action: QueueAction,
+    },
+
+    /// Show token usage and estimated cost of the claude: windows
+    Usage {
+        /// Only usage newer than this (e.g. "30m", "1h", "24h")
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Same as `--output json`
+        #[arg(long, hide = true)]
+        json: bool,
+    },
+
+    /// List the archived claude transcripts of a window
+    Transcripts {
+        /// Window name
+        window: String,
+
+        /// Open the newest archive in Explorer
+        #[arg(long)]
+        open: bool,
+    },
+
+    /// Start the workspace automatically at Windows logon
+    InstallStartup {
+        /// Register as a Startup folder shortcut or a Scheduled Task
+        #[arg(long, value_enum, default_value_t)]
+        method: startup::StartupMethod,
+
+        /// Run `watch` (and keep relaunching windows) instead of `launch`
+        #[arg(long)]
+        watch: bool,
+    },
+
+    /// Remove the logon entry created by install-startup
+    RemoveStartup,
+
+    /// Run `watch` as a Windows service, independent of any logged-in terminal
+    Service {
+        #[command(subcommand)]
+        action: ServiceAction,
     },
 
-    /// Show current configuration
-    Config,
+    /// Show current configuration, or read / change one value of the config file
+    Config {
+        #[command(subcommand)]
+        action: Option<ConfigAction>,
+    },
 
     /// Validate configuration file
     Validate,
 
+    /// Open the config file in $VISUAL / $EDITOR (Notepad without one) and validate it when the editor exits
+    Edit,
+
     /// Show display information
-    Displays,
+    Displays {
+        /// Keep watching and print the displays again whenever they change
+        #[arg(long)]
+        watch: bool,
+
+        /// Seconds between checks with --watch
+        #[arg(long, default_value = "2", requires = "watch")]
+        interval: u64,
+    },
 
     /// Arrange existing windows (without launching new ones)
-    Arrange,
+    Arrange {
+        /// Grid to use instead of layout.grid (e.g. 3x3); the windows arranged fill its cells in order
+        #[arg(long, value_name = "COLSxROWS")]
+        grid: Option<String>,
+
+        /// Display to use instead of target_display
+        #[arg(long, value_name = "INDEX")]
+        display: Option<u32>,
+
+        #[command(flatten)]
+        filter: config::WindowFilter,
+    },
+
+    /// Show how the running windows differ from the config
+    Plan,
+
+    /// Launch, close and move windows so the session matches the config
+    Reconcile {
+        /// Make the changes (otherwise they are only shown)
+        #[arg(long)]
+        apply: bool,
+
+        /// Kill extra windows that don't close
+        #[arg(short, long)]
+        force: bool,
+    },
 
     /// Show system status and available WSL distributions
     Status,
+
+    /// Check the environment (scripts, powershell.exe, wt.exe, wslpath, distribution, displays, config, claude CLI) and suggest fixes
+    Doctor,
+
+    /// Export configuration to other formats
+    Export {
+        #[command(subcommand)]
+        target: ExportTarget,
+    },
+
+    /// Generate the man pages and markdown reference of every command (for packaging)
+    #[command(hide = true)]
+    Docs {
+        /// Directory to write man/ and markdown/ into
+        #[arg(short, long, default_value = "docs/reference")]
+        out: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum SessionAction {
+    /// Save the running windows, their commands and exact positions
+    Save {
+        /// Name to save the workspace as
+        name: String,
+
+        /// Overwrite an existing saved session
+        #[arg(short, long)]
+        force: bool,
+    },
+
+    /// Launch the windows of a saved session at their saved positions
+    Restore {
+        /// Saved session name
+        name: String,
+    },
+
+    /// List saved sessions
+    List,
+
+    /// Delete a saved session
+    Delete {
+        /// Saved session name
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum ServiceAction {
+    /// Register the service (needs an elevated terminal)
+    Install,
+
+    /// Remove the service
+    Uninstall,
+
+    /// Start the service
+    Start,
+
+    /// Stop the service
+    Stop,
+
+    /// Show whether the service is installed and running
+    Status,
+}
+
+#[derive(Subcommand)]
+enum ConfigAction {
+    /// Print a value of the config file, e.g. `config get layout.grid`
+    Get {
+        /// Keys joined by dots, with [n] for list items (e.g. windows[2].command)
+        path: String,
+    },
+
+    /// Change a value of the config file, e.g. `config set windows[2].command htop`
+    Set {
+        /// Keys joined by dots, with [n] for list items (e.g. windows[2].command)
+        path: String,
+
+        /// New value, read as YAML (e.g. 3, true, "[a, b]"); anything else is a string
+        value: String,
+    },
 }
 
-fn main() -> Result<()> {
-    let cli = Cli::parse();
+#[derive(Subcommand)]
+enum QueueAction {
+    /// Add a task (the prompt text, or a file with --file)
+    Add {
+        /// Prompt of the task
+        #[arg(required_unless_present = "file", conflicts_with = "file")]
+        text: Option<String>,
 
-    // Initialize logging
-    let filter = if cli.verbose {
-        EnvFilter::new("debug")
-    } else {
-        EnvFilter::new("info")
+        /// Read the prompt from a file
+        #[arg(short, long)]
+        file: Option<String>,
+    },
+
+    /// Show pending, running, done and failed tasks
+    Status,
+}
+
+#[derive(Subcommand)]
+enum ExportTarget {
+    /// Windows Terminal profile fragment (one profile per window)
+    WtProfiles {
+        /// Write to a file instead of stdout
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// Install into Windows Terminal's fragments directory
+        #[arg(long, conflicts_with = "output")]
+        install: bool,
+    },
+}
+
+fn main() -> ExitCode {
+    let args = std::env::args_os().map(|arg| arg.to_string_lossy().into_owned()).collect();
+    let cli = match alias::resolve(args, Cli::command()) {
+        Ok(args) => Cli::parse_from(args),
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            return ExitCode::FAILURE;
+        }
     };
-    tracing_subscriber::fmt().with_env_filter(filter).init();
+
+    // `ps`, `events` and `usage` still take the `--json` they had before `--output`
+    let json_flag = matches!(
+        cli.command,
+        Commands::Ps { json: true } | Commands::Events { json: true, .. } | Commands::Usage { json: true, .. }
+    );
+    output::set(if json_flag { output::Format::Json } else { cli.output });
+    output::set_quiet(cli.quiet);
+    output::set_color(cli.no_color);
+    dry_run::set(cli.dry_run);
+    i18n::set(cli.lang);
+    confirm::set(cli.yes);
+    if let Err(e) = logging::init(cli.verbose, cli.quiet, cli.log_file.as_deref()) {
+        eprintln!("Error: {:#}", e);
+        return ExitCode::FAILURE;
+    }
 
     info!("wsl-multi-launcher v{}", env!("CARGO_PKG_VERSION"));
+    events::init(&session_name(&cli.config, cli.session.as_deref()));
+
+    let result = run(cli);
+    if let Err(ref e) = result {
+        if error::kind(e.as_ref()) == "config" {
+            exit_code::config_failed();
+        }
+        // Unless the command already reported it in its own document
+        if output::is_json() && !output::printed_json() {
+            let _ = output::print_json(&serde_json::json!({
+                "error": { "kind": error::kind(e.as_ref()), "message": format!("{:#}", e) },
+            }));
+        }
+        eprintln!("Error: {:?}", e);
+    }
+    exit_code::exit_code(result.is_err())
+}
 
+fn run(cli: Cli) -> Result<()> {
     match cli.command {
-        Commands::Init { windows: num_windows, grid, display, force } => {
+        Commands::Init { windows: num_windows, grid, display, force, from_running } => {
             let config_path = Path::new(&cli.config);
 
             if config_path.exists() && !force {
-                anyhow::bail!(
-                    "Config file '{}' already exists. Use --force to overwrite.",
-                    cli.config
-                );
+                anyhow::bail!("{}", t!("init-exists", path = cli.config.as_str()));
+            }
+            if config_path.exists() && !confirm::destructive(&t!("init-confirm-overwrite", path = cli.config.as_str()))? {
+                return Ok(());
             }
 
             // Get available WSL distributions
-            let distros = get_wsl_distributions()?;
+            let distros = wsl::distributions()?;
             let default_distro = distros.first()
                 .map(|s| s.as_str())
                 .unwrap_or("Ubuntu-24.04");
 
             // Generate config content
-            let config_content = generate_config(&grid, display, num_windows, default_distro)?;
+            let config_content = if from_running {
+                let discovered = discover_running(default_distro)?;
+                let names: Vec<String> = discovered.windows.iter().map(|(name, _)| name.clone()).collect();
+                let grid = format!("{}x{}", discovered.cols, discovered.rows);
+                generate_config(&grid, discovered.display, &names, default_distro)?
+            } else {
+                let names: Vec<String> = (1..=num_windows).map(|i| format!("window-{}", i)).collect();
+                generate_config(&grid, display, &names, default_distro)?
+            };
 
             std::fs::write(config_path, &config_content)
                 .with_context(|| format!("Failed to write config file: {}", cli.config))?;
 
-            println!("Created config file: {}", cli.config);
+            println!("{}", t!("init-created", path = cli.config.as_str()));
             println!();
-            println!("Next steps:");
-            println!("  1. Edit {} to customize your windows", cli.config);
-            println!("  2. Run 'wsl-multi-launcher displays' to see available displays");
-            println!("  3. Run 'wsl-multi-launcher validate' to check your config");
-            println!("  4. Run 'wsl-multi-launcher launch' to start!");
+            println!("{}", t!("init-next-steps"));
+            println!("  {}", t!("init-step-edit", path = cli.config.as_str()));
+            println!("  {}", t!("init-step-displays"));
+            println!("  {}", t!("init-step-validate"));
+            println!("  {}", t!("init-step-launch"));
+        }
+
+        Commands::Launch {
+            no_arrange,
+            mode,
+            resume,
+            collect_diagnostics,
+            no_preflight,
+            from_github,
+            label,
+            mut filter,
+            windows,
+        } => {
+            filter.only.extend(windows);
+            let session = session_name(&cli.config, cli.session.as_deref());
+            let started = chrono::Local::now();
+            let wall = std::time::Instant::now();
+            timing::start();
+            let config = match from_github {
+                Some(ref repo) => load_config_with_issues(&cli.config, repo, label.as_deref()),
+                None => load_config_with_helpful_error(&cli.config),
+            };
+            let result = config.and_then(|config| launch(config, &session, no_arrange, mode, resume, !no_preflight, &filter));
+            if collect_diagnostics && let Some(failure) = launch_failure(&result, &session, started) {
+                match diagnostics::collect(&cli.config, &session, &failure) {
+                    Ok(path) => println!("Diagnostics written to {}; attach it to your bug report.", path.display()),
+                    Err(e) => warn!("Failed to collect diagnostics: {:#}", e),
+                }
+            }
+            let timings = timing::windows();
+            if result.is_ok() || !timings.is_empty() {
+                say!();
+                for line in timing::table(&timings, wall.elapsed()) {
+                    say!("{}", line);
+                }
+            }
+            if output::is_json() {
+                output::print_json(&launch_json(&session, started, &result, &timings, wall.elapsed()))?;
+            }
+            result?;
         }
 
-        Commands::Launch { no_arrange } => {
+        Commands::Resume { no_arrange } => {
             let config = load_config_with_helpful_error(&cli.config)?;
-            let (cols, rows) = config.layout.parse_grid()?;
+            if config.layout.mode != LaunchMode::Windows {
+                anyhow::bail!("resume only supports layout.mode 'windows'");
+            }
 
-            info!(
-                "Launching {} windows in {}x{} grid on display {} using {}",
-                config.windows.len(),
-                cols,
-                rows,
-                config.target_display,
-                config.wsl_distribution
-            );
+            // Sessions that survived their window have no attached client
+            let mut detached = HashSet::new();
+            for kind in [multiplexer::Multiplexer::Tmux, multiplexer::Multiplexer::Zellij] {
+                let used = config
+                    .windows
+                    .iter()
+                    .any(|w| matches!(multiplexer::Multiplexer::for_window(w), Some((k, _)) if k == kind));
+                if used {
+                    for name in multiplexer::detached_sessions(&config.wsl_distribution, kind)? {
+                        detached.insert((kind, name));
+                    }
+                }
+            }
 
-            // Get display information
-            let displays = windows::get_displays()
-                .context("Failed to get display information. Make sure PowerShell is accessible.")?;
+            let orphaned: Vec<usize> = config
+                .windows
+                .iter()
+                .enumerate()
+                .filter(|(_, w)| {
+                    multiplexer::Multiplexer::for_window(w)
+                        .is_some_and(|(kind, name)| detached.contains(&(kind, name.to_string())))
+                })
+                .map(|(i, _)| i)
+                .collect();
 
-            let display_area = windows::get_display_working_area(&displays, config.target_display)
-                .with_context(|| format!(
-                    "Display {} not found. Run 'wsl-multi-launcher displays' to see available displays.",
-                    config.target_display
-                ))?;
+            if orphaned.is_empty() {
+                println!("No detached tmux/zellij sessions to resume.");
+                return Ok(());
+            }
 
-            info!(
-                "Target display working area: ({}, {}) {}x{}",
-                display_area.x, display_area.y, display_area.width, display_area.height
-            );
+            let (cols, rows) = config.layout.parse_grid()?;
+            let display_area = target_display_area(&config)?;
+            let terminal_kind = terminal::resolve(config.terminal);
+            let session = session_name(&cli.config, cli.session.as_deref());
+            let _lock = lock::SessionLock::acquire(&session, "resume")?;
+            let launcher = build_launcher(&config, terminal_kind, LaunchMode::Windows, &session)?;
 
-            // Calculate grid positions
+            println!("Found {} detached session(s); reattaching...", orphaned.len());
             let grid = layout::GridLayout::new(cols, rows, display_area);
-            let positions = grid.calculate_all_positions(config.windows.len());
+            let backend = terminal::create(terminal_kind, launcher, &session);
+            let mut state = load_state(&session);
+            let records = launch_windows(&config, &backend, &session, &grid, &orphaned, no_arrange);
+            save_state(&mut state, records);
+        }
 
-            // Launch windows and arrange them immediately after each launch
-            let launcher = wsl::WslLauncher::new(&config.wsl_distribution);
+        Commands::Attach { interactive, no_arrange } => {
+            let config = load_config_with_helpful_error(&cli.config)?;
+            if config.layout.mode != LaunchMode::Windows {
+                anyhow::bail!("attach only supports layout.mode 'windows'");
+            }
 
-            println!("Launching {} windows...", config.windows.len());
+            let session = session_name(&cli.config, cli.session.as_deref());
+            let (cols, rows) = config.layout.parse_grid()?;
+            let display_area = target_display_area(&config)?;
+            let terminal_kind = terminal::resolve(config.terminal);
+            let launcher = build_launcher(&config, terminal_kind, LaunchMode::Windows, &session)?;
+            let backend = terminal::create(terminal_kind, launcher, &session);
+            let grid = layout::GridLayout::new(cols, rows, display_area);
+            let mut state = load_state(&session);
 
-            // Track window handles for arrangement
-            let mut launched_handles: Vec<i64> = Vec::new();
+            let unattached: Vec<(usize, &config::WindowConfig)> = config
+                .windows
+                .iter()
+                .enumerate()
+                .filter(|(_, w)| !state.session_windows(&session).any(|r| r.name == w.name))
+                .collect();
+            if unattached.is_empty() {
+                println!("Every window of session '{}' is already recorded.", session);
+                return Ok(());
+            }
 
-            for (i, window) in config.windows.iter().enumerate() {
-                print!("  [{}] {} ... ", i + 1, window.name);
+            // Windows recorded by any session are not up for adoption
+            let recorded: HashSet<i64> = state.windows.iter().filter_map(|r| r.handle).collect();
+            let handles: Vec<i64> = backend
+                .window_handles()?
+                .into_iter()
+                .filter(|h| !recorded.contains(h))
+                .collect();
+            let info = windows::get_window_info(&handles)?;
+            let candidates: Vec<windows::WindowInfo> =
+                handles.iter().filter_map(|h| info.get(h).cloned()).collect();
 
-                // Get existing window handles before launch
-                let handles_before: std::collections::HashSet<i64> =
-                    windows::get_wt_window_handles()
-                        .unwrap_or_default()
-                        .into_iter()
+            let mut matches = attach::match_windows(&unattached, &candidates);
+            if interactive {
+                for &(i, window) in &unattached {
+                    if matches.iter().any(|&(m, _)| m == i) {
+                        continue;
+                    }
+                    let free: Vec<&windows::WindowInfo> = candidates
+                        .iter()
+                        .filter(|c| !matches.iter().any(|&(_, h)| h == c.handle))
                         .collect();
-
-                match launcher.launch_window(window) {
-                    Ok(()) => {
-                        // Wait for window to appear
-                        std::thread::sleep(std::time::Duration::from_millis(1000));
-
-                        // Find the new window handle
-                        let handles_after: std::collections::HashSet<i64> =
-                            windows::get_wt_window_handles()
-                                .unwrap_or_default()
-                                .into_iter()
-                                .collect();
-
-                        let new_handles: Vec<i64> = handles_after
-                            .difference(&handles_before)
-                            .copied()
-                            .collect();
-
-                        if let Some(&handle) = new_handles.first() {
-                            launched_handles.push(handle);
-                            println!("OK (handle: {})", handle);
-                        } else {
-                            println!("OK (handle not found)");
-                        }
+                    if free.is_empty() {
+                        break;
                     }
-                    Err(e) => {
-                        println!("FAILED");
-                        warn!("Failed to launch '{}': {}", window.name, e);
+                    if let Some(choice) = prompt_window(&window.name, &free)? {
+                        matches.push((i, free[choice].handle));
                     }
                 }
-                debug!("Window {} launched, position will be {:?}", window.name, positions[i]);
             }
 
-            // Arrange windows if not skipped
-            if !no_arrange && !launched_handles.is_empty() {
-                println!();
-                println!("Arranging windows...");
-
-                for (i, &handle) in launched_handles.iter().enumerate() {
-                    if i >= positions.len() {
-                        break;
-                    }
-                    let pos = &positions[i];
-                    let window_name = config.windows.get(i).map(|w| w.name.as_str()).unwrap_or("unknown");
-                    print!("  [{}] {} ... ", i + 1, window_name);
-
-                    match windows::move_window_by_handle(handle, pos) {
-                        Ok(()) => println!("OK"),
-                        Err(e) => {
-                            println!("FAILED");
-                            warn!("Failed to arrange '{}': {}", window_name, e);
-                        }
-                    }
+            if matches.is_empty() {
+                println!("No open {} window matches the configured windows.", backend.name());
+                if !interactive {
+                    println!("Hint: use 'attach --interactive' to pick windows by hand.");
                 }
+                return Ok(());
             }
 
-            println!();
-            println!("Done! {} windows launched.", config.windows.len());
-        }
+            let positions = window_positions(&config, &grid);
+            let mut records = Vec::new();
+            println!("Attaching {} window(s)...", matches.len());
+            for &(i, handle) in &matches {
+                let window = &config.windows[i];
+                let candidate = candidates.iter().find(|c| c.handle == handle).expect("matched candidate");
+                println!("  [{}] {} <- '{}' (handle: {})", i + 1, window.name, candidate.title, handle);
 
-        Commands::Config => {
-            let config = load_config_with_helpful_error(&cli.config)?;
-            println!("{:#?}", config);
+                // Titles are how arrange, focus and toggle find the window
+                if candidate.title != window.name
+                    && let Err(e) = backend.set_title(handle, &window.name)
+                {
+                    warn!("Failed to set title of '{}': {}", window.name, e);
+                }
+                // A process owning several windows (Windows Terminal) must not be killed for one
+                let pid = (candidates.iter().filter(|c| c.process_id == candidate.process_id).count() == 1
+                    && candidate.process_id != 0)
+                    .then_some(candidate.process_id);
+
+                records.push(state::WindowRecord {
+                    session: session.clone(),
+                    name: window.name.clone(),
+                    wt_window: None,
+                    handle: Some(handle),
+                    pid,
+                    rect: (!window.skips_arrange()).then_some(positions[i]),
+                    launched_at: chrono::Local::now(),
+                    health: None,
+                    idle_since: None,
+                    claude_session: None,
+                });
+
+                if no_arrange || window.skips_arrange() {
+                    continue;
+                }
+                let result = windows::move_window_by_handle(handle, &positions[i]).and_then(|()| {
+                    if window.dropdown { windows::hide_window(handle) } else { Ok(()) }
+                });
+                if let Err(e) = result {
+                    warn!("Failed to arrange '{}': {}", window.name, e);
+                }
+            }
+            save_state(&mut state, records);
+
+            let missing = unattached.len() - matches.len();
+            if missing > 0 {
+                println!("{} window(s) were not found; start them with 'watch' or 'restart <name>'.", missing);
+            }
         }
 
-        Commands::Validate => {
-            match config::load(&cli.config) {
-                Ok(config) => {
-                    let (cols, rows) = config.layout.parse_grid()?;
-                    println!("Configuration is valid!");
-                    println!();
-                    println!("  Distribution:   {}", config.wsl_distribution);
-                    println!("  Target display: {}", config.target_display);
-                    println!("  Grid:           {}x{} ({} cells)", cols, rows, cols * rows);
-                    println!("  Windows:        {}", config.windows.len());
-                    println!();
-                    println!("Windows:");
-                    for (i, w) in config.windows.iter().enumerate() {
-                        println!("  {}. {} - '{}'", i + 1, w.name, w.command);
-                        if let Some(ref dir) = w.working_dir {
-                            println!("     working_dir: {}", dir);
+        Commands::Session { action } => match action {
+            SessionAction::Save { name, force } => {
+                let config = load_config_with_helpful_error(&cli.config)?;
+                if config.layout.mode != LaunchMode::Windows {
+                    anyhow::bail!("session save only supports layout.mode 'windows'");
+                }
+                let path = snapshot::Snapshot::path(&name)?;
+                if path.exists() && !force {
+                    anyhow::bail!("Saved session '{}' already exists. Use --force to overwrite.", name);
+                }
+
+                let session = session_name(&cli.config, cli.session.as_deref());
+                let state = load_state(&session);
+                let records: Vec<&state::WindowRecord> = state.session_windows(&session).collect();
+                if records.is_empty() {
+                    anyhow::bail!("No running windows recorded for session '{}'", session);
+                }
+                let handles: Vec<i64> = records.iter().filter_map(|r| r.handle).collect();
+                let info = windows::get_window_info(&handles)?;
+
+                let mut saved = Vec::new();
+                let mut rects = std::collections::BTreeMap::new();
+                for record in &records {
+                    let Some(window) = config.windows.iter().find(|w| w.name == record.name) else {
+                        warn!("'{}' is no longer in the config; not saving it", record.name);
+                        continue;
+                    };
+                    let rect = record.handle.and_then(|h| info.get(&h)).map(|i| i.rect()).or(record.rect);
+                    if let Some(rect) = rect.filter(|_| !window.skips_arrange()) {
+                        rects.insert(window.name.clone(), rect);
+                    }
+                    saved.push(window.clone());
+                }
+                // Keep config order so restored windows launch in the same sequence
+                saved.sort_by_key(|w| config.windows.iter().position(|c| c.name == w.name));
+
+                let count = saved.len();
+                let snapshot = snapshot::Snapshot {
+                    session: session.clone(),
+                    saved_at: chrono::Local::now(),
+                    config: config::Config { windows: saved, ..config },
+                    rects,
+                };
+                snapshot.save_to(&path)?;
+                println!("Saved {} windows as '{}' ({})", count, name, path.display());
+            }
+
+            SessionAction::Restore { name } => {
+                let snapshot = snapshot::Snapshot::load(&name)?;
+                let config = &snapshot.config;
+                let session = cli.session.clone().unwrap_or_else(|| snapshot.session.clone());
+                events::init(&session);
+                let _lock = lock::SessionLock::acquire(&session, "session restore")?;
+                let (cols, rows) = config.layout.parse_grid()?;
+                let display_area = target_display_area(config)?;
+                let terminal_kind = terminal::resolve(config.terminal);
+                let launcher = build_launcher(config, terminal_kind, LaunchMode::Windows, &session)?;
+                let backend = terminal::create(terminal_kind, launcher, &session);
+                let grid = layout::GridLayout::new(cols, rows, display_area);
+                let mut state = load_state(&session);
+
+                let selected: Vec<usize> = config
+                    .windows
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, w)| !state.session_windows(&session).any(|r| r.name == w.name))
+                    .map(|(i, _)| i)
+                    .collect();
+                if selected.is_empty() {
+                    println!("Every window of '{}' is already running.", name);
+                    return Ok(());
+                }
+
+                let mut records = launch_windows(config, &backend, &session, &grid, &selected, true);
+
+                println!("Restoring saved positions...");
+                let to_restore: Vec<(usize, i64, layout::Rect)> = records
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, record)| Some((i, record.handle?, *snapshot.rects.get(&record.name)?)))
+                    .collect();
+                let moves: Vec<_> =
+                    to_restore.iter().map(|&(_, handle, rect)| (windows::MoveTarget::Handle(handle), rect)).collect();
+                let results = windows::move_windows(&moves, 1);
+                for (&(i, _, rect), result) in to_restore.iter().zip(results) {
+                    let record = &mut records[i];
+                    print!("  {} ... ", record.name);
+                    match result {
+                        Ok(()) => {
+                            println!("{}", output::ok("OK"));
+                            record.rect = Some(rect);
+                        }
+                        Err(e) => {
+                            println!("{}", output::fail("FAILED"));
+                            warn!("Failed to restore '{}': {}", record.name, e);
                         }
                     }
                 }
-                Err(e) => {
-                    println!("Configuration error!");
-                    println!();
-                    println!("{}", e);
-                    println!();
-                    println!("Hint: Run 'wsl-multi-launcher init' to create a new config file.");
-                    std::process::exit(1);
+                save_state(&mut state, records);
+            }
+
+            SessionAction::List => {
+                let names = snapshot::Snapshot::list()?;
+                if names.is_empty() {
+                    println!("No saved sessions.");
+                }
+                for name in names {
+                    match snapshot::Snapshot::load(&name) {
+                        Ok(s) => println!(
+                            "{:<20} {} windows, saved {}",
+                            name,
+                            s.config.windows.len(),
+                            s.saved_at.format("%Y-%m-%d %H:%M")
+                        ),
+                        Err(e) => println!("{:<20} (unreadable: {:#})", name, e),
+                    }
+                }
+            }
+
+            SessionAction::Delete { name } => {
+                let path = snapshot::Snapshot::path(&name)?;
+                if !path.exists() {
+                    anyhow::bail!("No saved session named '{}'", name);
+                }
+                std::fs::remove_file(&path)
+                    .with_context(|| format!("Failed to delete {}", path.display()))?;
+                println!("Deleted saved session '{}'", name);
+            }
+        },
+
+        Commands::Focus { name, mode } => {
+            let config = load_config_with_helpful_error(&cli.config)?;
+            let mode = mode.unwrap_or(config.layout.mode);
+            let index = window_index(&config, &name)?;
+            let name = &config.windows[index].name;
+
+            let terminal_kind = terminal::resolve(config.terminal);
+            if mode != LaunchMode::Windows && terminal_kind != config::TerminalKind::WindowsTerminal {
+                anyhow::bail!("{:?} mode requires Windows Terminal, but wt.exe was not found.", mode);
+            }
+            let launcher = wsl::WslLauncher::new(&config.wsl_distribution)
+                .with_wt_version(terminal::wt_version::detect());
+            let session = session_name(&cli.config, cli.session.as_deref());
+
+            match mode {
+                LaunchMode::Windows => {
+                    let backend = terminal::create(terminal_kind, launcher, &session);
+                    backend.focus(&config.windows[index], index)?;
+                }
+                LaunchMode::Tabs => {
+                    launcher.focus_tab(&terminal::wt_window_name(&session, "tabs"), index)?;
+                }
+                LaunchMode::Panes => {
+                    let (cols, _) = config.layout.parse_grid()?;
+                    let pane_id = layout::pane_ids(cols, config.windows.len())[index];
+                    launcher.focus_pane(&terminal::wt_window_name(&session, "panes"), pane_id)?;
                 }
             }
+            println!("Focused '{}'", name);
         }
 
-        Commands::Displays => {
-            let displays = windows::get_displays()
-                .context("Failed to get display information")?;
+        Commands::Prompt { name, text, mode } => {
+            let config = load_config_with_helpful_error(&cli.config)?;
+            let mode = mode.unwrap_or(config.layout.mode);
+            let index = window_index(&config, &name)?;
+            let name = &config.windows[index].name;
+            let session = session_name(&cli.config, cli.session.as_deref());
+            send_prompt(&config, &session, mode, index, &text)?;
+            println!("Sent prompt to '{}'", name);
+        }
 
-            println!("Found {} display(s):", displays.len());
-            for (i, display) in displays.iter().enumerate() {
-                println!();
-                println!(
-                    "  Display {} {}",
-                    i,
-                    if display.primary { "(Primary)" } else { "" }
-                );
-                println!("    Device:       {}", display.device_name);
-                println!(
-                    "    Resolution:   {}x{}",
-                    display.bounds.width, display.bounds.height
-                );
-                println!(
-                    "    Position:     ({}, {})",
-                    display.bounds.x, display.bounds.y
-                );
-                println!(
-                    "    Working Area: {}x{} at ({}, {})",
-                    display.working_area.width,
-                    display.working_area.height,
-                    display.working_area.x,
-                    display.working_area.y
-                );
+        Commands::Broadcast { text, tags, mode } => {
+            let config = load_config_with_helpful_error(&cli.config)?;
+            let mode = mode.unwrap_or(config.layout.mode);
+            let session = session_name(&cli.config, cli.session.as_deref());
+            let targets: Vec<usize> = config
+                .windows
+                .iter()
+                .enumerate()
+                .filter(|(_, w)| w.has_any_tag(&tags))
+                .map(|(i, _)| i)
+                .collect();
+            if targets.is_empty() {
+                anyhow::bail!("No windows tagged {}", tags.join(", "));
             }
-            println!();
-            println!("Use 'target_display: <index>' in your config to select a display.");
+
+            let mut failed = 0;
+            for (n, &index) in targets.iter().enumerate() {
+                let name = &config.windows[index].name;
+                ratelimit::before_prompt(&config, n)?;
+                match send_prompt(&config, &session, mode, index, &text) {
+                    Ok(()) => println!("  {} sent", name),
+                    Err(e) => {
+                        println!("  {} failed: {:#}", name, e);
+                        failed += 1;
+                    }
+                }
+            }
+            if failed > 0 {
+                anyhow::bail!("Failed to send to {} of {} window(s)", failed, targets.len());
+            }
+            println!("Sent to {} window(s)", targets.len());
         }
 
-        Commands::Arrange => {
+        Commands::Dispatch { file, tags, idle, mode } => {
             let config = load_config_with_helpful_error(&cli.config)?;
-            let (cols, rows) = config.layout.parse_grid()?;
+            let mode = mode.unwrap_or(config.layout.mode);
+            let session = session_name(&cli.config, cli.session.as_deref());
+            let markdown = std::fs::read_to_string(&file).with_context(|| format!("Failed to read {}", file))?;
+            let tasks = dispatch::parse(&markdown);
+            if tasks.is_empty() {
+                println!("No queued tasks in {}", file);
+                return Ok(());
+            }
 
-            let displays = windows::get_displays()?;
-            let display_area = windows::get_display_working_area(&displays, config.target_display)?;
+            let state = load_state(&session);
+            let running: HashSet<&str> = state.session_windows(&session).map(|r| r.name.as_str()).collect();
+            let windows: Vec<String> = config
+                .windows
+                .iter()
+                .filter(|w| if tags.is_empty() { w.claude.is_some() } else { w.has_any_tag(&tags) })
+                .filter(|w| running.contains(w.name.as_str()))
+                .map(|w| w.name.clone())
+                .collect();
+            if windows.is_empty() {
+                anyhow::bail!("No running target windows in session '{}' (claude: windows, or --tags)", session);
+            }
+            let windows = skip_over_budget(&config, windows)?;
 
-            let grid = layout::GridLayout::new(cols, rows, display_area);
-            let positions = grid.calculate_all_positions(config.windows.len());
+            let (strategy, idle_windows) = if idle {
+                let activity = claude::activity(&config, &session)?;
+                let idle_windows: HashSet<String> = activity
+                    .into_iter()
+                    .filter(|(_, activity)| *activity == claude::Activity::Idle)
+                    .map(|(name, _)| name)
+                    .collect();
+                (dispatch::Strategy::Idle, idle_windows)
+            } else {
+                (dispatch::Strategy::RoundRobin, HashSet::new())
+            };
+
+            let mut dispatched = Vec::new();
+            let assigned = dispatch::assign(&tasks, &windows, strategy, &idle_windows);
+            for (n, (task, name)) in assigned.into_iter().enumerate() {
+                let index = window_index(&config, name)?;
+                ratelimit::before_prompt(&config, n)?;
+                match send_prompt(&config, &session, mode, index, &task.text) {
+                    Ok(()) => {
+                        println!("  {} <- {}", name, task.text);
+                        events::record(EventKind::TaskDispatched, name, Some(task.text.clone()));
+                        dispatched.push((task.line, name.to_string()));
+                    }
+                    Err(e) => println!("  {} failed: {:#}", name, e),
+                }
+            }
+            if !dispatched.is_empty() {
+                std::fs::write(&file, dispatch::mark_dispatched(&markdown, &dispatched))
+                    .with_context(|| format!("Failed to update {}", file))?;
+            }
+            println!("Dispatched {} task(s), {} still queued", dispatched.len(), tasks.len() - dispatched.len());
+        }
+
+        Commands::Toggle { name } => {
+            let config = load_config_with_helpful_error(&cli.config)?;
+            let window = &config.windows[window_index(&config, &name)?];
+            let name = &window.name;
+            if !window.dropdown {
+                anyhow::bail!("Window '{}' is not a dropdown window (set 'dropdown: true')", name);
+            }
 
-            println!("Arranging {} windows...", config.windows.len());
+            let shown = windows::toggle_window(&window.name).with_context(|| {
+                format!("Dropdown '{}' not found. Is it running? (try 'launch')", name)
+            })?;
+            println!("{} {}", name, if shown { "shown" } else { "hidden" });
+        }
 
-            for (i, window) in config.windows.iter().enumerate() {
-                let pos = &positions[i];
-                print!("  [{}] {} ... ", i + 1, window.name);
+        Commands::Stop { names, all, force, grace, cleanup, archive } => {
+            let session = session_name(&cli.config, cli.session.as_deref());
+            // Stopping works from the state file alone; the config only adds graceful
+            // shutdown, the transcripts to archive and the worktrees to clean up
+            let config = if cleanup || archive {
+                Some(load_config_with_helpful_error(&cli.config)?)
+            } else {
+                config::load(&cli.config).ok()
+            };
+            let grace = grace.or(config.as_ref().map(|c| c.stop.grace_secs)).unwrap_or(0);
+            let mut state = state::SessionState::load()?;
+            let targets: Vec<state::WindowRecord> =
+                state.select(&session, &names)?.into_iter().cloned().collect();
 
-                match windows::move_window_with_retry(&window.name, pos, 3) {
-                    Ok(()) => println!("OK"),
-                    Err(e) => {
-                        println!("FAILED");
-                        warn!("Failed to arrange '{}': {}", window.name, e);
+            if targets.is_empty() {
+                println!("{}", t!("session-no-windows", session = session.as_str()));
+                if let Some(ref config) = config
+                    && cleanup
+                {
+                    cleanup_worktrees(config, &names, &HashSet::new())?;
+                }
+                return Ok(());
+            }
+            if all
+                && !confirm::destructive(&t!("stop-confirm-all", count = targets.len(), session = session.as_str()))?
+            {
+                return Ok(());
+            }
+
+            if let Some(ref config) = config {
+                warn_hook_failure(hooks::run_global(config, &session, HookEvent::PreStop));
+            }
+
+            say!("{}", t!("stop-windows", count = targets.len()));
+            // Tabs/panes share a window, which only needs closing once
+            let mut closed_handles = HashSet::new();
+            let mut still_open = HashSet::new();
+
+            for record in &targets {
+                say_start!("  {} ... ", record.name);
+
+                let shared = record.handle.is_some_and(|h| closed_handles.contains(&h));
+                if let Some(ref config) = config {
+                    run_stop_hooks(config, &session, &record.name);
+                    terminate_recorded_window(config, record, grace);
+                }
+                let closed = shared || close_recorded_window(record, force);
+
+                if closed {
+                    if let Some(handle) = record.handle {
+                        closed_handles.insert(handle);
+                    }
+                    state.remove(&session, &record.name);
+                    say!("CLOSED");
+                    events::record(EventKind::WindowClosed, &record.name, None);
+                } else {
+                    still_open.insert(record.name.clone());
+                    match (record.pid, force) {
+                        (Some(_), false) => say!("STILL OPEN (use --force to kill it)"),
+                        _ => say!("STILL OPEN (close it manually)"),
                     }
                 }
             }
 
-            println!();
-            println!("Window arrangement complete.");
+            state.save()?;
+            say!();
+            say!("{}", t!("stop-done", closed = targets.len() - still_open.len(), open = still_open.len()));
+
+            // Before the cleanup, which may remove the worktrees claude ran in
+            if let Some(ref config) = config
+                && (archive || config.stop.archive_transcripts)
+            {
+                archive_transcripts(config, &session, &targets)?;
+            }
+            if let Some(ref config) = config
+                && cleanup
+            {
+                cleanup_worktrees(config, &names, &still_open)?;
+            }
         }
 
-        Commands::Status => {
-            println!("System Status");
-            println!("=============");
-            println!();
+        Commands::Restart { name, force } => {
+            let config = load_config_with_helpful_error(&cli.config)?;
+            if config.layout.mode != LaunchMode::Windows {
+                anyhow::bail!("restart only supports layout.mode 'windows' (tabs/panes share one window)");
+            }
+            let index = window_index(&config, &name)?;
 
-            // WSL distributions
-            println!("WSL Distributions:");
-            match get_wsl_distributions() {
-                Ok(distros) => {
-                    if distros.is_empty() {
-                        println!("  (none found)");
-                    } else {
-                        for distro in &distros {
-                            println!("  - {}", distro);
+            let session = session_name(&cli.config, cli.session.as_deref());
+            let _lock = lock::SessionLock::acquire(&session, "restart")?;
+            let (cols, rows) = config.layout.parse_grid()?;
+            let display_area = target_display_area(&config)?;
+            let terminal_kind = terminal::resolve(config.terminal);
+            let launcher = build_launcher(&config, terminal_kind, LaunchMode::Windows, &session)?;
+            let backend = terminal::create(terminal_kind, launcher, &session);
+            let mut state = load_state(&session);
+            let grid = layout::GridLayout::new(cols, rows, display_area);
+            restart_window(&config, &backend, &session, &grid, &mut state, index, force)?;
+            state.save()?;
+        }
+
+        Commands::Dashboard { mode } => {
+            let config = load_config_with_helpful_error(&cli.config)?;
+            let session = session_name(&cli.config, cli.session.as_deref());
+            let exe = std::env::current_exe().context("Failed to get executable path")?;
+            let base_args = vec!["-c".to_string(), cli.config.clone(), "--session".to_string(), session.clone()];
+            let snapshot = move || dashboard_panes(&config, &session);
+
+            dashboard::run(snapshot, |action, name| {
+                let mut args = base_args.clone();
+                match action {
+                    dashboard::Action::Focus => {
+                        args.extend(["focus".to_string(), name.to_string()]);
+                        if let Some(mode) = mode {
+                            args.extend(["--mode".to_string(), format!("{:?}", mode).to_lowercase()]);
                         }
                     }
+                    dashboard::Action::Restart => args.extend(["restart".to_string(), name.to_string()]),
+                }
+                match std::process::Command::new(&exe).args(&args).output() {
+                    Ok(output) => {
+                        let text = if output.status.success() { output.stdout } else { output.stderr };
+                        let text = String::from_utf8_lossy(&text);
+                        text.lines().rev().find(|l| !l.trim().is_empty()).unwrap_or_default().trim().to_string()
+                    }
+                    Err(e) => format!("Failed to run {}: {}", exe.display(), e),
+                }
+            })?;
+        }
+
+        Commands::Tui => {
+            let profiles = tui_profiles(&cli.config, cli.session.as_deref())?;
+            let exe = std::env::current_exe().context("Failed to get executable path")?;
+            tui::run(profiles, |profile, windows| {
+                let mut args = vec!["-c", profile.path.as_str(), "--session", profile.session.as_str(), "launch"];
+                for window in windows {
+                    args.extend(["--window", window.as_str()]);
                 }
-                Err(e) => println!("  Error: {}", e),
+                std::process::Command::new(&exe)
+                    .args(&args)
+                    .stdin(std::process::Stdio::null())
+                    .stdout(std::process::Stdio::null())
+                    .stderr(std::process::Stdio::piped())
+                    .spawn()
+                    .with_context(|| format!("Failed to run {}", exe.display()))
+            })?;
+        }
+
+        Commands::Ps { .. } => {
+            let session = session_name(&cli.config, cli.session.as_deref());
+            let state = state::SessionState::load()?;
+            let mut rows = status_rows(&state, &session)?;
+            // ps works without a config; only claude: windows get an activity
+            if let Ok(config) = config::load(&cli.config) {
+                add_claude_activity(&config, &session, &mut rows);
+                add_budgets(&config, &mut rows);
             }
-            println!();
 
-            // Displays
-            println!("Displays:");
-            match windows::get_displays() {
-                Ok(displays) => {
-                    for (i, d) in displays.iter().enumerate() {
-                        println!(
-                            "  {}. {} {}x{}{}",
-                            i,
-                            d.device_name,
-                            d.bounds.width,
-                            d.bounds.height,
-                            if d.primary { " (Primary)" } else { "" }
-                        );
+            if output::is_json() {
+                return output::print_json(&rows);
+            }
+
+            if rows.is_empty() {
+                println!("{}", t!("session-no-windows", session = session.as_str()));
+                return Ok(());
+            }
+            let field = |row: &serde_json::Value, key: &str| match &row[key] {
+                serde_json::Value::Null => "-".to_string(),
+                serde_json::Value::String(s) => s.clone(),
+                value => value.to_string(),
+            };
+            println!(
+                "{:<20} {:<8} {:>4}  {:<8} {:<10} {:>6} {:>9} {:>9}  {:<22} {:>10}  PID",
+                "NAME", "STATUS", "EXIT", "ACTIVITY", "HEALTH", "BUDGET", "UPTIME", "IDLE", "RECT", "HANDLE"
+            );
+            for row in &rows {
+                let rect = serde_json::from_value::<layout::Rect>(row["rect"].clone())
+                    .map(|r| format!("{}x{} at ({}, {})", r.width, r.height, r.x, r.y))
+                    .unwrap_or_else(|_| "-".to_string());
+                let uptime = chrono::Duration::seconds(row["uptime_secs"].as_i64().unwrap_or(0));
+                let idle = row["idle_secs"]
+                    .as_i64()
+                    .map(|secs| format_uptime(chrono::Duration::seconds(secs)))
+                    .unwrap_or_else(|| "-".to_string());
+                // Over budget is flagged with a '!'
+                let budget = match row["budget_pct"].as_u64() {
+                    Some(pct) if row["over_budget"] == true => format!("{}%!", pct),
+                    Some(pct) => format!("{}%", pct),
+                    None => "-".to_string(),
+                };
+                println!(
+                    "{:<20} {:<8} {:>4}  {:<8} {:<10} {:>6} {:>9} {:>9}  {:<22} {:>10}  {}",
+                    field(row, "name"),
+                    field(row, "status"),
+                    field(row, "exit_code"),
+                    field(row, "activity"),
+                    field(row, "health"),
+                    budget,
+                    format_uptime(uptime),
+                    idle,
+                    rect,
+                    field(row, "handle"),
+                    field(row, "pid"),
+                );
+            }
+        }
+
+        Commands::Watch { interval, max_restarts, backoff, metrics, notify, status_titles } => {
+            let mut config = load_config_with_helpful_error(&cli.config)?;
+            if config.layout.mode != LaunchMode::Windows {
+                anyhow::bail!("watch only supports layout.mode 'windows'");
+            }
+            config.watch.interval_secs = interval.unwrap_or(config.watch.interval_secs).max(1);
+            config.watch.max_restarts = max_restarts.unwrap_or(config.watch.max_restarts);
+            config.watch.backoff_secs = backoff.unwrap_or(config.watch.backoff_secs);
+            config.watch.metrics_addr = metrics.or(config.watch.metrics_addr);
+            config.watch.notify |= notify;
+            config.watch.status_titles |= status_titles;
+
+            let session = session_name(&cli.config, cli.session.as_deref());
+            let (cols, rows) = config.layout.parse_grid()?;
+            let display_area = target_display_area(&config)?;
+            let terminal_kind = terminal::resolve(config.terminal);
+            let launcher = build_launcher(&config, terminal_kind, LaunchMode::Windows, &session)?;
+            let backend = terminal::create(terminal_kind, launcher, &session);
+            let grid = layout::GridLayout::new(cols, rows, display_area);
+
+            let socket = ipc::socket_path(&session)?;
+            let (tx, requests) = std::sync::mpsc::channel();
+            ipc::serve(&socket, tx.clone())?;
+            let _hotkeys = hotkey::listen(&config, tx)?;
+            let metrics = match config.watch.metrics_addr {
+                Some(ref addr) => {
+                    let metrics = std::sync::Arc::new(std::sync::Mutex::new(metrics::Metrics::new(&session)));
+                    metrics::serve(addr, metrics.clone())?;
+                    println!("Metrics: http://{}/metrics", addr);
+                    Some(metrics)
+                }
+                None => None,
+            };
+            let mut supervisor = watchdog::Supervisor::new(config.watch.clone());
+            let mut health = health::HealthTracker::new();
+            let mut idle = watchdog::IdleTracker::new();
+            let idle_after = config.watch.idle_after()?;
+            let mut notifier = watchdog::Notifier::new(chrono::Local::now());
+            // When the budgets were last checked
+            let mut budgets_checked: Option<std::time::Instant> = None;
+            // Status title last set on each window, with the handle it was set on
+            let mut titles: HashMap<String, (i64, String)> = HashMap::new();
+            // Whether the queue workers are paused for the rate limit; unknown at first
+            let mut queue_paused: Option<bool> = None;
+            // Windows we can't track (no handle found) or stopped restarting
+            let mut ignored: HashSet<usize> = HashSet::new();
+            let mut first_pass = true;
+
+            println!(
+                "Watching {} windows every {}s (max {} restarts each). Press Ctrl+C to stop.",
+                config.windows.len(),
+                config.watch.interval_secs,
+                config.watch.max_restarts
+            );
+            println!("Control socket: {}", socket.display());
+
+            loop {
+                let mut state = load_state(&session);
+                let running: HashSet<&str> =
+                    state.session_windows(&session).map(|w| w.name.as_str()).collect();
+                let now = std::time::Instant::now();
+
+                let mut relaunch = Vec::new();
+                for (i, window) in config.windows.iter().enumerate() {
+                    if running.contains(window.name.as_str()) || ignored.contains(&i) {
+                        continue;
+                    }
+                    // Windows that weren't running when watch started don't count as restarts
+                    if first_pass {
+                        relaunch.push(i);
+                        continue;
+                    }
+                    match supervisor.on_missing(&window.name, now) {
+                        watchdog::Decision::Restart => relaunch.push(i),
+                        watchdog::Decision::Wait => {}
+                        watchdog::Decision::GiveUp => {
+                            warn!(
+                                "'{}' was restarted {} times; no longer restarting it",
+                                window.name,
+                                supervisor.restarts(&window.name)
+                            );
+                            ignored.insert(i);
+                        }
+                    }
+                }
+                drop(running);
+
+                if !relaunch.is_empty() {
+                    println!();
+                    println!("[{}] Relaunching {} window(s)", chrono::Local::now().format("%H:%M:%S"), relaunch.len());
+                    // Read exit codes first; the relaunched command clears them
+                    let mut exits = std::collections::HashMap::new();
+                    for &i in relaunch.iter().filter(|_| !first_pass) {
+                        let name = &config.windows[i].name;
+                        if let Some(exit) = exit_status::read(&session, name) {
+                            println!("  {} exited with code {}", name, exit.code);
+                            if config.watch.notify && notifier.exited(name, exit.at) {
+                                notify_window(name, &format!("Exited with code {}", exit.code));
+                            }
+                            exits.insert(name.as_str(), exit.code);
+                        }
+                    }
+                    let started = chrono::Local::now();
+                    let together = can_launch_together(&config, backend.as_ref(), &relaunch);
+                    let records = launch_windows(&config, &backend, &session, &grid, &relaunch, false);
+                    // Windows launched together each took the time since the launch began;
+                    // otherwise each took the time since the previous one
+                    if let Some(ref metrics) = metrics
+                        && let Ok(mut metrics) = metrics.lock()
+                    {
+                        let mut previous = started;
+                        for record in &records {
+                            let took = (record.launched_at - previous).to_std().unwrap_or_default();
+                            metrics.observe_launch(&record.name, took);
+                            if !together {
+                                previous = record.launched_at;
+                            }
+                        }
+                    }
+                    if !first_pass {
+                        for record in &records {
+                            let detail = match exits.get(record.name.as_str()) {
+                                Some(code) => format!("watch, exit code {}", code),
+                                None => "watch".to_string(),
+                            };
+                            events::record(EventKind::WindowRestarted, &record.name, Some(detail));
+                        }
+                    }
+                    for record in records.iter().filter(|r| r.handle.is_none()) {
+                        warn!("'{}' has no window handle and can't be supervised", record.name);
+                        if let Some(i) = config.windows.iter().position(|w| w.name == record.name) {
+                            ignored.insert(i);
+                        }
+                    }
+                    save_state(&mut state, records);
+                }
+                first_pass = false;
+
+                check_health(&config, &backend, &session, &grid, &mut state, &mut health, now)?;
+                if let Some(threshold) = idle_after {
+                    ignored.extend(check_idle(&config, &session, &mut state, &mut idle, threshold, now));
+                }
+                let activity = if config.watch.notify || config.watch.status_titles {
+                    running_activity(&config, &session, &state)
+                } else {
+                    HashMap::new()
+                };
+                if config.watch.notify {
+                    notify_windows(&config, &session, &state, &activity, &mut notifier);
+                }
+                if config.watch.status_titles {
+                    update_titles(&config, backend.as_ref(), &session, &state, &activity, &mut titles);
+                }
+                if let (Some(limits), Some(queue)) = (&config.rate_limit, &config.queue) {
+                    pace_queue(&config, limits, queue, &mut queue_paused);
+                }
+                if budgets_checked.is_none_or(|at| now.duration_since(at) >= budget::CHECK_INTERVAL) {
+                    check_budgets(&config, &session, &state, &mut notifier);
+                    budgets_checked = Some(now);
+                }
+                if let Some(ref metrics) = metrics
+                    && let Ok(mut metrics) = metrics.lock()
+                {
+                    for window in &config.windows {
+                        let up = state.session_windows(&session).any(|r| r.name == window.name);
+                        metrics.set_window(&window.name, up, supervisor.restarts(&window.name));
+                    }
+                }
+
+                // Serve control requests until the next check is due
+                let deadline =
+                    std::time::Instant::now() + std::time::Duration::from_secs(config.watch.interval_secs);
+                while let Some(timeout) = deadline.checked_duration_since(std::time::Instant::now()) {
+                    match requests.recv_timeout(timeout) {
+                        Ok(pending) => {
+                            let response =
+                                handle_request(&pending.request, &config, &backend, &session, &grid);
+                            pending.reply(response);
+                        }
+                        Err(_) => break,
                     }
                 }
-                Err(e) => println!("  Error: {}", e),
             }
-            println!();
+        }
 
-            // Config file
-            println!("Config File:");
-            let config_path = Path::new(&cli.config);
-            if config_path.exists() {
-                println!("  {} (exists)", cli.config);
-                match config::load(&cli.config) {
-                    Ok(c) => {
-                        println!("  {} windows configured", c.windows.len());
+        Commands::Hold { grace, nearest, interval } => {
+            let config = load_config_with_helpful_error(&cli.config)?;
+            if config.layout.mode != LaunchMode::Windows {
+                anyhow::bail!("hold only supports layout.mode 'windows'");
+            }
+            let session = session_name(&cli.config, cli.session.as_deref());
+            let (cols, rows) = config.layout.parse_grid()?;
+            let grid = layout::GridLayout::new(cols, rows, target_display_area(&config)?);
+            let cells: Vec<layout::Rect> =
+                (0..(cols * rows) as usize).map(|i| grid.calculate_position(i)).collect();
+            let mut tracker = watchdog::SnapTracker::new(std::time::Duration::from_secs(grace));
+
+            println!(
+                "Holding the layout of session '{}' ({}s grace). Press Ctrl+C to stop.",
+                session, grace
+            );
+
+            loop {
+                let mut state = load_state(&session);
+                // Only windows that were placed somewhere (not maximized) are held
+                let placed: Vec<state::WindowRecord> = state
+                    .session_windows(&session)
+                    .filter(|w| w.handle.is_some() && w.rect.is_some())
+                    .cloned()
+                    .collect();
+                let handles: Vec<i64> = placed.iter().filter_map(|w| w.handle).collect();
+                let info = windows::get_window_info(&handles).unwrap_or_else(|e| {
+                    warn!("Failed to read window positions: {}", e);
+                    Default::default()
+                });
+                let now = std::time::Instant::now();
+                let mut changed = false;
+
+                for record in &placed {
+                    let (Some(handle), Some(assigned)) = (record.handle, record.rect) else { continue };
+                    let Some(current) = info.get(&handle).filter(|i| i.visible) else { continue };
+                    let moved = record.status(Some((current.rect(), true))) == state::WindowStatus::Moved;
+                    if !tracker.observe(&record.name, current.rect(), moved, now) {
+                        continue;
                     }
-                    Err(e) => {
-                        println!("  Error: {}", e);
+
+                    let target = if nearest {
+                        let occupied: Vec<layout::Rect> = placed
+                            .iter()
+                            .filter(|w| w.name != record.name)
+                            .filter_map(|w| w.rect)
+                            .collect();
+                        layout::nearest_free_cell(&cells, &occupied, &current.rect())
+                            .map(|i| cells[i])
+                            .unwrap_or(assigned)
+                    } else {
+                        assigned
+                    };
+
+                    match windows::move_window_by_handle(handle, &target) {
+                        Ok(()) => {
+                            println!("[{}] Snapped {} into place", chrono::Local::now().format("%H:%M:%S"), record.name);
+                            if target != assigned {
+                                state.record(state::WindowRecord { rect: Some(target), ..record.clone() });
+                                changed = true;
+                            }
+                        }
+                        Err(e) => warn!("Failed to snap '{}': {}", record.name, e),
                     }
                 }
-            } else {
-                println!("  {} (not found)", cli.config);
-                println!("  Run 'wsl-multi-launcher init' to create one.");
+
+                if changed && let Err(e) = state.save() {
+                    warn!("Failed to save session state: {:#}", e);
+                }
+                std::thread::sleep(std::time::Duration::from_secs(interval.max(1)));
             }
         }
-    }
 
-    Ok(())
-}
+        Commands::Config { action: None } => {
+            let config = load_config_with_helpful_error(&cli.config)?;
+            println!("{:#?}", config);
+        }
 
-/// Load config with helpful error messages
-fn load_config_with_helpful_error(path: &str) -> Result<config::Config> {
-    if !Path::new(path).exists() {
-        anyhow::bail!(
-            "Config file '{}' not found.\n\n\
-            Hint: Run 'wsl-multi-launcher init' to create a new config file,\n\
-            or use '-c <path>' to specify a different config file.",
-            path
-        );
-    }
-    config::load(path)
-}
+        Commands::Config { action: Some(ConfigAction::Get { path }) } => {
+            check_config_exists(&cli.config)?;
+            let content = std::fs::read_to_string(&cli.config)
+                .with_context(|| format!("Failed to read config file: {}", cli.config))?;
+            println!("{}", config_edit::get(&content, &path)?);
+        }
 
-/// Get list of available WSL distributions
-fn get_wsl_distributions() -> Result<Vec<String>> {
-    let output = std::process::Command::new("wsl.exe")
-        .args(["-l", "-q"])
-        .output()
-        .context("Failed to run wsl.exe")?;
+        Commands::Config { action: Some(ConfigAction::Set { path, value }) } => {
+            check_config_exists(&cli.config)?;
+            let content = std::fs::read_to_string(&cli.config)
+                .with_context(|| format!("Failed to read config file: {}", cli.config))?;
+            let edited = config_edit::set(&content, &path, &value)?;
+            config::load_str(&edited.content, Path::new(&cli.config))
+                .with_context(|| format!("Not changed: setting '{}' would make the config invalid", path))?;
+            if !edited.in_place {
+                warn!("'{}' can't be changed on its line; {} is rewritten without its comments", path, cli.config);
+            }
+            if dry_run::is_enabled() {
+                dry_run::show(format!("set {} to {} in {}", path, value, cli.config));
+                return Ok(());
+            }
+            std::fs::write(&cli.config, &edited.content)
+                .with_context(|| format!("Failed to write config file: {}", cli.config))?;
+            say!("Set {} to {} in {}", path, value, cli.config);
+        }
 
-    if !output.status.success() {
-        anyhow::bail!("wsl.exe failed");
-    }
+        Commands::Validate => {
+            if output::is_json() {
+                let report = match config::load(&cli.config) {
+                    Ok(config) => {
+                        let (cols, rows) = config.layout.parse_grid()?;
+                        let windows: Vec<serde_json::Value> = config
+                            .windows
+                            .iter()
+                            .map(|w| {
+                                serde_json::json!({
+                                    "name": w.name,
+                                    "command": w.command_summary(),
+                                    "working_dir": w.working_dir,
+                                    "tags": w.tags,
+                                })
+                            })
+                            .collect();
+                        serde_json::json!({
+                            "valid": true,
+                            "config": cli.config,
+                            "distribution": config.wsl_distribution,
+                            "target_display": config.target_display,
+                            "grid": { "cols": cols, "rows": rows },
+                            "windows": windows,
+                        })
+                    }
+                    Err(e) => serde_json::json!({ "valid": false, "config": cli.config, "error": format!("{:#}", e) }),
+                };
+                output::print_json(&report)?;
+                if report["valid"] == false {
+                    exit_code::config_failed();
+                }
+                return Ok(());
+            }
+            print_validation(&cli.config)?;
+        }
 
-    // Parse output (UTF-16 LE encoded on Windows)
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let distros: Vec<String> = stdout
-        .lines()
-        .map(|s| s.trim().replace('\0', ""))
-        .filter(|s| !s.is_empty())
-        .collect();
+        Commands::Edit => {
+            check_config_exists(&cli.config)?;
+            let path = Path::new(&cli.config);
+            let before = std::fs::read(path).with_context(|| format!("Failed to read {}", cli.config))?;
+            editor::open(path)?;
+            if dry_run::is_enabled() {
+                return Ok(());
+            }
+            let after = std::fs::read(path).with_context(|| format!("Failed to read {}", cli.config))?;
+            if after == before {
+                println!("{}", t!("edit-unchanged", path = cli.config.as_str()));
+            }
+            print_validation(&cli.config)?;
+        }
+
+        Commands::Displays { watch, interval } => {
+            if watch && output::is_json() {
+                anyhow::bail!("displays --watch doesn't support --output json");
+            }
+            let displays = windows::get_displays()
+                .context("Failed to get display information")?;
+            if output::is_json() {
+                let displays: Vec<serde_json::Value> =
+                    displays.iter().enumerate().map(|(i, d)| display_json(i, d)).collect();
+                return output::print_json(&serde_json::json!({ "displays": displays }));
+            }
+
+            // Without a loadable config no display is marked as the target
+            let target = || config::load(&cli.config).ok().map(|c| c.target_display);
+            let mut shown = (displays, target());
+            print_displays(&shown.0, shown.1, &cli.config);
+            println!();
+            println!("Use 'target_display: <index>' in your config to select a display.");
+            if !watch {
+                return Ok(());
+            }
 
-    Ok(distros)
+            println!();
+            println!("Watching for display changes every {}s. Press Ctrl+C to stop.", interval.max(1));
+            loop {
+                std::thread::sleep(std::time::Duration::from_secs(interval.max(1)));
+                let displays = match windows::get_displays() {
+                    Ok(displays) => displays,
+                    Err(e) => {
+                        warn!("Failed to get display information: {:#}", e);
+                        continue;
+                    }
+                };
+                let current = (displays, target());
+                if current == shown {
+                    continue;
+                }
+                println!();
+                println!("[{}] Displays changed:", chrono::Local::now().format("%H:%M:%S"));
+                print_displays(&current.0, current.1, &cli.config);
+                shown = current;
+            }
+        }
+
+        Commands::Arrange { grid, display, filter } => {
+            let mut config = load_config_with_helpful_error(&cli.config)?;
+            filter.check(&config)?;
+            if let Some(ref grid) = grid {
+                config.regrid(grid, &filter)?;
+            }
+            if let Some(display) = display {
+                config.target_display = display;
+            }
+            let (cols, rows) = config.layout.parse_grid()?;
+
+            let displays = windows::get_displays()?;
+            let display_area = windows::get_display_working_area(&displays, config.target_display)?;
+
+            let grid = layout::GridLayout::new(cols, rows, display_area);
+            let positions = window_positions(&config, &grid);
+            let session = session_name(&cli.config, cli.session.as_deref());
+            let _lock = lock::SessionLock::acquire(&session, "arrange")?;
+            let state = load_state(&session);
+
+            say!("{}", t!("arrange-windows", count = config.windows.iter().filter(|w| filter.matches(w)).count()));
+
+            // Prefer the recorded handle: titles can collide across sessions
+            let selected: Vec<usize> = (0..config.windows.len()).filter(|&i| filter.matches(&config.windows[i])).collect();
+            let to_move: Vec<usize> = selected
+                .iter()
+                .copied()
+                .filter(|&i| !config.windows[i].skips_arrange() && !config.windows[i].dropdown)
+                .collect();
+            let moves: Vec<_> = to_move
+                .iter()
+                .map(|&i| {
+                    let window = &config.windows[i];
+                    let target = match state.session_windows(&session).find(|r| r.name == window.name) {
+                        Some(state::WindowRecord { handle: Some(handle), .. }) => windows::MoveTarget::Handle(*handle),
+                        _ => windows::MoveTarget::Title(window.name.clone()),
+                    };
+                    (target, positions[i])
+                })
+                .collect();
+            // All windows are moved in one call, then reported in config order
+            let mut moved: HashMap<usize, _> = to_move.into_iter().zip(windows::move_windows(&moves, 3)).collect();
+
+            for i in selected {
+                let window = &config.windows[i];
+                say_start!("  [{}] {} ... ", i + 1, window.name);
+
+                if window.skips_arrange() {
+                    say!("{}", output::warn("SKIPPED (maximized/fullscreen)"));
+                    continue;
+                }
+                if window.dropdown {
+                    say!("{}", output::warn("SKIPPED (dropdown)"));
+                    continue;
+                }
+
+                match moved.remove(&i).expect("every arranged window was moved") {
+                    Ok(()) => {
+                        say!("{}", output::ok("OK"));
+                        events::record(EventKind::WindowMoved, &window.name, None);
+                        warn_hook_failure(hooks::run_window(&config, &session, window, HookEvent::PostArrange));
+                    }
+                    Err(e) => {
+                        say!("{}", output::fail("FAILED"));
+                        events::record(EventKind::MoveFailed, &window.name, Some(format!("{:#}", e)));
+                        warn!("Failed to arrange '{}': {}", window.name, e);
+                    }
+                }
+            }
+            warn_hook_failure(hooks::run_global(&config, &session, HookEvent::PostArrange));
+
+            say!();
+            say!("{}", t!("arrange-done"));
+        }
+
+        Commands::Plan => {
+            let session = session_name(&cli.config, cli.session.as_deref());
+            reconcile(&cli.config, &session, false, false)?;
+        }
+
+        Commands::Reconcile { apply, force } => {
+            let session = session_name(&cli.config, cli.session.as_deref());
+            reconcile(&cli.config, &session, apply, force)?;
+        }
+
+        Commands::Logs { name, follow, lines } => {
+            let session = session_name(&cli.config, cli.session.as_deref());
+            // A log under the exact name wins, so windows since removed from
+            // the config still have theirs
+            let name = match config::load(&cli.config) {
+                Ok(config) if !logs::log_path(&session, &name)?.exists() => {
+                    config.windows[window_index(&config, &name)?].name.clone()
+                }
+                _ => name,
+            };
+            let path = logs::log_path(&session, &name)?;
+            if !path.exists() {
+                anyhow::bail!(
+                    "No log for '{}' in session '{}' ({}).\n\n\
+                    Hint: set 'logs: {{enabled: true}}' in the config and relaunch the window.",
+                    name,
+                    session,
+                    path.display()
+                );
+            }
+
+            if follow {
+                let status = std::process::Command::new("tail")
+                    .args(["-n", &lines.to_string(), "-F"])
+                    .arg(&path)
+                    .status()
+                    .context("Failed to run tail")?;
+                if !status.success() {
+                    anyhow::bail!("tail exited with {}", status);
+                }
+            } else {
+                let bytes = std::fs::read(&path)
+                    .with_context(|| format!("Failed to read {}", path.display()))?;
+                for line in logs::tail_lines(&String::from_utf8_lossy(&bytes), lines) {
+                    println!("{}", line);
+                }
+            }
+        }
+
+        Commands::Events { since, all, .. } => {
+            let session = session_name(&cli.config, cli.session.as_deref());
+            let since = match since {
+                Some(ref s) => {
+                    let age = config::parse_duration(s).context("Invalid --since")?;
+                    Some(chrono::Local::now() - chrono::Duration::from_std(age)?)
+                }
+                None => None,
+            };
+            let path = events::path()?;
+            let events: Vec<events::Event> = events::load_from(&path, since)?
+                .into_iter()
+                .filter(|e| all || e.session == session)
+                .collect();
+
+            if output::is_json() {
+                return output::print_json(&events);
+            }
+            if events.is_empty() {
+                println!("No events recorded ({}).", path.display());
+                return Ok(());
+            }
+            println!("{:<19}  {:<12} {:<17} {:<20} DETAIL", "TIME", "SESSION", "EVENT", "WINDOW");
+            for event in &events {
+                println!(
+                    "{:<19}  {:<12} {:<17} {:<20} {}",
+                    event.time.format("%Y-%m-%d %H:%M:%S"),
+                    event.session,
+                    event.kind.to_string(),
+                    event.window,
+                    event.detail.as_deref().unwrap_or("-"),
+                );
+            }
+        }
+
+        Commands::Swarm {
+            count,
+            prompt_file,
+            repo,
+            base,
+            name,
+            model,
+            permission_mode,
+            force,
+            no_arrange,
+        } => {
+            let path = format!("{}.yaml", name);
+            if Path::new(&path).exists() && !force {
+                anyhow::bail!("Swarm config '{}' already exists. Use --force to overwrite.", path);
+            }
+            let prompt = std::fs::read_to_string(&prompt_file)
+                .with_context(|| format!("Failed to read prompt file: {}", prompt_file))?;
+            let repo = match repo {
+                Some(repo) => repo,
+                None => std::env::current_dir()?.to_string_lossy().to_string(),
+            };
+            // The distribution and display come from the usual config, if there is one
+            let (distribution, target_display) = match config::load(&cli.config) {
+                Ok(config) => (config.wsl_distribution, config.target_display),
+                Err(_) => {
+                    let distros = wsl::distributions()?;
+                    (distros.into_iter().next().unwrap_or_else(|| "Ubuntu-24.04".to_string()), 0)
+                }
+            };
+            let yaml = swarm::render(&swarm::Swarm {
+                name: name.clone(),
+                count,
+                prompt,
+                repo,
+                base,
+                model,
+                permission_mode,
+                distribution,
+                target_display,
+            })?;
+            std::fs::write(&path, yaml).with_context(|| format!("Failed to write swarm config: {}", path))?;
+            println!("Created swarm config: {}", path);
+
+            launch(load_config_with_helpful_error(&path)?, &name, no_arrange, None, false, true, &Default::default())?;
+            println!();
+            println!("Manage the swarm with '-c {}', e.g. 'wsl-multi-launcher -c {} stop --all --cleanup'.", path, path);
+        }
+
+        Commands::Queue { action } => {
+            let config = load_config_with_helpful_error(&cli.config)?;
+            let queue = config.queue.as_ref().context("No 'queue:' section in the config")?;
+            match action {
+                QueueAction::Add { text, file } => {
+                    let text = match (text, file) {
+                        (Some(text), _) => text,
+                        (None, Some(file)) => {
+                            std::fs::read_to_string(&file).with_context(|| format!("Failed to read {}", file))?
+                        }
+                        (None, None) => unreachable!("clap requires text or --file"),
+                    };
+                    if text.trim().is_empty() {
+                        anyhow::bail!("The task is empty");
+                    }
+                    let name = queue::add(&config.wsl_distribution, &queue.dir, &text)?;
+                    println!("Queued {}", name);
+                }
+                QueueAction::Status => {
+                    let status = queue::status(&config.wsl_distribution, &queue.dir)?;
+                    println!(
+                        "{}: {} pending, {} running, {} done, {} failed",
+                        queue.dir,
+                        status.pending.len(),
+                        status.running.len(),
+                        status.done.len(),
+                        status.failed.len()
+                    );
+                    if let Some(ref reason) = status.paused {
+                        println!("  Paused near the rate limit ({}); watch resumes the workers", reason);
+                    }
+                    for (worker, task) in &status.running {
+                        println!("  {:<12} {}", worker, task);
+                    }
+                    for task in &status.pending {
+                        println!("  {:<12} {}", "pending", task);
+                    }
+                    for task in &status.failed {
+                        println!("  {:<12} {} (output: {}/failed/{}.out)", "failed", task, queue.dir, task);
+                    }
+                }
+            }
+        }
+
+        Commands::Usage { since, .. } => {
+            let config = load_config_with_helpful_error(&cli.config)?;
+            let since = match since {
+                Some(ref s) => {
+                    let age = config::parse_duration(s).context("Invalid --since")?;
+                    Some(chrono::Local::now() - chrono::Duration::from_std(age)?)
+                }
+                None => None,
+            };
+            let report = usage::collect(&config, since)?;
+
+            if output::is_json() {
+                return output::print_json(&report);
+            }
+            println!(
+                "{:<20} {:>8} {:>12} {:>12} {:>12} {:>14} {:>10}",
+                "WINDOW", "REPLIES", "INPUT", "OUTPUT", "CACHE WRITE", "CACHE READ", "COST"
+            );
+            let line = |name: &str, usage: &usage::Usage| {
+                println!(
+                    "{:<20} {:>8} {:>12} {:>12} {:>12} {:>14} {:>10}",
+                    name,
+                    usage.replies,
+                    usage.input_tokens,
+                    usage.output_tokens,
+                    usage.cache_write_tokens,
+                    usage.cache_read_tokens,
+                    format!("${:.2}", usage.cost_usd),
+                );
+            };
+            for window in &report.windows {
+                line(&window.name, &window.usage);
+            }
+            line("TOTAL", &report.total);
+        }
+
+        Commands::Transcripts { window, open } => {
+            let config = load_config_with_helpful_error(&cli.config)?;
+            let session = session_name(&cli.config, cli.session.as_deref());
+            let archives = transcripts::list(&config.wsl_distribution, &session, &window)?;
+            let Some(newest) = archives.last() else {
+                println!("No archived transcripts of '{}' in session '{}'.", window, session);
+                println!("Archive them with: stop --archive");
+                return Ok(());
+            };
+
+            if open {
+                windows::open_folder(&config.wsl_distribution, &newest.path)?;
+                println!("Opened {}", newest.path);
+                return Ok(());
+            }
+            println!("{:<20} {:>6} {:>10}  PATH", "ARCHIVED", "FILES", "SIZE");
+            for archive in &archives {
+                let at = archive.at.map(|at| at.format("%Y-%m-%d %H:%M:%S").to_string()).unwrap_or_default();
+                let size = format!("{} KB", archive.bytes.div_ceil(1024));
+                println!("{:<20} {:>6} {:>10}  {}", at, archive.files, size, archive.path);
+            }
+        }
+
+        Commands::InstallStartup { method, watch } => {
+            let config = load_config_with_helpful_error(&cli.config)?;
+            let config_path = std::fs::canonicalize(&cli.config)
+                .with_context(|| format!("Failed to resolve config path: {}", cli.config))?;
+            let exe = std::env::current_exe().context("Failed to get executable path")?;
+            let name = startup::entry_name(&session_name(&cli.config, cli.session.as_deref()));
+            let command = if watch { "watch" } else { "launch" };
+            let arguments = startup::wsl_arguments(
+                &config.wsl_distribution,
+                &exe,
+                &config_path,
+                cli.session.as_deref(),
+                command,
+            );
+
+            windows::install_startup(method.script_name(), &name, &arguments)?;
+            println!("Registered '{}' to run at logon ({:?}):", name, method);
+            println!("  wsl.exe {}", arguments);
+            println!();
+            println!("Remove it with 'wsl-multi-launcher -c {} remove-startup'.", cli.config);
+        }
+
+        Commands::RemoveStartup => {
+            let name = startup::entry_name(&session_name(&cli.config, cli.session.as_deref()));
+            if windows::remove_startup(&name)? {
+                println!("Removed startup entry '{}'", name);
+            } else {
+                println!("No startup entry named '{}' was found.", name);
+            }
+        }
+
+        Commands::Service { action } => {
+            let name = startup::entry_name(&session_name(&cli.config, cli.session.as_deref()));
+            match action {
+                ServiceAction::Install => {
+                    let config = load_config_with_helpful_error(&cli.config)?;
+                    if config.layout.mode != LaunchMode::Windows {
+                        anyhow::bail!("service only supports layout.mode 'windows' (it runs watch)");
+                    }
+                    let config_path = std::fs::canonicalize(&cli.config)
+                        .with_context(|| format!("Failed to resolve config path: {}", cli.config))?;
+                    let exe = std::env::current_exe().context("Failed to get executable path")?;
+                    let arguments = startup::wsl_arguments(
+                        &config.wsl_distribution,
+                        &exe,
+                        &config_path,
+                        cli.session.as_deref(),
+                        "watch",
+                    );
+
+                    windows::install_service(&name, &arguments)?;
+                    println!("Installed service '{}':", name);
+                    println!("  wsl.exe {}", arguments);
+                    println!();
+                    println!("Start it with 'wsl-multi-launcher -c {} service start'.", cli.config);
+                }
+                ServiceAction::Uninstall | ServiceAction::Start | ServiceAction::Stop => {
+                    let (script_action, done) = match action {
+                        ServiceAction::Uninstall => ("Uninstall", "Uninstalled"),
+                        ServiceAction::Start => ("Start", "Started"),
+                        _ => ("Stop", "Stopped"),
+                    };
+                    if windows::service_control(script_action, &name)? == "not-found" {
+                        anyhow::bail!("No service named '{}' is installed", name);
+                    }
+                    println!("{} service '{}'", done, name);
+                }
+                ServiceAction::Status => match windows::service_control("Status", &name)?.as_str() {
+                    "not-found" => println!("Service '{}' is not installed", name),
+                    status => println!("Service '{}': {}", name, status),
+                },
+            }
+        }
+
+        Commands::Status => {
+            if output::is_json() {
+                return output::print_json(&status_json(&cli.config));
+            }
+            println!("{}", output::heading("System Status"));
+            println!("=============");
+            println!();
+
+            // WSL distributions
+            println!("{}", output::heading("WSL Distributions:"));
+            match wsl::distributions() {
+                Ok(distros) => {
+                    if distros.is_empty() {
+                        println!("  {}", output::warn("(none found)"));
+                    } else {
+                        for distro in &distros {
+                            println!("  - {}", distro);
+                        }
+                    }
+                }
+                Err(e) => println!("  {} {}", output::fail("Error:"), e),
+            }
+            println!();
+
+            // Displays
+            println!("{}", output::heading("Displays:"));
+            match windows::get_displays() {
+                Ok(displays) => {
+                    for (i, d) in displays.iter().enumerate() {
+                        println!(
+                            "  {}. {} {}x{}{}",
+                            i,
+                            d.device_name,
+                            d.bounds.width,
+                            d.bounds.height,
+                            if d.primary { format!(" {}", output::ok("(Primary)")) } else { String::new() }
+                        );
+                    }
+                }
+                Err(e) => println!("  {} {}", output::fail("Error:"), e),
+            }
+            println!();
+
+            // Terminal
+            println!("{}", output::heading("Windows Terminal:"));
+            match terminal::wt_version::detect() {
+                Some(version) => println!("  version {}", version),
+                None if terminal::is_available("wt.exe") => println!("  installed (version unknown)"),
+                None => println!("  {}", output::warn("(not found - console windows will be used)")),
+            }
+            println!();
+
+            // Config file
+            println!("{}", output::heading("Config File:"));
+            let config_path = Path::new(&cli.config);
+            if config_path.exists() {
+                println!("  {} (exists)", cli.config);
+                match config::load(&cli.config) {
+                    Ok(c) => {
+                        println!("  {} windows configured", c.windows.len());
+                    }
+                    Err(e) => {
+                        println!("  {} {}", output::fail("Error:"), e);
+                    }
+                }
+            } else {
+                println!("  {} {}", cli.config, output::warn("(not found)"));
+                println!("  Run 'wsl-multi-launcher init' to create one.");
+            }
+        }
+
+        Commands::Doctor => {
+            let checks = doctor::run(&cli.config);
+            let (passed, warned, failed) = doctor::count(&checks);
+            if output::is_json() {
+                output::print_json(&checks)?;
+            } else {
+                for check in &checks {
+                    let label = match check.level {
+                        doctor::Level::Pass => output::ok(check.level.label()),
+                        doctor::Level::Warn => output::warn(check.level.label()),
+                        doctor::Level::Fail => output::fail(check.level.label()),
+                    };
+                    println!("  [{}] {}: {}", label, check.name, check.detail);
+                    if let Some(ref fix) = check.fix {
+                        println!("         Fix: {}", fix);
+                    }
+                }
+                println!();
+                println!("{}", t!("doctor-summary", passed = passed, warned = warned, failed = failed));
+            }
+            if failed > 0 {
+                anyhow::bail!("{} of {} check(s) failed", failed, checks.len());
+            }
+        }
+
+        Commands::Docs { out } => {
+            let written = reference::generate(Cli::command(), Path::new(&out))?;
+            println!("Wrote man pages to {}/man and {} markdown page(s) to {}/markdown", out, written.len(), out);
+        }
+
+        Commands::Export { target } => match target {
+            ExportTarget::WtProfiles { output, install } => {
+                let config = load_config_with_helpful_error(&cli.config)?;
+                let fragment = wt_profiles::render_fragment(&config)?;
+
+                if install {
+                    let dir = windows::windows_env_path("LOCALAPPDATA")?
+                        .join("Microsoft/Windows Terminal/Fragments")
+                        .join(wt_profiles::FRAGMENT_APP_NAME);
+                    std::fs::create_dir_all(&dir)
+                        .with_context(|| format!("Failed to create {}", dir.display()))?;
+                    let path = dir.join("profiles.json");
+                    std::fs::write(&path, &fragment)
+                        .with_context(|| format!("Failed to write profiles: {}", path.display()))?;
+                    println!("Installed {} profile(s) to {}", config.windows.len(), path.display());
+                    println!("Restart Windows Terminal to load them.");
+                    return Ok(());
+                }
+
+                match output {
+                    Some(path) => {
+                        std::fs::write(&path, &fragment)
+                            .with_context(|| format!("Failed to write profiles: {}", path))?;
+                        println!("Wrote {} profile(s) to {}", config.windows.len(), path);
+                        println!();
+                        println!("Copy it to %LOCALAPPDATA%\\Microsoft\\Windows Terminal\\Fragments\\wsl-multi-launcher\\");
+                        println!("and restart Windows Terminal to see the profiles in the dropdown.");
+                    }
+                    None => println!("{}", fragment),
+                }
+            }
+        },
+    }
+
+    Ok(())
+}
+
+/// Compact uptime like "3d 4h", "2h 05m" or "42s"
+fn format_uptime(duration: chrono::Duration) -> String {
+    let secs = duration.num_seconds().max(0);
+    let (days, hours, mins) = (secs / 86_400, secs % 86_400 / 3600, secs % 3600 / 60);
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {:02}m", hours, mins)
+    } else if mins > 0 {
+        format!("{}m {:02}s", mins, secs % 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+/// Session name: `--session` if given, otherwise derived from the config
+/// file name (e.g. "config" for config.yaml)
+fn session_name(config_path: &str, session: Option<&str>) -> String {
+    if let Some(session) = session {
+        return session.to_string();
+    }
+    Path::new(config_path)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "default".to_string())
+}
+
+/// Session names end up in wt window names, socket and task names
+fn parse_session(name: &str) -> std::result::Result<String, String> {
+    if !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || "-_.".contains(c)) {
+        Ok(name.to_string())
+    } else {
+        Err("use letters, digits, '-', '_' and '.'".to_string())
+    }
+}
+
+/// Working area of the configured target display
+fn target_display_area(config: &config::Config) -> Result<layout::Rect> {
+    let displays = windows::get_displays()
+        .context("Failed to get display information. Make sure PowerShell is accessible.")?;
+
+    let display_area = windows::get_display_working_area(&displays, config.target_display)
+        .with_context(|| format!(
+            "Display {} not found. Run 'wsl-multi-launcher displays' to see available displays.",
+            config.target_display
+        ))?;
+
+    info!(
+        "Target display working area: ({}, {}) {}x{}",
+        display_area.x, display_area.y, display_area.width, display_area.height
+    );
+    Ok(display_area)
+}
+
+/// Build the launcher, adapting wt arguments to the installed Windows Terminal
+fn build_launcher(
+    config: &config::Config,
+    terminal_kind: config::TerminalKind,
+    mode: LaunchMode,
+    session: &str,
+) -> Result<wsl::WslLauncher> {
+    let wt_version = if terminal_kind == config::TerminalKind::WindowsTerminal {
+        terminal::wt_version::detect()
+    } else {
+        None
+    };
+    if let Some(version) = wt_version {
+        info!("Windows Terminal version: {}", version);
+        if mode == LaunchMode::Panes && !version.supports(terminal::wt_version::WtFeature::PaneSizing) {
+            anyhow::bail!(
+                "Panes mode requires Windows Terminal {} or newer (installed: {})",
+                terminal::wt_version::WtFeature::PaneSizing.min_version(),
+                version
+            );
+        }
+        for feature in terminal::wt_version::unsupported_features(config, version) {
+            warn!(
+                "Windows Terminal {} does not support {} (requires {}); it will be skipped",
+                version,
+                feature.describe(),
+                feature.min_version()
+            );
+        }
+    }
+    Ok(wsl::WslLauncher::new(&config.wsl_distribution)
+        .with_session(session)
+        .with_logs(&config.logs)
+        .with_wsl_cd(config.use_wsl_cd)
+        .with_wt_version(wt_version))
+}
+
+/// Launch the `selected` windows (config indices) and arrange them in
+/// their grid slots
+fn launch_windows(
+    config: &config::Config,
+    backend: &Arc<dyn TerminalBackend>,
+    session: &str,
+    grid: &layout::GridLayout,
+    selected: &[usize],
+    no_arrange: bool,
+) -> Vec<state::WindowRecord> {
+    let resolved = resolve_conversations(config, session, selected);
+    let none = HashMap::new();
+    let (config, conversations) = match resolved {
+        Some((ref config, ref ids)) => (config, ids),
+        None => (config, &none),
+    };
+    let positions = window_positions(config, grid);
+
+    say!("{}", t!("launch-windows", count = selected.len(), terminal = backend.name()));
+
+    let elevated = selected.iter().filter(|&&i| config.windows[i].elevated).count();
+    if elevated > 0 {
+        say!("{}", t!("launch-elevated", count = elevated));
+        say!("      {}", t!("launch-elevated-arrange"));
+    }
+
+    // Track window handles (with their config index) for arrangement
+    let mut launched_handles: Vec<(usize, i64)> = Vec::new();
+    // A dry run launches nothing, so there are no handles to arrange
+    let mut dry_run_launched = Vec::new();
+    let mut records = Vec::new();
+    let mut summary = summary::Summary::default();
+
+    let mut to_launch = Vec::new();
+    for &i in selected {
+        let window = &config.windows[i];
+        match run_pre_launch_hooks(config, session, window) {
+            Ok(()) => to_launch.push(i),
+            Err(e) => {
+                say!("  [{}] {} ... {}", i + 1, window.name, output::warn("SKIPPED (pre_launch hook failed)"));
+                summary.set(&window.name, Outcome::Skipped, Some(format!("{:#}", e)));
+            }
+        }
+    }
+    let mut launched_together = if can_launch_together(config, backend.as_ref(), &to_launch) {
+        launch_together(config, backend, &to_launch)
+    } else {
+        HashMap::new()
+    };
+
+    for &i in &to_launch {
+        let window = &config.windows[i];
+        say_start!("  [{}] {} ... ", i + 1, window.name);
+
+        let (result, handle) = match launched_together.remove(&i) {
+            Some(launched) => launched,
+            None => {
+                // Get existing window handles before launch
+                let handles_before = handle_snapshot(backend.as_ref());
+                let result = timing::measure(&window.name, timing::Phase::Spawn, || backend.launch(window, i));
+                let handle = result.is_ok().then(|| {
+                    timing::measure(&window.name, timing::Phase::Detect, || find_new_handle(backend.as_ref(), &handles_before))
+                });
+                (result, handle.flatten())
+            }
+        };
+
+        match result {
+            Ok(pid) => {
+                if let Some(pid) = pid {
+                    debug!("Window {} terminal process PID: {}", window.name, pid);
+                }
+                events::record(
+                    EventKind::WindowLaunched,
+                    &window.name,
+                    handle.map(|h| format!("handle {}", h)),
+                );
+                records.push(state::WindowRecord {
+                    session: session.to_string(),
+                    name: window.name.clone(),
+                    wt_window: backend.window_name(i),
+                    handle,
+                    pid,
+                    rect: (!window.skips_arrange()).then_some(positions[i]),
+                    launched_at: chrono::Local::now(),
+                    health: None,
+                    idle_since: None,
+                    claude_session: conversations.get(&window.name).cloned(),
+                });
+                summary.set(&window.name, Outcome::Launched, None);
+                if let Some(handle) = handle {
+                    // Terminals without a title flag get titled now so arrange can match them
+                    if !backend.titles_on_launch()
+                        && let Err(e) = backend.set_title(handle, &window.name)
+                    {
+                        warn!("Failed to set title of '{}': {}", window.name, e);
+                    }
+                    launched_handles.push((i, handle));
+                    match backend.window_name(i) {
+                        Some(name) => say!("{} (handle: {}, window: {})", output::ok("OK"), handle, name),
+                        None => say!("{} (handle: {})", output::ok("OK"), handle),
+                    }
+                } else {
+                    say!("{} (handle not found)", output::ok("OK"));
+                    if dry_run::is_enabled() {
+                        dry_run_launched.push(i);
+                    } else if window.dropdown || (!no_arrange && !window.skips_arrange()) {
+                        // Without its handle the window can't be moved into its cell
+                        let reason = "window not found after launch".to_string();
+                        events::record(EventKind::MoveFailed, &window.name, Some(reason.clone()));
+                        summary.set(&window.name, Outcome::ArrangeFailed, Some(reason));
+                    }
+                }
+                warn_hook_failure(hooks::run_window(config, session, window, HookEvent::PostLaunch));
+            }
+            Err(e) => {
+                say!("{}", output::fail("FAILED"));
+                events::record(EventKind::LaunchFailed, &window.name, Some(format!("{:#}", e)));
+                summary.set(&window.name, Outcome::LaunchFailed, Some(format!("{:#}", e)));
+                warn!("Failed to launch '{}': {}", window.name, e);
+            }
+        }
+        debug!("Window {} launched, position will be {:?}", window.name, positions[i]);
+    }
+
+    // Dropdown windows are sized and hidden regardless of --no-arrange;
+    // they only appear through `toggle`
+    for &(i, handle) in launched_handles.iter().filter(|(i, _)| config.windows[*i].dropdown) {
+        let window = &config.windows[i];
+        let result = windows::move_window_by_handle(handle, &positions[i]).and_then(|()| windows::hide_window(handle));
+        match result {
+            Ok(()) => {
+                say!("  {} is hidden; show it with 'toggle {}'", window.name, window.name);
+                summary.set(&window.name, Outcome::Launched, Some("dropdown, hidden".to_string()));
+            }
+            Err(e) => {
+                events::record(EventKind::MoveFailed, &window.name, Some(format!("{:#}", e)));
+                summary.set(&window.name, Outcome::ArrangeFailed, Some(format!("{:#}", e)));
+                warn!("Failed to hide dropdown '{}': {}", window.name, e);
+            }
+        }
+    }
+    launched_handles.retain(|(i, _)| !config.windows[*i].dropdown);
+    for &i in &dry_run_launched {
+        let window = &config.windows[i];
+        if window.dropdown {
+            dry_run::show(format!("move window '{}' to {} and hide it", window.name, positions[i]));
+        } else if !no_arrange && !window.skips_arrange() {
+            dry_run::show(format!("move window '{}' to {}", window.name, positions[i]));
+        }
+    }
+
+    // Arrange windows if not skipped
+    if !no_arrange && !launched_handles.is_empty() {
+        say!();
+        say!("{}", t!("launch-arranging"));
+
+        // All windows are moved in one call, then reported in launch order
+        let to_move: Vec<(usize, i64)> =
+            launched_handles.iter().copied().filter(|(i, _)| !config.windows[*i].skips_arrange()).collect();
+        let moves: Vec<_> = to_move.iter().map(|&(i, handle)| (windows::MoveTarget::Handle(handle), positions[i])).collect();
+        let started = std::time::Instant::now();
+        let results = windows::move_windows(&moves, 1);
+        let took = started.elapsed();
+        for &(i, _) in &to_move {
+            timing::add(&config.windows[i].name, timing::Phase::Arrange, took);
+        }
+        let mut moved: HashMap<usize, _> = to_move.iter().map(|&(i, _)| i).zip(results).collect();
+
+        for &(i, _) in &launched_handles {
+            let window = &config.windows[i];
+            say_start!("  [{}] {} ... ", i + 1, window.name);
+
+            // Maximized/fullscreen windows keep the size wt gave them
+            let Some(result) = moved.remove(&i) else {
+                say!("{}", output::warn("SKIPPED (maximized/fullscreen)"));
+                summary.set(&window.name, Outcome::Launched, Some("maximized/fullscreen".to_string()));
+                continue;
+            };
+
+            match result {
+                Ok(()) => {
+                    say!("{}", output::ok("OK"));
+                    events::record(EventKind::WindowMoved, &window.name, None);
+                    summary.set(&window.name, Outcome::Arranged, None);
+                    warn_hook_failure(hooks::run_window(config, session, window, HookEvent::PostArrange));
+                }
+                Err(e) => {
+                    say!("{}", output::fail("FAILED"));
+                    events::record(EventKind::MoveFailed, &window.name, Some(format!("{:#}", e)));
+                    summary.set(&window.name, Outcome::ArrangeFailed, Some(format!("{:#}", e)));
+                    warn!("Failed to arrange '{}': {}", window.name, e);
+                }
+            }
+        }
+    }
+
+    print_summary(&summary, t!("launch-done", count = summary.running()));
+    records
+}
+
+/// Whether the `indices` windows can be launched side by side
+///
+/// Windows launched together are told apart by their title, so terminals
+/// that title windows only after launch, and windows behind a UAC prompt,
+/// are launched one at a time.
+fn can_launch_together(config: &config::Config, backend: &dyn TerminalBackend, indices: &[usize]) -> bool {
+    indices.len() > 1
+        && backend.titles_on_launch()
+        && !dry_run::is_enabled()
+        && !indices.iter().any(|&i| config.windows[i].elevated)
+}
+
+/// Result of launching a window (the terminal's PID) and the handle of the
+/// window, if it showed up
+type Launched = (Result<Option<u32>, error::LauncherError>, Option<i64>);
+
+/// Launch the `indices` windows side by side and wait for them to show up
+fn launch_together(
+    config: &config::Config,
+    backend: &Arc<dyn TerminalBackend>,
+    indices: &[usize],
+) -> HashMap<usize, Launched> {
+    let handles_before = handle_snapshot(backend.as_ref());
+    let jobs: Vec<_> = indices
+        .iter()
+        .map(|&i| {
+            let window = &config.windows[i];
+            move || {
+                let started = std::time::Instant::now();
+                let result = backend.launch(window, i);
+                (result, started, std::time::Instant::now())
+            }
+        })
+        .collect();
+    let launched = parallel::run_all(jobs, parallel::PARALLELISM);
+
+    let names: Vec<String> = indices
+        .iter()
+        .zip(&launched)
+        .filter(|(_, (result, _, _))| result.is_ok())
+        .map(|(&i, _)| config.windows[i].name.clone())
+        .collect();
+    let found =
+        parallel::wait_for_windows(|| window_titles(backend.as_ref()), &handles_before, &names, parallel::READY_TIMEOUT);
+
+    indices
+        .iter()
+        .zip(launched)
+        .map(|(&i, (result, started, spawned))| {
+            let name = &config.windows[i].name;
+            timing::add(name, timing::Phase::Spawn, spawned - started);
+            let handle = found.get(name).map(|&(handle, at)| {
+                timing::add(name, timing::Phase::Detect, at.saturating_duration_since(spawned));
+                handle
+            });
+            (i, (result, handle))
+        })
+        .collect()
+}
+
+/// Handle and title of every open window of the terminal
+fn window_titles(backend: &dyn TerminalBackend) -> Vec<(i64, String)> {
+    let handles = backend.window_handles().unwrap_or_default();
+    let info = windows::get_window_info(&handles).unwrap_or_default();
+    handles.into_iter().filter_map(|h| info.get(&h).map(|w| (h, w.title.clone()))).collect()
+}
+
+/// Print the outcome of every window, then `done` if all of them worked or
+/// how many didn't
+fn print_summary(summary: &summary::Summary, done: String) {
+    say!();
+    for line in summary.lines() {
+        say!("{}", line);
+    }
+    say!();
+    if summary.failed() == 0 {
+        say!("{}", done);
+    } else {
+        say!("{}", t!("launch-partial", failed = summary.failed(), count = summary.len(), running = summary.running()));
+    }
+}
+
+/// Target rectangle of every window: its grid cell, or the dropdown area
+fn window_positions(config: &config::Config, grid: &layout::GridLayout) -> Vec<layout::Rect> {
+    config
+        .grid_slots()
+        .into_iter()
+        .map(|slot| match slot {
+            Some(cell) => grid.calculate_position(cell),
+            None => grid.dropdown_area(),
+        })
+        .collect()
+}
+
+/// Launch every session as a tab of a single Windows Terminal window
+/// and place that window over the whole display working area
+fn launch_tabs(
+    config: &config::Config,
+    launcher: &wsl::WslLauncher,
+    session: &str,
+    display_area: layout::Rect,
+    no_arrange: bool,
+) -> Vec<state::WindowRecord> {
+    say!("{}", t!("launch-tabs", count = config.windows.len()));
+
+    let wt_window = terminal::wt_window_name(session, "tabs");
+    let wt = terminal::WindowsTerminal::new(launcher.clone(), session);
+    let handles_before = handle_snapshot(&wt);
+    let mut window_handle = None;
+    let mut launched = Vec::new();
+    let mut summary = summary::Summary::default();
+
+    for (i, window) in config.windows.iter().enumerate() {
+        if let Err(e) = run_pre_launch_hooks(config, session, window) {
+            say!("  [{}] {} ... {}", i + 1, window.name, output::warn("SKIPPED (pre_launch hook failed)"));
+            summary.set(&window.name, Outcome::Skipped, Some(format!("{:#}", e)));
+            continue;
+        }
+        say_start!("  [{}] {} ... ", i + 1, window.name);
+
+        match launcher.launch_tab(window, &wt_window) {
+            Ok(()) => {
+                // The first tab creates the window; later tabs reuse it
+                if window_handle.is_none() {
+                    window_handle = find_new_handle(&wt, &handles_before);
+                }
+                launched.push(window);
+                say!("{}", output::ok("OK"));
+                events::record(EventKind::WindowLaunched, &window.name, Some("tab".to_string()));
+                summary.set(&window.name, Outcome::Launched, None);
+                warn_hook_failure(hooks::run_window(config, session, window, HookEvent::PostLaunch));
+            }
+            Err(e) => {
+                say!("{}", output::fail("FAILED"));
+                events::record(EventKind::LaunchFailed, &window.name, Some(format!("{:#}", e)));
+                summary.set(&window.name, Outcome::LaunchFailed, Some(format!("{:#}", e)));
+                warn!("Failed to launch '{}': {}", window.name, e);
+            }
+        }
+    }
+
+    if !no_arrange && !launched.is_empty() {
+        say!();
+        say_start!("Arranging tab window ... ");
+        let arranged = match window_handle {
+            Some(handle) => match windows::move_window_by_handle(handle, &display_area) {
+                Ok(()) => {
+                    say!("{}", output::ok("OK"));
+                    run_post_arrange_hooks(config, session, launched.iter().copied());
+                    Ok(())
+                }
+                Err(e) => {
+                    say!("{}", output::fail("FAILED"));
+                    warn!("Failed to arrange tab window: {}", e);
+                    Err(format!("{:#}", e))
+                }
+            },
+            None if dry_run::is_enabled() => {
+                dry_run::show(format!("move the tab window to {}", display_area));
+                Ok(())
+            }
+            None => {
+                say!("{}", output::warn("SKIPPED (handle not found)"));
+                Err("tab window not found after launch".to_string())
+            }
+        };
+        note_shared_arrangement(&mut summary, launched.iter().copied(), arranged);
+    }
+
+    print_summary(&summary, t!("launch-tabs-done", count = summary.running()));
+    shared_window_records(session, launched, &wt_window, window_handle, display_area)
+}
+
+/// Launch all sessions as panes of one Windows Terminal window laid out
+/// like the grid, then place that window over the display working area
+fn launch_panes(
+    config: &config::Config,
+    launcher: &wsl::WslLauncher,
+    session: &str,
+    cols: u32,
+    display_area: layout::Rect,
+    no_arrange: bool,
+) -> Vec<state::WindowRecord> {
+    say_start!("Launching {} panes ... ", config.windows.len());
+
+    // Panes whose pre_launch hook failed are left out of the layout
+    let mut summary = summary::Summary::default();
+    let panes: Vec<config::WindowConfig> = config
+        .windows
+        .iter()
+        .filter(|w| match run_pre_launch_hooks(config, session, w) {
+            Ok(()) => true,
+            Err(e) => {
+                say!("  {} ... {}", w.name, output::warn("SKIPPED (pre_launch hook failed)"));
+                summary.set(&w.name, Outcome::Skipped, Some(format!("{:#}", e)));
+                false
+            }
+        })
+        .cloned()
+        .collect();
+    if panes.is_empty() {
+        say!("No panes to launch.");
+        if summary.len() > 0 {
+            print_summary(&summary, String::new());
+        }
+        return Vec::new();
+    }
+
+    let wt_window = terminal::wt_window_name(session, "panes");
+    let wt = terminal::WindowsTerminal::new(launcher.clone(), session);
+    let handles_before = handle_snapshot(&wt);
+    if let Err(e) = launcher.launch_panes(&panes, cols, &wt_window) {
+        say!("{}", output::fail("FAILED"));
+        for window in &panes {
+            events::record(EventKind::LaunchFailed, &window.name, Some(format!("{:#}", e)));
+            summary.set(&window.name, Outcome::LaunchFailed, Some(format!("{:#}", e)));
+        }
+        warn!("Failed to launch panes: {}", e);
+        print_summary(&summary, String::new());
+        return Vec::new();
+    }
+    let window_handle = find_new_handle(&wt, &handles_before);
+    say!("{}", output::ok("OK"));
+    for window in &panes {
+        events::record(EventKind::WindowLaunched, &window.name, Some("pane".to_string()));
+        summary.set(&window.name, Outcome::Launched, None);
+        warn_hook_failure(hooks::run_window(config, session, window, HookEvent::PostLaunch));
+    }
+
+    if !no_arrange {
+        say_start!("Arranging pane window ... ");
+        let arranged = match window_handle {
+            Some(handle) => match windows::move_window_by_handle(handle, &display_area) {
+                Ok(()) => {
+                    say!("{}", output::ok("OK"));
+                    run_post_arrange_hooks(config, session, &panes);
+                    Ok(())
+                }
+                Err(e) => {
+                    say!("{}", output::fail("FAILED"));
+                    warn!("Failed to arrange pane window: {}", e);
+                    Err(format!("{:#}", e))
+                }
+            },
+            None if dry_run::is_enabled() => {
+                dry_run::show(format!("move the pane window to {}", display_area));
+                Ok(())
+            }
+            None => {
+                say!("{}", output::warn("SKIPPED (handle not found)"));
+                Err("pane window not found after launch".to_string())
+            }
+        };
+        note_shared_arrangement(&mut summary, &panes, arranged);
+    }
+
+    print_summary(&summary, t!("launch-panes-done", count = summary.running()));
+    shared_window_records(session, panes.iter(), &wt_window, window_handle, display_area)
+}
+
+/// Outcome of the windows sharing one wt window (tabs/panes mode) once that
+/// window was arranged, or why it couldn't be
+fn note_shared_arrangement<'a>(
+    summary: &mut summary::Summary,
+    windows: impl IntoIterator<Item = &'a config::WindowConfig>,
+    arranged: std::result::Result<(), String>,
+) {
+    for window in windows {
+        match &arranged {
+            Ok(()) => summary.set(&window.name, Outcome::Arranged, None),
+            Err(reason) => {
+                events::record(EventKind::MoveFailed, &window.name, Some(reason.clone()));
+                summary.set(&window.name, Outcome::ArrangeFailed, Some(reason.clone()));
+            }
+        }
+    }
+}
+
+/// Records for sessions sharing one wt window (tabs/panes mode)
+fn shared_window_records<'a>(
+    session: &str,
+    windows: impl IntoIterator<Item = &'a config::WindowConfig>,
+    wt_window: &str,
+    handle: Option<i64>,
+    rect: layout::Rect,
+) -> Vec<state::WindowRecord> {
+    let launched_at = chrono::Local::now();
+    windows
+        .into_iter()
+        .map(|window| state::WindowRecord {
+            session: session.to_string(),
+            name: window.name.clone(),
+            wt_window: Some(wt_window.to_string()),
+            handle,
+            pid: None,
+            rect: Some(rect),
+            launched_at,
+            health: None,
+            idle_since: None,
+            claude_session: None,
+        })
+        .collect()
+}
+
+/// Pick the conversation of each `claude.resume: always|ask` window among
+/// `indices`: the one recorded for it, unless the user declines it with
+/// `ask`, or a new one
+///
+/// Returns the config with those windows' commands set and the conversation
+/// ID of each window, to record; None if no window resumes.
+fn resolve_conversations(
+    config: &config::Config,
+    session: &str,
+    indices: &[usize],
+) -> Option<(config::Config, HashMap<String, String>)> {
+    let policy = |i: usize| config.windows[i].claude.as_ref()?.resume.as_ref()?.recorded_policy();
+    let resuming: Vec<(usize, config::ResumePolicy)> =
+        indices.iter().filter_map(|&i| Some((i, policy(i)?))).collect();
+    if resuming.is_empty() {
+        return None;
+    }
+
+    // Closed windows keep their conversations, so there's nothing to prune
+    let state = state::SessionState::load().unwrap_or_default();
+    let mut resolved = config.clone();
+    let mut ids = HashMap::new();
+    for (i, policy) in resuming {
+        let window = &config.windows[i];
+        let Some(ref preset) = window.claude else { continue };
+        let recorded = state
+            .claude_session(session, &window.name)
+            .filter(|id| policy != config::ResumePolicy::Ask || confirm_resume(&window.name, id));
+        let id = match recorded {
+            Some(id) => id.to_string(),
+            None => match claude::new_session_id() {
+                Ok(id) => id,
+                Err(e) => {
+                    warn!("Starting '{}' without recording its conversation: {:#}", window.name, e);
+                    continue;
+                }
+            },
+        };
+        let conversation = match recorded {
+            Some(_) => claude::Conversation::Continue(&id),
+            None => claude::Conversation::Start(&id),
+        };
+        resolved.windows[i].command = Some(claude::conversation_command(preset, &config.roles, conversation));
+        ids.insert(window.name.clone(), id);
+    }
+    Some((resolved, ids))
+}
+
+/// Ask whether to continue a window's recorded claude conversation; yes
+/// if there is no terminal to ask on
+fn confirm_resume(name: &str, id: &str) -> bool {
+    confirm::ask(&format!("Continue the previous claude conversation of '{}' ({})?", name, id), true)
+}
+
+/// Ask which open window belongs to `name`; None if the user skips it
+fn prompt_window(name: &str, candidates: &[&windows::WindowInfo]) -> Result<Option<usize>> {
+    use std::io::Write;
+
+    println!();
+    println!("Open windows:");
+    for (n, candidate) in candidates.iter().enumerate() {
+        println!("  {}) {} (handle: {})", n + 1, candidate.title, candidate.handle);
+    }
+    loop {
+        print!("Window for '{}' [1-{}, Enter to skip]: ", name, candidates.len());
+        std::io::stdout().flush()?;
+        let mut answer = String::new();
+        if std::io::stdin().read_line(&mut answer)? == 0 {
+            return Ok(None);
+        }
+        let answer = answer.trim();
+        if answer.is_empty() {
+            return Ok(None);
+        }
+        match answer.parse::<usize>() {
+            Ok(n) if (1..=candidates.len()).contains(&n) => return Ok(Some(n - 1)),
+            _ => println!("Enter a number between 1 and {}.", candidates.len()),
+        }
+    }
+}
+
+/// Run a window's pre_launch hooks; false if one failed and the window
+/// should not be launched
+fn run_pre_launch_hooks(config: &config::Config, session: &str, window: &config::WindowConfig) -> Result<()> {
+    hooks::run_window(config, session, window, HookEvent::PreLaunch).inspect_err(|e| {
+        warn!("Skipping '{}': {:#}", window.name, e);
+        events::record(EventKind::LaunchFailed, &window.name, Some(format!("{:#}", e)));
+    })
+}
+
+/// Run the post_arrange hooks of windows sharing one arranged wt window
+fn run_post_arrange_hooks<'a>(
+    config: &config::Config,
+    session: &str,
+    windows: impl IntoIterator<Item = &'a config::WindowConfig>,
+) {
+    for window in windows {
+        warn_hook_failure(hooks::run_window(config, session, window, HookEvent::PostArrange));
+    }
+}
+
+/// Run the pre_stop hooks of the configured window called `name`, if any
+fn run_stop_hooks(config: &config::Config, session: &str, name: &str) {
+    if let Some(window) = config.windows.iter().find(|w| w.name == name) {
+        warn_hook_failure(hooks::run_window(config, session, window, HookEvent::PreStop));
+    }
+}
+
+/// Hooks other than pre_launch never stop a command; their failures are logged
+fn warn_hook_failure(result: Result<()>) {
+    if let Err(e) = result {
+        warn!("{:#}", e);
+    }
+}
+
+/// Type `text` into the session of the window at `index` and submit it
+fn send_prompt(config: &config::Config, session: &str, mode: LaunchMode, index: usize, text: &str) -> Result<()> {
+    let window = &config.windows[index];
+    // A multiplexer session takes input directly, without touching the terminal
+    if let Some((kind, mux_session)) = multiplexer::Multiplexer::for_window(window) {
+        return multiplexer::send_text(&config.wsl_distribution, kind, mux_session, text);
+    }
+    match mode {
+        LaunchMode::Windows => {
+            let state = load_state(session);
+            let handle = state
+                .session_windows(session)
+                .find(|r| r.name == window.name)
+                .and_then(|r| r.handle)
+                .with_context(|| format!("'{}' is not running in session '{}'", window.name, session))?;
+            Ok(windows::type_text(Some(handle), text)?)
+        }
+        LaunchMode::Tabs | LaunchMode::Panes => {
+            let launcher = wsl::WslLauncher::new(&config.wsl_distribution)
+                .with_wt_version(terminal::wt_version::detect());
+            if mode == LaunchMode::Tabs {
+                launcher.focus_tab(&terminal::wt_window_name(session, "tabs"), index)?;
+            } else {
+                let (cols, _) = config.layout.parse_grid()?;
+                let pane_id = layout::pane_ids(cols, config.windows.len())[index];
+                launcher.focus_pane(&terminal::wt_window_name(session, "panes"), pane_id)?;
+            }
+            Ok(windows::type_text(None, text)?)
+        }
+    }
+}
+
+/// Refuse to launch if claude can't start in some of the claude windows
+///
+/// A check that can't run at all (e.g. wsl.exe failing) only warns, since
+/// launching will report the real problem.
+fn preflight_claude(config: &config::Config, windows: &[&config::WindowConfig]) -> Result<()> {
+    let checks = match preflight::check_windows(config, windows) {
+        Ok(checks) => checks,
+        Err(e) => {
+            warn!("Skipping the claude preflight check: {:#}", e);
+            return Ok(());
+        }
+    };
+    let failures: Vec<String> = checks
+        .iter()
+        .filter_map(|c| c.cli.problem().map(|p| format!("  {} ({}): {}", c.window, preflight::shell(c.login_shell), p)))
+        .collect();
+    if !failures.is_empty() {
+        anyhow::bail!(
+            "Not launching: claude would fail to start in {} window(s):\n{}\n\
+            Run 'wsl-multi-launcher doctor' for details, or launch with --no-preflight.",
+            failures.len(),
+            failures.join("\n")
+        );
+    }
+    Ok(())
+}
+
+/// Create the missing git worktrees of `worktrees:` windows about to launch
+fn ensure_worktrees(config: &config::Config, windows: &[&config::WindowConfig]) -> Result<()> {
+    for path in worktree::ensure(&config.wsl_distribution, windows)? {
+        say!("Created worktree {}", path);
+    }
+    Ok(())
+}
+
+/// Copy or link the `provision:` files into the working directories
+fn provision_windows(config: &config::Config, windows: &[&config::WindowConfig]) -> Result<()> {
+    for (name, files) in provision::apply(&config.wsl_distribution, windows)? {
+        say!("Provisioned {} file(s) for '{}'", files, name);
+    }
+    Ok(())
+}
+
+/// Archive the transcripts of the claude windows among the stopped ones
+fn archive_transcripts(config: &config::Config, session: &str, stopped: &[state::WindowRecord]) -> Result<()> {
+    let windows: Vec<(&config::WindowConfig, chrono::DateTime<chrono::Local>)> = stopped
+        .iter()
+        .filter_map(|record| {
+            let window = config.windows.iter().find(|w| w.name == record.name && w.claude.is_some())?;
+            Some((window, record.launched_at))
+        })
+        .collect();
+    if windows.is_empty() {
+        return Ok(());
+    }
+
+    say!();
+    say!("Archiving transcripts of {} window(s)...", windows.len());
+    for archived in transcripts::archive(&config.wsl_distribution, session, &windows)? {
+        match archived.path {
+            Some(ref path) => say!("  {} ... {} file(s) to {}", archived.window, archived.files, path),
+            None => say!("  {} ... NONE", archived.window),
+        }
+    }
+    Ok(())
+}
+
+/// Remove the merged worktrees of the worktree windows in `names` (all if
+/// empty), except the windows that are still open
+fn cleanup_worktrees(config: &config::Config, names: &[String], still_open: &HashSet<String>) -> Result<()> {
+    let windows: Vec<&config::WindowConfig> = config
+        .windows
+        .iter()
+        .filter(|w| w.worktree.is_some())
+        .filter(|w| names.is_empty() || names.contains(&w.name))
+        .collect();
+    if windows.is_empty() {
+        return Ok(());
+    }
+    let removable: Vec<&str> =
+        windows.iter().filter(|w| !still_open.contains(&w.name)).map(|w| w.name.as_str()).collect();
+    if !removable.is_empty() && !confirm::destructive(&t!("cleanup-confirm", names = removable.join(", ")))? {
+        return Ok(());
+    }
+
+    say!();
+    say!("Cleaning up {} worktree(s)...", windows.len());
+    let mut failed = 0;
+    for window in windows {
+        say_start!("  {} ... ", window.name);
+        if still_open.contains(&window.name) {
+            say!("KEPT (window still open)");
+            continue;
+        }
+        let Some(ref worktree) = window.worktree else { continue };
+        match worktree::cleanup(&config.wsl_distribution, worktree) {
+            Ok(outcome) => say!("{}", outcome),
+            Err(e) => {
+                failed += 1;
+                say!("{}", output::fail("FAILED"));
+                eprintln!("    {:#}", e);
+            }
+        }
+    }
+    if failed > 0 {
+        anyhow::bail!("Failed to clean up {} worktree(s)", failed);
+    }
+    Ok(())
+}
+
+/// Profiles for `tui`: the config, then the other configs in its directory
+fn tui_profiles(config_path: &str, session: Option<&str>) -> Result<Vec<tui::Profile>> {
+    let config = load_config_with_helpful_error(config_path)?;
+    let mut profiles = vec![tui::Profile {
+        path: config_path.to_string(),
+        session: session_name(config_path, session),
+        config,
+    }];
+
+    let dir = match Path::new(config_path).parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let mut paths: Vec<std::path::PathBuf> = std::fs::read_dir(dir)
+        .map(|entries| entries.filter_map(|e| e.ok()).map(|e| e.path()).collect())
+        .unwrap_or_default();
+    paths.retain(|p| matches!(p.extension().and_then(|e| e.to_str()), Some("yaml" | "yml")));
+    paths.sort();
+    for path in paths {
+        if path.file_name() == Path::new(config_path).file_name() {
+            continue;
+        }
+        // Other YAML files in the directory aren't necessarily configs
+        let path = path.to_string_lossy().to_string();
+        match config::load(&path) {
+            Ok(config) if !config.windows.is_empty() => {
+                let session = session_name(&path, None);
+                profiles.push(tui::Profile { path, session, config });
+            }
+            Ok(_) => {}
+            Err(e) => debug!("Not a profile: {}: {:#}", path, e),
+        }
+    }
+    Ok(profiles)
+}
+
+/// Launch the configured windows matching `filter` (all of them, or only
+/// the missing ones with `resume`)
+fn launch(
+    mut config: config::Config,
+    session: &str,
+    no_arrange: bool,
+    mode: Option<LaunchMode>,
+    resume: bool,
+    preflight: bool,
+    filter: &config::WindowFilter,
+) -> Result<()> {
+    let (cols, rows) = config.layout.parse_grid()?;
+    let mode = mode.unwrap_or(config.layout.mode);
+    filter.check(&config)?;
+    // Windows keep their cells; tabs and panes are opened from the windows left
+    if mode != LaunchMode::Windows {
+        config.windows.retain(|w| filter.matches(w));
+    }
+    if resume && mode != LaunchMode::Windows {
+        anyhow::bail!("launch --resume only supports layout.mode 'windows' (tabs/panes share one window)");
+    }
+
+    info!(
+        "Launching {} windows in {}x{} grid on display {} using {} ({:?} mode)",
+        config.windows.len(),
+        cols,
+        rows,
+        config.target_display,
+        config.wsl_distribution,
+        mode
+    );
+
+    let display_area = target_display_area(&config)?;
+    let terminal_kind = terminal::resolve(config.terminal);
+    let _lock = lock::SessionLock::acquire(session, "launch")?;
+    let launcher = build_launcher(&config, terminal_kind, mode, session)?;
+
+    if mode != LaunchMode::Windows && terminal_kind != config::TerminalKind::WindowsTerminal {
+        anyhow::bail!(
+            "{:?} mode requires Windows Terminal, but wt.exe was not found. \
+            Install Windows Terminal or use '--mode windows'.",
+            mode
+        );
+    }
+
+    let backend = terminal::create(terminal_kind, launcher.clone(), session);
+    let mut state = load_state(session);
+    // Windows filtered out are left alone
+    let running = state
+        .session_windows(session)
+        .filter(|r| filter.is_empty() || config.windows.iter().any(|w| w.name == r.name && filter.matches(w)))
+        .count();
+    // With --resume, windows still up from the previous launch are kept
+    let selected: Vec<usize> = config
+        .windows
+        .iter()
+        .enumerate()
+        .filter(|(_, w)| !resume || !state.session_windows(session).any(|r| r.name == w.name))
+        .filter(|(_, w)| filter.matches(w))
+        .map(|(i, _)| i)
+        .collect();
+    if resume {
+        if selected.is_empty() {
+            say!("All {} windows of session '{}' are already running.", config.windows.len(), session);
+            return Ok(());
+        }
+        say!(
+            "Resuming launch: {} window(s) already running, {} to launch.",
+            config.windows.len() - selected.len(),
+            selected.len()
+        );
+    } else if running > 0 {
+        warn!(
+            "Session '{}' already has {} window(s) open from a previous launch; \
+            launching again creates duplicates (use --resume to launch only missing ones)",
+            session, running
+        );
+    }
+
+    hooks::run_global(&config, session, HookEvent::PreLaunch)
+        .context("Not launching: pre_launch hook failed")?;
+    // Tabs and panes open every window
+    let windows: Vec<&config::WindowConfig> = match mode {
+        LaunchMode::Windows => selected.iter().map(|&i| &config.windows[i]).collect(),
+        LaunchMode::Tabs | LaunchMode::Panes => config.windows.iter().collect(),
+    };
+    if preflight {
+        preflight_claude(&config, &windows)?;
+    }
+    ensure_worktrees(&config, &windows)?;
+    provision_windows(&config, &windows)?;
+
+    let records = match mode {
+        LaunchMode::Windows => {
+            let grid = layout::GridLayout::new(cols, rows, display_area);
+            launch_windows(&config, &backend, session, &grid, &selected, no_arrange)
+        }
+        LaunchMode::Tabs | LaunchMode::Panes => {
+            let all: Vec<usize> = (0..config.windows.len()).collect();
+            let resolved = resolve_conversations(&config, session, &all);
+            let config = resolved.as_ref().map(|(config, _)| config).unwrap_or(&config);
+            let mut records = match mode {
+                LaunchMode::Tabs => launch_tabs(config, &launcher, session, display_area, no_arrange),
+                _ => launch_panes(config, &launcher, session, cols, display_area, no_arrange),
+            };
+            if let Some((_, ref ids)) = resolved {
+                for record in &mut records {
+                    record.claude_session = ids.get(&record.name).cloned();
+                }
+            }
+            records
+        }
+    };
+    save_state(&mut state, records);
+
+    warn_hook_failure(hooks::run_global(&config, session, HookEvent::PostLaunch));
+    if !no_arrange {
+        warn_hook_failure(hooks::run_global(&config, session, HookEvent::PostArrange));
+    }
+    Ok(())
+}
+
+/// Why a launch failed: its error, or the windows that failed to launch or
+/// move; None if everything worked
+fn launch_failure(result: &Result<()>, session: &str, started: chrono::DateTime<chrono::Local>) -> Option<String> {
+    if let Err(e) = result {
+        return Some(format!("{:#}", e));
+    }
+    let failed: Vec<String> = launch_problems(session, started)
+        .iter()
+        .map(|e| format!("{} {}: {}", e.kind, e.window, e.detail.as_deref().unwrap_or("")))
+        .collect();
+    (!failed.is_empty()).then(|| failed.join("\n"))
+}
+
+/// Windows of `session` that failed to launch or move since `started`
+fn launch_problems(session: &str, started: chrono::DateTime<chrono::Local>) -> Vec<events::Event> {
+    let events = events::path().and_then(|path| events::load_from(&path, Some(started))).unwrap_or_default();
+    events
+        .into_iter()
+        .filter(|e| e.session == session && matches!(e.kind, EventKind::LaunchFailed | EventKind::MoveFailed))
+        .collect()
+}
+
+/// JSON output of `launch`: the windows launched (as in `ps`) and the
+/// failures
+fn launch_json(
+    session: &str,
+    started: chrono::DateTime<chrono::Local>,
+    result: &Result<()>,
+    timings: &[timing::WindowTiming],
+    wall: std::time::Duration,
+) -> serde_json::Value {
+    let mut state = load_state(session);
+    state.windows.retain(|r| r.launched_at >= started);
+    let windows = status_rows(&state, session).unwrap_or_else(|e| {
+        warn!("Failed to check the launched windows: {:#}", e);
+        Vec::new()
+    });
+    let failures = launch_problems(session, started);
+    serde_json::json!({
+        "session": session,
+        "ok": result.is_ok() && failures.is_empty(),
+        "error": result.as_ref().err().map(|e| format!("{:#}", e)),
+        "error_kind": result.as_ref().err().map(|e| error::kind(e.as_ref())),
+        "windows": windows,
+        "failures": failures,
+        "timing": {
+            "total_ms": wall.as_millis() as u64,
+            "windows": timings,
+        },
+    })
+}
+
+/// Print how `session` differs from the config and, with `apply`, launch,
+/// close and move windows until it matches
+fn reconcile(config_path: &str, session: &str, apply: bool, force: bool) -> Result<()> {
+    let config = load_config_with_helpful_error(config_path)?;
+    if config.layout.mode != LaunchMode::Windows {
+        anyhow::bail!("plan/reconcile only support layout.mode 'windows' (tabs/panes share one window)");
+    }
+    let (cols, rows) = config.layout.parse_grid()?;
+    let display_area = target_display_area(&config)?;
+    let grid = layout::GridLayout::new(cols, rows, display_area);
+    let positions = window_positions(&config, &grid);
+    let _lock = if apply { Some(lock::SessionLock::acquire(session, "reconcile")?) } else { None };
+
+    let mut state = load_state(session);
+    let records: Vec<state::WindowRecord> = state.session_windows(session).cloned().collect();
+    let handles: Vec<i64> = records.iter().filter_map(|r| r.handle).collect();
+    let current: std::collections::HashMap<i64, layout::Rect> = windows::get_window_info(&handles)?
+        .into_values()
+        .filter(|info| info.visible)
+        .map(|info| (info.handle, info.rect()))
+        .collect();
+
+    let changes = plan::diff(&config.windows, &positions, &records, &current);
+    if changes.is_empty() {
+        println!("Session '{}' matches the config.", session);
+        return Ok(());
+    }
+    for change in &changes {
+        println!("  {}", change);
+    }
+    println!();
+    println!("Plan: {}.", plan::summary(&changes));
+    if !apply {
+        println!("Run 'reconcile --apply' to make these changes.");
+        return Ok(());
+    }
+    let closing: Vec<&str> = changes
+        .iter()
+        .filter_map(|change| match change {
+            plan::Change::Close { name } => Some(name.as_str()),
+            _ => None,
+        })
+        .collect();
+    if !closing.is_empty()
+        && !confirm::destructive(&t!("reconcile-confirm-close", count = closing.len(), names = closing.join(", ")))?
+    {
+        return Ok(());
+    }
+
+    say!();
+    let mut to_launch = Vec::new();
+    for change in &changes {
+        match change {
+            plan::Change::Launch { index, .. } => to_launch.push(*index),
+            plan::Change::Close { name } => {
+                let Some(record) = records.iter().find(|r| &r.name == name) else { continue };
+                say_start!("Closing {} ... ", name);
+                terminate_recorded_window(&config, record, config.stop.grace_secs);
+                if close_recorded_window(record, force) {
+                    state.remove(session, name);
+                    events::record(EventKind::WindowClosed, name, Some("reconcile".to_string()));
+                    say!("{}", output::ok("OK"));
+                } else {
+                    say!("{}", output::fail("FAILED (use --force to kill it)"));
+                }
+            }
+            plan::Change::Move { index, name, handle, to, .. } => {
+                say_start!("Moving {} ... ", name);
+                match windows::move_window_by_handle(*handle, to) {
+                    Ok(()) => {
+                        say!("{}", output::ok("OK"));
+                        // The cell may have changed with the config; `ps` compares against it
+                        if let Some(record) = records.iter().find(|r| &r.name == name) {
+                            state.record(state::WindowRecord { rect: Some(*to), ..record.clone() });
+                        }
+                        events::record(EventKind::WindowMoved, name, Some("reconcile".to_string()));
+                        warn_hook_failure(hooks::run_window(
+                            &config,
+                            session,
+                            &config.windows[*index],
+                            HookEvent::PostArrange,
+                        ));
+                    }
+                    Err(e) => {
+                        say!("{}", output::fail("FAILED"));
+                        events::record(EventKind::MoveFailed, name, Some(format!("{:#}", e)));
+                        warn!("Failed to move '{}': {}", name, e);
+                    }
+                }
+            }
+        }
+    }
+
+    let launched = if to_launch.is_empty() {
+        Vec::new()
+    } else {
+        say!();
+        let terminal_kind = terminal::resolve(config.terminal);
+        let launcher = build_launcher(&config, terminal_kind, LaunchMode::Windows, session)?;
+        let backend = terminal::create(terminal_kind, launcher, session);
+        let windows: Vec<&config::WindowConfig> = to_launch.iter().map(|&i| &config.windows[i]).collect();
+        ensure_worktrees(&config, &windows)?;
+        provision_windows(&config, &windows)?;
+        launch_windows(&config, &backend, session, &grid, &to_launch, false)
+    };
+    save_state(&mut state, launched);
+    Ok(())
+}
+
+/// Drop the windows over a `claude.budget` with `pause_dispatch` from the
+/// dispatch targets
+fn skip_over_budget(config: &config::Config, windows: Vec<String>) -> Result<Vec<String>> {
+    let paused: Vec<&config::WindowConfig> = config
+        .windows
+        .iter()
+        .filter(|w| windows.contains(&w.name))
+        .filter(|w| w.claude.as_ref().and_then(|c| c.budget.as_ref()).is_some_and(|b| b.pause_dispatch))
+        .collect();
+    if paused.is_empty() {
+        return Ok(windows);
+    }
+    let over: Vec<budget::Spent> =
+        budget::check(&config.wsl_distribution, &paused)?.into_iter().filter(|s| s.over()).collect();
+    for spent in &over {
+        println!("  {} skipped: over budget ({})", spent.window, spent.describe());
+    }
+    let windows: Vec<String> = windows.into_iter().filter(|name| !over.iter().any(|s| &s.window == name)).collect();
+    if windows.is_empty() {
+        anyhow::bail!("Every target window is over its budget; not dispatching");
+    }
+    Ok(windows)
+}
+
+/// Index of the configured window `name` picks out: its exact name, or an
+/// unambiguous prefix or fuzzy match
+fn window_index(config: &config::Config, name: &str) -> Result<usize> {
+    let names: Vec<&str> = config.windows.iter().map(|w| w.name.as_str()).collect();
+    fuzzy::find(name, &names)
+}
+
+/// Close a recorded window (if running) and launch it again in its cell,
+/// recording the new window in `state`
+fn restart_window(
+    config: &config::Config,
+    backend: &Arc<dyn TerminalBackend>,
+    session: &str,
+    grid: &layout::GridLayout,
+    state: &mut state::SessionState,
+    index: usize,
+    force: bool,
+) -> Result<()> {
+    let name = &config.windows[index].name;
+    match state.remove(session, name) {
+        Some(record) => {
+            run_stop_hooks(config, session, name);
+            terminate_recorded_window(config, &record, config.stop.grace_secs);
+            say_start!("Closing {} ... ", name);
+            if close_recorded_window(&record, force) {
+                say!("{}", output::ok("OK"));
+            } else {
+                say!("{}", output::fail("FAILED"));
+                state.record(record);
+                anyhow::bail!("'{}' did not close; retry with --force or close it manually", name);
+            }
+        }
+        None => say!("'{}' is not running; launching it", name),
+    }
+
+    let records = launch_windows(config, backend, session, grid, &[index], false);
+    if records.is_empty() {
+        anyhow::bail!("Failed to launch '{}'", name);
+    }
+    events::record(EventKind::WindowRestarted, name, None);
+    for record in records {
+        state.record(record);
+    }
+    Ok(())
+}
+
+/// Live status of every recorded window of `session`, as JSON rows
+fn status_rows(state: &state::SessionState, session: &str) -> Result<Vec<serde_json::Value>> {
+    let records: Vec<&state::WindowRecord> = state.session_windows(session).collect();
+    let handles: Vec<i64> = records.iter().filter_map(|w| w.handle).collect();
+    let info = windows::get_window_info(&handles)?;
+    let now = chrono::Local::now();
+
+    Ok(records
+        .iter()
+        .map(|record| {
+            let current = record.handle.and_then(|h| info.get(&h));
+            let status = record.status(current.map(|i| (i.rect(), i.visible)));
+            // Ignore a status left over from before this launch
+            let exit = exit_status::read(session, &record.name).filter(|e| e.at >= record.launched_at);
+            serde_json::json!({
+                "name": record.name,
+                "status": status,
+                "exit_code": exit.map(|e| e.code),
+                "exited_at": exit.map(|e| e.at),
+                "uptime_secs": (now - record.launched_at).num_seconds(),
+                "launched_at": record.launched_at,
+                "rect": current.map(|i| i.rect()),
+                "handle": record.handle,
+                "pid": record.pid,
+                "wt_window": record.wt_window,
+                "health": record.health,
+                "idle_secs": record.idle_since.map(|since| (now - since).num_seconds()),
+            })
+        })
+        .collect())
+}
+
+/// Table of `displays`, marking the one `target_display` of the config
+/// selects
+fn print_displays(displays: &[layout::DisplayInfo], target: Option<u32>, config_path: &str) {
+    println!("Found {} display(s):", displays.len());
+    for (i, display) in displays.iter().enumerate() {
+        println!();
+        let mut marks = Vec::new();
+        if display.primary {
+            marks.push(output::ok("(Primary)"));
+        }
+        if target == Some(i as u32) {
+            marks.push(output::ok(format!("(Target of {})", config_path)));
+        }
+        println!("  {} {}", output::heading(format!("Display {}", i)), marks.join(" "));
+        println!("    Device:       {}", display.device_name);
+        println!(
+            "    Resolution:   {}x{}",
+            display.bounds.width, display.bounds.height
+        );
+        println!(
+            "    Position:     ({}, {})",
+            display.bounds.x, display.bounds.y
+        );
+        println!(
+            "    Working Area: {}x{} at ({}, {})",
+            display.working_area.width,
+            display.working_area.height,
+            display.working_area.x,
+            display.working_area.y
+        );
+    }
+    if let Some(target) = target.filter(|&t| t as usize >= displays.len()) {
+        println!();
+        println!("  {}", output::warn(format!("target_display {} of {} is not connected", target, config_path)));
+    }
+}
+
+/// `displays` entry of the JSON output
+fn display_json(index: usize, display: &layout::DisplayInfo) -> serde_json::Value {
+    let bounds = |b: &layout::BoundsInfo| serde_json::json!({ "x": b.x, "y": b.y, "width": b.width, "height": b.height });
+    serde_json::json!({
+        "index": index,
+        "device_name": display.device_name,
+        "primary": display.primary,
+        "bounds": bounds(&display.bounds),
+        "working_area": bounds(&display.working_area),
+    })
+}
+
+/// JSON output of `status`; a check that fails is null, with its error
+/// under `errors`
+fn status_json(config_path: &str) -> serde_json::Value {
+    let mut errors = serde_json::Map::new();
+    let distributions = wsl::distributions()
+        .map_err(|e| errors.insert("wsl_distributions".to_string(), format!("{:#}", e).into()))
+        .ok();
+    let displays = windows::get_displays()
+        .map(|displays| displays.iter().enumerate().map(|(i, d)| display_json(i, d)).collect::<Vec<_>>())
+        .map_err(|e| errors.insert("displays".to_string(), format!("{:#}", e).into()))
+        .ok();
+    let version = terminal::wt_version::detect();
+    let config = if Path::new(config_path).exists() {
+        match config::load(config_path) {
+            Ok(c) => serde_json::json!({ "path": config_path, "exists": true, "windows": c.windows.len() }),
+            Err(e) => serde_json::json!({ "path": config_path, "exists": true, "error": format!("{:#}", e) }),
+        }
+    } else {
+        serde_json::json!({ "path": config_path, "exists": false })
+    };
+    serde_json::json!({
+        "wsl_distributions": distributions,
+        "displays": displays,
+        "windows_terminal": {
+            "installed": version.is_some() || terminal::is_available("wt.exe"),
+            "version": version.map(|v| v.to_string()),
+        },
+        "config": config,
+        "errors": errors,
+    })
+}
+
+/// One dashboard pane per configured window, from the recorded windows,
+/// claude activity and output logs
+fn dashboard_panes(config: &config::Config, session: &str) -> Result<Vec<dashboard::Pane>> {
+    let state = state::SessionState::load()?;
+    let mut rows = status_rows(&state, session)?;
+    if config.windows.iter().any(|w| w.claude.is_some()) {
+        let activity = claude::activity(config, session).unwrap_or_default();
+        for row in &mut rows {
+            let value = row["name"].as_str().filter(|_| row["status"] != "missing").and_then(|n| activity.get(n));
+            row["activity"] = serde_json::json!(value);
+        }
+    }
+
+    Ok(config
+        .windows
+        .iter()
+        .map(|window| {
+            let row = rows.iter().find(|row| row["name"] == window.name.as_str());
+            let tail = match logs::log_path(session, &window.name).map(|path| dashboard::read_tail(&path, 50)) {
+                Ok(Ok(lines)) => lines,
+                _ if !config.logs.enabled => vec!["(no output: set logs.enabled to capture it)".to_string()],
+                _ => vec!["(no output yet)".to_string()],
+            };
+            dashboard::Pane {
+                name: window.name.clone(),
+                status: row.and_then(|r| r["status"].as_str()).unwrap_or("stopped").to_string(),
+                activity: row.and_then(|r| r["activity"].as_str()).map(str::to_string),
+                uptime: row
+                    .filter(|r| r["status"] != "missing")
+                    .and_then(|r| r["uptime_secs"].as_i64())
+                    .map(|secs| format_uptime(chrono::Duration::seconds(secs))),
+                exit_code: row.and_then(|r| r["exit_code"].as_i64()),
+                tail,
+            }
+        })
+        .collect())
+}
+
+/// Set `activity` (busy / waiting / idle) on the status rows of open
+/// claude: windows; other rows get null
+fn add_claude_activity(config: &config::Config, session: &str, rows: &mut [serde_json::Value]) {
+    let running = rows.iter().any(|row| {
+        row["status"] != "missing"
+            && config.windows.iter().any(|w| w.claude.is_some() && row["name"] == w.name.as_str())
+    });
+    let activity = if running {
+        claude::activity(config, session).unwrap_or_else(|e| {
+            warn!("Failed to check claude sessions: {:#}", e);
+            HashMap::new()
+        })
+    } else {
+        HashMap::new()
+    };
+    for row in rows {
+        let value = row["name"]
+            .as_str()
+            .filter(|_| row["status"] != "missing")
+            .and_then(|name| activity.get(name));
+        row["activity"] = serde_json::json!(value);
+    }
+}
+
+/// Add how much of its `claude.budget` each window used to the `ps` rows
+fn add_budgets(config: &config::Config, rows: &mut [serde_json::Value]) {
+    let windows: Vec<&config::WindowConfig> = config
+        .windows
+        .iter()
+        .filter(|w| w.claude.as_ref().is_some_and(|c| c.budget.is_some()))
+        .filter(|w| rows.iter().any(|row| row["name"] == w.name.as_str()))
+        .collect();
+    let spent = if windows.is_empty() {
+        Vec::new()
+    } else {
+        budget::check(&config.wsl_distribution, &windows).unwrap_or_else(|e| {
+            warn!("Failed to check budgets: {:#}", e);
+            Vec::new()
+        })
+    };
+    for row in rows {
+        let spent = spent.iter().find(|s| row["name"] == s.window.as_str());
+        row["budget_pct"] = serde_json::json!(spent.map(|s| (s.used() * 100.0).round() as u64));
+        row["over_budget"] = serde_json::json!(spent.map(|s| s.over()));
+    }
+}
+
+/// Probe the health checks that are due, record the results in the state
+/// and restart windows that became unhealthy if configured to
+fn check_health(
+    config: &config::Config,
+    backend: &Arc<dyn TerminalBackend>,
+    session: &str,
+    grid: &layout::GridLayout,
+    state: &mut state::SessionState,
+    tracker: &mut health::HealthTracker,
+    now: std::time::Instant,
+) -> Result<()> {
+    let mut changed = false;
+    let mut unhealthy = Vec::new();
+
+    for (i, window) in config.windows.iter().enumerate() {
+        let Some(ref check) = window.healthcheck else { continue };
+        if !state.session_windows(session).any(|r| r.name == window.name) {
+            continue;
+        }
+        if !tracker.due(&window.name, check.interval()?, now) {
+            continue;
+        }
+
+        let passed = match health::probe(&config.wsl_distribution, window, check) {
+            Ok(passed) => passed,
+            Err(e) => {
+                warn!("Failed to run health check of '{}': {:#}", window.name, e);
+                continue;
+            }
+        };
+        let result = tracker.report(&window.name, passed, check.retries);
+        if !passed {
+            println!(
+                "[{}] Health check of '{}' failed ({}/{})",
+                chrono::Local::now().format("%H:%M:%S"),
+                window.name,
+                tracker.failures(&window.name),
+                check.retries
+            );
+        }
+
+        if let Some(record) = state.windows.iter_mut().find(|r| r.session == session && r.name == window.name)
+            && record.health != Some(result)
+        {
+            record.health = Some(result);
+            changed = true;
+        }
+        if result == health::Health::Unhealthy && check.restart {
+            unhealthy.push(i);
+        }
+    }
+    if changed {
+        save_state(state, Vec::new());
+    }
+
+    for i in unhealthy {
+        let window = &config.windows[i];
+        println!("Restarting unhealthy window '{}'", window.name);
+        match restart_window(config, backend, session, grid, state, i, false) {
+            Ok(()) => {
+                // Give the new window one interval to come up before probing it
+                let interval = window.healthcheck.as_ref().map(|c| c.interval()).transpose()?;
+                tracker.reset(&window.name, std::time::Instant::now(), interval.unwrap_or_default());
+            }
+            Err(e) => warn!("Failed to restart '{}': {:#}", window.name, e),
+        }
+        save_state(state, Vec::new());
+    }
+    Ok(())
+}
+
+/// Pause the queue workers while the claude windows are near the rate
+/// limit, and let them go on once there is room again
+fn pace_queue(
+    config: &config::Config,
+    limits: &config::RateLimitConfig,
+    queue: &config::QueueConfig,
+    paused: &mut Option<bool>,
+) {
+    let reason = match ratelimit::current(config) {
+        Ok(load) => load.near_limit(limits),
+        Err(e) => {
+            warn!("Failed to check the rate limit: {:#}", e);
+            return;
+        }
+    };
+    if *paused == Some(reason.is_some()) {
+        return;
+    }
+    if let Err(e) = queue::set_paused(&config.wsl_distribution, &queue.dir, reason.as_deref()) {
+        warn!("{:#}", e);
+        return;
+    }
+    let now = chrono::Local::now().format("%H:%M:%S");
+    match reason {
+        Some(ref reason) => println!("[{}] Pausing queue workers: {}", now, reason),
+        None if paused.is_some() => println!("[{}] Resuming queue workers", now),
+        None => {}
+    }
+    *paused = Some(reason.is_some());
+}
+
+/// Raise a toast, logging instead of failing if Windows refuses
+fn notify_window(window: &str, message: &str) {
+    if let Err(e) = windows::notify(window, message) {
+        warn!("Failed to notify about '{}': {:#}", window, e);
+    }
+}
+
+/// Activity of the running claude windows; empty if there are none or it
+/// can't be told
+fn running_activity(
+    config: &config::Config,
+    session: &str,
+    state: &state::SessionState,
+) -> HashMap<String, claude::Activity> {
+    let running: HashSet<&str> = state.session_windows(session).map(|w| w.name.as_str()).collect();
+    if !config.windows.iter().any(|w| w.claude.is_some() && running.contains(w.name.as_str())) {
+        return HashMap::new();
+    }
+    match claude::activity(config, session) {
+        Ok(mut activity) => {
+            activity.retain(|name, _| running.contains(name.as_str()));
+            activity
+        }
+        Err(e) => {
+            warn!("Failed to check claude activity: {:#}", e);
+            HashMap::new()
+        }
+    }
+}
+
+/// Notify about running windows whose command exited and claude windows that
+/// started waiting for a permission answer since the last check
+fn notify_windows(
+    config: &config::Config,
+    session: &str,
+    state: &state::SessionState,
+    activity: &HashMap<String, claude::Activity>,
+    notifier: &mut watchdog::Notifier,
+) {
+    let running: HashSet<&str> = state.session_windows(session).map(|w| w.name.as_str()).collect();
+    for window in config.windows.iter().filter(|w| running.contains(w.name.as_str())) {
+        if let Some(exit) = exit_status::read(session, &window.name)
+            && notifier.exited(&window.name, exit.at)
+        {
+            notify_window(&window.name, &format!("Exited with code {}", exit.code));
+        }
+    }
+
+    for window in config.windows.iter().filter(|w| w.claude.is_some()) {
+        let waiting = activity.get(&window.name) == Some(&claude::Activity::Waiting);
+        if notifier.waiting(&window.name, waiting) {
+            notify_window(&window.name, "Claude is waiting for a permission answer");
+        }
+    }
+}
+
+/// Warn about running windows that went over their `claude.budget`
+fn check_budgets(
+    config: &config::Config,
+    session: &str,
+    state: &state::SessionState,
+    notifier: &mut watchdog::Notifier,
+) {
+    let running: HashSet<&str> = state.session_windows(session).map(|w| w.name.as_str()).collect();
+    let windows: Vec<&config::WindowConfig> = config
+        .windows
+        .iter()
+        .filter(|w| w.claude.as_ref().is_some_and(|c| c.budget.is_some()) && running.contains(w.name.as_str()))
+        .collect();
+    if windows.is_empty() {
+        return;
+    }
+    let spent = match budget::check(&config.wsl_distribution, &windows) {
+        Ok(spent) => spent,
+        Err(e) => {
+            warn!("Failed to check budgets: {:#}", e);
+            return;
+        }
+    };
+    for spent in spent {
+        if notifier.over_budget(&spent.window, spent.over()) {
+            println!(
+                "[{}] '{}' is over its budget: {}",
+                chrono::Local::now().format("%H:%M:%S"),
+                spent.window,
+                spent.describe()
+            );
+            events::record(EventKind::BudgetExceeded, &spent.window, Some(spent.describe()));
+            notify_window(&spent.window, &format!("Over budget: {}", spent.describe()));
+        }
+    }
+}
+
+/// Retitle the running claude windows whose model, branch or activity
+/// changed since their title was last set (or that were relaunched since)
+fn update_titles(
+    config: &config::Config,
+    backend: &dyn terminal::TerminalBackend,
+    session: &str,
+    state: &state::SessionState,
+    activity: &HashMap<String, claude::Activity>,
+    titles: &mut HashMap<String, (i64, String)>,
+) {
+    for window in config.windows.iter().filter(|w| w.claude.is_some()) {
+        let Some(handle) = state.session_windows(session).find(|r| r.name == window.name).and_then(|r| r.handle)
+        else {
+            continue;
+        };
+        let title = claude::status_title(window, activity.get(&window.name).copied());
+        if titles.get(&window.name) == Some(&(handle, title.clone())) {
+            continue;
+        }
+        match backend.set_title(handle, &title) {
+            Ok(()) => {
+                titles.insert(window.name.clone(), (handle, title));
+            }
+            Err(e) => warn!("Failed to set title of '{}': {:#}", window.name, e),
+        }
+    }
+}
+
+/// Sample the CPU use of the session's WSL windows, mark windows idle for
+/// `threshold` in the state and close them if `watch.idle_action` says so
+///
+/// Returns the config indices of closed windows, which watch must not relaunch.
+fn check_idle(
+    config: &config::Config,
+    session: &str,
+    state: &mut state::SessionState,
+    tracker: &mut watchdog::IdleTracker,
+    threshold: std::time::Duration,
+    now: std::time::Instant,
+) -> Vec<usize> {
+    let launcher = wsl::WslLauncher::new(&config.wsl_distribution);
+    let ticks = match launcher.window_cpu_ticks(session) {
+        Ok(ticks) => ticks,
+        Err(e) => {
+            warn!("Failed to check idle windows: {:#}", e);
+            return Vec::new();
+        }
+    };
+
+    let mut changed = false;
+    let mut idle_windows = Vec::new();
+    for (i, window) in config.windows.iter().enumerate() {
+        let Some(record) = state.windows.iter_mut().find(|r| r.session == session && r.name == window.name)
+        else {
+            tracker.forget(&window.name);
+            continue;
+        };
+        // Non-WSL windows have no marked processes to sample
+        let Some(&window_ticks) = ticks.get(&window.name) else { continue };
+
+        let idle_for = tracker.observe(&window.name, window_ticks, now);
+        let idle = idle_for >= threshold;
+        if idle != record.idle_since.is_some() {
+            changed = true;
+            record.idle_since = idle.then(|| {
+                chrono::Local::now() - chrono::Duration::from_std(idle_for).unwrap_or_default()
+            });
+            if idle {
+                println!(
+                    "[{}] '{}' has been idle for {}",
+                    chrono::Local::now().format("%H:%M:%S"),
+                    window.name,
+                    format_uptime(chrono::Duration::from_std(idle_for).unwrap_or_default())
+                );
+            }
+        }
+        if idle {
+            idle_windows.push(i);
+        }
+    }
+
+    let mut closed = Vec::new();
+    if config.watch.idle_action == config::IdleAction::Close {
+        for i in idle_windows {
+            let name = &config.windows[i].name;
+            let Some(record) = state.remove(session, name) else { continue };
+            println!("Closing idle window '{}'", name);
+            terminate_recorded_window(config, &record, config.stop.grace_secs);
+            if close_recorded_window(&record, false) {
+                tracker.forget(name);
+                closed.push(i);
+            } else {
+                warn!("Idle window '{}' did not close", name);
+                state.record(record);
+            }
+            changed = true;
+        }
+    }
+    if changed {
+        save_state(state, Vec::new());
+    }
+    closed
+}
+
+/// Handle a control socket request inside the watch loop
+fn handle_request(
+    request: &ipc::Request,
+    config: &config::Config,
+    backend: &Arc<dyn TerminalBackend>,
+    session: &str,
+    grid: &layout::GridLayout,
+) -> ipc::Response {
+    let result = (|| -> Result<ipc::Response> {
+        match request {
+            ipc::Request::Focus { name } => {
+                let index = window_index(config, name)?;
+                backend.focus(&config.windows[index], index)?;
+                Ok(ipc::Response::ok(format!("focused {}", name)))
+            }
+            ipc::Request::Restart { name } => {
+                let index = window_index(config, name)?;
+                let mut state = load_state(session);
+                restart_window(config, backend, session, grid, &mut state, index, false)?;
+                state.save()?;
+                Ok(ipc::Response::ok(format!("restarted {}", name)))
+            }
+            ipc::Request::Arrange => {
+                let state = load_state(session);
+                let moves: Vec<_> = state
+                    .session_windows(session)
+                    .filter_map(|record| Some((windows::MoveTarget::Handle(record.handle?), record.rect?)))
+                    .collect();
+                for result in windows::move_windows(&moves, 1) {
+                    result?;
+                }
+                Ok(ipc::Response::ok(format!("arranged {} window(s)", moves.len())))
+            }
+            ipc::Request::LaunchWindow { name } => {
+                let index = window_index(config, name)?;
+                let mut state = load_state(session);
+                if state.session_windows(session).any(|w| &w.name == name) {
+                    anyhow::bail!("'{}' is already running", name);
+                }
+                let records = launch_windows(config, backend, session, grid, &[index], false);
+                if records.is_empty() {
+                    anyhow::bail!("Failed to launch '{}'", name);
+                }
+                save_state(&mut state, records);
+                Ok(ipc::Response::ok(format!("launched {}", name)))
+            }
+            ipc::Request::Status => {
+                let state = load_state(session);
+                let mut rows = status_rows(&state, session)?;
+                add_claude_activity(config, session, &mut rows);
+                Ok(ipc::Response::data(serde_json::Value::Array(rows)))
+            }
+        }
+    })();
+    result.unwrap_or_else(|e| ipc::Response::error(format!("{:#}", e)))
+}
+
+/// Send SIGTERM to a WSL window's processes and give them `grace_secs`
+/// to flush state before the terminal window is closed
+fn terminate_recorded_window(config: &config::Config, record: &state::WindowRecord, grace_secs: u64) {
+    let is_wsl = config
+        .windows
+        .iter()
+        .find(|w| w.name == record.name)
+        .is_some_and(|w| w.kind == config::WindowKind::Wsl);
+    if grace_secs == 0 || !is_wsl {
+        return;
+    }
+    let launcher = wsl::WslLauncher::new(&config.wsl_distribution);
+    let grace = std::time::Duration::from_secs(grace_secs);
+    match launcher.terminate_window(&record.session, &record.name, grace) {
+        Ok(true) => debug!("'{}' exited after SIGTERM", record.name),
+        Ok(false) => debug!("'{}' still running after {}s; closing its window", record.name, grace_secs),
+        Err(e) => warn!("Failed to signal '{}': {:#}", record.name, e),
+    }
+}
+
+/// Close a recorded window (WM_CLOSE), killing its process if `force` is
+/// set and it doesn't close. Returns true if the window is gone.
+fn close_recorded_window(record: &state::WindowRecord, force: bool) -> bool {
+    let mut closed = match record.handle {
+        Some(handle) => windows::close_window(handle, 3000).unwrap_or_else(|e| {
+            warn!("Failed to close '{}': {}", record.name, e);
+            false
+        }),
+        None => false,
+    };
+    if !closed
+        && force
+        && let Some(pid) = record.pid
+    {
+        match windows::kill_process(pid) {
+            Ok(()) => closed = true,
+            Err(e) => warn!("Failed to kill '{}' (PID {}): {}", record.name, pid, e),
+        }
+    }
+    closed
+}
+
+/// Load the session state, forgetting windows of `session` that were closed
+///
+/// State problems never block a launch; they are logged and an empty state
+/// is used instead.
+fn load_state(session: &str) -> state::SessionState {
+    let mut state = state::SessionState::load().unwrap_or_else(|e| {
+        warn!("Ignoring unreadable session state: {:#}", e);
+        state::SessionState::default()
+    });
+    let handles: Vec<i64> = state.session_windows(session).filter_map(|w| w.handle).collect();
+    let open: HashSet<i64> = match windows::get_window_info(&handles) {
+        Ok(info) => info.into_keys().collect(),
+        Err(e) => {
+            warn!("Failed to check recorded windows: {}", e);
+            return state;
+        }
+    };
+    let removed = state.prune(session, &open);
+    if removed > 0 {
+        debug!("Dropped {} closed window(s) from the session state", removed);
+    }
+    state
+}
+
+/// Record launched windows in the state file
+fn save_state(state: &mut state::SessionState, records: Vec<state::WindowRecord>) {
+    for record in records {
+        state.record(record);
+    }
+    if let Err(e) = state.save() {
+        warn!("Failed to save session state: {:#}", e);
+    }
+}
+
+/// Current set of the terminal's window handles
+fn handle_snapshot(backend: &dyn TerminalBackend) -> HashSet<i64> {
+    backend
+        .window_handles()
+        .unwrap_or_default()
+        .into_iter()
+        .collect()
+}
+
+/// Wait for a launched window to appear and return its handle
+fn find_new_handle(backend: &dyn TerminalBackend, handles_before: &HashSet<i64>) -> Option<i64> {
+    if dry_run::is_enabled() {
+        return None;
+    }
+    parallel::poll(parallel::READY_TIMEOUT, || handle_snapshot(backend).difference(handles_before).copied().next())
+}
+
+/// Infer a layout from the open Windows Terminal windows, printing what
+/// was found
+fn discover_running(distro: &str) -> Result<discover::Discovered> {
+    let wt = terminal::WindowsTerminal::new(wsl::WslLauncher::new(distro), "init");
+    let handles = wt.window_handles()?;
+    let info = windows::get_window_info(&handles)?;
+    let open: Vec<windows::WindowInfo> = handles.iter().filter_map(|h| info.get(h).cloned()).collect();
+    let displays = windows::get_displays()?;
+    let discovered = discover::discover(&open, &displays)
+        .context("No open Windows Terminal window found; arrange your windows first, or run 'init' without --from-running")?;
+
+    println!(
+        "Found {} Windows Terminal window(s) on display {}; using a {}x{} grid:",
+        discovered.windows.len(),
+        discovered.display,
+        discovered.cols,
+        discovered.rows
+    );
+    for (i, (name, rect)) in discovered.windows.iter().enumerate() {
+        println!("  [{}] {} ({})", i + 1, name, rect);
+    }
+    if discovered.skipped > 0 {
+        println!("Left out {} window(s) on other displays.", discovered.skipped);
+    }
+    println!();
+    Ok(discovered)
+}
+
+/// Print whether the config at `path` is valid, with its windows or the
+/// error
+fn print_validation(path: &str) -> Result<()> {
+    match config::load(path) {
+        Ok(config) => {
+            let (cols, rows) = config.layout.parse_grid()?;
+            println!("{}", output::ok(t!("config-valid")));
+            println!();
+            println!("  Distribution:   {}", config.wsl_distribution);
+            println!("  Target display: {}", config.target_display);
+            println!("  Grid:           {}x{} ({} cells)", cols, rows, cols * rows);
+            println!("  Windows:        {}", config.windows.len());
+            println!();
+            println!("Windows:");
+            for (i, w) in config.windows.iter().enumerate() {
+                println!("  {}. {} - '{}'", i + 1, w.name, w.command_summary());
+                if let Some(ref dir) = w.working_dir {
+                    println!("     working_dir: {}", dir);
+                }
+            }
+        }
+        Err(e) => {
+            println!("{}", output::fail(t!("config-invalid")));
+            println!();
+            println!("{}", e);
+            println!();
+            println!("{}", t!("config-init-hint"));
+            exit_code::config_failed();
+        }
+    }
+    Ok(())
+}
+
+/// Load config with helpful error messages
+fn load_config_with_helpful_error(path: &str) -> Result<config::Config> {
+    check_config_exists(path)?;
+    Ok(config::load(path).inspect_err(|_| exit_code::config_failed())?)
+}
+
+/// Fail with a hint to create one if the config file doesn't exist
+fn check_config_exists(path: &str) -> Result<()> {
+    if !Path::new(path).exists() {
+        exit_code::config_failed();
+        anyhow::bail!("{}", t!("config-not-found", path = path));
+    }
+    Ok(())
+}
+
+/// Load the config with a `worktrees.branches` entry added per open GitHub
+/// issue of `repo` (with `label`)
+fn load_config_with_issues(path: &str, repo: &str, label: Option<&str>) -> Result<config::Config> {
+    check_config_exists(path)?;
+    let config = config::load_with(path, |config| {
+        if config.worktrees.is_none() {
+            anyhow::bail!("--from-github needs a 'worktrees:' section with the repo to add the issue worktrees to");
+        }
+        let issues = github::fetch(&config.wsl_distribution, repo, label)?;
+        if issues.is_empty() {
+            anyhow::bail!(
+                "No open issues in {}{}",
+                repo,
+                label.map(|l| format!(" labeled '{}'", l)).unwrap_or_default()
+            );
+        }
+        say!("Found {} issue(s) in {}:", issues.len(), repo);
+        for issue in &issues {
+            say!("  {}  {}", github::window_name(issue), issue.title);
+        }
+        let worktrees = config.worktrees.as_mut().expect("checked above");
+        worktrees.branches.extend(issues.iter().map(|issue| github::branch(repo, issue)));
+        Ok(())
+    })?;
+    Ok(config)
 }
 
 /// Generate a default config file content
-fn generate_config(grid: &str, display: u32, num_windows: u32, distro: &str) -> Result<String> {
+fn generate_config(grid: &str, display: u32, names: &[String], distro: &str) -> Result<String> {
     let parts: Vec<&str> = grid.split('x').collect();
     if parts.len() != 2 {
         anyhow::bail!("Invalid grid format. Use format like '2x2' or '2x4'.");
     }
 
     let mut windows_yaml = String::new();
-    for i in 1..=num_windows {
+    for (i, name) in names.iter().enumerate() {
+        // A JSON string is a double-quoted YAML scalar, whatever the title
         windows_yaml.push_str(&format!(
-            r#"  - name: "window-{}"
+            r#"  - name: {}
     command: "bash"
     working_dir: "~"
 "#,
-            i
+            serde_json::to_string(name)?
         ));
-        if i < num_windows {
+        if i + 1 < names.len() {
             windows_yaml.push('\n');
         }
     }
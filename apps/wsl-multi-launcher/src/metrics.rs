@@ -0,0 +1,184 @@
+//! Prometheus metrics of `watch` (`watch.metrics_addr`)
+//!
+//! The watch loop updates a shared [`Metrics`] after every check; a small
+//! HTTP server thread renders it in the Prometheus text format for any
+//! request, so alerts can fire when supervised windows die.
+
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{debug, warn};
+
+#[derive(Debug, Default, Clone, PartialEq)]
+struct WindowMetrics {
+    up: bool,
+    restarts: u32,
+    launches: u64,
+    launch_seconds_sum: f64,
+    last_launch_seconds: Option<f64>,
+}
+
+/// Live numbers of one watched session
+#[derive(Debug, Default)]
+pub struct Metrics {
+    session: String,
+    windows: BTreeMap<String, WindowMetrics>,
+}
+
+impl Metrics {
+    pub fn new(session: &str) -> Self {
+        Self { session: session.to_string(), windows: BTreeMap::new() }
+    }
+
+    /// Record whether a window is running and how often it was restarted
+    pub fn set_window(&mut self, name: &str, up: bool, restarts: u32) {
+        let window = self.windows.entry(name.to_string()).or_default();
+        window.up = up;
+        window.restarts = restarts;
+    }
+
+    /// Record how long launching a window took
+    pub fn observe_launch(&mut self, name: &str, took: Duration) {
+        let window = self.windows.entry(name.to_string()).or_default();
+        window.launches += 1;
+        window.launch_seconds_sum += took.as_secs_f64();
+        window.last_launch_seconds = Some(took.as_secs_f64());
+    }
+
+    /// Prometheus text exposition of all metrics
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        let labels = |name: &str| {
+            format!("session=\"{}\",window=\"{}\"", escape(&self.session), escape(name))
+        };
+
+        let mut family = |metric: &str, kind: &str, help: &str, value: &dyn Fn(&WindowMetrics) -> Option<String>| {
+            let _ = writeln!(out, "# HELP {} {}", metric, help);
+            let _ = writeln!(out, "# TYPE {} {}", metric, kind);
+            for (name, window) in &self.windows {
+                if let Some(value) = value(window) {
+                    let _ = writeln!(out, "{}{{{}}} {}", metric, labels(name), value);
+                }
+            }
+        };
+        family("wsl_multi_launcher_window_up", "gauge", "Whether the window is open (1) or not (0)", &|w| {
+            Some(u8::from(w.up).to_string())
+        });
+        family(
+            "wsl_multi_launcher_window_restarts_total",
+            "counter",
+            "Restarts of the window by watch",
+            &|w| Some(w.restarts.to_string()),
+        );
+        family(
+            "wsl_multi_launcher_launch_duration_seconds_sum",
+            "counter",
+            "Total seconds spent launching the window",
+            &|w| Some(w.launch_seconds_sum.to_string()),
+        );
+        family(
+            "wsl_multi_launcher_launch_duration_seconds_count",
+            "counter",
+            "Launches of the window",
+            &|w| Some(w.launches.to_string()),
+        );
+        family(
+            "wsl_multi_launcher_last_launch_duration_seconds",
+            "gauge",
+            "Seconds the last launch of the window took",
+            &|w| w.last_launch_seconds.map(|s| s.to_string()),
+        );
+        out
+    }
+}
+
+/// Escape a label value
+fn escape(value: &str) -> String {
+    value.replace('\\', r"\\").replace('"', "\\\"").replace('\n', r"\n")
+}
+
+/// Serve `metrics` over HTTP on `addr` from a background thread
+pub fn serve(addr: &str, metrics: Arc<Mutex<Metrics>>) -> Result<()> {
+    let listener =
+        TcpListener::bind(addr).with_context(|| format!("Failed to listen for metrics on {}", addr))?;
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    if let Err(e) = respond(stream, &metrics) {
+                        debug!("Metrics request failed: {}", e);
+                    }
+                }
+                Err(e) => warn!("Metrics accept failed: {}", e),
+            }
+        }
+    });
+    Ok(())
+}
+
+/// Answer any request with the current metrics
+fn respond(mut stream: TcpStream, metrics: &Mutex<Metrics>) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    // Read up to the end of the request headers
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut line = String::new();
+    while reader.read_line(&mut line)? > 0 && line != "\r\n" && line != "\n" {
+        line.clear();
+    }
+
+    let body = metrics.lock().map(|m| m.render()).unwrap_or_default();
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        body.len(),
+        body
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn test_render() {
+        let mut metrics = Metrics::new("work");
+        metrics.set_window("claude-1", true, 2);
+        metrics.observe_launch("claude-1", Duration::from_millis(1500));
+        metrics.observe_launch("claude-1", Duration::from_millis(500));
+        metrics.set_window("say \"hi\"", false, 0);
+
+        let text = metrics.render();
+        assert!(text.contains("# TYPE wsl_multi_launcher_window_up gauge\n"));
+        assert!(text.contains("wsl_multi_launcher_window_up{session=\"work\",window=\"claude-1\"} 1\n"));
+        assert!(text.contains("wsl_multi_launcher_window_up{session=\"work\",window=\"say \\\"hi\\\"\"} 0\n"));
+        assert!(text.contains("wsl_multi_launcher_window_restarts_total{session=\"work\",window=\"claude-1\"} 2\n"));
+        assert!(text.contains("wsl_multi_launcher_launch_duration_seconds_sum{session=\"work\",window=\"claude-1\"} 2\n"));
+        assert!(text.contains("wsl_multi_launcher_launch_duration_seconds_count{session=\"work\",window=\"claude-1\"} 2\n"));
+        assert!(text.contains("wsl_multi_launcher_last_launch_duration_seconds{session=\"work\",window=\"claude-1\"} 0.5\n"));
+        // Never launched by watch: no last-launch sample
+        assert!(!text.contains("wsl_multi_launcher_last_launch_duration_seconds{session=\"work\",window=\"say"));
+    }
+
+    #[test]
+    fn test_serve() {
+        let metrics = Arc::new(Mutex::new(Metrics::new("work")));
+        metrics.lock().unwrap().set_window("claude-1", true, 0);
+
+        // Bind to a free port first so the test doesn't race for one
+        let addr = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().to_string();
+        serve(&addr, metrics).unwrap();
+
+        let mut stream = TcpStream::connect(&addr).unwrap();
+        write!(stream, "GET /metrics HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.ends_with("wsl_multi_launcher_last_launch_duration_seconds gauge\n"));
+        assert!(response.contains("window=\"claude-1\"} 1\n"));
+    }
+}
@@ -0,0 +1,169 @@
+//! Terminal multiplexer (tmux / Zellij) session queries inside WSL
+//!
+//! Windows configured with `tmux_session` or `zellij_session` keep running
+//! inside WSL when their terminal window dies. A session that still exists
+//! but has no attached client is one whose window is gone, which is what
+//! `resume` relaunches.
+
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+use std::process::Command;
+use tracing::debug;
+
+use crate::config::WindowConfig;
+use crate::shell;
+
+/// Multiplexer a window's command runs in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Multiplexer {
+    Tmux,
+    Zellij,
+}
+
+impl Multiplexer {
+    /// Multiplexer and session name configured for a window, if any
+    pub fn for_window(window: &WindowConfig) -> Option<(Multiplexer, &str)> {
+        if let Some(ref session) = window.tmux_session {
+            Some((Multiplexer::Tmux, session))
+        } else {
+            window
+                .zellij_session
+                .as_deref()
+                .map(|session| (Multiplexer::Zellij, session))
+        }
+    }
+
+    /// Script printing one `name:clients` line per session
+    fn list_script(self) -> &'static str {
+        match self {
+            Multiplexer::Tmux => {
+                "tmux list-sessions -F '#{session_name}:#{session_attached}' 2>/dev/null || true"
+            }
+            // Exited sessions fail list-clients and count as detached; attaching resurrects them
+            Multiplexer::Zellij => {
+                "for s in $(zellij list-sessions --short --no-formatting 2>/dev/null); do \
+                 n=$(zellij --session \"$s\" action list-clients 2>/dev/null | tail -n +2 | grep -c .); \
+                 echo \"$s:$n\"; done"
+            }
+        }
+    }
+
+    /// Script typing `text` followed by Enter into the active pane of `session`
+    fn send_script(self, session: &str, text: &str) -> String {
+        match self {
+            Multiplexer::Tmux => {
+                // "=name:" is the exact session name, not a prefix match
+                let target = shell::quote(&format!("={}:", session));
+                format!(
+                    "tmux send-keys -t {target} -l {} && tmux send-keys -t {target} Enter",
+                    shell::quote(text)
+                )
+            }
+            Multiplexer::Zellij => {
+                let session = shell::quote(session);
+                format!(
+                    "zellij --session {session} action write-chars {} && zellij --session {session} action write 13",
+                    shell::quote(text)
+                )
+            }
+        }
+    }
+}
+
+/// Type `text` and Enter into a multiplexer session in `distribution`
+pub fn send_text(distribution: &str, multiplexer: Multiplexer, session: &str, text: &str) -> Result<()> {
+    let output = Command::new("wsl.exe")
+        .args(["-d", distribution, "--", "bash", "-c", &multiplexer.send_script(session, text)])
+        .output()
+        .context("Failed to run wsl.exe")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to send text to {:?} session '{}': {}",
+            multiplexer,
+            session,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+/// Sessions of `multiplexer` in `distribution` that have no attached client
+pub fn detached_sessions(distribution: &str, multiplexer: Multiplexer) -> Result<HashSet<String>> {
+    let output = Command::new("wsl.exe")
+        .args(["-d", distribution, "--", "bash", "-c", multiplexer.list_script()])
+        .output()
+        .context("Failed to run wsl.exe")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to list {:?} sessions: {}",
+            multiplexer,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    debug!("{:?} sessions: {}", multiplexer, stdout.trim());
+
+    Ok(parse_session_clients(&stdout)
+        .into_iter()
+        .filter(|(_, clients)| *clients == 0)
+        .map(|(name, _)| name)
+        .collect())
+}
+
+/// Parse `name:clients` lines; session names may themselves contain ':'
+fn parse_session_clients(output: &str) -> Vec<(String, u32)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (name, clients) = line.trim().rsplit_once(':')?;
+            let clients = clients.trim().parse().ok()?;
+            (!name.is_empty()).then(|| (name.to_string(), clients))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_session_clients() {
+        let output = "claude-1:0\nclaude-2:1\n\nbroken line\nwith:colon:2\n";
+        assert_eq!(
+            parse_session_clients(output),
+            vec![
+                ("claude-1".to_string(), 0),
+                ("claude-2".to_string(), 1),
+                ("with:colon".to_string(), 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_send_script() {
+        assert_eq!(
+            Multiplexer::Tmux.send_script("claude-1", "fix the failing tests"),
+            "tmux send-keys -t =claude-1: -l 'fix the failing tests' && tmux send-keys -t =claude-1: Enter"
+        );
+        assert_eq!(
+            Multiplexer::Zellij.send_script("work", "it's done"),
+            "zellij --session work action write-chars 'it'\\''s done' && zellij --session work action write 13"
+        );
+    }
+
+    #[test]
+    fn test_for_window() {
+        let mut window = WindowConfig { name: "a".to_string(), ..Default::default() };
+        assert_eq!(Multiplexer::for_window(&window), None);
+
+        window.zellij_session = Some("z".to_string());
+        assert_eq!(Multiplexer::for_window(&window), Some((Multiplexer::Zellij, "z")));
+
+        window.zellij_session = None;
+        window.tmux_session = Some("t".to_string());
+        assert_eq!(Multiplexer::for_window(&window), Some((Multiplexer::Tmux, "t")));
+    }
+}
@@ -0,0 +1,146 @@
+//! Output format of the commands (`--output`)
+//!
+//! With `--output json`, `displays`, `status`, `validate`, `ps`, `events`,
+//! `usage`, `launch` and `doctor` print one JSON document on stdout (the
+//! hidden `--json` of `ps`, `events` and `usage` is the same). Progress
+//! messages printed with `say!` are left out and log lines go to stderr,
+//! so the output can be piped straight into other tools.
+//! `--quiet` leaves out the progress messages and logs of the text output.
+//!
+//! Text output is colored (green OK, red FAILED, yellow warnings) when
+//! stdout is a terminal, unless `--no-color` is given or `NO_COLOR` is set.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::fmt::Display;
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Value of `--output`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    /// Human-readable text
+    #[default]
+    Text,
+    /// One JSON document
+    Json,
+}
+
+static JSON: AtomicBool = AtomicBool::new(false);
+static QUIET: AtomicBool = AtomicBool::new(false);
+static COLOR: AtomicBool = AtomicBool::new(false);
+static PRINTED: AtomicBool = AtomicBool::new(false);
+
+/// Set the format for the rest of the process
+pub fn set(format: Format) {
+    JSON.store(format == Format::Json, Ordering::Relaxed);
+}
+
+pub fn is_json() -> bool {
+    JSON.load(Ordering::Relaxed)
+}
+
+/// Leave out progress messages (`--quiet`)
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+/// Whether progress messages are printed: not with JSON output, `--quiet`
+/// or `--dry-run` (which prints the skipped commands instead)
+pub fn shows_progress() -> bool {
+    !is_json() && !QUIET.load(Ordering::Relaxed) && !crate::dry_run::is_enabled()
+}
+
+/// Whether to color: `NO_COLOR` counts when set to anything but ""
+fn color_enabled(no_color: bool, no_color_env: Option<&str>, terminal: bool) -> bool {
+    !no_color && no_color_env.is_none_or(str::is_empty) && terminal
+}
+
+/// Decide on colors for the rest of the process (after `set`)
+pub fn set_color(no_color: bool) {
+    let env = std::env::var("NO_COLOR").ok();
+    let enabled = color_enabled(no_color, env.as_deref(), std::io::stdout().is_terminal()) && !is_json();
+    COLOR.store(enabled, Ordering::Relaxed);
+}
+
+pub fn is_color() -> bool {
+    COLOR.load(Ordering::Relaxed)
+}
+
+fn paint(code: &str, text: impl Display) -> String {
+    if is_color() { format!("\x1b[{}m{}\x1b[0m", code, text) } else { text.to_string() }
+}
+
+/// Success, e.g. "OK"
+pub fn ok(text: impl Display) -> String {
+    paint("32", text)
+}
+
+/// Failure, e.g. "FAILED"
+pub fn fail(text: impl Display) -> String {
+    paint("31", text)
+}
+
+/// Something skipped or worth a look
+pub fn warn(text: impl Display) -> String {
+    paint("33", text)
+}
+
+pub fn heading(text: impl Display) -> String {
+    paint("1", text)
+}
+
+/// Print `value` as the command's JSON document
+pub fn print_json(value: &impl Serialize) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    PRINTED.store(true, Ordering::Relaxed);
+    Ok(())
+}
+
+/// Whether the command printed its JSON document
+pub fn printed_json() -> bool {
+    PRINTED.load(Ordering::Relaxed)
+}
+
+/// `println!` for progress messages, which JSON output and `--quiet` leave out
+#[macro_export]
+macro_rules! say {
+    ($($arg:tt)*) => {
+        if $crate::output::shows_progress() {
+            println!($($arg)*);
+        }
+    };
+}
+
+/// `print!` for the start of a progress line finished by `say!`
+#[macro_export]
+macro_rules! say_start {
+    ($($arg:tt)*) => {
+        if $crate::output::shows_progress() {
+            print!($($arg)*);
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_color_enabled() {
+        assert!(color_enabled(false, None, true));
+        assert!(color_enabled(false, Some(""), true));
+        assert!(!color_enabled(false, Some("1"), true));
+        assert!(!color_enabled(true, None, true));
+        assert!(!color_enabled(false, None, false));
+    }
+
+    #[test]
+    fn test_paint() {
+        // Tests don't run on a terminal with colors turned on
+        assert_eq!(ok("OK"), "OK");
+        COLOR.store(true, Ordering::Relaxed);
+        assert_eq!(fail("FAILED"), "\x1b[31mFAILED\x1b[0m");
+        COLOR.store(false, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,173 @@
+//! Launching several windows at once
+//!
+//! Starting a terminal waits on a Windows process (wt.exe), so `launch`
+//! runs up to `PARALLELISM` launches at a time on scoped threads. The new
+//! windows are then polled for and told apart by their title, which
+//! backends that title on launch set to the window's name, instead of
+//! sleeping a fixed time after each launch.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Windows started at the same time
+pub const PARALLELISM: usize = 4;
+
+/// How long launched windows get to show up
+pub const READY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Pause between looks for new windows
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Run `jobs` on at most `limit` threads and return their results in the
+/// order of the jobs
+pub fn run_all<T, F>(jobs: Vec<F>, limit: usize) -> Vec<T>
+where
+    T: Send,
+    F: FnOnce() -> T + Send,
+{
+    let count = jobs.len();
+    let queue = Mutex::new(jobs.into_iter().enumerate());
+    let results: Mutex<Vec<Option<T>>> = Mutex::new((0..count).map(|_| None).collect());
+    std::thread::scope(|scope| {
+        for _ in 0..limit.clamp(1, count.max(1)) {
+            scope.spawn(|| {
+                loop {
+                    let next = queue.lock().unwrap_or_else(|e| e.into_inner()).next();
+                    let Some((i, job)) = next else { break };
+                    let result = job();
+                    results.lock().unwrap_or_else(|e| e.into_inner())[i] = Some(result);
+                }
+            });
+        }
+    });
+    results
+        .into_inner()
+        .unwrap_or_else(|e| e.into_inner())
+        .into_iter()
+        .map(|result| result.expect("every job ran"))
+        .collect()
+}
+
+/// Call `check` until it finds something or `timeout` passes
+pub fn poll<T>(timeout: Duration, mut check: impl FnMut() -> Option<T>) -> Option<T> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Some(found) = check() {
+            return Some(found);
+        }
+        if Instant::now() >= deadline {
+            return None;
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Poll `windows` (handle and title of every open window of the terminal)
+/// until a window that wasn't in `before` shows up titled with each of
+/// `names`, or `timeout` passes; returns the handle of each window found
+/// and when it was found
+///
+/// A window whose title hasn't been set yet is still matched when it is the
+/// only new window left for the only name left.
+pub fn wait_for_windows(
+    mut windows: impl FnMut() -> Vec<(i64, String)>,
+    before: &HashSet<i64>,
+    names: &[String],
+    timeout: Duration,
+) -> HashMap<String, (i64, Instant)> {
+    let deadline = Instant::now() + timeout;
+    let mut found: HashMap<String, (i64, Instant)> = HashMap::new();
+    loop {
+        let fresh: Vec<(i64, String)> = windows()
+            .into_iter()
+            .filter(|(handle, _)| !before.contains(handle) && !found.values().any(|(h, _)| h == handle))
+            .collect();
+        let now = Instant::now();
+        for name in names {
+            if found.contains_key(name) {
+                continue;
+            }
+            if let Some((handle, _)) = fresh.iter().find(|(_, title)| title == name) {
+                found.insert(name.clone(), (*handle, now));
+            }
+        }
+        let missing: Vec<&String> = names.iter().filter(|name| !found.contains_key(*name)).collect();
+        if missing.is_empty() {
+            return found;
+        }
+        if now >= deadline {
+            let claimed: HashSet<i64> = found.values().map(|(h, _)| *h).collect();
+            let unclaimed: Vec<i64> = fresh.iter().map(|(h, _)| *h).filter(|h| !claimed.contains(h)).collect();
+            if let ([name], [handle]) = (&missing[..], &unclaimed[..]) {
+                found.insert((*name).clone(), (*handle, now));
+            }
+            return found;
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_run_all() {
+        let running = Arc::new(AtomicUsize::new(0));
+        let most = Arc::new(AtomicUsize::new(0));
+        let jobs: Vec<_> = (0..8)
+            .map(|i| {
+                let (running, most) = (running.clone(), most.clone());
+                move || {
+                    let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    most.fetch_max(now, Ordering::SeqCst);
+                    std::thread::sleep(Duration::from_millis(20 * (8 - i)));
+                    running.fetch_sub(1, Ordering::SeqCst);
+                    i
+                }
+            })
+            .collect();
+        assert_eq!(run_all(jobs, 3), (0..8).collect::<Vec<_>>());
+        assert!(most.load(Ordering::SeqCst) <= 3);
+        assert!(most.load(Ordering::SeqCst) > 1);
+    }
+
+    #[test]
+    fn test_poll() {
+        let mut checks = 0;
+        let check = || {
+            checks += 1;
+            (checks == 3).then_some(checks)
+        };
+        assert_eq!(poll(Duration::from_secs(5), check), Some(3));
+        assert_eq!(poll(Duration::ZERO, || None::<u32>), None);
+    }
+
+    #[test]
+    fn test_wait_for_windows() {
+        let before: HashSet<i64> = [1].into();
+        let names = vec!["api".to_string(), "web".to_string()];
+        let mut polls = 0;
+        let windows = || {
+            polls += 1;
+            let mut open = vec![(1, "api".to_string()), (2, "web".to_string())];
+            if polls > 1 {
+                open.push((3, "api".to_string()));
+            }
+            open
+        };
+        let found = wait_for_windows(windows, &before, &names, Duration::from_secs(5));
+        assert_eq!((found["api"].0, found["web"].0), (3, 2));
+        assert_eq!(polls, 2);
+
+        // The last window is paired up with the last new handle
+        let windows = || vec![(1, "api".to_string()), (2, "Windows Terminal".to_string())];
+        let found = wait_for_windows(windows, &before, &names[..1], Duration::ZERO);
+        assert_eq!(found["api"].0, 2);
+        let found = wait_for_windows(windows, &before, &names, Duration::ZERO);
+        assert!(found.is_empty());
+    }
+}
@@ -0,0 +1,173 @@
+//! Desired vs actual workspace (`plan` / `reconcile`)
+//!
+//! The config is the desired state; the recorded windows of the session and
+//! their live positions are the actual state. The difference is a list of
+//! windows to launch, close and move, which `reconcile --apply` carries out.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::config::WindowConfig;
+use crate::layout::Rect;
+use crate::state::{self, WindowRecord};
+
+/// One step towards the configured workspace
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    /// Configured window that isn't running
+    Launch { index: usize, name: String },
+    /// Running window that isn't in the config
+    Close { name: String },
+    /// Running window outside its cell
+    Move { index: usize, name: String, handle: i64, from: Rect, to: Rect },
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rect = |r: &Rect| format!("{}x{} at ({}, {})", r.width, r.height, r.x, r.y);
+        match self {
+            Change::Launch { name, .. } => write!(f, "+ {} (launch)", name),
+            Change::Close { name } => write!(f, "- {} (close)", name),
+            Change::Move { name, from, to, .. } => {
+                write!(f, "~ {} (move {} -> {})", name, rect(from), rect(to))
+            }
+        }
+    }
+}
+
+/// Changes that make the session match the config
+///
+/// `positions` are the target rects of the configured windows, `current`
+/// the live rects of the visible recorded windows by handle. Maximized,
+/// fullscreen and dropdown windows are never moved, and windows without a
+/// known position are left alone.
+pub fn diff(
+    windows: &[WindowConfig],
+    positions: &[Rect],
+    records: &[WindowRecord],
+    current: &HashMap<i64, Rect>,
+) -> Vec<Change> {
+    let mut changes = Vec::new();
+    for (index, window) in windows.iter().enumerate() {
+        let Some(record) = records.iter().find(|r| r.name == window.name) else {
+            changes.push(Change::Launch { index, name: window.name.clone() });
+            continue;
+        };
+        if window.skips_arrange() || window.dropdown {
+            continue;
+        }
+        let Some(handle) = record.handle else { continue };
+        if let Some(rect) = current.get(&handle)
+            && !state::same_place(&positions[index], rect)
+        {
+            changes.push(Change::Move {
+                index,
+                name: window.name.clone(),
+                handle,
+                from: *rect,
+                to: positions[index],
+            });
+        }
+    }
+    for record in records {
+        if !windows.iter().any(|w| w.name == record.name) {
+            changes.push(Change::Close { name: record.name.clone() });
+        }
+    }
+    changes
+}
+
+/// One-line count of the changes, e.g. "1 to launch, 0 to move, 2 to close"
+pub fn summary(changes: &[Change]) -> String {
+    let count = |f: fn(&Change) -> bool| changes.iter().filter(|c| f(c)).count();
+    format!(
+        "{} to launch, {} to move, {} to close",
+        count(|c| matches!(c, Change::Launch { .. })),
+        count(|c| matches!(c, Change::Move { .. })),
+        count(|c| matches!(c, Change::Close { .. })),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(name: &str) -> WindowConfig {
+        WindowConfig { name: name.to_string(), ..Default::default() }
+    }
+
+    fn record(name: &str, handle: i64) -> WindowRecord {
+        WindowRecord {
+            session: "work".to_string(),
+            name: name.to_string(),
+            wt_window: None,
+            handle: Some(handle),
+            pid: None,
+            rect: None,
+            launched_at: chrono::Local::now(),
+            health: None,
+            idle_since: None,
+            claude_session: None,
+        }
+    }
+
+    #[test]
+    fn test_diff() {
+        let windows = vec![window("a"), window("b"), window("c"), window("d")];
+        let positions = vec![
+            Rect::new(0, 0, 960, 540),
+            Rect::new(960, 0, 960, 540),
+            Rect::new(0, 540, 960, 540),
+            Rect::new(960, 540, 960, 540),
+        ];
+        let records = vec![record("a", 1), record("b", 2), record("c", 3), record("old", 4)];
+        let current = HashMap::from([
+            // Borders make the live rect a little bigger
+            (1, Rect::new(-7, 0, 974, 547)),
+            (2, Rect::new(100, 100, 800, 600)),
+            (4, Rect::new(0, 0, 500, 500)),
+        ]);
+
+        assert_eq!(
+            diff(&windows, &positions, &records, &current),
+            vec![
+                Change::Move {
+                    index: 1,
+                    name: "b".to_string(),
+                    handle: 2,
+                    from: Rect::new(100, 100, 800, 600),
+                    to: Rect::new(960, 0, 960, 540),
+                },
+                Change::Launch { index: 3, name: "d".to_string() },
+                Change::Close { name: "old".to_string() },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_skips_unarranged_windows() {
+        let windows = vec![WindowConfig { wt_maximized: true, ..window("a") }];
+        let records = vec![record("a", 1)];
+        let current = HashMap::from([(1, Rect::new(0, 0, 1920, 1080))]);
+        assert!(diff(&windows, &[Rect::new(0, 0, 960, 540)], &records, &current).is_empty());
+    }
+
+    #[test]
+    fn test_display_and_summary() {
+        let changes = vec![
+            Change::Launch { index: 0, name: "a".to_string() },
+            Change::Close { name: "b".to_string() },
+            Change::Move {
+                index: 2,
+                name: "c".to_string(),
+                handle: 3,
+                from: Rect::new(0, 0, 10, 10),
+                to: Rect::new(5, 5, 20, 20),
+            },
+        ];
+        assert_eq!(changes[0].to_string(), "+ a (launch)");
+        assert_eq!(changes[1].to_string(), "- b (close)");
+        assert_eq!(changes[2].to_string(), "~ c (move 10x10 at (0, 0) -> 20x20 at (5, 5))");
+        assert_eq!(summary(&changes), "1 to launch, 1 to move, 1 to close");
+    }
+}
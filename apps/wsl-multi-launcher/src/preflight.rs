@@ -0,0 +1,155 @@
+//! Checks that the claude CLI can start in the windows that run it
+//!
+//! A `claude:` window whose shell can't find `claude`, or whose claude
+//! isn't logged in, opens and immediately shows an error (or a login
+//! prompt) instead of an agent. `launch` and `doctor` run the same shell a
+//! window would (`bash -c`, or `bash -lc` with `login_shell`) once per
+//! shell mode, and report which windows would fail before any of them open.
+
+use anyhow::{Context, Result};
+use std::process::Command;
+use tracing::debug;
+
+use crate::config::{Config, WindowConfig};
+
+/// Oldest claude release with every flag the presets use
+/// (`--append-system-prompt`, `--session-id`, `--settings`, ...)
+pub const MIN_VERSION: (u32, u32, u32) = (1, 0, 0);
+
+/// Script printing where claude is ("path <path>", or "missing"), its
+/// version and whether it has credentials
+const CHECK_SCRIPT: &str = "c=$(command -v claude) || { echo missing; exit 0; }; echo \"path $c\"; \
+    echo \"version $(claude --version 2>/dev/null | head -n 1)\"; \
+    if [ -n \"$ANTHROPIC_API_KEY\" ] || [ -s \"${CLAUDE_CONFIG_DIR:-$HOME/.claude}/.credentials.json\" ] \
+    || grep -qs '\"oauthAccount\"' \"$HOME/.claude.json\"; then echo 'login yes'; else echo 'login no'; fi";
+
+/// What a shell found out about the claude CLI
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClaudeCli {
+    /// Where `claude` is; None if it isn't on the PATH
+    pub path: Option<String>,
+    /// Output of `claude --version`
+    pub version: Option<String>,
+    pub logged_in: bool,
+}
+
+/// Leading "X.Y.Z" of a version string such as "2.0.14 (Claude Code)"
+fn parse_version(text: &str) -> Option<(u32, u32, u32)> {
+    let word = text.split_whitespace().next()?;
+    let mut parts = word.trim_start_matches('v').split('.').map(|p| p.parse::<u32>().ok());
+    Some((parts.next()??, parts.next()??, parts.next().flatten().unwrap_or(0)))
+}
+
+impl ClaudeCli {
+    fn parse(output: &str) -> Self {
+        let mut cli = Self::default();
+        for line in output.lines() {
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+            match key {
+                "path" => cli.path = Some(value.to_string()),
+                "version" if !value.trim().is_empty() => cli.version = Some(value.trim().to_string()),
+                "login" => cli.logged_in = value == "yes",
+                _ => {}
+            }
+        }
+        cli
+    }
+
+    /// Why claude would fail to start; None if it looks ready
+    pub fn problem(&self) -> Option<String> {
+        if self.path.is_none() {
+            return Some("claude not found on the PATH".to_string());
+        }
+        let (major, minor, patch) = MIN_VERSION;
+        match self.version.as_deref().map(|v| (v, parse_version(v))) {
+            None => return Some("claude --version failed".to_string()),
+            Some((version, Some(found))) if found < MIN_VERSION => {
+                return Some(format!("claude {} is older than {}.{}.{}", version, major, minor, patch));
+            }
+            _ => {}
+        }
+        if !self.logged_in {
+            return Some("claude is not logged in (run 'claude' and /login, or set ANTHROPIC_API_KEY)".to_string());
+        }
+        None
+    }
+}
+
+/// Shell a window runs its command with
+pub fn shell(login_shell: bool) -> &'static str {
+    if login_shell { "bash -lc" } else { "bash -c" }
+}
+
+/// Check claude in the shell `wsl.exe` starts for a window
+pub fn check(distribution: &str, login_shell: bool) -> Result<ClaudeCli> {
+    let flag = if login_shell { "-lc" } else { "-c" };
+    debug!("Checking claude ({}): {}", shell(login_shell), CHECK_SCRIPT);
+    let output = Command::new("wsl.exe")
+        .args(["-d", distribution, "--", "bash", flag, CHECK_SCRIPT])
+        .output()
+        .context("Failed to run wsl.exe")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to check claude in {}: {}",
+            distribution,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(ClaudeCli::parse(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Result of the check for one window
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WindowCheck {
+    pub window: String,
+    pub login_shell: bool,
+    pub cli: ClaudeCli,
+}
+
+/// Check claude for each claude window of `windows`, running the check once
+/// per shell mode
+pub fn check_windows(config: &Config, windows: &[&WindowConfig]) -> Result<Vec<WindowCheck>> {
+    let mut by_shell: Vec<(bool, ClaudeCli)> = Vec::new();
+    let mut checks = Vec::new();
+    for window in windows.iter().filter(|w| w.claude.is_some()) {
+        let cli = match by_shell.iter().find(|(login, _)| *login == window.login_shell) {
+            Some((_, cli)) => cli.clone(),
+            None => {
+                let cli = check(&config.wsl_distribution, window.login_shell)?;
+                by_shell.push((window.login_shell, cli.clone()));
+                cli
+            }
+        };
+        checks.push(WindowCheck { window: window.name.clone(), login_shell: window.login_shell, cli });
+    }
+    Ok(checks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(parse_version("2.0.14 (Claude Code)"), Some((2, 0, 14)));
+        assert_eq!(parse_version("v1.2"), Some((1, 2, 0)));
+        assert_eq!(parse_version("unknown"), None);
+        assert_eq!(parse_version(""), None);
+    }
+
+    #[test]
+    fn test_problem() {
+        let cli = ClaudeCli::parse("path /home/u/.local/bin/claude\nversion 2.0.14 (Claude Code)\nlogin yes\n");
+        assert_eq!(cli.path.as_deref(), Some("/home/u/.local/bin/claude"));
+        assert_eq!(cli.version.as_deref(), Some("2.0.14 (Claude Code)"));
+        assert_eq!(cli.problem(), None);
+
+        assert_eq!(ClaudeCli::parse("missing\n").problem().as_deref(), Some("claude not found on the PATH"));
+        let cli = ClaudeCli::parse("path /usr/bin/claude\nversion \nlogin yes\n");
+        assert_eq!(cli.problem().as_deref(), Some("claude --version failed"));
+        let cli = ClaudeCli::parse("path /usr/bin/claude\nversion 0.2.9 (Claude Code)\nlogin yes\n");
+        assert_eq!(cli.problem().as_deref(), Some("claude 0.2.9 (Claude Code) is older than 1.0.0"));
+        let cli = ClaudeCli::parse("path /usr/bin/claude\nversion 2.0.14\nlogin no\n");
+        assert!(cli.problem().unwrap().starts_with("claude is not logged in"));
+    }
+}
@@ -0,0 +1,95 @@
+//! Files put into a window's working directory before it launches (`provision:`)
+//!
+//! Each entry copies (or symlinks) a file, a directory or the matches of a
+//! glob into a directory under the working directory, e.g. a role's slash
+//! commands into `.claude/commands`, so the agent in the window starts with
+//! them. Copies are made again on every launch; an entry matching nothing
+//! fails the launch rather than starting the agent without its files.
+
+use anyhow::{Context, Result};
+use std::process::Command;
+use tracing::debug;
+
+use crate::config::{ProvisionFile, WindowConfig};
+use crate::dry_run;
+use crate::shell;
+
+/// Script putting `files` into `working_dir`; prints "provisioned <count>"
+fn script(working_dir: &str, files: &[ProvisionFile]) -> String {
+    let mut script = format!("cd {} || exit 1; n=0", shell::quote_path(working_dir));
+    for file in files {
+        let put = if file.link() { "ln -sfn" } else { "cp -R" };
+        script.push_str(&format!(
+            "; t={to}; mkdir -p \"$t\" || exit 1; m=0; \
+             for f in {from}; do [ -e \"$f\" ] || break; {put} \"$f\" \"$t/\" || exit 1; m=$((m+1)); done; \
+             [ \"$m\" -gt 0 ] || {{ echo {missing} >&2; exit 1; }}; n=$((n+m))",
+            to = shell::quote(file.to()),
+            from = shell::quote_glob(file.from()),
+            missing = shell::quote(&format!("{}: no such file", file.from())),
+        ));
+    }
+    script.push_str("; echo \"provisioned $n\"");
+    script
+}
+
+/// Provision the working directories of `windows` in `distribution`,
+/// returning the windows provisioned with their number of files
+pub fn apply(distribution: &str, windows: &[&WindowConfig]) -> Result<Vec<(String, usize)>> {
+    let mut provisioned = Vec::new();
+    for window in windows.iter().filter(|w| !w.provision.is_empty()) {
+        let working_dir = window
+            .working_dir
+            .as_deref()
+            .with_context(|| format!("Window '{}' has 'provision' but no working directory", window.name))?;
+        let script = script(working_dir, &window.provision);
+        debug!("Provisioning '{}': {}", window.name, script);
+        let mut cmd = Command::new("wsl.exe");
+        cmd.args(["-d", distribution, "--", "bash", "-c", &script]);
+        if dry_run::skip(&cmd) {
+            continue;
+        }
+        let output = cmd
+            .output()
+            .context("Failed to run wsl.exe")?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "Failed to provision '{}' in {}: {}",
+                window.name,
+                working_dir,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let count = stdout
+            .lines()
+            .find_map(|l| l.strip_prefix("provisioned "))
+            .and_then(|n| n.trim().parse().ok())
+            .unwrap_or(0);
+        provisioned.push((window.name.clone(), count));
+    }
+    Ok(provisioned)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_script() {
+        let files = vec![
+            ProvisionFile::Path("~/agents/CLAUDE.md".to_string()),
+            ProvisionFile::Entry {
+                from: "~/agents/reviewer/*.md".to_string(),
+                to: Some(".claude/commands".to_string()),
+                link: true,
+            },
+        ];
+        let script = script("~/work/app", &files);
+        assert!(script.starts_with("cd \"$HOME\"/work/app || exit 1; n=0; t=.; "));
+        assert!(script.contains("for f in \"$HOME\"/agents/CLAUDE.md; do [ -e \"$f\" ] || break; cp -R \"$f\" \"$t/\""));
+        assert!(script.contains("t=.claude/commands; mkdir -p \"$t\""));
+        assert!(script.contains("for f in \"$HOME\"/agents/reviewer/*.md; do [ -e \"$f\" ] || break; ln -sfn \"$f\" \"$t/\""));
+        assert!(script.contains("echo '~/agents/reviewer/*.md: no such file' >&2; exit 1;"));
+        assert!(script.ends_with("; echo \"provisioned $n\""));
+    }
+}
@@ -0,0 +1,107 @@
+//! Request budget shared by the claude windows (`rate_limit:`)
+//!
+//! The load is what the transcripts of every `claude:` window recorded in
+//! the last minute: one request per reply, and the reply's input, cache
+//! write and output tokens (cache reads don't count towards the API's
+//! token limits). Once it reaches `threshold` of a limit, new work waits:
+//! prompts sent by `dispatch` / `broadcast`, and tasks started by the queue
+//! workers, which `watch` pauses.
+
+use anyhow::Result;
+use chrono::Local;
+use std::time::Duration;
+
+use crate::config::{Config, RateLimitConfig};
+use crate::usage::{self, Usage};
+
+/// How often a held-back prompt checks the load again
+const RECHECK: Duration = Duration::from_secs(10);
+
+/// Requests and tokens of the last minute
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Load {
+    pub requests: u64,
+    pub tokens: u64,
+}
+
+impl Load {
+    fn from_usage(usage: &Usage) -> Self {
+        Self {
+            requests: usage.replies,
+            tokens: usage.tokens(),
+        }
+    }
+
+    /// The limit this load is close to, described for the user; None if
+    /// there is room for more work
+    pub fn near_limit(&self, limits: &RateLimitConfig) -> Option<String> {
+        let near = |used: u64, limit: Option<u64>| limit.filter(|&limit| used as f64 >= limit as f64 * limits.threshold);
+        if let Some(limit) = near(self.requests, limits.requests_per_minute) {
+            return Some(format!("{}/{} requests in the last minute", self.requests, limit));
+        }
+        near(self.tokens, limits.tokens_per_minute)
+            .map(|limit| format!("{}/{} tokens in the last minute", self.tokens, limit))
+    }
+}
+
+/// Load of the claude windows of `config`
+pub fn current(config: &Config) -> Result<Load> {
+    if !config.windows.iter().any(|w| w.claude.is_some()) {
+        return Ok(Load::default());
+    }
+    let report = usage::collect(config, Some(Local::now() - chrono::Duration::minutes(1)))?;
+    Ok(Load::from_usage(&report.total))
+}
+
+/// Block until the load leaves room for more work
+pub fn wait_for_capacity(config: &Config, limits: &RateLimitConfig) -> Result<()> {
+    while let Some(reason) = current(config)?.near_limit(limits) {
+        println!("  Rate limit: {}; waiting {}s...", reason, RECHECK.as_secs());
+        std::thread::sleep(RECHECK);
+    }
+    Ok(())
+}
+
+/// Wait before the prompt to the `n`-th window (counting from 0), so
+/// prompts go out `stagger_secs` apart and only while there is room
+pub fn before_prompt(config: &Config, n: usize) -> Result<()> {
+    let Some(ref limits) = config.rate_limit else { return Ok(()) };
+    if n > 0 {
+        std::thread::sleep(Duration::from_secs(limits.stagger_secs));
+    }
+    wait_for_capacity(config, limits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits(requests: Option<u64>, tokens: Option<u64>) -> RateLimitConfig {
+        RateLimitConfig { requests_per_minute: requests, tokens_per_minute: tokens, threshold: 0.8, stagger_secs: 5 }
+    }
+
+    #[test]
+    fn test_from_usage() {
+        let usage = Usage {
+            input_tokens: 10,
+            output_tokens: 20,
+            cache_write_tokens: 30,
+            cache_read_tokens: 1000,
+            cost_usd: 0.0,
+            replies: 3,
+        };
+        assert_eq!(Load::from_usage(&usage), Load { requests: 3, tokens: 60 });
+    }
+
+    #[test]
+    fn test_near_limit() {
+        let load = Load { requests: 40, tokens: 10_000 };
+        assert_eq!(load.near_limit(&limits(Some(50), None)).as_deref(), Some("40/50 requests in the last minute"));
+        assert_eq!(load.near_limit(&limits(Some(51), None)), None);
+        assert_eq!(
+            load.near_limit(&limits(Some(100), Some(12_000))).as_deref(),
+            Some("10000/12000 tokens in the last minute")
+        );
+        assert_eq!(load.near_limit(&limits(None, Some(20_000))), None);
+    }
+}
@@ -0,0 +1,182 @@
+//! Command reference generated from the clap definitions (`docs`)
+//!
+//! Packages ship a man page per command (clap_mangen) and a markdown page
+//! per subcommand, so the reference never drifts from the actual flags.
+
+use anyhow::{Context, Result};
+use clap::{Arg, Command};
+use std::path::{Path, PathBuf};
+
+/// Command names from the top, e.g. ["wsl-multi-launcher", "session", "save"]
+fn path_name(path: &[&str]) -> String {
+    path.join(" ")
+}
+
+/// How an argument is written on the command line, e.g. `-c, --config <CONFIG>`
+fn signature(arg: &Arg) -> String {
+    let values: Vec<String> = match arg.get_value_names() {
+        Some(names) => names.iter().map(|n| format!("<{}>", n)).collect(),
+        None => vec![format!("<{}>", arg.get_id().as_str().to_uppercase())],
+    };
+    if arg.is_positional() {
+        return values.join(" ");
+    }
+    let mut flags = Vec::new();
+    if let Some(short) = arg.get_short() {
+        flags.push(format!("-{}", short));
+    }
+    if let Some(long) = arg.get_long() {
+        flags.push(format!("--{}", long));
+    }
+    let mut signature = flags.join(", ");
+    if arg.get_action().takes_values() {
+        signature.push(' ');
+        signature.push_str(&values.join(" "));
+    }
+    signature
+}
+
+/// Markdown list item of an argument
+fn item(arg: &Arg) -> String {
+    let help = arg.get_long_help().or(arg.get_help()).map(|h| h.to_string()).unwrap_or_default();
+    let mut notes = Vec::new();
+    let possible: Vec<String> =
+        arg.get_possible_values().iter().filter(|v| !v.is_hide_set()).map(|v| format!("`{}`", v.get_name())).collect();
+    if !possible.is_empty() && arg.get_action().takes_values() {
+        notes.push(format!("one of {}", possible.join(", ")));
+    }
+    let defaults: Vec<String> = arg.get_default_values().iter().map(|v| v.to_string_lossy().into_owned()).collect();
+    if !defaults.is_empty() && arg.get_action().takes_values() {
+        notes.push(format!("default: `{}`", defaults.join(",")));
+    }
+    let mut item = format!("- `{}`", signature(arg));
+    if !help.is_empty() {
+        item.push_str(&format!(": {}", help.replace('\n', " ")));
+    }
+    if !notes.is_empty() {
+        item.push_str(&format!(" ({})", notes.join("; ")));
+    }
+    item
+}
+
+/// Markdown page of `cmd`, reached from the top through `path`
+pub fn markdown(cmd: &Command, path: &[&str]) -> String {
+    let mut page = format!("# {}\n\n", path_name(path));
+    if let Some(about) = cmd.get_long_about().or(cmd.get_about()) {
+        page.push_str(&format!("{}\n\n", about.to_string().trim()));
+    }
+    let usage = cmd.clone().render_usage().to_string();
+    let usage = usage.strip_prefix("Usage: ").unwrap_or(&usage);
+    page.push_str(&format!("## Usage\n\n```\n{}\n```\n", usage));
+
+    let shown: Vec<&Arg> = cmd
+        .get_arguments()
+        .filter(|a| !a.is_hide_set() && !matches!(a.get_id().as_str(), "help" | "version"))
+        .collect();
+    for (heading, positional) in [("Arguments", true), ("Options", false)] {
+        let items: Vec<String> = shown.iter().filter(|a| a.is_positional() == positional).map(|a| item(a)).collect();
+        if !items.is_empty() {
+            page.push_str(&format!("\n## {}\n\n{}\n", heading, items.join("\n")));
+        }
+    }
+
+    let subcommands: Vec<String> = cmd
+        .get_subcommands()
+        .filter(|s| !s.is_hide_set())
+        .map(|s| {
+            let about = s.get_about().map(|a| a.to_string()).unwrap_or_default();
+            format!("- [`{} {}`]({}): {}", path_name(path), s.get_name(), file_name(path, s.get_name()), about)
+        })
+        .collect();
+    if !subcommands.is_empty() {
+        page.push_str(&format!("\n## Subcommands\n\n{}\n", subcommands.join("\n")));
+    }
+    page
+}
+
+/// Markdown file of subcommand `name` of the command at `path`
+fn file_name(path: &[&str], name: &str) -> String {
+    let mut parts: Vec<&str> = path.iter().skip(1).copied().collect();
+    parts.push(name);
+    format!("{}.md", parts.join("-"))
+}
+
+fn write_markdown(cmd: &Command, path: &[&str], file: &str, dir: &Path, written: &mut Vec<PathBuf>) -> Result<()> {
+    let target = dir.join(file);
+    std::fs::write(&target, markdown(cmd, path)).with_context(|| format!("Failed to write {}", target.display()))?;
+    written.push(target);
+    for sub in cmd.get_subcommands().filter(|s| !s.is_hide_set()) {
+        let mut sub_path = path.to_vec();
+        sub_path.push(sub.get_name());
+        write_markdown(sub, &sub_path, &file_name(path, sub.get_name()), dir, written)?;
+    }
+    Ok(())
+}
+
+/// Write the man pages to `<out>/man` and the markdown pages to
+/// `<out>/markdown` (`index.md` for the top command), returning the
+/// markdown files written
+pub fn generate(cmd: Command, out: &Path) -> Result<Vec<PathBuf>> {
+    let mut cmd = cmd.disable_help_subcommand(true);
+    cmd.build();
+
+    let man = out.join("man");
+    std::fs::create_dir_all(&man).with_context(|| format!("Failed to create {}", man.display()))?;
+    clap_mangen::generate_to(cmd.clone(), &man).with_context(|| format!("Failed to write man pages to {}", man.display()))?;
+
+    let markdown = out.join("markdown");
+    std::fs::create_dir_all(&markdown).with_context(|| format!("Failed to create {}", markdown.display()))?;
+    let mut written = Vec::new();
+    let name = cmd.get_name().to_string();
+    write_markdown(&cmd, &[&name], "index.md", &markdown, &mut written)?;
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::ArgAction;
+
+    fn demo() -> Command {
+        Command::new("demo")
+            .about("Run demos")
+            .arg(Arg::new("config").short('c').long("config").default_value("config.yaml").help("Path to config file"))
+            .arg(Arg::new("secret").long("secret").hide(true))
+            .subcommand(
+                Command::new("launch")
+                    .about("Launch windows")
+                    .arg(Arg::new("names").num_args(1..).help("Windows to launch"))
+                    .arg(Arg::new("mode").long("mode").value_parser(["windows", "tabs"]))
+                    .arg(Arg::new("resume").long("resume").action(ArgAction::SetTrue).help("Only\nmissing ones")),
+            )
+            .subcommand(Command::new("internal").hide(true))
+    }
+
+    #[test]
+    fn test_markdown() {
+        let mut cmd = demo();
+        cmd.build();
+        let top = markdown(&cmd, &["demo"]);
+        assert!(top.starts_with("# demo\n\nRun demos\n\n## Usage\n\n```\ndemo [OPTIONS] [COMMAND]\n```\n"));
+        assert!(top.contains("- `-c, --config <CONFIG>`: Path to config file (default: `config.yaml`)"));
+        assert!(!top.contains("secret"));
+        assert!(top.contains("## Subcommands\n\n- [`demo launch`](launch.md): Launch windows\n"));
+        assert!(!top.contains("internal"));
+
+        let launch = cmd.find_subcommand("launch").unwrap();
+        let page = markdown(launch, &["demo", "launch"]);
+        assert!(page.contains("## Arguments\n\n- `<NAMES>`: Windows to launch\n"));
+        assert!(page.contains("- `--mode <MODE>` (one of `windows`, `tabs`)"));
+        assert!(page.contains("- `--resume`: Only missing ones"));
+    }
+
+    #[test]
+    fn test_generate() {
+        let dir = tempfile::tempdir().unwrap();
+        let written = generate(demo(), dir.path()).unwrap();
+        let names: Vec<String> = written.iter().map(|p| p.file_name().unwrap().to_string_lossy().into_owned()).collect();
+        assert_eq!(names, ["index.md", "launch.md"]);
+        assert!(dir.path().join("man/demo.1").is_file());
+        assert!(dir.path().join("man/demo-launch.1").is_file());
+    }
+}
@@ -0,0 +1,155 @@
+//! Saved workspaces (`session save` / `session restore`)
+//!
+//! A snapshot stores the windows that were running, with the config they
+//! were launched from and the exact rectangle each one had, so the
+//! workspace can be recreated later even if the config file changed.
+//! Snapshots are YAML files in the `sessions` directory next to the state
+//! file.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::layout::Rect;
+use crate::state::SessionState;
+
+/// A saved set of windows
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Session the windows are recorded under when restored
+    pub session: String,
+
+    pub saved_at: DateTime<Local>,
+
+    /// Config the windows were launched from, reduced to the saved windows
+    pub config: Config,
+
+    /// Rectangle of each window by name; missing for maximized windows
+    #[serde(default)]
+    pub rects: BTreeMap<String, Rect>,
+}
+
+impl Snapshot {
+    /// Directory holding the snapshots
+    pub fn dir() -> Result<PathBuf> {
+        Ok(SessionState::default_dir()?.join("sessions"))
+    }
+
+    /// Path of the snapshot called `name`
+    pub fn path(name: &str) -> Result<PathBuf> {
+        validate_name(name)?;
+        Ok(Self::dir()?.join(format!("{}.yaml", name)))
+    }
+
+    pub fn load(name: &str) -> Result<Self> {
+        let path = Self::path(name)?;
+        if !path.exists() {
+            anyhow::bail!("No saved session named '{}'", name);
+        }
+        Self::load_from(&path)
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read snapshot: {}", path.display()))?;
+        serde_yaml::from_str(&content)
+            .with_context(|| format!("Failed to parse snapshot: {}", path.display()))
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        let yaml = serde_yaml::to_string(self)?;
+        fs::write(path, yaml)
+            .with_context(|| format!("Failed to write snapshot: {}", path.display()))
+    }
+
+    /// Names of all saved snapshots, sorted
+    pub fn list() -> Result<Vec<String>> {
+        list_in(&Self::dir()?)
+    }
+}
+
+fn list_in(dir: &Path) -> Result<Vec<String>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut names: Vec<String> = fs::read_dir(dir)
+        .with_context(|| format!("Failed to read {}", dir.display()))?
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            if path.extension()? != "yaml" {
+                return None;
+            }
+            Some(path.file_stem()?.to_string_lossy().into_owned())
+        })
+        .collect();
+    names.sort();
+    Ok(names)
+}
+
+/// Snapshot names become file names, so keep them to a safe character set
+fn validate_name(name: &str) -> Result<()> {
+    if name.is_empty()
+        || name.starts_with('.')
+        || !name.chars().all(|c| c.is_ascii_alphanumeric() || "-_.".contains(c))
+    {
+        anyhow::bail!(
+            "Invalid session name '{}': use letters, digits, '-', '_' and '.'",
+            name
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::WindowConfig;
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sessions").join("review.yaml");
+
+        let snapshot = Snapshot {
+            session: "work".to_string(),
+            saved_at: Local::now(),
+            config: Config {
+                wsl_distribution: "Ubuntu".to_string(),
+                windows: vec![WindowConfig {
+                    name: "claude-1".to_string(),
+                    command: Some("claude".to_string()),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+            rects: BTreeMap::from([("claude-1".to_string(), Rect::new(10, 20, 800, 600))]),
+        };
+        snapshot.save_to(&path).unwrap();
+
+        let loaded = Snapshot::load_from(&path).unwrap();
+        assert_eq!(loaded.session, "work");
+        assert_eq!(loaded.config.windows[0].name, "claude-1");
+        assert_eq!(loaded.config.windows[0].command.as_deref(), Some("claude"));
+        assert_eq!(loaded.rects["claude-1"], Rect::new(10, 20, 800, 600));
+
+        assert_eq!(list_in(path.parent().unwrap()).unwrap(), vec!["review"]);
+        assert!(list_in(&dir.path().join("missing")).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_validate_name() {
+        assert!(validate_name("review-2024.10").is_ok());
+        assert!(validate_name("").is_err());
+        assert!(validate_name("../etc").is_err());
+        assert!(validate_name(".hidden").is_err());
+        assert!(validate_name("a b").is_err());
+    }
+}
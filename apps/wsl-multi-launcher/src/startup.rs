@@ -0,0 +1,86 @@
+//! Launch-at-login registration (`install-startup` / `remove-startup`)
+//!
+//! The registered command is `wsl.exe -d <distro> --exec <this binary> -c
+//! <config> launch` (or `watch`), so the workspace comes back after a
+//! reboot without opening a shell first.
+
+use std::path::Path;
+
+use crate::shell;
+
+/// Where the logon command is registered
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum StartupMethod {
+    /// Shortcut in the user's Startup folder
+    #[default]
+    Shortcut,
+    /// Scheduled Task triggered at logon
+    Task,
+}
+
+impl StartupMethod {
+    /// Value of the script's -Method parameter
+    pub fn script_name(self) -> &'static str {
+        match self {
+            StartupMethod::Shortcut => "Shortcut",
+            StartupMethod::Task => "Task",
+        }
+    }
+}
+
+/// Name of the shortcut / task for a session
+pub fn entry_name(session: &str) -> String {
+    format!("wsl-multi-launcher-{}", session)
+}
+
+/// wsl.exe arguments that run `command` (launch or watch) for a config,
+/// passing `--session` when one was given explicitly
+pub fn wsl_arguments(
+    distribution: &str,
+    exe: &Path,
+    config: &Path,
+    session: Option<&str>,
+    command: &str,
+) -> String {
+    let mut args = vec![
+        "-d".to_string(),
+        distribution.to_string(),
+        "--exec".to_string(),
+        exe.to_string_lossy().into_owned(),
+        "-c".to_string(),
+        config.to_string_lossy().into_owned(),
+    ];
+    if let Some(session) = session {
+        args.extend(["--session".to_string(), session.to_string()]);
+    }
+    args.push(command.to_string());
+    shell::join_windows(&args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entry_name() {
+        assert_eq!(entry_name("config"), "wsl-multi-launcher-config");
+    }
+
+    #[test]
+    fn test_wsl_arguments() {
+        assert_eq!(
+            wsl_arguments(
+                "Ubuntu-24.04",
+                Path::new("/home/u/.cargo/bin/wsl-multi-launcher"),
+                Path::new("/home/u/my configs/work.yaml"),
+                None,
+                "launch"
+            ),
+            r#"-d Ubuntu-24.04 --exec /home/u/.cargo/bin/wsl-multi-launcher -c "/home/u/my configs/work.yaml" launch"#
+        );
+        assert_eq!(
+            wsl_arguments("Ubuntu", Path::new("/bin/wml"), Path::new("/c.yaml"), Some("client-a"), "watch"),
+            "-d Ubuntu --exec /bin/wml -c /c.yaml --session client-a watch"
+        );
+    }
+}
@@ -0,0 +1,129 @@
+//! What became of each window of a launch
+//!
+//! Launching carries on past a window that fails, so it ends with a table
+//! of every window (arranged, launched, skipped or failed, with the reason)
+//! instead of a count that hides the failures.
+
+use crate::output;
+
+/// Result of launching one window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// Launched and moved into its cell
+    Arranged,
+    /// Launched, but deliberately not arranged
+    Launched,
+    /// Not launched because its pre_launch hook failed
+    Skipped,
+    /// The terminal failed to start
+    LaunchFailed,
+    /// Launched, but it couldn't be moved into its cell
+    ArrangeFailed,
+}
+
+impl Outcome {
+    fn label(self) -> &'static str {
+        match self {
+            Outcome::Arranged => "arranged",
+            Outcome::Launched => "launched",
+            Outcome::Skipped => "skipped",
+            Outcome::LaunchFailed => "launch failed",
+            Outcome::ArrangeFailed => "arrange failed",
+        }
+    }
+
+    /// Whether the window is running
+    fn is_running(self) -> bool {
+        matches!(self, Outcome::Arranged | Outcome::Launched | Outcome::ArrangeFailed)
+    }
+
+    /// Whether the window didn't end up as configured
+    fn is_failure(self) -> bool {
+        matches!(self, Outcome::Skipped | Outcome::LaunchFailed | Outcome::ArrangeFailed)
+    }
+}
+
+#[derive(Debug)]
+struct Entry {
+    window: String,
+    outcome: Outcome,
+    reason: Option<String>,
+}
+
+/// Outcomes of the windows of one launch, in launch order
+#[derive(Debug, Default)]
+pub struct Summary {
+    entries: Vec<Entry>,
+}
+
+impl Summary {
+    /// Set the outcome of `window`, replacing an earlier one
+    pub fn set(&mut self, window: &str, outcome: Outcome, reason: Option<String>) {
+        match self.entries.iter_mut().find(|e| e.window == window) {
+            Some(entry) => {
+                entry.outcome = outcome;
+                entry.reason = reason;
+            }
+            None => self.entries.push(Entry { window: window.to_string(), outcome, reason }),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Windows that are running, arranged or not
+    pub fn running(&self) -> usize {
+        self.entries.iter().filter(|e| e.outcome.is_running()).count()
+    }
+
+    /// Windows that were skipped or failed to launch or arrange
+    pub fn failed(&self) -> usize {
+        self.entries.iter().filter(|e| e.outcome.is_failure()).count()
+    }
+
+    /// Table of every window with its outcome and the reason
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = vec![format!("{:<20} {:<15} {}", "WINDOW", "RESULT", "REASON").trim_end().to_string()];
+        for entry in &self.entries {
+            let label = format!("{:<15}", entry.outcome.label());
+            let label = match entry.outcome {
+                Outcome::Arranged | Outcome::Launched => output::ok(label),
+                Outcome::Skipped => output::warn(label),
+                Outcome::LaunchFailed | Outcome::ArrangeFailed => output::fail(label),
+            };
+            let line = format!("{:<20} {} {}", entry.window, label, entry.reason.as_deref().unwrap_or(""));
+            lines.push(line.trim_end().to_string());
+        }
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary() {
+        let mut summary = Summary::default();
+        summary.set("api", Outcome::Launched, None);
+        summary.set("web", Outcome::LaunchFailed, Some("wt.exe exited with 1".to_string()));
+        summary.set("docs", Outcome::Skipped, Some("pre_launch hook failed".to_string()));
+        summary.set("api", Outcome::ArrangeFailed, Some("window not found".to_string()));
+        summary.set("db", Outcome::Launched, Some("maximized".to_string()));
+
+        assert_eq!(summary.len(), 4);
+        assert_eq!(summary.running(), 2);
+        assert_eq!(summary.failed(), 3);
+        assert_eq!(
+            summary.lines(),
+            [
+                "WINDOW               RESULT          REASON",
+                "api                  arrange failed  window not found",
+                "web                  launch failed   wt.exe exited with 1",
+                "docs                 skipped         pre_launch hook failed",
+                "db                   launched        maximized",
+            ]
+        );
+    }
+}
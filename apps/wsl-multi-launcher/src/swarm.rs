@@ -0,0 +1,163 @@
+//! Config of a swarm: N claude workers on one plan (`swarm`)
+//!
+//! A swarm is an ordinary config file with a `worktrees:` section: one
+//! branch and worktree per worker, each session started with the shared
+//! plan and the part of it that falls to that worker, on a grid that fits
+//! the workers. Writing it out (instead of launching from memory) makes
+//! the swarm a session like any other: `ps`, `watch` and
+//! `stop --cleanup` work on it with `-c <name>.yaml`.
+
+use anyhow::Result;
+use serde_yaml::{Mapping, Value};
+
+use crate::layout;
+
+/// What to start a swarm with
+#[derive(Debug, Clone)]
+pub struct Swarm {
+    /// Session name; also the prefix of window and branch names
+    pub name: String,
+    pub count: usize,
+    /// Shared plan every worker gets
+    pub prompt: String,
+    /// Repository the worktrees are added to
+    pub repo: String,
+    pub base: Option<String>,
+    pub model: Option<String>,
+    pub permission_mode: Option<String>,
+    pub distribution: String,
+    pub target_display: u32,
+}
+
+impl Swarm {
+    /// Window (and worktree directory) name of the `index`-th worker (from 1)
+    fn window(&self, index: usize) -> String {
+        format!("{}-{}", self.name, index)
+    }
+
+    fn branch(&self, index: usize) -> String {
+        format!("{}/worker-{}", self.name, index)
+    }
+}
+
+/// The shared plan followed by the worker's own index and scope
+pub fn worker_prompt(plan: &str, index: usize, count: usize, branch: &str) -> String {
+    format!(
+        "{}\n\n---\n\nYou are worker {} of {} working on the plan above at the same time, each in your own \
+         git worktree. Your branch is {}. Split the plan into {} parts in the order it lists the work, do \
+         part {} and leave the other parts to the other workers. Commit your work on your branch.",
+        plan.trim(),
+        index,
+        count,
+        branch,
+        count,
+        index
+    )
+}
+
+fn insert(map: &mut Mapping, key: &str, value: impl Into<Value>) {
+    map.insert(key.into(), value.into());
+}
+
+/// YAML config of the swarm
+pub fn render(swarm: &Swarm) -> Result<String> {
+    if swarm.count == 0 {
+        anyhow::bail!("A swarm needs at least one worker");
+    }
+    if swarm.prompt.trim().is_empty() {
+        anyhow::bail!("The swarm's prompt is empty");
+    }
+    let (cols, rows) = layout::fitting_grid(swarm.count);
+
+    let mut layout = Mapping::new();
+    insert(&mut layout, "grid", format!("{}x{}", cols, rows));
+
+    let mut claude = Mapping::new();
+    if let Some(ref model) = swarm.model {
+        insert(&mut claude, "model", model.as_str());
+    }
+    if let Some(ref mode) = swarm.permission_mode {
+        insert(&mut claude, "permission_mode", mode.as_str());
+    }
+
+    let branches: Vec<Value> = (1..=swarm.count)
+        .map(|i| {
+            let mut branch = Mapping::new();
+            insert(&mut branch, "branch", swarm.branch(i));
+            insert(&mut branch, "name", swarm.window(i));
+            insert(&mut branch, "prompt", worker_prompt(&swarm.prompt, i, swarm.count, &swarm.branch(i)));
+            Value::Mapping(branch)
+        })
+        .collect();
+
+    let mut worktrees = Mapping::new();
+    insert(&mut worktrees, "repo", swarm.repo.as_str());
+    if let Some(ref base) = swarm.base {
+        insert(&mut worktrees, "base", base.as_str());
+    }
+    insert(&mut worktrees, "claude", claude);
+    insert(&mut worktrees, "branches", branches);
+
+    let mut config = Mapping::new();
+    insert(&mut config, "wsl_distribution", swarm.distribution.as_str());
+    insert(&mut config, "target_display", swarm.target_display);
+    insert(&mut config, "layout", layout);
+    insert(&mut config, "worktrees", worktrees);
+    insert(&mut config, "windows", Vec::<Value>::new());
+
+    Ok(format!(
+        "# Swarm '{}': {} claude workers, generated by `wsl-multi-launcher swarm`\n{}",
+        swarm.name,
+        swarm.count,
+        serde_yaml::to_string(&config)?
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{self, Config};
+
+    fn swarm(count: usize) -> Swarm {
+        Swarm {
+            name: "swarm".to_string(),
+            count,
+            prompt: "# Plan\n\n1. API\n2. UI\n".to_string(),
+            repo: "/home/u/app".to_string(),
+            base: Some("origin/main".to_string()),
+            model: Some("sonnet".to_string()),
+            permission_mode: None,
+            distribution: "Ubuntu".to_string(),
+            target_display: 1,
+        }
+    }
+
+    #[test]
+    fn test_worker_prompt() {
+        let prompt = worker_prompt("Plan\n", 2, 6, "swarm/worker-2");
+        assert!(prompt.starts_with("Plan\n\n---\n\nYou are worker 2 of 6 "));
+        assert!(prompt.contains("Your branch is swarm/worker-2. Split the plan into 6 parts"));
+        assert!(prompt.contains("do part 2 and leave"));
+    }
+
+    #[test]
+    fn test_render() {
+        let yaml = render(&swarm(6)).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("swarm.yaml");
+        std::fs::write(&path, &yaml).unwrap();
+        let config: Config = config::load(&path).unwrap();
+
+        assert_eq!(config.layout.parse_grid().unwrap(), (3, 2));
+        assert_eq!(config.target_display, 1);
+        let names: Vec<_> = config.windows.iter().map(|w| w.name.as_str()).collect();
+        assert_eq!(names, ["swarm-1", "swarm-2", "swarm-3", "swarm-4", "swarm-5", "swarm-6"]);
+        let window = &config.windows[2];
+        assert_eq!(window.worktree.as_ref().unwrap().branch, "swarm/worker-3");
+        assert_eq!(window.working_dir.as_deref(), Some("/home/u/app-worktrees/swarm-3"));
+        let command = window.command.as_deref().unwrap();
+        assert!(command.starts_with("claude --model sonnet $'# Plan\\n\\n1. API\\n2. UI\\n\\n---\\n\\nYou are worker 3 of 6"));
+
+        assert!(render(&swarm(0)).is_err());
+    }
+}
@@ -0,0 +1,133 @@
+//! How long each window of a launch took
+//!
+//! `launch` reports, per window, the time to spawn its terminal, to find
+//! the new window among the terminal's windows and to move it into its
+//! cell, so a slow launch shows which delay to tune. Timings are only kept
+//! after `start`, so commands that keep relaunching windows (`watch`)
+//! don't pile them up.
+
+use serde::Serialize;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static TIMINGS: Mutex<Vec<WindowTiming>> = Mutex::new(Vec::new());
+
+/// Part of launching a window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// Starting the terminal
+    Spawn,
+    /// Waiting for the new window to show up
+    Detect,
+    /// Moving the window into its cell
+    Arrange,
+}
+
+/// Phase durations of one window, in milliseconds (None if it didn't get
+/// that far)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct WindowTiming {
+    pub window: String,
+    pub spawn_ms: Option<u64>,
+    pub detect_ms: Option<u64>,
+    pub arrange_ms: Option<u64>,
+}
+
+impl WindowTiming {
+    fn total_ms(&self) -> u64 {
+        [self.spawn_ms, self.detect_ms, self.arrange_ms].into_iter().flatten().sum()
+    }
+}
+
+/// Keep timings from now on
+pub fn start() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Run `f` as `phase` of launching `window`, noting how long it took
+pub fn measure<T>(window: &str, phase: Phase, f: impl FnOnce() -> T) -> T {
+    let started = Instant::now();
+    let result = f();
+    add(window, phase, started.elapsed());
+    result
+}
+
+/// Note that `phase` of launching `window` took `took`, for phases that
+/// aren't one call (waiting for windows launched together)
+pub fn add(window: &str, phase: Phase, took: Duration) {
+    if ENABLED.load(Ordering::Relaxed) {
+        record(&mut TIMINGS.lock().unwrap_or_else(|e| e.into_inner()), window, phase, took);
+    }
+}
+
+fn record(timings: &mut Vec<WindowTiming>, window: &str, phase: Phase, took: Duration) {
+    let index = match timings.iter().position(|t| t.window == window) {
+        Some(index) => index,
+        None => {
+            timings.push(WindowTiming { window: window.to_string(), ..Default::default() });
+            timings.len() - 1
+        }
+    };
+    let ms = Some(took.as_millis() as u64);
+    let timing = &mut timings[index];
+    match phase {
+        Phase::Spawn => timing.spawn_ms = ms,
+        Phase::Detect => timing.detect_ms = ms,
+        Phase::Arrange => timing.arrange_ms = ms,
+    }
+}
+
+/// Timings kept so far, in launch order
+pub fn windows() -> Vec<WindowTiming> {
+    TIMINGS.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+fn seconds(ms: Option<u64>) -> String {
+    ms.map_or_else(|| "-".to_string(), |ms| format!("{:.2}s", ms as f64 / 1000.0))
+}
+
+/// Table of the window timings and the wall time of the whole launch (just
+/// the wall time when no window was timed, as with tabs and panes)
+pub fn table(windows: &[WindowTiming], total: Duration) -> Vec<String> {
+    let mut lines = Vec::new();
+    if !windows.is_empty() {
+        lines.push(format!("{:<20} {:>8} {:>8} {:>8} {:>8}", "WINDOW", "SPAWN", "DETECT", "ARRANGE", "TOTAL"));
+    }
+    for timing in windows {
+        lines.push(format!(
+            "{:<20} {:>8} {:>8} {:>8} {:>8}",
+            timing.window,
+            seconds(timing.spawn_ms),
+            seconds(timing.detect_ms),
+            seconds(timing.arrange_ms),
+            seconds(Some(timing.total_ms())),
+        ));
+    }
+    lines.push(format!("Total wall time: {}", seconds(Some(total.as_millis() as u64))));
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record() {
+        let mut timings = Vec::new();
+        record(&mut timings, "api", Phase::Spawn, Duration::from_millis(120));
+        record(&mut timings, "web", Phase::Spawn, Duration::from_millis(80));
+        record(&mut timings, "api", Phase::Detect, Duration::from_millis(1030));
+        record(&mut timings, "api", Phase::Arrange, Duration::from_millis(210));
+        assert_eq!(timings.len(), 2);
+        assert_eq!((timings[0].spawn_ms, timings[0].detect_ms, timings[0].arrange_ms), (Some(120), Some(1030), Some(210)));
+        assert_eq!(timings[1].detect_ms, None);
+
+        let table = table(&timings, Duration::from_millis(5432));
+        assert_eq!(table[1], "api                     0.12s    1.03s    0.21s    1.36s");
+        assert_eq!(table[2], "web                     0.08s        -        -    0.08s");
+        assert_eq!(table[3], "Total wall time: 5.43s");
+        assert_eq!(super::table(&[], Duration::from_millis(800)), ["Total wall time: 0.80s"]);
+    }
+}
@@ -0,0 +1,214 @@
+//! Archived claude transcripts of stopped windows (`stop --archive`, `transcripts`)
+//!
+//! When a `claude:` window is stopped, the transcripts its project got
+//! while the window was up are copied into
+//! `~/.local/state/wsl-multi-launcher/transcripts/<session>/<window>/<date>/`
+//! inside the WSL distribution. Claude keeps one transcript directory per
+//! project and cleans it up on its own schedule; the archive keeps a record
+//! of what each agent did, filed under the window that did it.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, NaiveDateTime};
+use std::process::Command;
+use tracing::debug;
+
+use crate::claude;
+use crate::config::WindowConfig;
+use crate::dry_run;
+use crate::exit_status;
+use crate::shell;
+
+/// Shell snippet setting `$a` to the archive root
+const ARCHIVE_ROOT: &str = "a=\"${XDG_STATE_HOME:-$HOME/.local/state}/wsl-multi-launcher/transcripts\"";
+
+/// Name format of an archive directory
+const STAMP_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+/// Script copying the transcripts (with subagents' in their subdirectories)
+/// each window's project got since the window was launched into a new
+/// archive directory; prints "<files> <dir>" after each window's `SECTION`
+/// line ("0 -" if there were none)
+fn archive_script(session: &str, windows: &[(&WindowConfig, DateTime<Local>)], now: DateTime<Local>) -> String {
+    let stamp = now.format(STAMP_FORMAT).to_string();
+    let mut script = format!("{}; {}", claude::PROJECTS_DIR, ARCHIVE_ROOT);
+    for (window, launched_at) in windows {
+        let Some(ref preset) = window.claude else { continue };
+        script.push_str(&format!(
+            "; {section}; {dir} && [ -d \"$p\" ] && o=\"$a\"/{session}/{name}/{stamp} && mkdir -p \"$o\" && \
+             (cd \"$p\" && find . -name '*.jsonl' -mmin -{minutes} -exec cp -p --parents -t \"$o\" {{}} +) && \
+             n=$(find \"$o\" -name '*.jsonl' | wc -l) && \
+             if [ \"$n\" -gt 0 ]; then echo \"$n $o\"; else rmdir \"$o\"; echo '0 -'; fi",
+            section = claude::section_script(&window.name),
+            dir = claude::transcript_dir_script(&preset.project),
+            session = shell::quote(&exit_status::file_name(session)),
+            name = shell::quote(&exit_status::file_name(&window.name)),
+            minutes = (now - *launched_at).num_minutes() + 1,
+        ));
+    }
+    script
+}
+
+/// Transcripts archived for one window
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Archived {
+    pub window: String,
+    pub files: usize,
+    /// Archive directory; None if there was nothing to archive
+    pub path: Option<String>,
+}
+
+fn parse_archived(windows: &[(&WindowConfig, DateTime<Local>)], output: &str) -> Vec<Archived> {
+    let sections = claude::parse_sections(output);
+    windows
+        .iter()
+        .filter(|(window, _)| window.claude.is_some())
+        .map(|(window, _)| {
+            let line = sections.get(&window.name).and_then(|lines| lines.first());
+            let (files, path) = line
+                .and_then(|line| line.split_once(' '))
+                .map(|(files, path)| (files.parse().unwrap_or(0), path))
+                .unwrap_or((0, "-"));
+            Archived {
+                window: window.name.clone(),
+                files,
+                path: (files > 0).then(|| path.to_string()),
+            }
+        })
+        .collect()
+}
+
+/// Archive the transcripts of the claude windows in `windows`, given with
+/// the time each was launched
+pub fn archive(
+    distribution: &str,
+    session: &str,
+    windows: &[(&WindowConfig, DateTime<Local>)],
+) -> Result<Vec<Archived>> {
+    let script = archive_script(session, windows, Local::now());
+    debug!("Archiving transcripts: {}", script);
+    let mut cmd = Command::new("wsl.exe");
+    cmd.args(["-d", distribution, "--exec", "bash", "-c", &script]);
+    if dry_run::skip(&cmd) {
+        return Ok(Vec::new());
+    }
+    let output = cmd
+        .output()
+        .context("Failed to run wsl.exe")?;
+    Ok(parse_archived(windows, &String::from_utf8_lossy(&output.stdout)))
+}
+
+/// One archive directory of a window
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Archive {
+    pub path: String,
+    /// When it was archived
+    pub at: Option<NaiveDateTime>,
+    pub files: usize,
+    pub bytes: u64,
+}
+
+/// Script printing the window's archive root, then "<size> <archive>/<file>"
+/// for every archived transcript
+fn list_script(session: &str, window: &str) -> String {
+    format!(
+        "{}; d=\"$a\"/{}/{}; echo \"$d\"; [ -d \"$d\" ] && find \"$d\" -mindepth 2 -name '*.jsonl' -printf '%s %P\\n'",
+        ARCHIVE_ROOT,
+        shell::quote(&exit_status::file_name(session)),
+        shell::quote(&exit_status::file_name(window)),
+    )
+}
+
+/// Group the transcripts listed by `list_script` by archive, oldest first
+fn parse_list(output: &str) -> Vec<Archive> {
+    let mut lines = output.lines();
+    let Some(root) = lines.next() else { return Vec::new() };
+    let mut archives: Vec<Archive> = Vec::new();
+    for line in lines {
+        let Some((size, file)) = line.split_once(' ') else { continue };
+        let Some((stamp, _)) = file.split_once('/') else { continue };
+        let path = format!("{}/{}", root, stamp);
+        let bytes: u64 = size.parse().unwrap_or(0);
+        match archives.iter_mut().find(|a| a.path == path) {
+            Some(archive) => {
+                archive.files += 1;
+                archive.bytes += bytes;
+            }
+            None => archives.push(Archive {
+                path,
+                at: NaiveDateTime::parse_from_str(stamp, STAMP_FORMAT).ok(),
+                files: 1,
+                bytes,
+            }),
+        }
+    }
+    archives.sort_by(|a, b| a.path.cmp(&b.path));
+    archives
+}
+
+/// Archives of `window` in `session`, oldest first
+pub fn list(distribution: &str, session: &str, window: &str) -> Result<Vec<Archive>> {
+    let output = Command::new("wsl.exe")
+        .args(["-d", distribution, "--exec", "bash", "-c", &list_script(session, window)])
+        .output()
+        .context("Failed to run wsl.exe")?;
+    Ok(parse_list(&String::from_utf8_lossy(&output.stdout)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ClaudeConfig;
+
+    fn window(name: &str) -> WindowConfig {
+        WindowConfig {
+            name: name.to_string(),
+            claude: Some(ClaudeConfig { project: "~/api".to_string(), ..Default::default() }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_archive_script() {
+        let now = Local::now();
+        let api = window("api/v2");
+        let plain = WindowConfig { name: "shell".to_string(), ..Default::default() };
+        let script = archive_script("work", &[(&api, now - chrono::Duration::minutes(90)), (&plain, now)], now);
+        assert!(script.starts_with(claude::PROJECTS_DIR));
+        assert!(script.contains(&format!("o=\"$a\"/work/api_v2/{} && mkdir -p", now.format(STAMP_FORMAT))));
+        assert!(script.contains("-name '*.jsonl' -mmin -91 -exec cp -p --parents -t \"$o\" {} +)"));
+        assert!(!script.contains("shell"));
+    }
+
+    #[test]
+    fn test_parse_archived() {
+        let now = Local::now();
+        let (api, web) = (window("api"), window("web"));
+        let output = "==wml== api\n2 /home/u/.local/state/wsl-multi-launcher/transcripts/work/api/20260101-120000\n\
+                      ==wml== web\n0 -\n";
+        let archived = parse_archived(&[(&api, now), (&web, now)], output);
+        assert_eq!(archived[0].files, 2);
+        assert_eq!(
+            archived[0].path.as_deref(),
+            Some("/home/u/.local/state/wsl-multi-launcher/transcripts/work/api/20260101-120000")
+        );
+        assert_eq!(archived[1], Archived { window: "web".to_string(), files: 0, path: None });
+    }
+
+    #[test]
+    fn test_parse_list() {
+        let output = "/a/work/api\n\
+                      100 20260102-090000/s1.jsonl\n\
+                      50 20260101-120000/s1.jsonl\n\
+                      25 20260101-120000/s1/subagents/agent-1.jsonl\n";
+        let archives = parse_list(output);
+        assert_eq!(archives.len(), 2);
+        assert_eq!(archives[0].path, "/a/work/api/20260101-120000");
+        assert_eq!((archives[0].files, archives[0].bytes), (2, 75));
+        assert_eq!(
+            archives[0].at.unwrap().format("%Y-%m-%d %H:%M").to_string(),
+            "2026-01-01 12:00"
+        );
+        assert_eq!(archives[1].bytes, 100);
+        assert!(parse_list("/a/work/api\n").is_empty());
+    }
+}
@@ -0,0 +1,522 @@
+//! Interactive launcher (`tui`)
+//!
+//! Lists the profiles (config files) and the windows of the selected one
+//! with checkboxes, and previews where the checked windows land on the
+//! grid. Enter launches the checked windows with a `launch` subcommand of
+//! this executable, whose output is captured so it can't scribble over the
+//! screen; its progress is followed through the event log the launch
+//! writes, window by window, in the same screen.
+
+use anyhow::Result;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+use std::io::Read;
+use std::process::Child;
+use std::sync::mpsc;
+use std::time::Duration;
+
+use crate::config::{Config, LaunchMode};
+use crate::events::{self, EventKind};
+use crate::layout::fitting_grid;
+
+/// A config file to launch from
+pub struct Profile {
+    pub path: String,
+    pub session: String,
+    pub config: Config,
+}
+
+/// How far the launch of a window got
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Progress {
+    Pending,
+    Launched,
+    /// Moved into its cell
+    Arranged,
+    Retrying,
+    Failed(String),
+    /// The launch finished without reaching the window
+    Skipped,
+}
+
+impl Progress {
+    fn label(&self) -> String {
+        match self {
+            Progress::Pending => "waiting".to_string(),
+            Progress::Launched => "launched".to_string(),
+            Progress::Arranged => "arranged".to_string(),
+            Progress::Retrying => "retrying move".to_string(),
+            Progress::Failed(detail) => format!("failed: {}", detail),
+            Progress::Skipped => "skipped".to_string(),
+        }
+    }
+
+    fn color(&self) -> Color {
+        match self {
+            Progress::Pending | Progress::Launched => Color::Yellow,
+            Progress::Arranged => Color::Green,
+            Progress::Retrying => Color::Magenta,
+            Progress::Failed(_) | Progress::Skipped => Color::Red,
+        }
+    }
+}
+
+/// Progress of each of `windows` from the events of `session`
+fn progress(events: &[events::Event], session: &str, windows: &[String]) -> Vec<Progress> {
+    windows
+        .iter()
+        .map(|name| {
+            let mut progress = Progress::Pending;
+            for event in events.iter().filter(|e| e.session == session && &e.window == name) {
+                progress = match event.kind {
+                    EventKind::WindowLaunched => Progress::Launched,
+                    EventKind::WindowMoved => Progress::Arranged,
+                    EventKind::MoveRetried => Progress::Retrying,
+                    EventKind::LaunchFailed | EventKind::MoveFailed => {
+                        Progress::Failed(event.detail.clone().unwrap_or_else(|| event.kind.to_string()))
+                    }
+                    _ => continue,
+                };
+            }
+            progress
+        })
+        .collect()
+}
+
+/// Grid cell of each window of `config` with only the `checked` ones
+/// launched; None for windows that don't take a cell
+///
+/// In windows mode every window keeps its cell; panes are split for the
+/// checked windows only, and tabs share one window.
+fn cells(config: &Config, checked: &[bool]) -> Vec<Option<usize>> {
+    match config.layout.mode {
+        LaunchMode::Windows => config.grid_slots(),
+        LaunchMode::Panes => {
+            let mut next = 0;
+            checked
+                .iter()
+                .map(|&on| {
+                    on.then(|| {
+                        next += 1;
+                        next - 1
+                    })
+                })
+                .collect()
+        }
+        LaunchMode::Tabs => vec![None; checked.len()],
+    }
+}
+
+/// Columns and rows of the preview grid
+fn grid(config: &Config) -> (usize, usize) {
+    let (cols, rows) = config.layout.parse_grid().unwrap_or_else(|_| fitting_grid(config.windows.len()));
+    (cols.max(1) as usize, rows.max(1) as usize)
+}
+
+/// A launch running in the background
+struct Running {
+    child: Child,
+    started: chrono::DateTime<chrono::Local>,
+    /// Windows being launched
+    windows: Vec<String>,
+    progress: Vec<Progress>,
+    /// Error output of the launch, once it's done
+    stderr: mpsc::Receiver<String>,
+    /// Set once the launch exited: whether it succeeded and what it said
+    done: Option<(bool, String)>,
+}
+
+impl Running {
+    fn update(&mut self, session: &str) {
+        if self.done.is_none()
+            && let Ok(Some(status)) = self.child.try_wait()
+        {
+            let stderr = self.stderr.recv_timeout(Duration::from_secs(1)).unwrap_or_default();
+            let message = stderr
+                .lines()
+                .find_map(|line| line.strip_prefix("Error: "))
+                .unwrap_or_default()
+                .to_string();
+            self.done = Some((status.success(), message));
+        }
+        let events = events::path().and_then(|path| events::load_from(&path, Some(self.started))).unwrap_or_default();
+        self.progress = progress(&events, session, &self.windows);
+        if self.done.is_some() {
+            for progress in &mut self.progress {
+                if *progress == Progress::Pending {
+                    *progress = Progress::Skipped;
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Focus {
+    Profiles,
+    Windows,
+}
+
+struct App {
+    profiles: Vec<Profile>,
+    /// Checked windows of each profile
+    checked: Vec<Vec<bool>>,
+    profile: usize,
+    window: usize,
+    focus: Focus,
+    running: Option<Running>,
+    message: String,
+}
+
+impl App {
+    fn current(&self) -> &Profile {
+        &self.profiles[self.profile]
+    }
+
+    fn checked_names(&self) -> Vec<String> {
+        self.current()
+            .config
+            .windows
+            .iter()
+            .zip(&self.checked[self.profile])
+            .filter(|(_, on)| **on)
+            .map(|(w, _)| w.name.clone())
+            .collect()
+    }
+
+    /// Progress of a window of the current profile while launching
+    fn progress_of(&self, name: &str) -> Option<&Progress> {
+        let running = self.running.as_ref()?;
+        let i = running.windows.iter().position(|w| w == name)?;
+        running.progress.get(i)
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [body, footer] = Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+        let [lists, preview] = Layout::horizontal([Constraint::Percentage(35), Constraint::Min(1)]).areas(body);
+        let profiles_height = (self.profiles.len() as u16 + 2).min(lists.height / 3).max(3);
+        let [profiles, windows] =
+            Layout::vertical([Constraint::Length(profiles_height), Constraint::Min(1)]).areas(lists);
+
+        self.draw_profiles(frame, profiles);
+        self.draw_windows(frame, windows);
+        self.draw_preview(frame, preview);
+
+        let key = |k: &'static str| Span::styled(k, Style::default().add_modifier(Modifier::BOLD));
+        let text = match self.running {
+            Some(ref running) if running.done.is_none() => {
+                Line::from(vec![Span::raw(" Launching... "), key("q"), Span::raw(" quit (the launch keeps running)")])
+            }
+            Some(_) => Line::from(vec![
+                key(" esc"),
+                Span::raw(" back  "),
+                key("q"),
+                Span::raw(" quit   "),
+                Span::raw(self.message.as_str()),
+            ]),
+            None => Line::from(vec![
+                key(" tab"),
+                Span::raw(" profiles/windows  "),
+                key("arrows/jk"),
+                Span::raw(" move  "),
+                key("space"),
+                Span::raw(" check  "),
+                key("a"),
+                Span::raw(" all/none  "),
+                key("enter"),
+                Span::raw(" launch  "),
+                key("q"),
+                Span::raw(" quit   "),
+                Span::raw(self.message.as_str()),
+            ]),
+        };
+        frame.render_widget(Paragraph::new(text), footer);
+    }
+
+    fn border(&self, focus: Focus) -> Style {
+        if self.focus == focus && self.running.is_none() {
+            Style::default().fg(Color::Cyan)
+        } else {
+            Style::default().fg(Color::DarkGray)
+        }
+    }
+
+    fn draw_profiles(&self, frame: &mut Frame, area: Rect) {
+        let lines: Vec<Line> = self
+            .profiles
+            .iter()
+            .enumerate()
+            .map(|(i, profile)| {
+                let text = format!(" {} ({}, {} windows)", profile.path, profile.session, profile.config.windows.len());
+                if i == self.profile {
+                    Line::styled(text, Style::default().add_modifier(Modifier::BOLD | Modifier::REVERSED))
+                } else {
+                    Line::raw(text)
+                }
+            })
+            .collect();
+        let block = Block::bordered().title(" Profiles ").border_style(self.border(Focus::Profiles));
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+
+    fn draw_windows(&self, frame: &mut Frame, area: Rect) {
+        let checked = &self.checked[self.profile];
+        let lines: Vec<Line> = self
+            .current()
+            .config
+            .windows
+            .iter()
+            .enumerate()
+            .map(|(i, window)| {
+                let mut spans = vec![Span::raw(if checked[i] { " [x] " } else { " [ ] " })];
+                let mut name = Style::default();
+                if i == self.window && self.focus == Focus::Windows && self.running.is_none() {
+                    name = name.add_modifier(Modifier::REVERSED);
+                }
+                spans.push(Span::styled(window.name.clone(), name));
+                if window.dropdown {
+                    spans.push(Span::styled(" (dropdown)", Style::default().fg(Color::DarkGray)));
+                }
+                if let Some(progress) = self.progress_of(&window.name) {
+                    spans.push(Span::styled(format!("  {}", progress.label()), Style::default().fg(progress.color())));
+                }
+                Line::from(spans)
+            })
+            .collect();
+        let title = format!(" Windows ({}/{}) ", checked.iter().filter(|c| **c).count(), checked.len());
+        let block = Block::bordered().title(title).border_style(self.border(Focus::Windows));
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+
+    fn draw_preview(&self, frame: &mut Frame, area: Rect) {
+        let config = &self.current().config;
+        let checked = &self.checked[self.profile];
+        let (cols, rows) = grid(config);
+        let mut title = format!(
+            " Display {} · {}x{} · {:?} mode ",
+            config.target_display, cols, rows, config.layout.mode
+        );
+        if let Some(Running { done: Some((ok, ref message)), .. }) = self.running {
+            title = if ok { " Launch finished ".to_string() } else { format!(" Launch failed: {} ", message) };
+        }
+        let block = Block::bordered().title(title);
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        // Windows without a cell: dropdowns, or all of them as tabs
+        let cells = cells(config, checked);
+        let loose: Vec<&str> = config
+            .windows
+            .iter()
+            .zip(&cells)
+            .zip(checked)
+            .filter(|((_, cell), on)| cell.is_none() && **on)
+            .map(|((w, _), _)| w.name.as_str())
+            .collect();
+        let [header, grid_area] = Layout::vertical([Constraint::Length(u16::from(!loose.is_empty())), Constraint::Min(1)])
+            .areas(inner);
+        if !loose.is_empty() {
+            let kind = if config.layout.mode == LaunchMode::Tabs { "tabs" } else { "dropdown" };
+            frame.render_widget(Paragraph::new(format!(" {}: {}", kind, loose.join(", "))), header);
+        }
+        if config.layout.mode == LaunchMode::Tabs {
+            return;
+        }
+
+        let row_areas = Layout::vertical(vec![Constraint::Ratio(1, rows as u32); rows]).split(grid_area);
+        for row in 0..rows {
+            let col_areas = Layout::horizontal(vec![Constraint::Ratio(1, cols as u32); cols]).split(row_areas[row]);
+            for col in 0..cols {
+                let cell = row * cols + col;
+                let window = cells.iter().position(|c| *c == Some(cell)).map(|i| (i, &config.windows[i]));
+                let (title, style) = match window {
+                    Some((i, window)) if checked[i] => {
+                        let color = self.progress_of(&window.name).map(Progress::color).unwrap_or(Color::Cyan);
+                        (format!(" {} ", window.name), Style::default().fg(color))
+                    }
+                    Some((_, window)) => (format!(" {} (off) ", window.name), Style::default().fg(Color::DarkGray)),
+                    None => (String::new(), Style::default().fg(Color::DarkGray)),
+                };
+                frame.render_widget(Block::bordered().title(title).border_style(style), col_areas[col]);
+            }
+        }
+    }
+
+    fn key(&mut self, code: KeyCode) {
+        let windows = self.current().config.windows.len();
+        match (self.focus, code) {
+            (_, KeyCode::Tab | KeyCode::BackTab) => {
+                self.focus = if self.focus == Focus::Profiles { Focus::Windows } else { Focus::Profiles };
+            }
+            (Focus::Profiles, KeyCode::Up | KeyCode::Char('k')) => {
+                self.profile = self.profile.saturating_sub(1);
+                self.window = 0;
+            }
+            (Focus::Profiles, KeyCode::Down | KeyCode::Char('j')) => {
+                self.profile = (self.profile + 1).min(self.profiles.len() - 1);
+                self.window = 0;
+            }
+            (Focus::Windows, KeyCode::Up | KeyCode::Char('k')) => self.window = self.window.saturating_sub(1),
+            (Focus::Windows, KeyCode::Down | KeyCode::Char('j')) => {
+                self.window = (self.window + 1).min(windows.saturating_sub(1));
+            }
+            (Focus::Windows, KeyCode::Char(' ')) => {
+                if let Some(on) = self.checked[self.profile].get_mut(self.window) {
+                    *on = !*on;
+                }
+            }
+            (_, KeyCode::Char('a')) => {
+                let checked = &mut self.checked[self.profile];
+                let all = checked.iter().all(|c| *c);
+                checked.iter_mut().for_each(|c| *c = !all);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Run the launcher until the user quits
+///
+/// `launch` starts launching the named windows of a profile in the
+/// background, with its standard error piped.
+pub fn run<L>(profiles: Vec<Profile>, mut launch: L) -> Result<()>
+where
+    L: FnMut(&Profile, &[String]) -> Result<Child>,
+{
+    if profiles.is_empty() {
+        anyhow::bail!("No config files to launch from");
+    }
+    let checked = profiles.iter().map(|p| vec![true; p.config.windows.len()]).collect();
+    let mut app = App {
+        profiles,
+        checked,
+        profile: 0,
+        window: 0,
+        focus: Focus::Windows,
+        running: None,
+        message: String::new(),
+    };
+
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, &mut app, &mut launch);
+    ratatui::restore();
+    result
+}
+
+fn event_loop<L>(terminal: &mut DefaultTerminal, app: &mut App, launch: &mut L) -> Result<()>
+where
+    L: FnMut(&Profile, &[String]) -> Result<Child>,
+{
+    loop {
+        if let Some(ref mut running) = app.running {
+            running.update(&app.profiles[app.profile].session);
+        }
+        terminal.draw(|frame| app.draw(frame))?;
+
+        if !event::poll(Duration::from_millis(300))? {
+            continue;
+        }
+        let Event::Key(key) = event::read()? else { continue };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        match key.code {
+            KeyCode::Char('q') => return Ok(()),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(()),
+            _ => {}
+        }
+
+        if let Some(ref running) = app.running {
+            if running.done.is_some() && matches!(key.code, KeyCode::Esc | KeyCode::Enter) {
+                app.running = None;
+                app.message.clear();
+            }
+            continue;
+        }
+        match key.code {
+            KeyCode::Esc => return Ok(()),
+            KeyCode::Enter => {
+                let windows = app.checked_names();
+                if windows.is_empty() {
+                    app.message = "Check at least one window".to_string();
+                    continue;
+                }
+                let started = chrono::Local::now();
+                match launch(app.current(), &windows) {
+                    Ok(mut child) => {
+                        let (sender, stderr) = mpsc::channel();
+                        if let Some(mut pipe) = child.stderr.take() {
+                            std::thread::spawn(move || {
+                                let mut text = String::new();
+                                let _ = pipe.read_to_string(&mut text);
+                                let _ = sender.send(text);
+                            });
+                        }
+                        let progress = vec![Progress::Pending; windows.len()];
+                        app.running = Some(Running { child, started, windows, progress, stderr, done: None });
+                    }
+                    Err(e) => app.message = format!("{:#}", e),
+                }
+            }
+            code => app.key(code),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Local;
+
+    fn event(kind: EventKind, window: &str, detail: Option<&str>) -> events::Event {
+        events::Event {
+            time: Local::now(),
+            session: "work".to_string(),
+            kind,
+            window: window.to_string(),
+            detail: detail.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_progress() {
+        let events = vec![
+            event(EventKind::WindowLaunched, "api", Some("handle 1")),
+            event(EventKind::WindowLaunched, "web", None),
+            event(EventKind::LaunchFailed, "db", Some("wt.exe failed")),
+            event(EventKind::MoveRetried, "web", None),
+            event(EventKind::WindowMoved, "api", None),
+            event(EventKind::TaskDispatched, "api", Some("task")),
+        ];
+        let mut other = event(EventKind::WindowMoved, "web", None);
+        other.session = "other".to_string();
+        let windows: Vec<String> = ["api", "web", "db", "docs"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(
+            progress(&[events, vec![other]].concat(), "work", &windows),
+            vec![
+                Progress::Arranged,
+                Progress::Retrying,
+                Progress::Failed("wt.exe failed".to_string()),
+                Progress::Pending,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cells() {
+        let mut config: Config = serde_yaml::from_str(
+            "wsl_distribution: Ubuntu\nlayout:\n  grid: 2x2\nwindows:\n  - name: a\n  - name: drop\n    dropdown: true\n  - name: b\n  - name: c\n",
+        )
+        .unwrap();
+        let checked = [false, true, true, true];
+        assert_eq!(cells(&config, &checked), vec![Some(0), None, Some(1), Some(2)]);
+        config.layout.mode = LaunchMode::Panes;
+        assert_eq!(cells(&config, &checked), vec![None, Some(0), Some(1), Some(2)]);
+        config.layout.mode = LaunchMode::Tabs;
+        assert_eq!(cells(&config, &checked), vec![None; 4]);
+    }
+}
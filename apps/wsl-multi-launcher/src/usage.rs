@@ -0,0 +1,282 @@
+//! Token usage and cost of claude windows (`usage`)
+//!
+//! Every reply claude writes to a transcript carries the token counts of
+//! the request. The replies in the transcripts of each `claude:` window's
+//! project are summed up per window, and the cost is estimated from the
+//! list prices of the reply's model. A reply split over several transcript
+//! lines (one per content block) repeats its usage, so replies are counted
+//! once by message id.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::process::Command;
+
+use crate::claude;
+use crate::config::{Config, WindowConfig};
+
+/// List prices in USD per million tokens
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Price {
+    input: f64,
+    output: f64,
+    cache_write: f64,
+    cache_read: f64,
+}
+
+const fn price(input: f64, output: f64) -> Price {
+    // Cache writes (5 minute TTL) cost 1.25x input, cache reads 0.1x
+    Price { input, output, cache_write: input * 1.25, cache_read: input * 0.1 }
+}
+
+/// Prices by model id fragment; the first match wins
+const PRICES: &[(&str, Price)] = &[
+    ("claude-opus-4-1", price(15.0, 75.0)),
+    ("claude-opus-4-2025", price(15.0, 75.0)),
+    ("claude-3-opus", price(15.0, 75.0)),
+    ("opus", price(5.0, 25.0)),
+    ("sonnet", price(3.0, 15.0)),
+    ("claude-3-5-haiku", price(0.8, 4.0)),
+    ("claude-3-haiku", price(0.25, 1.25)),
+    ("haiku", price(1.0, 5.0)),
+];
+
+fn model_price(model: &str) -> Option<Price> {
+    PRICES.iter().find(|(fragment, _)| model.contains(fragment)).map(|(_, price)| *price)
+}
+
+/// Tokens used and their estimated cost
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct Usage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_write_tokens: u64,
+    pub cache_read_tokens: u64,
+    /// Estimated cost in USD; replies of unknown models count as free
+    pub cost_usd: f64,
+    /// Replies counted
+    pub replies: u64,
+}
+
+impl Usage {
+    /// Tokens that count towards rate limits and budgets: input, cache
+    /// write and output (cache reads don't)
+    pub fn tokens(&self) -> u64 {
+        self.input_tokens + self.cache_write_tokens + self.output_tokens
+    }
+
+    fn add(&mut self, other: &Usage) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cache_write_tokens += other.cache_write_tokens;
+        self.cache_read_tokens += other.cache_read_tokens;
+        self.cost_usd += other.cost_usd;
+        self.replies += other.replies;
+    }
+}
+
+/// Message id and usage of a transcript line, if it's a reply newer than
+/// `since`
+fn reply_usage(line: &str, since: Option<DateTime<Local>>) -> Option<(String, Usage)> {
+    let entry: serde_json::Value = serde_json::from_str(line).ok()?;
+    if entry["type"] != "assistant" {
+        return None;
+    }
+    if let Some(since) = since {
+        let time = DateTime::parse_from_rfc3339(entry["timestamp"].as_str()?).ok()?;
+        if time < since {
+            return None;
+        }
+    }
+    let message = &entry["message"];
+    let tokens = &message["usage"];
+    let count = |key: &str| tokens[key].as_u64().unwrap_or(0);
+    let mut usage = Usage {
+        input_tokens: count("input_tokens"),
+        output_tokens: count("output_tokens"),
+        cache_write_tokens: count("cache_creation_input_tokens"),
+        cache_read_tokens: count("cache_read_input_tokens"),
+        cost_usd: 0.0,
+        replies: 1,
+    };
+    if let Some(price) = message["model"].as_str().and_then(model_price) {
+        usage.cost_usd = (usage.input_tokens as f64 * price.input
+            + usage.output_tokens as f64 * price.output
+            + usage.cache_write_tokens as f64 * price.cache_write
+            + usage.cache_read_tokens as f64 * price.cache_read)
+            / 1_000_000.0;
+    }
+    let id = message["id"].as_str().or(entry["uuid"].as_str())?;
+    Some((id.to_string(), usage))
+}
+
+/// Sum the replies in `lines` that aren't in `seen` yet, adding them to it
+fn sum_replies(lines: &[String], since: Option<DateTime<Local>>, seen: &mut HashSet<String>) -> Usage {
+    let mut total = Usage::default();
+    for (id, usage) in lines.iter().filter_map(|line| reply_usage(line, since)) {
+        if seen.insert(id) {
+            total.add(&usage);
+        }
+    }
+    total
+}
+
+/// Script printing the replies in every transcript of each window's
+/// project, each after a `SECTION` line with the window name
+fn replies_script(windows: &[&WindowConfig], since: Option<DateTime<Local>>) -> String {
+    // A transcript last written before `since` has nothing newer
+    let newer = since
+        .map(|since| format!(" -mmin -{}", (Local::now() - since).num_minutes() + 1))
+        .unwrap_or_default();
+    let mut script = String::from(claude::PROJECTS_DIR);
+    for window in windows {
+        let Some(ref preset) = window.claude else { continue };
+        script.push_str(&format!(
+            "; {}; {} && [ -d \"$p\" ] && find \"$p\" -name '*.jsonl'{} -exec grep -h '\"type\":\"assistant\"' {{}} +",
+            claude::section_script(&window.name),
+            claude::transcript_dir_script(&preset.project),
+            newer
+        ));
+    }
+    script
+}
+
+/// Usage of one window
+#[derive(Debug, Clone, Serialize)]
+pub struct WindowUsage {
+    pub name: String,
+    pub project: String,
+    #[serde(flatten)]
+    pub usage: Usage,
+}
+
+/// Usage of every claude window, plus the total
+#[derive(Debug, Clone, Serialize)]
+pub struct Report {
+    pub windows: Vec<WindowUsage>,
+    /// Windows sharing a project share replies; they count once here
+    pub total: Usage,
+}
+
+fn report(windows: &[&WindowConfig], output: &str, since: Option<DateTime<Local>>) -> Report {
+    let sections = claude::parse_sections(output);
+    let mut counted = HashSet::new();
+    let mut total = Usage::default();
+    let windows = windows
+        .iter()
+        .filter_map(|window| {
+            let preset = window.claude.as_ref()?;
+            let lines = sections.get(&window.name).map(Vec::as_slice).unwrap_or_default();
+            let usage = sum_replies(lines, since, &mut HashSet::new());
+            total.add(&sum_replies(lines, since, &mut counted));
+            Some(WindowUsage { name: window.name.clone(), project: preset.project.clone(), usage })
+        })
+        .collect();
+    Report { windows, total }
+}
+
+/// Usage of the claude windows of `config` since `since` (all time if None)
+pub fn collect(config: &Config, since: Option<DateTime<Local>>) -> Result<Report> {
+    let windows: Vec<&WindowConfig> = config.windows.iter().filter(|w| w.claude.is_some()).collect();
+    if windows.is_empty() {
+        anyhow::bail!("No claude: windows in the config");
+    }
+    collect_windows(&config.wsl_distribution, &windows, since)
+}
+
+/// Usage of the claude windows among `windows` since `since`
+pub fn collect_windows(distribution: &str, windows: &[&WindowConfig], since: Option<DateTime<Local>>) -> Result<Report> {
+    let output = Command::new("wsl.exe")
+        .args(["-d", distribution, "--exec", "bash", "-c", &replies_script(windows, since)])
+        .output()
+        .context("Failed to run wsl.exe")?;
+    Ok(report(windows, &String::from_utf8_lossy(&output.stdout), since))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ClaudeConfig;
+
+    fn reply(id: &str, model: &str, time: &str, input: u64, output: u64) -> String {
+        format!(
+            r#"{{"type":"assistant","timestamp":"{}","message":{{"id":"{}","model":"{}","usage":{{"input_tokens":{},"output_tokens":{},"cache_creation_input_tokens":1000000,"cache_read_input_tokens":0}}}}}}"#,
+            time, id, model, input, output
+        )
+    }
+
+    #[test]
+    fn test_model_price() {
+        assert_eq!(model_price("claude-opus-4-1-20250805").unwrap().input, 15.0);
+        assert_eq!(model_price("claude-opus-4-20250514").unwrap().input, 15.0);
+        assert_eq!(model_price("claude-opus-4-6").unwrap().input, 5.0);
+        assert_eq!(model_price("claude-sonnet-4-5-20250929").unwrap().output, 15.0);
+        assert_eq!(model_price("claude-3-5-haiku-20241022").unwrap().input, 0.8);
+        assert_eq!(model_price("claude-haiku-4-5").unwrap().cache_read, 0.1);
+        assert!(model_price("<synthetic>").is_none());
+    }
+
+    #[test]
+    fn test_sum_replies() {
+        let lines = vec![
+            reply("msg_1", "claude-sonnet-4-5", "2026-01-01T10:00:00Z", 1_000_000, 0),
+            // Second content block of the same reply
+            reply("msg_1", "claude-sonnet-4-5", "2026-01-01T10:00:00Z", 1_000_000, 0),
+            reply("msg_2", "claude-sonnet-4-5", "2026-01-02T10:00:00Z", 0, 1_000_000),
+            r#"{"type":"user","message":{"content":"hi"}}"#.to_string(),
+            "not json".to_string(),
+        ];
+        let usage = sum_replies(&lines, None, &mut HashSet::new());
+        assert_eq!(usage.replies, 2);
+        assert_eq!(usage.input_tokens, 1_000_000);
+        assert_eq!(usage.output_tokens, 1_000_000);
+        assert_eq!(usage.cache_write_tokens, 2_000_000);
+        // 3 input + 15 output + 2 x 3.75 cache writes
+        assert!((usage.cost_usd - 25.5).abs() < 1e-9);
+
+        let since = DateTime::parse_from_rfc3339("2026-01-02T00:00:00Z").unwrap().with_timezone(&Local);
+        let usage = sum_replies(&lines, Some(since), &mut HashSet::new());
+        assert_eq!(usage.replies, 1);
+        assert_eq!(usage.input_tokens, 0);
+    }
+
+    #[test]
+    fn test_report() {
+        let window = |name: &str, project: &str| WindowConfig {
+            name: name.to_string(),
+            claude: Some(ClaudeConfig { project: project.to_string(), ..Default::default() }),
+            ..Default::default()
+        };
+        let (api, api2, web) = (window("api", "~/api"), window("api-2", "~/api"), window("web", "~/web"));
+        let api_replies = reply("msg_1", "claude-haiku-4-5", "2026-01-01T10:00:00Z", 10, 20);
+        let output = format!(
+            "==wml== api\n{api_replies}\n==wml== api-2\n{api_replies}\n==wml== web\n{}\n",
+            reply("msg_2", "claude-haiku-4-5", "2026-01-01T10:00:00Z", 1, 2)
+        );
+        let report = report(&[&api, &api2, &web], &output, None);
+        assert_eq!(report.windows.len(), 3);
+        assert_eq!(report.windows[0].usage.input_tokens, 10);
+        assert_eq!(report.windows[1].usage.input_tokens, 10);
+        assert_eq!(report.windows[2].project, "~/web");
+        // The shared project counts once
+        assert_eq!(report.total.input_tokens, 11);
+        assert_eq!(report.total.replies, 2);
+    }
+
+    #[test]
+    fn test_replies_script() {
+        let api = WindowConfig {
+            name: "api".to_string(),
+            claude: Some(ClaudeConfig { project: "~/api".to_string(), ..Default::default() }),
+            ..Default::default()
+        };
+        let script = replies_script(&[&api], None);
+        assert!(script.starts_with(claude::PROJECTS_DIR));
+        assert!(script.contains("printf '%s\\n' '==wml== api'"));
+        assert!(script.contains("find \"$p\" -name '*.jsonl' -exec grep -h"));
+
+        let since = Local::now() - chrono::Duration::hours(2);
+        assert!(replies_script(&[&api], Some(since)).contains("-name '*.jsonl' -mmin -121 -exec"));
+    }
+}
@@ -0,0 +1,307 @@
+//! Bookkeeping for the long-running `watch` and `hold` loops
+//!
+//! The loops themselves live in main; this keeps track of how often each
+//! window was restarted and when it may be restarted next (so a command
+//! that crashes immediately doesn't spin), of how long a window has been
+//! sitting away from its cell, of how long a window has been idle, and of
+//! which exits and permission prompts were already notified.
+
+use chrono::{DateTime, Local};
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+use crate::config::WatchConfig;
+use crate::layout::Rect;
+
+/// Longest wait between restarts, however many there were
+const MAX_BACKOFF: Duration = Duration::from_secs(300);
+
+/// What to do about a window that has gone missing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decision {
+    /// Relaunch it now
+    Restart,
+    /// Still backing off; check again later
+    Wait,
+    /// `max_restarts` reached; leave it closed
+    GiveUp,
+}
+
+#[derive(Debug, Default)]
+struct WindowHistory {
+    restarts: u32,
+    next_allowed: Option<Instant>,
+}
+
+/// Per-window restart counters and backoff timers
+pub struct Supervisor {
+    policy: WatchConfig,
+    history: HashMap<String, WindowHistory>,
+}
+
+impl Supervisor {
+    pub fn new(policy: WatchConfig) -> Self {
+        Self {
+            policy,
+            history: HashMap::new(),
+        }
+    }
+
+    /// Decide what to do about a missing window, recording the restart
+    /// when the answer is `Restart`
+    pub fn on_missing(&mut self, name: &str, now: Instant) -> Decision {
+        let entry = self.history.entry(name.to_string()).or_default();
+        if entry.restarts >= self.policy.max_restarts {
+            return Decision::GiveUp;
+        }
+        if entry.next_allowed.is_some_and(|t| now < t) {
+            return Decision::Wait;
+        }
+
+        entry.restarts += 1;
+        entry.next_allowed = Some(now + backoff(&self.policy, entry.restarts));
+        Decision::Restart
+    }
+
+    /// Restarts performed so far for a window
+    pub fn restarts(&self, name: &str) -> u32 {
+        self.history.get(name).map_or(0, |h| h.restarts)
+    }
+}
+
+/// Wait before the restart after the `restarts`-th one
+fn backoff(policy: &WatchConfig, restarts: u32) -> Duration {
+    let factor = 2u64.saturating_pow(restarts.saturating_sub(1));
+    Duration::from_secs(policy.backoff_secs.saturating_mul(factor)).min(MAX_BACKOFF)
+}
+
+/// Tracks windows moved away from their cell for `hold`
+///
+/// A window is snapped back once it has stayed at the same place for the
+/// grace period, so it isn't yanked away while the user is still dragging.
+pub struct SnapTracker {
+    grace: Duration,
+    pending: HashMap<String, (Rect, Instant)>,
+}
+
+impl SnapTracker {
+    pub fn new(grace: Duration) -> Self {
+        Self {
+            grace,
+            pending: HashMap::new(),
+        }
+    }
+
+    /// Report a window's current rect; returns true when it should be snapped
+    pub fn observe(&mut self, name: &str, current: Rect, moved: bool, now: Instant) -> bool {
+        if !moved {
+            self.pending.remove(name);
+            return false;
+        }
+        match self.pending.get(name) {
+            Some(&(rect, since)) if rect == current => {
+                if now.duration_since(since) >= self.grace {
+                    self.pending.remove(name);
+                    true
+                } else {
+                    false
+                }
+            }
+            // Newly moved, or still moving: restart the grace period
+            _ => {
+                self.pending.insert(name.to_string(), (current, now));
+                false
+            }
+        }
+    }
+}
+
+/// Tracks how long each window's processes have used no CPU
+///
+/// A shell waiting at its prompt (no foreground child, no output) burns no
+/// CPU, so unchanged CPU time between samples means the window is idle.
+#[derive(Debug, Default)]
+pub struct IdleTracker {
+    windows: HashMap<String, (u64, Instant)>,
+}
+
+impl IdleTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Report a window's total CPU ticks; returns how long they haven't changed
+    pub fn observe(&mut self, name: &str, ticks: u64, now: Instant) -> Duration {
+        match self.windows.get(name) {
+            Some(&(last, since)) if last == ticks => now.duration_since(since),
+            _ => {
+                self.windows.insert(name.to_string(), (ticks, now));
+                Duration::ZERO
+            }
+        }
+    }
+
+    /// Forget a window (closed or restarted)
+    pub fn forget(&mut self, name: &str) {
+        self.windows.remove(name);
+    }
+}
+
+/// Decides which window events get a desktop notification, so every exit,
+/// every permission prompt and every budget overrun is announced once
+#[derive(Debug)]
+pub struct Notifier {
+    /// Exits before this (e.g. before `watch` started) are old news
+    since: DateTime<Local>,
+    exits: HashMap<String, DateTime<Local>>,
+    waiting: HashSet<String>,
+    over_budget: HashSet<String>,
+}
+
+impl Notifier {
+    pub fn new(since: DateTime<Local>) -> Self {
+        Self { since, exits: HashMap::new(), waiting: HashSet::new(), over_budget: HashSet::new() }
+    }
+
+    /// Report a window's last exit; true if it should be announced
+    pub fn exited(&mut self, name: &str, at: DateTime<Local>) -> bool {
+        if at < self.since || self.exits.get(name).is_some_and(|&last| at <= last) {
+            return false;
+        }
+        self.exits.insert(name.to_string(), at);
+        true
+    }
+
+    /// Report whether a claude window waits for a permission answer; true
+    /// when it has just started waiting
+    pub fn waiting(&mut self, name: &str, waiting: bool) -> bool {
+        if waiting {
+            self.waiting.insert(name.to_string())
+        } else {
+            self.waiting.remove(name);
+            false
+        }
+    }
+
+    /// Report whether a window is over its budget; true when it has just
+    /// gone over
+    pub fn over_budget(&mut self, name: &str, over: bool) -> bool {
+        if over {
+            self.over_budget.insert(name.to_string())
+        } else {
+            self.over_budget.remove(name);
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(max_restarts: u32, backoff_secs: u64) -> WatchConfig {
+        WatchConfig {
+            max_restarts,
+            backoff_secs,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_restart_with_backoff() {
+        let mut sup = Supervisor::new(policy(5, 2));
+        let t0 = Instant::now();
+
+        assert_eq!(sup.on_missing("a", t0), Decision::Restart);
+        // Crashed again straight away: wait out the 2s backoff
+        assert_eq!(sup.on_missing("a", t0 + Duration::from_secs(1)), Decision::Wait);
+        assert_eq!(sup.on_missing("a", t0 + Duration::from_secs(2)), Decision::Restart);
+        // Second restart doubles the backoff to 4s
+        assert_eq!(sup.on_missing("a", t0 + Duration::from_secs(5)), Decision::Wait);
+        assert_eq!(sup.on_missing("a", t0 + Duration::from_secs(6)), Decision::Restart);
+        assert_eq!(sup.restarts("a"), 3);
+        assert_eq!(sup.restarts("b"), 0);
+    }
+
+    #[test]
+    fn test_give_up_after_max_restarts() {
+        let mut sup = Supervisor::new(policy(1, 0));
+        let t0 = Instant::now();
+
+        assert_eq!(sup.on_missing("a", t0), Decision::Restart);
+        assert_eq!(sup.on_missing("a", t0), Decision::GiveUp);
+    }
+
+    #[test]
+    fn test_backoff_is_capped() {
+        let policy = policy(100, 60);
+        assert_eq!(backoff(&policy, 1), Duration::from_secs(60));
+        assert_eq!(backoff(&policy, 20), MAX_BACKOFF);
+    }
+
+    #[test]
+    fn test_snap_after_grace_period() {
+        let mut tracker = SnapTracker::new(Duration::from_secs(3));
+        let t0 = Instant::now();
+        let dragged = Rect::new(100, 100, 800, 600);
+        let dropped = Rect::new(300, 200, 800, 600);
+
+        assert!(!tracker.observe("a", dragged, true, t0));
+        // Still moving: the grace period starts over
+        assert!(!tracker.observe("a", dropped, true, t0 + Duration::from_secs(2)));
+        assert!(!tracker.observe("a", dropped, true, t0 + Duration::from_secs(4)));
+        assert!(tracker.observe("a", dropped, true, t0 + Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn test_snap_cancelled_when_back_in_place() {
+        let mut tracker = SnapTracker::new(Duration::from_secs(1));
+        let t0 = Instant::now();
+        let rect = Rect::new(0, 0, 10, 10);
+
+        assert!(!tracker.observe("a", rect, true, t0));
+        assert!(!tracker.observe("a", rect, false, t0 + Duration::from_secs(1)));
+        assert!(!tracker.observe("a", rect, true, t0 + Duration::from_secs(2)));
+    }
+
+    #[test]
+    fn test_idle_tracker() {
+        let mut tracker = IdleTracker::new();
+        let start = Instant::now();
+        let later = |secs| start + Duration::from_secs(secs);
+
+        assert_eq!(tracker.observe("a", 100, start), Duration::ZERO);
+        assert_eq!(tracker.observe("a", 100, later(30)), Duration::from_secs(30));
+        // Any CPU use restarts the idle period
+        assert_eq!(tracker.observe("a", 105, later(40)), Duration::ZERO);
+        assert_eq!(tracker.observe("a", 105, later(100)), Duration::from_secs(60));
+
+        tracker.forget("a");
+        assert_eq!(tracker.observe("a", 105, later(200)), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_notify_each_event_once() {
+        let t0 = Local::now();
+        let mut notifier = Notifier::new(t0);
+
+        // Exited before watch started
+        assert!(!notifier.exited("a", t0 - chrono::Duration::seconds(5)));
+        assert!(notifier.exited("a", t0 + chrono::Duration::seconds(1)));
+        assert!(!notifier.exited("a", t0 + chrono::Duration::seconds(1)));
+        // Relaunched and exited again
+        assert!(notifier.exited("a", t0 + chrono::Duration::seconds(9)));
+
+        assert!(notifier.waiting("b", true));
+        assert!(!notifier.waiting("b", true));
+        assert!(!notifier.waiting("b", false));
+        assert!(notifier.waiting("b", true));
+
+        assert!(notifier.over_budget("c", true));
+        assert!(!notifier.over_budget("c", true));
+        // Still waiting on "b" doesn't mean "c" is announced again
+        assert!(!notifier.waiting("b", true));
+        assert!(!notifier.over_budget("c", false));
+        assert!(notifier.over_budget("c", true));
+    }
+}